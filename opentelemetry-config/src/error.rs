@@ -0,0 +1,47 @@
+use std::io;
+
+/// Errors returned while loading a configuration or building the providers it
+/// describes.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The configuration file could not be read.
+    #[error("cannot read configuration file: {0}")]
+    Io(#[from] io::Error),
+
+    /// The configuration is not valid JSON or does not match the model.
+    #[error("invalid configuration: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    /// The configuration is not valid YAML or does not match the model.
+    #[cfg(feature = "yaml")]
+    #[error("invalid configuration: {0}")]
+    ParseYaml(#[from] serde_yaml::Error),
+
+    /// The `file_format` of the configuration is not supported.
+    #[error("unsupported file_format {0:?}, expected one of {supported:?}", supported = crate::SUPPORTED_FILE_FORMATS)]
+    UnsupportedFileFormat(String),
+
+    /// The configuration names a component unknown to this crate, or whose
+    /// feature is not enabled.
+    #[error("unsupported {kind} {name:?}")]
+    Unsupported {
+        /// The kind of the component, e.g. `propagator`.
+        kind: &'static str,
+        /// The name of the component.
+        name: String,
+    },
+
+    /// A value of the configuration is invalid.
+    #[error("invalid {field}: {message}")]
+    InvalidValue {
+        /// The field holding the value.
+        field: &'static str,
+        /// Why the value is invalid.
+        message: String,
+    },
+
+    /// An exporter could not be built.
+    #[error("cannot build exporter: {0}")]
+    Exporter(String),
+}
@@ -0,0 +1,164 @@
+//! Construction of the OTLP exporters.
+use std::time::Duration;
+
+use opentelemetry_otlp::{
+    DeltaTemporalitySelector, HttpExporterBuilder, LogExporterBuilder,
+    LowMemoryTemporalitySelector, MetricsExporterBuilder, SpanExporterBuilder,
+    TonicExporterBuilder, WithExportConfig,
+};
+use opentelemetry_sdk::metrics::reader::{
+    DefaultAggregationSelector, DefaultTemporalitySelector, TemporalitySelector,
+};
+
+use crate::model::{
+    OtlpExporterConfig, OtlpMetricExporterConfig, OtlpProtocol, TemporalityPreference,
+};
+use crate::ConfigError;
+
+/// The transport independent options of an OTLP exporter.
+struct OtlpOptions<'a> {
+    protocol: OtlpProtocol,
+    endpoint: Option<&'a str>,
+    headers: &'a std::collections::HashMap<String, String>,
+    compression: Option<&'a str>,
+    timeout: Option<u64>,
+}
+
+impl<'a> From<&'a OtlpExporterConfig> for OtlpOptions<'a> {
+    fn from(config: &'a OtlpExporterConfig) -> Self {
+        OtlpOptions {
+            protocol: config.protocol,
+            endpoint: config.endpoint.as_deref(),
+            headers: &config.headers,
+            compression: config.compression.as_deref(),
+            timeout: config.timeout,
+        }
+    }
+}
+
+impl<'a> From<&'a OtlpMetricExporterConfig> for OtlpOptions<'a> {
+    fn from(config: &'a OtlpMetricExporterConfig) -> Self {
+        OtlpOptions {
+            protocol: config.protocol,
+            endpoint: config.endpoint.as_deref(),
+            headers: &config.headers,
+            compression: config.compression.as_deref(),
+            timeout: config.timeout,
+        }
+    }
+}
+
+impl OtlpOptions<'_> {
+    /// The signal specific exporter builder, over the configured transport.
+    fn builder<B>(&self) -> Result<B, ConfigError>
+    where
+        B: From<TonicExporterBuilder> + From<HttpExporterBuilder>,
+    {
+        let compression = match self.compression {
+            None | Some("none") => None,
+            Some("gzip") => Some(opentelemetry_otlp::Compression::Gzip),
+            Some(other) => {
+                return Err(ConfigError::Unsupported {
+                    kind: "compression",
+                    name: other.to_string(),
+                })
+            }
+        };
+
+        match self.protocol {
+            OtlpProtocol::Grpc => {
+                let mut builder = opentelemetry_otlp::new_exporter().tonic();
+                if let Some(endpoint) = self.endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.with_timeout(Duration::from_millis(timeout));
+                }
+                if !self.headers.is_empty() {
+                    builder = builder.with_metadata(metadata(self.headers)?);
+                }
+                if let Some(compression) = compression {
+                    builder = builder.with_compression(compression);
+                }
+                Ok(builder.into())
+            }
+            OtlpProtocol::HttpProtobuf => {
+                if compression.is_some() {
+                    return Err(ConfigError::Unsupported {
+                        kind: "http/protobuf compression",
+                        name: "gzip".to_string(),
+                    });
+                }
+                let mut builder = opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_protocol(opentelemetry_otlp::Protocol::HttpBinary);
+                if let Some(endpoint) = self.endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.with_timeout(Duration::from_millis(timeout));
+                }
+                if !self.headers.is_empty() {
+                    builder = builder.with_headers(self.headers.clone());
+                }
+                Ok(builder.into())
+            }
+        }
+    }
+}
+
+fn metadata(
+    headers: &std::collections::HashMap<String, String>,
+) -> Result<tonic::metadata::MetadataMap, ConfigError> {
+    let mut metadata = tonic::metadata::MetadataMap::with_capacity(headers.len());
+    for (key, value) in headers {
+        let key = key
+            .parse::<tonic::metadata::MetadataKey<tonic::metadata::Ascii>>()
+            .map_err(|err| ConfigError::InvalidValue {
+                field: "headers",
+                message: format!("{key:?}: {err}"),
+            })?;
+        let value = value.parse().map_err(|err| ConfigError::InvalidValue {
+            field: "headers",
+            message: format!("{value:?}: {err}"),
+        })?;
+        metadata.insert(key, value);
+    }
+    Ok(metadata)
+}
+
+pub(crate) fn otlp_span_exporter(
+    config: &OtlpExporterConfig,
+) -> Result<opentelemetry_otlp::SpanExporter, ConfigError> {
+    let builder: SpanExporterBuilder = OtlpOptions::from(config).builder()?;
+    builder
+        .build_span_exporter()
+        .map_err(|err| ConfigError::Exporter(err.to_string()))
+}
+
+pub(crate) fn otlp_log_exporter(
+    config: &OtlpExporterConfig,
+) -> Result<opentelemetry_otlp::LogExporter, ConfigError> {
+    let builder: LogExporterBuilder = OtlpOptions::from(config).builder()?;
+    builder
+        .build_log_exporter()
+        .map_err(|err| ConfigError::Exporter(err.to_string()))
+}
+
+pub(crate) fn otlp_metrics_exporter(
+    config: &OtlpMetricExporterConfig,
+) -> Result<opentelemetry_otlp::MetricsExporter, ConfigError> {
+    let builder: MetricsExporterBuilder = OtlpOptions::from(config).builder()?;
+    let temporality_selector: Box<dyn TemporalitySelector> =
+        match config.temporality_preference.unwrap_or_default() {
+            TemporalityPreference::Cumulative => Box::new(DefaultTemporalitySelector::new()),
+            TemporalityPreference::Delta => Box::new(DeltaTemporalitySelector::new()),
+            TemporalityPreference::LowMemory => Box::new(LowMemoryTemporalitySelector::new()),
+        };
+    builder
+        .build_metrics_exporter(
+            temporality_selector,
+            Box::new(DefaultAggregationSelector::new()),
+        )
+        .map_err(|err| ConfigError::Exporter(err.to_string()))
+}
@@ -0,0 +1,543 @@
+//! Declarative configuration of the OpenTelemetry SDK.
+//!
+//! This crate builds the tracer, meter and logger providers, as well as the
+//! propagator, described by a configuration file following the
+//! [OpenTelemetry configuration schema], instead of wiring them in code.
+//!
+//! *Compiler support: [requires `rustc` 1.65+][msrv]*
+//!
+//! # Getting started
+//!
+//! ```no_run
+//! use opentelemetry_config::Configuration;
+//! use opentelemetry_sdk::runtime;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Configuration::from_json(
+//!     r#"{
+//!         "file_format": "0.2",
+//!         "resource": {
+//!             "attributes": { "service.name": "${SERVICE_NAME:-my-service}" }
+//!         },
+//!         "propagator": { "composite": ["tracecontext", "baggage"] },
+//!         "tracer_provider": {
+//!             "processors": [
+//!                 { "batch": { "exporter": { "otlp": { "protocol": "grpc" } } } }
+//!             ],
+//!             "sampler": { "parent_based": { "root": { "trace_id_ratio_based": { "ratio": 0.1 } } } }
+//!         },
+//!         "meter_provider": {
+//!             "readers": [
+//!                 { "periodic": { "interval": 30000, "exporter": { "otlp": {} } } }
+//!             ]
+//!         }
+//!     }"#,
+//! )?;
+//!
+//! let providers = config.build(runtime::Tokio)?;
+//! if let Some(tracer_provider) = providers.tracer_provider {
+//!     opentelemetry::global::set_tracer_provider(tracer_provider);
+//! }
+//! if let Some(meter_provider) = providers.meter_provider {
+//!     opentelemetry::global::set_meter_provider(meter_provider);
+//! }
+//! if let Some(propagator) = providers.propagator {
+//!     opentelemetry::global::set_text_map_propagator(propagator);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Configuration files are loaded with [`Configuration::from_file`].
+//!
+//! # Environment variable substitution
+//!
+//! References to environment variables of the form `${NAME}` or `${env:NAME}`
+//! are replaced with the value of the variable before the configuration is
+//! parsed, or with the empty string if the variable is not set. A default
+//! value can be given with `${NAME:-default}`, and `$$` escapes a `$`.
+//!
+//! # YAML
+//!
+//! With the `yaml` feature, YAML configurations are parsed with
+//! `Configuration::from_yaml`, and [`Configuration::from_file`] parses the
+//! files with a `.yaml` or `.yml` extension as YAML.
+//!
+//! # Crate Feature Flags
+//!
+//! * `otlp`: Enables the `otlp` exporters, over gRPC and HTTP. Enabled by default.
+//! * `gzip`: Enables the `gzip` compression of the `otlp` gRPC exporters.
+//! * `stdout`: Enables the `console` exporters.
+//! * `jaeger`: Enables the `jaeger` propagator.
+//! * `zipkin`: Enables the `b3` and `b3multi` propagators.
+//! * `yaml`: Enables loading YAML configurations.
+//!
+//! Configurations referring to components whose feature is not enabled fail to
+//! build with [`ConfigError::Unsupported`].
+//!
+//! [OpenTelemetry configuration schema]: https://github.com/open-telemetry/opentelemetry-configuration
+//! [msrv]: #supported-rust-versions
+//!
+//! # Supported Rust Versions
+//!
+//! OpenTelemetry is built against the latest stable release. The minimum
+//! supported version is 1.65. The current OpenTelemetry version is not
+//! guaranteed to build on Rust versions earlier than the minimum supported
+//! version.
+//!
+//! The current stable Rust compiler and the three most recent minor versions
+//! before it will always be supported. For example, if the current stable
+//! compiler version is 1.65, the minimum supported version will not be
+//! increased past 1.62, three minor versions prior. Increasing the minimum
+//! supported compiler version is not considered a semver breaking change as
+//! long as doing so complies with this policy.
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+use std::path::Path;
+
+use opentelemetry::propagation::TextMapCompositePropagator;
+use opentelemetry_sdk::logs::LoggerProvider;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::runtime::RuntimeChannel;
+use opentelemetry_sdk::trace::TracerProvider;
+
+mod error;
+#[cfg(feature = "otlp")]
+mod exporter;
+mod logs;
+mod metrics;
+pub mod model;
+mod propagator;
+mod resource;
+mod trace;
+
+pub use error::ConfigError;
+pub use model::Configuration;
+
+/// The versions of the configuration schema supported by this crate.
+pub(crate) const SUPPORTED_FILE_FORMATS: [&str; 2] = ["0.1", "0.2"];
+
+/// The providers and propagator built from a [`Configuration`].
+///
+/// Providers and propagator are only built when configured, and none is built
+/// when the configuration is `disabled`. They are not installed globally.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct SdkProviders {
+    /// The tracer provider.
+    pub tracer_provider: Option<TracerProvider>,
+    /// The meter provider.
+    pub meter_provider: Option<SdkMeterProvider>,
+    /// The logger provider.
+    pub logger_provider: Option<LoggerProvider>,
+    /// The propagator.
+    pub propagator: Option<TextMapCompositePropagator>,
+}
+
+impl Configuration {
+    /// Parse a JSON configuration, after substituting the environment
+    /// variables it refers to.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let config: Configuration = serde_json::from_str(&substitute_env_vars(json))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a YAML configuration, after substituting the environment
+    /// variables it refers to.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, ConfigError> {
+        // Read as a JSON value first, as serde_yaml expects tags rather than
+        // single key maps for the enums of the model.
+        let value: serde_json::Value = serde_yaml::from_str(&substitute_env_vars(yaml))?;
+        let config: Configuration = serde_json::from_value(value)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a configuration file, after substituting the environment
+    /// variables it refers to.
+    ///
+    /// Files with a `.yaml` or `.yml` extension are parsed as YAML, which
+    /// requires the `yaml` feature, and the other files as JSON.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&content),
+            #[cfg(not(feature = "yaml"))]
+            Some(extension @ ("yaml" | "yml")) => Err(ConfigError::Unsupported {
+                kind: "configuration file extension",
+                name: extension.to_string(),
+            }),
+            _ => Self::from_json(&content),
+        }
+    }
+
+    /// Check that the `file_format` of the configuration is supported.
+    ///
+    /// This is done by [`Configuration::from_json`],
+    /// `Configuration::from_yaml` and
+    /// [`Configuration::build`], and only needed when the configuration is
+    /// deserialized by other means.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if SUPPORTED_FILE_FORMATS.contains(&self.file_format.as_str()) {
+            Ok(())
+        } else {
+            Err(ConfigError::UnsupportedFileFormat(self.file_format.clone()))
+        }
+    }
+
+    /// Build the providers and propagator described by the configuration,
+    /// running their background tasks on `runtime`.
+    pub fn build<R: RuntimeChannel>(&self, runtime: R) -> Result<SdkProviders, ConfigError> {
+        self.validate()?;
+        if self.disabled {
+            return Ok(SdkProviders::default());
+        }
+
+        let resource = resource::resource(self.resource.as_ref());
+        let attribute_limits = self.attribute_limits.as_ref();
+
+        Ok(SdkProviders {
+            tracer_provider: self
+                .tracer_provider
+                .as_ref()
+                .map(|config| {
+                    trace::tracer_provider(
+                        config,
+                        attribute_limits,
+                        resource.clone(),
+                        runtime.clone(),
+                    )
+                })
+                .transpose()?,
+            meter_provider: self
+                .meter_provider
+                .as_ref()
+                .map(|config| metrics::meter_provider(config, resource.clone(), runtime.clone()))
+                .transpose()?,
+            logger_provider: self
+                .logger_provider
+                .as_ref()
+                .map(|config| {
+                    logs::logger_provider(
+                        config,
+                        attribute_limits,
+                        resource.clone(),
+                        runtime.clone(),
+                    )
+                })
+                .transpose()?,
+            propagator: self
+                .propagator
+                .as_ref()
+                .map(propagator::propagator)
+                .transpose()?,
+        })
+    }
+}
+
+/// Replace the references to environment variables in `input`.
+///
+/// `${NAME}` and `${env:NAME}` are replaced with the value of the variable
+/// `NAME`, or the empty string if it is not set, `${NAME:-default}` with
+/// `default` if it is not set, and `$$` with `$`. Any other `$` is kept as is.
+pub fn substitute_env_vars(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(index) = rest.find('$') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            output.push('$');
+            rest = after;
+            continue;
+        }
+
+        let reference = rest.strip_prefix("${").and_then(|after| {
+            after
+                .find('}')
+                .map(|end| (&after[..end], &after[end + 1..]))
+        });
+        match reference {
+            Some((reference, after)) if is_valid_reference(reference) => {
+                let reference = reference.strip_prefix("env:").unwrap_or(reference);
+                let (name, default) = match reference.split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (reference, None),
+                };
+                match std::env::var(name) {
+                    Ok(value) => output.push_str(&value),
+                    Err(_) => output.push_str(default.unwrap_or_default()),
+                }
+                rest = after;
+            }
+            _ => {
+                output.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn is_valid_reference(reference: &str) -> bool {
+    let reference = reference.strip_prefix("env:").unwrap_or(reference);
+    let name = reference
+        .split_once(":-")
+        .map_or(reference, |(name, _)| name);
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{SamplerConfig, SpanExporterConfig, SpanProcessorConfig};
+
+    const CONFIG: &str = r#"{
+        "file_format": "0.2",
+        "resource": {
+            "attributes": {
+                "service.name": "${OTEL_CONFIG_TEST_SERVICE:-unknown}",
+                "replicas": 3,
+                "tags": ["a", "b"]
+            },
+            "schema_url": "https://opentelemetry.io/schemas/1.21.0"
+        },
+        "attribute_limits": { "attribute_count_limit": 64 },
+        "propagator": { "composite": ["tracecontext", "baggage"] },
+        "tracer_provider": {
+            "processors": [
+                {
+                    "batch": {
+                        "schedule_delay": 1000,
+                        "exporter": {
+                            "otlp": {
+                                "protocol": "http/protobuf",
+                                "endpoint": "http://localhost:4318",
+                                "headers": { "api-key": "${env:OTEL_CONFIG_TEST_KEY}" },
+                                "timeout": 5000
+                            }
+                        }
+                    }
+                },
+                { "simple": { "exporter": { "otlp": { "compression": "none" } } } }
+            ],
+            "limits": { "event_count_limit": 16 },
+            "sampler": {
+                "parent_based": {
+                    "root": { "trace_id_ratio_based": { "ratio": 0.5 } },
+                    "remote_parent_not_sampled": { "always_on": {} }
+                }
+            }
+        },
+        "meter_provider": {
+            "readers": [
+                {
+                    "periodic": {
+                        "interval": 60000,
+                        "timeout": 100,
+                        "exporter": {
+                            "otlp": { "temporality_preference": "delta", "timeout": 100 }
+                        }
+                    }
+                }
+            ],
+            "views": [
+                {
+                    "selector": { "instrument_name": "http.*", "instrument_type": "histogram" },
+                    "stream": {
+                        "aggregation": {
+                            "explicit_bucket_histogram": { "boundaries": [1.0, 10.0, 100.0] }
+                        },
+                        "attribute_keys": ["http.method"]
+                    }
+                }
+            ]
+        },
+        "logger_provider": {
+            "processors": [
+                { "batch": { "exporter": { "otlp": { "protocol": "grpc" } } } }
+            ],
+            "limits": { "attribute_value_length_limit": 256 }
+        }
+    }"#;
+
+    #[test]
+    fn substitute() {
+        temp_env::with_vars(
+            [
+                ("OTEL_CONFIG_TEST_SET", Some("value")),
+                ("OTEL_CONFIG_TEST_UNSET", None),
+            ],
+            || {
+                assert_eq!(substitute_env_vars("${OTEL_CONFIG_TEST_SET}"), "value");
+                assert_eq!(
+                    substitute_env_vars("a${env:OTEL_CONFIG_TEST_SET}b"),
+                    "avalueb"
+                );
+                assert_eq!(substitute_env_vars("${OTEL_CONFIG_TEST_UNSET}"), "");
+                assert_eq!(
+                    substitute_env_vars("${OTEL_CONFIG_TEST_UNSET:-default}"),
+                    "default"
+                );
+                assert_eq!(
+                    substitute_env_vars("${OTEL_CONFIG_TEST_SET:-default}"),
+                    "value"
+                );
+                assert_eq!(
+                    substitute_env_vars("$${OTEL_CONFIG_TEST_SET}"),
+                    "${OTEL_CONFIG_TEST_SET}"
+                );
+                assert_eq!(substitute_env_vars("$ ${} ${1A} $"), "$ ${} ${1A} $");
+            },
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let config = temp_env::with_vars(
+            [
+                ("OTEL_CONFIG_TEST_SERVICE", Some("checkout")),
+                ("OTEL_CONFIG_TEST_KEY", Some("secret")),
+            ],
+            || Configuration::from_json(CONFIG).unwrap(),
+        );
+
+        assert_eq!(
+            config.resource.as_ref().unwrap().attributes["service.name"],
+            model::AttributeValue::String("checkout".to_string())
+        );
+        let tracer_provider = config.tracer_provider.as_ref().unwrap();
+        assert_eq!(tracer_provider.processors.len(), 2);
+        match &tracer_provider.processors[0] {
+            SpanProcessorConfig::Batch(batch) => {
+                assert_eq!(batch.schedule_delay, Some(1000));
+                match &batch.exporter {
+                    SpanExporterConfig::Otlp(otlp) => {
+                        assert_eq!(otlp.protocol, model::OtlpProtocol::HttpProtobuf);
+                        assert_eq!(otlp.headers["api-key"], "secret");
+                    }
+                    other => panic!("unexpected exporter {other:?}"),
+                }
+            }
+            other => panic!("unexpected processor {other:?}"),
+        }
+        assert!(matches!(
+            tracer_provider.sampler,
+            Some(SamplerConfig::ParentBased(_))
+        ));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            Configuration::from_json(r#"{ "file_format": "1.0" }"#),
+            Err(ConfigError::UnsupportedFileFormat(format)) if format == "1.0"
+        ));
+        assert!(matches!(
+            Configuration::from_json(r#"{ "file_format": "0.2", "unknown": {} }"#),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_yaml() {
+        let yaml = r#"
+file_format: "0.2"
+meter_provider:
+  readers:
+    - periodic:
+        exporter:
+          otlp:
+            protocol: grpc
+            temporality_preference: lowmemory
+"#;
+        let config = Configuration::from_yaml(yaml).unwrap();
+        let model::MetricReaderConfig::Periodic(periodic) =
+            &config.meter_provider.as_ref().unwrap().readers[0];
+        match &periodic.exporter {
+            model::MetricExporterConfig::Otlp(otlp) => assert_eq!(
+                otlp.temporality_preference,
+                Some(model::TemporalityPreference::LowMemory)
+            ),
+            other => panic!("unexpected exporter {other:?}"),
+        }
+    }
+
+    #[test]
+    fn build() {
+        // Entered rather than blocked on, as the blocking HTTP clients cannot
+        // be created in an asynchronous context.
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let config = Configuration::from_json(CONFIG).unwrap();
+        let providers = config.build(opentelemetry_sdk::runtime::Tokio).unwrap();
+
+        assert!(providers.tracer_provider.is_some());
+        assert!(providers.meter_provider.is_some());
+        assert!(providers.logger_provider.is_some());
+        assert!(providers.propagator.is_some());
+    }
+
+    #[tokio::test]
+    async fn build_errors() {
+        let unsupported_propagator =
+            r#"{ "file_format": "0.2", "propagator": { "composite": ["unknown"] } }"#;
+        assert!(matches!(
+            Configuration::from_json(unsupported_propagator)
+                .unwrap()
+                .build(opentelemetry_sdk::runtime::Tokio),
+            Err(ConfigError::Unsupported { kind: "propagator", name }) if name == "unknown"
+        ));
+
+        let invalid_ratio = r#"{
+            "file_format": "0.2",
+            "tracer_provider": { "sampler": { "trace_id_ratio_based": { "ratio": 2.0 } } }
+        }"#;
+        assert!(matches!(
+            Configuration::from_json(invalid_ratio)
+                .unwrap()
+                .build(opentelemetry_sdk::runtime::Tokio),
+            Err(ConfigError::InvalidValue { field: "ratio", .. })
+        ));
+    }
+
+    #[test]
+    fn disabled() {
+        let config = Configuration::from_json(
+            r#"{ "file_format": "0.2", "disabled": true, "tracer_provider": {} }"#,
+        )
+        .unwrap();
+        let providers = config.build(opentelemetry_sdk::runtime::Tokio).unwrap();
+
+        assert!(providers.tracer_provider.is_none());
+        assert!(providers.meter_provider.is_none());
+        assert!(providers.logger_provider.is_none());
+        assert!(providers.propagator.is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! Construction of the logger provider.
+use opentelemetry_sdk::logs::{Builder, LogLimits, LoggerProvider};
+use opentelemetry_sdk::runtime::RuntimeChannel;
+use opentelemetry_sdk::Resource;
+
+#[cfg(any(feature = "otlp", feature = "stdout"))]
+use crate::model::LogRecordExporterConfig;
+use crate::model::{AttributeLimits, LogRecordProcessorConfig, LoggerProviderConfig};
+use crate::trace::unsupported_exporter;
+use crate::ConfigError;
+
+pub(crate) fn logger_provider<R: RuntimeChannel>(
+    config: &LoggerProviderConfig,
+    attribute_limits: Option<&AttributeLimits>,
+    resource: Resource,
+    runtime: R,
+) -> Result<LoggerProvider, ConfigError> {
+    let mut builder = LoggerProvider::builder()
+        .with_config(opentelemetry_sdk::logs::config().with_resource(resource))
+        .with_log_limits(log_limits(attribute_limits, config.limits.as_ref()));
+    for processor in &config.processors {
+        builder = with_log_processor(builder, processor, runtime.clone())?;
+    }
+
+    Ok(builder.build())
+}
+
+fn log_limits(
+    attribute_limits: Option<&AttributeLimits>,
+    logger_limits: Option<&AttributeLimits>,
+) -> LogLimits {
+    let mut limits = LogLimits::default();
+    for config in attribute_limits.into_iter().chain(logger_limits) {
+        if let Some(count) = config.attribute_count_limit {
+            limits.max_attributes_per_log_record = count;
+        }
+        if let Some(length) = config.attribute_value_length_limit {
+            limits.max_attribute_value_length = Some(length);
+        }
+    }
+    limits
+}
+
+#[cfg_attr(
+    not(any(feature = "otlp", feature = "stdout")),
+    allow(unused_variables)
+)]
+fn with_log_processor<R: RuntimeChannel>(
+    builder: Builder,
+    config: &LogRecordProcessorConfig,
+    runtime: R,
+) -> Result<Builder, ConfigError> {
+    match config {
+        LogRecordProcessorConfig::Batch(batch) => match &batch.exporter {
+            #[cfg(feature = "otlp")]
+            LogRecordExporterConfig::Otlp(otlp) => {
+                let exporter = crate::exporter::otlp_log_exporter(otlp)?;
+                Ok(builder.with_log_processor(batch_processor(batch, exporter, runtime)))
+            }
+            #[cfg(feature = "stdout")]
+            LogRecordExporterConfig::Console(_) => {
+                let exporter = opentelemetry_stdout::LogExporter::default();
+                Ok(builder.with_log_processor(batch_processor(batch, exporter, runtime)))
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported_exporter(other)),
+        },
+        LogRecordProcessorConfig::Simple(simple) => match &simple.exporter {
+            #[cfg(feature = "otlp")]
+            LogRecordExporterConfig::Otlp(otlp) => {
+                let exporter = crate::exporter::otlp_log_exporter(otlp)?;
+                Ok(builder.with_simple_exporter(exporter))
+            }
+            #[cfg(feature = "stdout")]
+            LogRecordExporterConfig::Console(_) => {
+                Ok(builder.with_simple_exporter(opentelemetry_stdout::LogExporter::default()))
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported_exporter(other)),
+        },
+    }
+}
+
+#[cfg(any(feature = "otlp", feature = "stdout"))]
+fn batch_processor<E: opentelemetry_sdk::export::logs::LogExporter + 'static, R: RuntimeChannel>(
+    config: &crate::model::BatchProcessorConfig<LogRecordExporterConfig>,
+    exporter: E,
+    runtime: R,
+) -> opentelemetry_sdk::logs::BatchLogProcessor<R> {
+    use opentelemetry_sdk::logs::{BatchConfigBuilder, BatchLogProcessor};
+    use std::time::Duration;
+
+    let mut batch_config = BatchConfigBuilder::default();
+    if let Some(delay) = config.schedule_delay {
+        batch_config = batch_config.with_scheduled_delay(Duration::from_millis(delay));
+    }
+    if let Some(timeout) = config.export_timeout {
+        batch_config = batch_config.with_max_export_timeout(Duration::from_millis(timeout));
+    }
+    if let Some(size) = config.max_queue_size {
+        batch_config = batch_config.with_max_queue_size(size);
+    }
+    if let Some(size) = config.max_export_batch_size {
+        batch_config = batch_config.with_max_export_batch_size(size);
+    }
+
+    BatchLogProcessor::builder(exporter, runtime)
+        .with_batch_config(batch_config.build())
+        .build()
+}
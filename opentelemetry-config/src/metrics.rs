@@ -0,0 +1,176 @@
+//! Construction of the meter provider.
+use opentelemetry::metrics::Unit;
+use opentelemetry::InstrumentationLibrary;
+use opentelemetry_sdk::metrics::reader::{AggregationSelector, DefaultAggregationSelector};
+use opentelemetry_sdk::metrics::{
+    new_view, Aggregation, Instrument, InstrumentKind, MeterProviderBuilder, SdkMeterProvider,
+    Stream,
+};
+use opentelemetry_sdk::runtime::RuntimeChannel;
+use opentelemetry_sdk::Resource;
+
+use crate::model::{
+    AggregationConfig, InstrumentType, MeterProviderConfig, MetricExporterConfig,
+    MetricReaderConfig, ViewConfig,
+};
+use crate::ConfigError;
+
+pub(crate) fn meter_provider<R: RuntimeChannel>(
+    config: &MeterProviderConfig,
+    resource: Resource,
+    runtime: R,
+) -> Result<SdkMeterProvider, ConfigError> {
+    let mut builder = SdkMeterProvider::builder().with_resource(resource);
+    for reader in &config.readers {
+        builder = with_reader(builder, reader, runtime.clone())?;
+    }
+    for view in &config.views {
+        builder = builder.with_view(self::view(view)?);
+    }
+
+    Ok(builder.build())
+}
+
+#[cfg_attr(
+    not(any(feature = "otlp", feature = "stdout")),
+    allow(unused_variables)
+)]
+fn with_reader<R: RuntimeChannel>(
+    builder: MeterProviderBuilder,
+    config: &MetricReaderConfig,
+    runtime: R,
+) -> Result<MeterProviderBuilder, ConfigError> {
+    match config {
+        MetricReaderConfig::Periodic(periodic) => match &periodic.exporter {
+            #[cfg(feature = "otlp")]
+            MetricExporterConfig::Otlp(otlp) => {
+                let exporter = crate::exporter::otlp_metrics_exporter(otlp)?;
+                Ok(builder.with_reader(periodic_reader(periodic, exporter, runtime)))
+            }
+            #[cfg(feature = "stdout")]
+            MetricExporterConfig::Console(_) => {
+                let exporter = opentelemetry_stdout::MetricsExporter::default();
+                Ok(builder.with_reader(periodic_reader(periodic, exporter, runtime)))
+            }
+            #[allow(unreachable_patterns)]
+            other => {
+                let name = match other {
+                    MetricExporterConfig::Otlp(_) => "otlp",
+                    MetricExporterConfig::Console(_) => "console",
+                };
+                Err(ConfigError::Unsupported {
+                    kind: "exporter",
+                    name: name.to_string(),
+                })
+            }
+        },
+    }
+}
+
+#[cfg(any(feature = "otlp", feature = "stdout"))]
+fn periodic_reader<E, R>(
+    config: &crate::model::PeriodicReaderConfig,
+    exporter: E,
+    runtime: R,
+) -> opentelemetry_sdk::metrics::PeriodicReader
+where
+    E: opentelemetry_sdk::metrics::exporter::PushMetricsExporter,
+    R: RuntimeChannel,
+{
+    use std::time::Duration;
+
+    let mut builder = opentelemetry_sdk::metrics::PeriodicReader::builder(exporter, runtime);
+    if let Some(interval) = config.interval {
+        builder = builder.with_interval(Duration::from_millis(interval));
+    }
+    if let Some(timeout) = config.timeout {
+        builder = builder.with_timeout(Duration::from_millis(timeout));
+    }
+    builder.build()
+}
+
+fn view(config: &ViewConfig) -> Result<Box<dyn opentelemetry_sdk::metrics::View>, ConfigError> {
+    let selector = &config.selector;
+    let mut criteria = Instrument::new();
+    if let Some(name) = &selector.instrument_name {
+        criteria = criteria.name(name.clone());
+    }
+    if let Some(unit) = &selector.unit {
+        criteria = criteria.unit(Unit::new(unit.clone()));
+    }
+    if let Some(meter_name) = &selector.meter_name {
+        let mut scope = InstrumentationLibrary::builder(meter_name.clone());
+        if let Some(version) = &selector.meter_version {
+            scope = scope.with_version(version.clone());
+        }
+        if let Some(schema_url) = &selector.meter_schema_url {
+            scope = scope.with_schema_url(schema_url.clone());
+        }
+        criteria = criteria.scope(scope.build());
+    } else if selector.meter_version.is_some() || selector.meter_schema_url.is_some() {
+        return Err(ConfigError::InvalidValue {
+            field: "selector",
+            message: "meter_version and meter_schema_url require meter_name".to_string(),
+        });
+    }
+    criteria.kind = selector.instrument_type.map(|kind| match kind {
+        InstrumentType::Counter => InstrumentKind::Counter,
+        InstrumentType::Histogram => InstrumentKind::Histogram,
+        InstrumentType::ObservableCounter => InstrumentKind::ObservableCounter,
+        InstrumentType::ObservableGauge => InstrumentKind::ObservableGauge,
+        InstrumentType::ObservableUpDownCounter => InstrumentKind::ObservableUpDownCounter,
+        InstrumentType::UpDownCounter => InstrumentKind::UpDownCounter,
+    });
+
+    let mut mask = Stream::new();
+    if let Some(name) = &config.stream.name {
+        mask = mask.name(name.clone());
+    }
+    if let Some(description) = &config.stream.description {
+        mask = mask.description(description.clone());
+    }
+    if let Some(aggregation) = &config.stream.aggregation {
+        mask = mask.aggregation(self::aggregation(aggregation));
+    }
+    if let Some(keys) = &config.stream.attribute_keys {
+        mask = mask.allowed_attribute_keys(keys.iter().cloned().map(Into::into));
+    }
+
+    new_view(criteria, mask).map_err(|err| ConfigError::InvalidValue {
+        field: "views",
+        message: err.to_string(),
+    })
+}
+
+fn aggregation(config: &AggregationConfig) -> Aggregation {
+    match config {
+        AggregationConfig::Default(_) => Aggregation::Default,
+        AggregationConfig::Drop(_) => Aggregation::Drop,
+        AggregationConfig::Sum(_) => Aggregation::Sum,
+        AggregationConfig::LastValue(_) => Aggregation::LastValue,
+        AggregationConfig::ExplicitBucketHistogram(histogram) => {
+            Aggregation::ExplicitBucketHistogram {
+                boundaries: histogram
+                    .boundaries
+                    .clone()
+                    .unwrap_or_else(default_boundaries),
+                record_min_max: histogram.record_min_max.unwrap_or(true),
+            }
+        }
+        AggregationConfig::Base2ExponentialBucketHistogram(histogram) => {
+            Aggregation::Base2ExponentialHistogram {
+                max_size: histogram.max_size.unwrap_or(160),
+                max_scale: histogram.max_scale.unwrap_or(20),
+                record_min_max: histogram.record_min_max.unwrap_or(true),
+            }
+        }
+    }
+}
+
+/// The bucket boundaries of the default histogram aggregation of the SDK.
+fn default_boundaries() -> Vec<f64> {
+    match DefaultAggregationSelector::new().aggregation(InstrumentKind::Histogram) {
+        Aggregation::ExplicitBucketHistogram { boundaries, .. } => boundaries,
+        _ => Vec::new(),
+    }
+}
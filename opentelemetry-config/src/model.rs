@@ -0,0 +1,445 @@
+//! The configuration model, following the [OpenTelemetry configuration schema].
+//!
+//! Durations are expressed in milliseconds.
+//!
+//! [OpenTelemetry configuration schema]: https://github.com/open-telemetry/opentelemetry-configuration
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// The root of the configuration.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Configuration {
+    /// The version of the configuration schema, e.g. `"0.2"`.
+    pub file_format: String,
+    /// Whether the SDK is disabled, in which case no provider is built.
+    #[serde(default)]
+    pub disabled: bool,
+    /// The resource shared by all providers.
+    pub resource: Option<ResourceConfig>,
+    /// The attribute limits applied by all providers, unless overridden by
+    /// the limits of a provider.
+    pub attribute_limits: Option<AttributeLimits>,
+    /// The propagator to install.
+    pub propagator: Option<PropagatorConfig>,
+    /// The tracer provider. No tracer provider is built if not set.
+    pub tracer_provider: Option<TracerProviderConfig>,
+    /// The meter provider. No meter provider is built if not set.
+    pub meter_provider: Option<MeterProviderConfig>,
+    /// The logger provider. No logger provider is built if not set.
+    pub logger_provider: Option<LoggerProviderConfig>,
+}
+
+/// The configuration of the resource.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceConfig {
+    /// The attributes of the resource.
+    #[serde(default)]
+    pub attributes: HashMap<String, AttributeValue>,
+    /// The schema URL of the resource.
+    pub schema_url: Option<String>,
+}
+
+/// The value of a resource attribute.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    /// A boolean value.
+    Bool(bool),
+    /// An integer value.
+    I64(i64),
+    /// A floating point value.
+    F64(f64),
+    /// A string value.
+    String(String),
+    /// An array of booleans.
+    BoolArray(Vec<bool>),
+    /// An array of integers.
+    I64Array(Vec<i64>),
+    /// An array of floating point values.
+    F64Array(Vec<f64>),
+    /// An array of strings.
+    StringArray(Vec<String>),
+}
+
+/// The limits of the attributes of spans and log records.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AttributeLimits {
+    /// The maximum length of string attribute values.
+    pub attribute_value_length_limit: Option<u32>,
+    /// The maximum number of attributes.
+    pub attribute_count_limit: Option<u32>,
+}
+
+/// The configuration of the propagator.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PropagatorConfig {
+    /// The names of the propagators combined into a composite propagator, one
+    /// of `tracecontext`, `baggage`, `jaeger` (with the `jaeger` feature),
+    /// `b3` and `b3multi` (with the `zipkin` feature).
+    #[serde(default)]
+    pub composite: Vec<String>,
+}
+
+/// The configuration of the tracer provider.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TracerProviderConfig {
+    /// The span processors, in the order they are invoked.
+    #[serde(default)]
+    pub processors: Vec<SpanProcessorConfig>,
+    /// The span limits.
+    pub limits: Option<SpanLimitsConfig>,
+    /// The sampler, `parent_based` with an `always_on` root if not set.
+    pub sampler: Option<SamplerConfig>,
+}
+
+/// The configuration of a span processor.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SpanProcessorConfig {
+    /// A batch span processor.
+    Batch(BatchProcessorConfig<SpanExporterConfig>),
+    /// A simple span processor.
+    Simple(SimpleProcessorConfig<SpanExporterConfig>),
+}
+
+/// The configuration of a batch span or log record processor.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BatchProcessorConfig<E> {
+    /// The delay between two consecutive exports.
+    pub schedule_delay: Option<u64>,
+    /// The maximum duration of an export.
+    pub export_timeout: Option<u64>,
+    /// The maximum number of items buffered before they are dropped.
+    pub max_queue_size: Option<usize>,
+    /// The maximum number of items exported at once.
+    pub max_export_batch_size: Option<usize>,
+    /// The exporter.
+    pub exporter: E,
+}
+
+/// The configuration of a simple span or log record processor.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SimpleProcessorConfig<E> {
+    /// The exporter.
+    pub exporter: E,
+}
+
+/// The configuration of a span exporter.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SpanExporterConfig {
+    /// An OTLP exporter, with the `otlp` feature.
+    Otlp(OtlpExporterConfig),
+    /// An exporter writing to stdout, with the `stdout` feature.
+    Console(ConsoleExporterConfig),
+}
+
+/// The configuration of a log record exporter.
+pub type LogRecordExporterConfig = SpanExporterConfig;
+
+/// The configuration of an OTLP exporter.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpExporterConfig {
+    /// The transport protocol.
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    /// The endpoint, the default one of the protocol if not set.
+    pub endpoint: Option<String>,
+    /// The headers added to the export requests.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// The compression of the export requests, `gzip` or `none`.
+    pub compression: Option<String>,
+    /// The maximum duration of an export request.
+    pub timeout: Option<u64>,
+}
+
+/// The transport protocol of an OTLP exporter.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+pub enum OtlpProtocol {
+    /// gRPC.
+    #[default]
+    #[serde(rename = "grpc")]
+    Grpc,
+    /// HTTP with protobuf encoded bodies.
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+}
+
+/// The configuration of a console exporter, which has no options.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ConsoleExporterConfig {}
+
+/// The configuration of the span limits.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SpanLimitsConfig {
+    /// The maximum length of string attribute values.
+    pub attribute_value_length_limit: Option<u32>,
+    /// The maximum number of attributes per span.
+    pub attribute_count_limit: Option<u32>,
+    /// The maximum number of events per span.
+    pub event_count_limit: Option<u32>,
+    /// The maximum number of links per span.
+    pub link_count_limit: Option<u32>,
+    /// The maximum number of attributes per event.
+    pub event_attribute_count_limit: Option<u32>,
+    /// The maximum number of attributes per link.
+    pub link_attribute_count_limit: Option<u32>,
+}
+
+/// The configuration of a sampler.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum SamplerConfig {
+    /// Sample all spans.
+    AlwaysOn(EmptyConfig),
+    /// Sample no span.
+    AlwaysOff(EmptyConfig),
+    /// Sample a fraction of the traces.
+    TraceIdRatioBased(TraceIdRatioBasedConfig),
+    /// Follow the sampling decision of the parent span.
+    ParentBased(Box<ParentBasedConfig>),
+}
+
+/// An empty configuration object.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EmptyConfig {}
+
+/// The configuration of a trace id ratio based sampler.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TraceIdRatioBasedConfig {
+    /// The fraction of traces sampled, `1.0` if not set.
+    pub ratio: Option<f64>,
+}
+
+/// The configuration of a parent based sampler.
+///
+/// The samplers default to `always_on` for root spans and sampled parents,
+/// and to `always_off` for parents that are not sampled.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParentBasedConfig {
+    /// The sampler of spans without parent.
+    pub root: Option<SamplerConfig>,
+    /// The sampler of spans with a sampled remote parent.
+    pub remote_parent_sampled: Option<SamplerConfig>,
+    /// The sampler of spans with a remote parent that is not sampled.
+    pub remote_parent_not_sampled: Option<SamplerConfig>,
+    /// The sampler of spans with a sampled local parent.
+    pub local_parent_sampled: Option<SamplerConfig>,
+    /// The sampler of spans with a local parent that is not sampled.
+    pub local_parent_not_sampled: Option<SamplerConfig>,
+}
+
+/// The configuration of the meter provider.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MeterProviderConfig {
+    /// The metric readers.
+    #[serde(default)]
+    pub readers: Vec<MetricReaderConfig>,
+    /// The views.
+    #[serde(default)]
+    pub views: Vec<ViewConfig>,
+}
+
+/// The configuration of a metric reader.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum MetricReaderConfig {
+    /// A reader exporting metrics periodically.
+    Periodic(PeriodicReaderConfig),
+}
+
+/// The configuration of a periodic metric reader.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PeriodicReaderConfig {
+    /// The delay between two consecutive exports.
+    pub interval: Option<u64>,
+    /// The maximum duration of an export.
+    pub timeout: Option<u64>,
+    /// The exporter.
+    pub exporter: MetricExporterConfig,
+}
+
+/// The configuration of a metric exporter.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum MetricExporterConfig {
+    /// An OTLP exporter, with the `otlp` feature.
+    Otlp(OtlpMetricExporterConfig),
+    /// An exporter writing to stdout, with the `stdout` feature.
+    Console(ConsoleExporterConfig),
+}
+
+/// The configuration of an OTLP metric exporter.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpMetricExporterConfig {
+    /// The transport protocol.
+    #[serde(default)]
+    pub protocol: OtlpProtocol,
+    /// The endpoint, the default one of the protocol if not set.
+    pub endpoint: Option<String>,
+    /// The headers added to the export requests.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// The compression of the export requests, `gzip` or `none`.
+    pub compression: Option<String>,
+    /// The maximum duration of an export request.
+    pub timeout: Option<u64>,
+    /// The temporality of the exported metrics.
+    pub temporality_preference: Option<TemporalityPreference>,
+}
+
+/// The temporality preference of a metric exporter.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TemporalityPreference {
+    /// Cumulative temporality for all instruments.
+    #[default]
+    Cumulative,
+    /// Delta temporality for all instruments but up-down counters.
+    Delta,
+    /// Delta temporality for synchronous counters and histograms, and
+    /// cumulative temporality for the other instruments.
+    #[serde(rename = "lowmemory")]
+    LowMemory,
+}
+
+/// The configuration of a view.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ViewConfig {
+    /// The criteria of the instruments the view applies to.
+    pub selector: ViewSelector,
+    /// The stream the matched instruments produce.
+    #[serde(default)]
+    pub stream: ViewStream,
+}
+
+/// The criteria of the instruments a view applies to.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ViewSelector {
+    /// The name of the instrument, which may contain `*` and `?` wildcards.
+    pub instrument_name: Option<String>,
+    /// The kind of the instrument.
+    pub instrument_type: Option<InstrumentType>,
+    /// The unit of the instrument.
+    pub unit: Option<String>,
+    /// The name of the meter of the instrument.
+    pub meter_name: Option<String>,
+    /// The version of the meter of the instrument.
+    pub meter_version: Option<String>,
+    /// The schema URL of the meter of the instrument.
+    pub meter_schema_url: Option<String>,
+}
+
+/// The kind of an instrument.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstrumentType {
+    /// A counter.
+    Counter,
+    /// A histogram.
+    Histogram,
+    /// An observable counter.
+    ObservableCounter,
+    /// An observable gauge.
+    ObservableGauge,
+    /// An observable up-down counter.
+    ObservableUpDownCounter,
+    /// An up-down counter.
+    UpDownCounter,
+}
+
+/// The stream of the instruments a view applies to.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ViewStream {
+    /// The name of the stream, the name of the instrument if not set.
+    pub name: Option<String>,
+    /// The description of the stream, the description of the instrument if not
+    /// set.
+    pub description: Option<String>,
+    /// The aggregation of the stream.
+    pub aggregation: Option<AggregationConfig>,
+    /// The attribute keys kept in the stream, all keys if not set.
+    pub attribute_keys: Option<Vec<String>>,
+}
+
+/// The configuration of an aggregation.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum AggregationConfig {
+    /// The default aggregation of the instrument.
+    Default(EmptyConfig),
+    /// Drop all measurements.
+    Drop(EmptyConfig),
+    /// Sum the measurements.
+    Sum(EmptyConfig),
+    /// Keep the last measurement.
+    LastValue(EmptyConfig),
+    /// A histogram with explicit bucket boundaries.
+    ExplicitBucketHistogram(ExplicitBucketHistogramConfig),
+    /// A histogram with exponentially growing buckets.
+    Base2ExponentialBucketHistogram(Base2ExponentialBucketHistogramConfig),
+}
+
+/// The configuration of an explicit bucket histogram aggregation.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExplicitBucketHistogramConfig {
+    /// The bucket boundaries, the default ones of the SDK if not set.
+    pub boundaries: Option<Vec<f64>>,
+    /// Whether the min and max measurements are recorded, `true` if not set.
+    pub record_min_max: Option<bool>,
+}
+
+/// The configuration of a base2 exponential bucket histogram aggregation.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Base2ExponentialBucketHistogramConfig {
+    /// The maximum scale, `20` if not set.
+    pub max_scale: Option<i8>,
+    /// The maximum number of buckets, `160` if not set.
+    pub max_size: Option<u32>,
+    /// Whether the min and max measurements are recorded, `true` if not set.
+    pub record_min_max: Option<bool>,
+}
+
+/// The configuration of the logger provider.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LoggerProviderConfig {
+    /// The log record processors, in the order they are invoked.
+    #[serde(default)]
+    pub processors: Vec<LogRecordProcessorConfig>,
+    /// The log record limits.
+    pub limits: Option<AttributeLimits>,
+}
+
+/// The configuration of a log record processor.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum LogRecordProcessorConfig {
+    /// A batch log record processor.
+    Batch(BatchProcessorConfig<LogRecordExporterConfig>),
+    /// A simple log record processor.
+    Simple(SimpleProcessorConfig<LogRecordExporterConfig>),
+}
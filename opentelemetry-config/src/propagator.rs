@@ -0,0 +1,36 @@
+//! Construction of the propagator.
+use opentelemetry::propagation::TextMapCompositePropagator;
+use opentelemetry_sdk::propagation::PropagatorRegistry;
+
+use crate::model::PropagatorConfig;
+use crate::ConfigError;
+
+pub(crate) fn propagator(
+    config: &PropagatorConfig,
+) -> Result<TextMapCompositePropagator, ConfigError> {
+    let registry = registry();
+    let propagators = config
+        .composite
+        .iter()
+        .filter(|name| name.as_str() != "none")
+        .map(|name| {
+            registry.get(name).ok_or_else(|| ConfigError::Unsupported {
+                kind: "propagator",
+                name: name.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TextMapCompositePropagator::new(propagators))
+}
+
+/// The propagators of the SDK, along with the ones of the enabled features.
+fn registry() -> PropagatorRegistry {
+    #[allow(unused_mut)]
+    let mut registry = PropagatorRegistry::new();
+    #[cfg(feature = "jaeger")]
+    opentelemetry_jaeger_propagator::register_propagator(&mut registry);
+    #[cfg(feature = "zipkin")]
+    opentelemetry_zipkin::register_propagators(&mut registry);
+    registry
+}
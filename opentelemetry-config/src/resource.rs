@@ -0,0 +1,48 @@
+//! Construction of the resource.
+use std::time::Duration;
+
+use opentelemetry::{Array, KeyValue, Value};
+use opentelemetry_sdk::resource::{SdkProvidedResourceDetector, TelemetryResourceDetector};
+use opentelemetry_sdk::Resource;
+
+use crate::model::{AttributeValue, ResourceConfig};
+
+/// The resource detected by the SDK, merged with the configured one.
+pub(crate) fn resource(config: Option<&ResourceConfig>) -> Resource {
+    let detected = Resource::from_detectors(
+        Duration::ZERO,
+        vec![
+            Box::new(SdkProvidedResourceDetector),
+            Box::new(TelemetryResourceDetector),
+        ],
+    );
+
+    let Some(config) = config else {
+        return detected;
+    };
+    let attributes = config
+        .attributes
+        .iter()
+        .map(|(key, value)| KeyValue::new(key.clone(), self::value(value)));
+    let configured = match &config.schema_url {
+        Some(schema_url) => Resource::from_schema_url(attributes, schema_url.clone()),
+        None => Resource::new(attributes),
+    };
+
+    detected.merge(&configured)
+}
+
+fn value(value: &AttributeValue) -> Value {
+    match value {
+        AttributeValue::Bool(value) => Value::Bool(*value),
+        AttributeValue::I64(value) => Value::I64(*value),
+        AttributeValue::F64(value) => Value::F64(*value),
+        AttributeValue::String(value) => Value::String(value.clone().into()),
+        AttributeValue::BoolArray(values) => Value::Array(Array::Bool(values.clone())),
+        AttributeValue::I64Array(values) => Value::Array(Array::I64(values.clone())),
+        AttributeValue::F64Array(values) => Value::Array(Array::F64(values.clone())),
+        AttributeValue::StringArray(values) => Value::Array(Array::String(
+            values.iter().cloned().map(Into::into).collect(),
+        )),
+    }
+}
@@ -0,0 +1,272 @@
+//! Construction of the tracer provider.
+use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceContextExt, TraceId};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_sdk::runtime::RuntimeChannel;
+use opentelemetry_sdk::trace::{Builder, Sampler, ShouldSample, SpanLimits, TracerProvider};
+use opentelemetry_sdk::Resource;
+
+use crate::model::{
+    AttributeLimits, ParentBasedConfig, SamplerConfig, SpanExporterConfig, SpanProcessorConfig,
+    TracerProviderConfig,
+};
+use crate::ConfigError;
+
+pub(crate) fn tracer_provider<R: RuntimeChannel>(
+    config: &TracerProviderConfig,
+    attribute_limits: Option<&AttributeLimits>,
+    resource: Resource,
+    runtime: R,
+) -> Result<TracerProvider, ConfigError> {
+    let mut sdk_config = opentelemetry_sdk::trace::config()
+        .with_resource(resource)
+        .with_span_limits(span_limits(config, attribute_limits));
+    if let Some(sampler) = &config.sampler {
+        sdk_config = sdk_config.with_sampler(self::sampler(sampler)?);
+    }
+
+    let mut builder = TracerProvider::builder().with_config(sdk_config);
+    for processor in &config.processors {
+        builder = with_span_processor(builder, processor, runtime.clone())?;
+    }
+
+    Ok(builder.build())
+}
+
+fn span_limits(
+    config: &TracerProviderConfig,
+    attribute_limits: Option<&AttributeLimits>,
+) -> SpanLimits {
+    let mut limits = SpanLimits::default();
+    if let Some(attribute_limits) = attribute_limits {
+        if let Some(count) = attribute_limits.attribute_count_limit {
+            limits.max_attributes_per_span = count;
+        }
+        if let Some(length) = attribute_limits.attribute_value_length_limit {
+            limits = limits.with_max_attribute_value_length(length);
+        }
+    }
+
+    if let Some(config) = &config.limits {
+        if let Some(length) = config.attribute_value_length_limit {
+            limits = limits.with_max_attribute_value_length(length);
+        }
+        if let Some(count) = config.attribute_count_limit {
+            limits.max_attributes_per_span = count;
+        }
+        if let Some(count) = config.event_count_limit {
+            limits.max_events_per_span = count;
+        }
+        if let Some(count) = config.link_count_limit {
+            limits.max_links_per_span = count;
+        }
+        if let Some(count) = config.event_attribute_count_limit {
+            limits.max_attributes_per_event = count;
+        }
+        if let Some(count) = config.link_attribute_count_limit {
+            limits.max_attributes_per_link = count;
+        }
+    }
+
+    limits
+}
+
+#[cfg_attr(
+    not(any(feature = "otlp", feature = "stdout")),
+    allow(unused_variables)
+)]
+fn with_span_processor<R: RuntimeChannel>(
+    builder: Builder,
+    config: &SpanProcessorConfig,
+    runtime: R,
+) -> Result<Builder, ConfigError> {
+    match config {
+        SpanProcessorConfig::Batch(batch) => match &batch.exporter {
+            #[cfg(feature = "otlp")]
+            SpanExporterConfig::Otlp(otlp) => {
+                let exporter = crate::exporter::otlp_span_exporter(otlp)?;
+                Ok(builder.with_span_processor(batch_processor(batch, exporter, runtime)))
+            }
+            #[cfg(feature = "stdout")]
+            SpanExporterConfig::Console(_) => {
+                let exporter = opentelemetry_stdout::SpanExporter::default();
+                Ok(builder.with_span_processor(batch_processor(batch, exporter, runtime)))
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported_exporter(other)),
+        },
+        SpanProcessorConfig::Simple(simple) => match &simple.exporter {
+            #[cfg(feature = "otlp")]
+            SpanExporterConfig::Otlp(otlp) => {
+                let exporter = crate::exporter::otlp_span_exporter(otlp)?;
+                Ok(builder.with_simple_exporter(exporter))
+            }
+            #[cfg(feature = "stdout")]
+            SpanExporterConfig::Console(_) => {
+                Ok(builder.with_simple_exporter(opentelemetry_stdout::SpanExporter::default()))
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(unsupported_exporter(other)),
+        },
+    }
+}
+
+#[cfg(any(feature = "otlp", feature = "stdout"))]
+fn batch_processor<
+    E: opentelemetry_sdk::export::trace::SpanExporter + 'static,
+    R: RuntimeChannel,
+>(
+    config: &crate::model::BatchProcessorConfig<SpanExporterConfig>,
+    exporter: E,
+    runtime: R,
+) -> opentelemetry_sdk::trace::BatchSpanProcessor<R> {
+    use opentelemetry_sdk::trace::{BatchConfigBuilder, BatchSpanProcessor};
+    use std::time::Duration;
+
+    let mut batch_config = BatchConfigBuilder::default();
+    if let Some(delay) = config.schedule_delay {
+        batch_config = batch_config.with_scheduled_delay(Duration::from_millis(delay));
+    }
+    if let Some(timeout) = config.export_timeout {
+        batch_config = batch_config.with_max_export_timeout(Duration::from_millis(timeout));
+    }
+    if let Some(size) = config.max_queue_size {
+        batch_config = batch_config.with_max_queue_size(size);
+    }
+    if let Some(size) = config.max_export_batch_size {
+        batch_config = batch_config.with_max_export_batch_size(size);
+    }
+
+    BatchSpanProcessor::builder(exporter, runtime)
+        .with_batch_config(batch_config.build())
+        .build()
+}
+
+pub(crate) fn unsupported_exporter(config: &SpanExporterConfig) -> ConfigError {
+    let name = match config {
+        SpanExporterConfig::Otlp(_) => "otlp",
+        SpanExporterConfig::Console(_) => "console",
+    };
+    ConfigError::Unsupported {
+        kind: "exporter",
+        name: name.to_string(),
+    }
+}
+
+fn sampler(config: &SamplerConfig) -> Result<ConfiguredSampler, ConfigError> {
+    Ok(ConfiguredSampler::Sdk(match config {
+        SamplerConfig::AlwaysOn(_) => Sampler::AlwaysOn,
+        SamplerConfig::AlwaysOff(_) => Sampler::AlwaysOff,
+        SamplerConfig::TraceIdRatioBased(ratio_based) => {
+            let ratio = ratio_based.ratio.unwrap_or(1.0);
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(ConfigError::InvalidValue {
+                    field: "ratio",
+                    message: format!("{ratio} is not between 0 and 1"),
+                });
+            }
+            Sampler::TraceIdRatioBased(ratio)
+        }
+        SamplerConfig::ParentBased(parent_based) => return parent_based_sampler(parent_based),
+    }))
+}
+
+fn parent_based_sampler(config: &ParentBasedConfig) -> Result<ConfiguredSampler, ConfigError> {
+    let sampler_or = |config: &Option<SamplerConfig>, default: Sampler| match config {
+        Some(config) => sampler(config),
+        None => Ok(ConfiguredSampler::Sdk(default)),
+    };
+    let root = sampler_or(&config.root, Sampler::AlwaysOn)?;
+
+    if config.remote_parent_sampled.is_none()
+        && config.remote_parent_not_sampled.is_none()
+        && config.local_parent_sampled.is_none()
+        && config.local_parent_not_sampled.is_none()
+    {
+        return Ok(ConfiguredSampler::Sdk(Sampler::ParentBased(Box::new(root))));
+    }
+
+    Ok(ConfiguredSampler::ParentBased(Box::new(
+        ParentBasedSampler {
+            root,
+            remote_parent_sampled: sampler_or(&config.remote_parent_sampled, Sampler::AlwaysOn)?,
+            remote_parent_not_sampled: sampler_or(
+                &config.remote_parent_not_sampled,
+                Sampler::AlwaysOff,
+            )?,
+            local_parent_sampled: sampler_or(&config.local_parent_sampled, Sampler::AlwaysOn)?,
+            local_parent_not_sampled: sampler_or(
+                &config.local_parent_not_sampled,
+                Sampler::AlwaysOff,
+            )?,
+        },
+    )))
+}
+
+/// A sampler built from the configuration.
+///
+/// [`Sampler::ParentBased`] only supports configuring the sampler of root
+/// spans, the other cases of a `parent_based` sampler are handled by
+/// [`ParentBasedSampler`].
+#[derive(Clone, Debug)]
+enum ConfiguredSampler {
+    Sdk(Sampler),
+    ParentBased(Box<ParentBasedSampler>),
+}
+
+impl ShouldSample for ConfiguredSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        match self {
+            ConfiguredSampler::Sdk(sampler) => {
+                sampler.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+            }
+            ConfiguredSampler::ParentBased(sampler) => {
+                sampler.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+            }
+        }
+    }
+}
+
+/// A parent based sampler delegating to a different sampler depending on
+/// whether the parent is remote and sampled.
+#[derive(Clone, Debug)]
+struct ParentBasedSampler {
+    root: ConfiguredSampler,
+    remote_parent_sampled: ConfiguredSampler,
+    remote_parent_not_sampled: ConfiguredSampler,
+    local_parent_sampled: ConfiguredSampler,
+    local_parent_not_sampled: ConfiguredSampler,
+}
+impl ShouldSample for ParentBasedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let delegate = match parent_context.filter(|cx| cx.has_active_span()) {
+            None => &self.root,
+            Some(cx) => {
+                let span = cx.span();
+                let parent = span.span_context();
+                match (parent.is_remote(), parent.is_sampled()) {
+                    (true, true) => &self.remote_parent_sampled,
+                    (true, false) => &self.remote_parent_not_sampled,
+                    (false, true) => &self.local_parent_sampled,
+                    (false, false) => &self.local_parent_not_sampled,
+                }
+            }
+        };
+        delegate.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
+}
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 
 #[doc(no_inline)]
 pub use bytes::Bytes;
@@ -47,6 +47,61 @@ impl<'a> Extractor for HeaderExtractor<'a> {
 
 pub type HttpError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
+/// The error returned when the HTTP status of a response does not indicate
+/// success.
+///
+/// The status and headers of the response are kept so callers can decide
+/// whether, and when, to retry the request, along with its body when the
+/// response was received by an [`HttpClient`] of this crate. It can be
+/// recovered from an [`HttpError`] with `downcast_ref`.
+#[derive(Debug)]
+pub struct HttpStatusError {
+    status: http::StatusCode,
+    headers: http::HeaderMap,
+    body: Bytes,
+}
+
+impl HttpStatusError {
+    /// The status of the response.
+    pub fn status(&self) -> http::StatusCode {
+        self.status
+    }
+
+    /// The headers of the response.
+    pub fn headers(&self) -> &http::HeaderMap {
+        &self.headers
+    }
+
+    /// The body of the response, empty if it was not kept.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+}
+
+/// Turn a response into an [`HttpStatusError`] keeping its body if the HTTP
+/// status does not indicate success.
+#[cfg(any(feature = "reqwest", feature = "isahc", feature = "hyper"))]
+fn error_for_status(response: Response<Bytes>) -> Result<Response<Bytes>, HttpError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let (parts, body) = response.into_parts();
+        Err(Box::new(HttpStatusError {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        }))
+    }
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request failed with status {}", self.status)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
 /// A minimal interface necessary for sending requests over HTTP.
 /// Used primarily for exporting telemetry over HTTP. Also used for fetching
 /// sampling strategies for JaegerRemoteSampler
@@ -72,14 +127,14 @@ mod reqwest {
     impl HttpClient for reqwest::Client {
         async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Bytes>, HttpError> {
             let request = request.try_into()?;
-            let mut response = self.execute(request).await?.error_for_status()?;
+            let mut response = self.execute(request).await?;
             let headers = std::mem::take(response.headers_mut());
             let mut http_response = Response::builder()
                 .status(response.status())
                 .body(response.bytes().await?)?;
             *http_response.headers_mut() = headers;
 
-            Ok(http_response)
+            super::error_for_status(http_response)
         }
     }
 
@@ -87,22 +142,20 @@ mod reqwest {
     impl HttpClient for reqwest::blocking::Client {
         async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Bytes>, HttpError> {
             let request = request.try_into()?;
-            let mut response = self.execute(request)?.error_for_status()?;
+            let mut response = self.execute(request)?;
             let headers = std::mem::take(response.headers_mut());
             let mut http_response = Response::builder()
                 .status(response.status())
                 .body(response.bytes()?)?;
             *http_response.headers_mut() = headers;
 
-            Ok(http_response)
+            super::error_for_status(http_response)
         }
     }
 }
 
 #[cfg(feature = "isahc")]
 mod isahc {
-    use crate::ResponseExt;
-
     use super::{async_trait, Bytes, HttpClient, HttpError, Request, Response};
     use isahc::AsyncReadResponseExt;
     use std::convert::TryInto as _;
@@ -120,15 +173,13 @@ mod isahc {
                 .body(bytes.into())?;
             *http_response.headers_mut() = headers;
 
-            Ok(http_response.error_for_status()?)
+            super::error_for_status(http_response)
         }
     }
 }
 
 #[cfg(any(feature = "hyper", feature = "hyper_tls"))]
 pub mod hyper {
-    use crate::ResponseExt;
-
     use super::{async_trait, Bytes, HttpClient, HttpError, Request, Response};
     use http::HeaderValue;
     use hyper::client::connect::Connect;
@@ -186,7 +237,7 @@ pub mod hyper {
                 .body(hyper::body::to_bytes(response.into_body()).await?)?;
             *http_response.headers_mut() = headers;
 
-            Ok(http_response.error_for_status()?)
+            super::error_for_status(http_response)
         }
     }
 }
@@ -202,7 +253,11 @@ impl<T> ResponseExt for Response<T> {
         if self.status().is_success() {
             Ok(self)
         } else {
-            Err(format!("request failed with status {}", self.status()).into())
+            Err(Box::new(HttpStatusError {
+                status: self.status(),
+                headers: self.headers().clone(),
+                body: Bytes::new(),
+            }))
         }
     }
 }
@@ -223,6 +278,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn error_for_status_keeps_status_and_headers() {
+        let response = Response::builder()
+            .status(503)
+            .header("Retry-After", "5")
+            .body(())
+            .unwrap();
+
+        let err = response.error_for_status().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "request failed with status 503 Service Unavailable"
+        );
+        let err = err
+            .downcast_ref::<HttpStatusError>()
+            .expect("status error expected");
+        assert_eq!(err.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(err.headers().get("retry-after").unwrap(), "5");
+    }
+
     #[test]
     fn http_headers_keys() {
         let mut carrier = http::HeaderMap::new();
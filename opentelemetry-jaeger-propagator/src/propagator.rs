@@ -5,6 +5,8 @@ use opentelemetry::{
     trace::{SpanContext, SpanId, TraceContextExt, TraceError, TraceFlags, TraceId, TraceState},
     Context,
 };
+#[cfg(feature = "registry")]
+use opentelemetry_sdk::propagation::PropagatorRegistry;
 use std::borrow::Cow;
 use std::str::FromStr;
 
@@ -175,6 +177,23 @@ impl Propagator {
     }
 }
 
+/// Registers the Jaeger propagator in `registry` under the `jaeger` name, as
+/// expected in the `OTEL_PROPAGATORS` environment variable.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::propagation::PropagatorRegistry;
+///
+/// let mut registry = PropagatorRegistry::new();
+/// opentelemetry_jaeger_propagator::register_propagator(&mut registry);
+/// opentelemetry::global::set_text_map_propagator(registry.build_from_env());
+/// ```
+#[cfg(feature = "registry")]
+pub fn register_propagator(registry: &mut PropagatorRegistry) {
+    registry.register("jaeger", || Box::new(Propagator::new()));
+}
+
 impl TextMapPropagator for Propagator {
     fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
         let span = cx.span();
@@ -732,4 +751,14 @@ mod tests {
         assert_eq!(fields.len(), 1);
         assert_eq!(fields.first().unwrap(), &JAEGER_HEADER);
     }
+
+    #[cfg(feature = "registry")]
+    #[test]
+    fn register_jaeger_propagator() {
+        let mut registry = PropagatorRegistry::new();
+        register_propagator(&mut registry);
+
+        let propagator = registry.get("jaeger").expect("jaeger is registered");
+        assert_eq!(propagator.fields().collect::<Vec<_>>(), vec![JAEGER_HEADER]);
+    }
 }
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::logs::{LogError, LogResult};
 use opentelemetry_sdk::export::logs::{LogData, LogExporter};
 
@@ -20,29 +19,7 @@ impl LogExporter for OtlpHttpClient {
             })?;
 
         let (body, content_type) = self.build_logs_export_body(batch)?;
-        let mut request = http::Request::builder()
-            .method(Method::POST)
-            .uri(&self.collector_endpoint)
-            .header(CONTENT_TYPE, content_type)
-            .body(body)
-            .map_err(|e| crate::Error::RequestFailed(Box::new(e)))?;
-
-        for (k, v) in &self.headers {
-            request.headers_mut().insert(k.clone(), v.clone());
-        }
-
-        let request_uri = request.uri().to_string();
-        let response = client.send(request).await?;
-
-        if !response.status().is_success() {
-            let error = format!(
-                "OpenTelemetry logs export failed. Url: {}, Status Code: {}, Response: {:?}",
-                response.status().as_u16(),
-                request_uri,
-                response.body()
-            );
-            return Err(LogError::Other(error.into()));
-        }
+        self.send(client, body, content_type).await?;
 
         Ok(())
     }
@@ -1,11 +1,10 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::metrics::{MetricsError, Result};
 use opentelemetry_sdk::metrics::data::ResourceMetrics;
 
-use crate::{metric::MetricsClient, Error};
+use crate::metric::MetricsClient;
 
 use super::OtlpHttpClient;
 
@@ -22,21 +21,9 @@ impl MetricsClient for OtlpHttpClient {
             })?;
 
         let (body, content_type) = self.build_metrics_export_body(metrics)?;
-        let mut request = http::Request::builder()
-            .method(Method::POST)
-            .uri(&self.collector_endpoint)
-            .header(CONTENT_TYPE, content_type)
-            .body(body)
-            .map_err(|e| crate::Error::RequestFailed(Box::new(e)))?;
-
-        for (k, v) in &self.headers {
-            request.headers_mut().insert(k.clone(), v.clone());
-        }
-
-        client
-            .send(request)
+        self.send(client, body, content_type)
             .await
-            .map_err(|e| MetricsError::ExportErr(Box::new(Error::RequestFailed(e))))?;
+            .map_err(|e| MetricsError::ExportErr(Box::new(e)))?;
 
         Ok(())
     }
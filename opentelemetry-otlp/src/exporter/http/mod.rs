@@ -1,10 +1,12 @@
+use super::retry::{retry_with_backoff, RetryAction, RetryConfig};
 use super::{default_headers, default_protocol, parse_header_string};
 use crate::{
     ExportConfig, Protocol, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_HEADERS,
     OTEL_EXPORTER_OTLP_TIMEOUT,
 };
-use http::{HeaderName, HeaderValue, Uri};
-use opentelemetry_http::HttpClient;
+use futures_util::future::{self, Either};
+use http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri};
+use opentelemetry_http::{HttpClient, HttpError, HttpStatusError, ResponseExt};
 #[cfg(feature = "logs")]
 use opentelemetry_sdk::export::logs::LogData;
 #[cfg(feature = "trace")]
@@ -14,9 +16,10 @@ use opentelemetry_sdk::metrics::data::ResourceMetrics;
 use prost::Message;
 use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 #[cfg(feature = "metrics")]
 mod metrics;
@@ -98,6 +101,7 @@ impl Default for HttpConfig {
 pub struct HttpExporterBuilder {
     pub(crate) exporter_config: ExportConfig,
     pub(crate) http_config: HttpConfig,
+    pub(crate) retry_config: RetryConfig,
 }
 
 impl Default for HttpExporterBuilder {
@@ -111,6 +115,7 @@ impl Default for HttpExporterBuilder {
                 headers: Some(default_headers()),
                 ..HttpConfig::default()
             },
+            retry_config: RetryConfig::default(),
         }
     }
 }
@@ -141,6 +146,16 @@ impl HttpExporterBuilder {
         self
     }
 
+    /// Set the [RetryConfig] of failed exports.
+    ///
+    /// Exports failing because the collector could not be reached, or with the
+    /// `429`, `502`, `503` or `504` status codes, are retried. If this option
+    /// is not used, [RetryConfig::default] is used.
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
     fn build_client(
         &mut self,
         signal_endpoint_var: &str,
@@ -199,6 +214,7 @@ impl HttpExporterBuilder {
             headers,
             self.exporter_config.protocol,
             timeout,
+            self.retry_config.clone(),
         ))
     }
 
@@ -273,7 +289,8 @@ struct OtlpHttpClient {
     collector_endpoint: Uri,
     headers: HashMap<HeaderName, HeaderValue>,
     protocol: Protocol,
-    _timeout: Duration,
+    timeout: Duration,
+    retry_config: RetryConfig,
 }
 
 impl OtlpHttpClient {
@@ -284,13 +301,53 @@ impl OtlpHttpClient {
         headers: HashMap<HeaderName, HeaderValue>,
         protocol: Protocol,
         timeout: Duration,
+        retry_config: RetryConfig,
     ) -> Self {
         OtlpHttpClient {
             client: Mutex::new(Some(client)),
             collector_endpoint,
             headers,
             protocol,
-            _timeout: timeout,
+            timeout,
+            retry_config,
+        }
+    }
+
+    /// Sends the export request with `body` to the collector, retrying the
+    /// attempts failing with a retryable error.
+    ///
+    /// Each attempt is given up once the export timeout elapses.
+    fn send(
+        &self,
+        client: Arc<dyn HttpClient>,
+        body: Vec<u8>,
+        content_type: &'static str,
+    ) -> impl Future<Output = Result<(), crate::Error>> + Send + 'static {
+        let endpoint = self.collector_endpoint.clone();
+        #[allow(clippy::mutable_key_type)] // http headers are not mutated
+        let headers = self.headers.clone();
+        let retry_config = self.retry_config.clone();
+        let timeout = self.timeout;
+
+        async move {
+            retry_with_backoff(&retry_config, timeout, retry_action, |remaining| {
+                let client = Arc::clone(&client);
+                let request = build_request(&endpoint, &headers, body.clone(), content_type);
+                async move {
+                    let send = client.send(request?);
+                    match future::select(send, futures_timer::Delay::new(remaining)).await {
+                        Either::Left((response, _)) => {
+                            response?.error_for_status()?;
+                            Ok(())
+                        }
+                        Either::Right(_) => {
+                            Err(format!("request timed out after {:?}", remaining).into())
+                        }
+                    }
+                }
+            })
+            .await
+            .map_err(|err| export_error(&endpoint, err))
         }
     }
 
@@ -354,6 +411,89 @@ impl OtlpHttpClient {
     }
 }
 
+#[allow(clippy::mutable_key_type)] // http headers are not mutated
+fn build_request(
+    endpoint: &Uri,
+    headers: &HashMap<HeaderName, HeaderValue>,
+    body: Vec<u8>,
+    content_type: &'static str,
+) -> Result<http::Request<Vec<u8>>, HttpError> {
+    let mut request = http::Request::builder()
+        .method(Method::POST)
+        .uri(endpoint)
+        .header(CONTENT_TYPE, content_type)
+        .body(body)?;
+
+    for (k, v) in headers {
+        request.headers_mut().insert(k.clone(), v.clone());
+    }
+
+    Ok(request)
+}
+
+/// Describes the failed export to `endpoint`, with the response of the
+/// collector if any.
+fn export_error(endpoint: &Uri, err: HttpError) -> crate::Error {
+    let message = match err.downcast_ref::<HttpStatusError>() {
+        Some(err) => format!(
+            "OpenTelemetry export failed. Url: {}, Status Code: {}, Response: {:?}",
+            endpoint,
+            err.status().as_u16(),
+            err.body()
+        ),
+        None => format!(
+            "OpenTelemetry export failed. Url: {}, Error: {}",
+            endpoint, err
+        ),
+    };
+    crate::Error::RequestFailed(message.into())
+}
+
+/// Decides whether an export failing with `err` can be retried.
+///
+/// Requests that could not reach the collector are retried, as are requests
+/// failing with one of the retryable status codes of the [OTLP/HTTP
+/// specification].
+///
+/// [OTLP/HTTP specification]: https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#failures-1
+fn retry_action(err: &HttpError) -> RetryAction {
+    if err.is::<http::Error>() {
+        return RetryAction::Fail;
+    }
+
+    match err.downcast_ref::<HttpStatusError>() {
+        Some(err) => match err.status() {
+            StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => {
+                retry_after(err.headers()).map_or(RetryAction::Retry, RetryAction::RetryAfter)
+            }
+            _ => RetryAction::Fail,
+        },
+        None => RetryAction::Retry,
+    }
+}
+
+/// Returns the delay requested by the `Retry-After` header, given either in
+/// seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
 fn build_endpoint_uri(endpoint: &str, path: &str) -> Result<Uri, crate::Error> {
     let path = if endpoint.ends_with('/') && path.starts_with('/') {
         path.strip_prefix('/').unwrap()
@@ -406,6 +546,81 @@ mod tests {
 
     use super::build_endpoint_uri;
 
+    #[test]
+    fn test_retry_action() {
+        use super::{retry_action, RetryAction};
+        use opentelemetry_http::{HttpError, ResponseExt};
+        use std::time::{Duration, SystemTime};
+
+        let status_error = |status: u16, retry_after: Option<String>| -> HttpError {
+            let mut response = http::Response::builder().status(status);
+            if let Some(retry_after) = retry_after {
+                response = response.header(http::header::RETRY_AFTER, retry_after);
+            }
+            response.body(()).unwrap().error_for_status().unwrap_err()
+        };
+
+        assert_eq!(retry_action(&status_error(503, None)), RetryAction::Retry);
+        assert_eq!(retry_action(&status_error(400, None)), RetryAction::Fail);
+        assert_eq!(
+            retry_action(&status_error(429, Some("3".into()))),
+            RetryAction::RetryAfter(Duration::from_secs(3))
+        );
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        match retry_action(&status_error(502, Some(date))) {
+            RetryAction::RetryAfter(delay) => assert!(delay > Duration::from_secs(50)),
+            action => panic!("expected a retry after the given date, got {action:?}"),
+        }
+        assert_eq!(
+            retry_action(&"connection refused".into()),
+            RetryAction::Retry
+        );
+    }
+
+    /// A client whose requests never complete.
+    #[derive(Debug)]
+    struct StalledClient;
+
+    #[async_trait::async_trait]
+    impl opentelemetry_http::HttpClient for StalledClient {
+        async fn send(
+            &self,
+            _request: http::Request<Vec<u8>>,
+        ) -> Result<http::Response<opentelemetry_http::Bytes>, opentelemetry_http::HttpError>
+        {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_times_out_with_url() {
+        use super::OtlpHttpClient;
+        use crate::{Protocol, RetryConfig};
+        use std::{collections::HashMap, sync::Arc, time::Duration};
+
+        let client = Arc::new(StalledClient);
+        let exporter = OtlpHttpClient::new(
+            client.clone(),
+            "http://localhost:4318/v1/traces".parse().unwrap(),
+            HashMap::new(),
+            Protocol::HttpBinary,
+            Duration::from_millis(10),
+            RetryConfig::disabled(),
+        );
+
+        let err = exporter
+            .send(client, Vec::new(), "application/x-protobuf")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Url: http://localhost:4318/v1/traces"),
+            "{}",
+            err
+        );
+        assert!(err.contains("timed out"), "{}", err);
+    }
+
     #[test]
     fn test_append_signal_path_to_generic_env() {
         run_env_test(
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use futures_core::future::BoxFuture;
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::trace::TraceError;
 use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
 
@@ -26,38 +25,9 @@ impl SpanExporter for OtlpHttpClient {
             Err(e) => return Box::pin(std::future::ready(Err(e))),
         };
 
-        let mut request = match http::Request::builder()
-            .method(Method::POST)
-            .uri(&self.collector_endpoint)
-            .header(CONTENT_TYPE, content_type)
-            .body(body)
-        {
-            Ok(req) => req,
-            Err(e) => {
-                return Box::pin(std::future::ready(Err(crate::Error::RequestFailed(
-                    Box::new(e),
-                )
-                .into())))
-            }
-        };
-
-        for (k, v) in &self.headers {
-            request.headers_mut().insert(k.clone(), v.clone());
-        }
-
+        let send = self.send(client, body, content_type);
         Box::pin(async move {
-            let request_uri = request.uri().to_string();
-            let response = client.send(request).await?;
-
-            if !response.status().is_success() {
-                let error = format!(
-                    "OpenTelemetry trace export failed. Url: {}, Status Code: {}, Response: {:?}",
-                    response.status().as_u16(),
-                    request_uri,
-                    response.body()
-                );
-                return Err(TraceError::Other(error.into()));
-            }
+            send.await?;
 
             Ok(())
         })
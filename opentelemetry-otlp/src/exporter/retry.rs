@@ -0,0 +1,343 @@
+//! Retries of failed exports with exponential backoff.
+//!
+//! Failed exports are retried only when the error is transient, e.g. the
+//! collector is unavailable or throttles the exporter, following the
+//! [OTLP specification].
+//!
+//! [OTLP specification]: https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#failures
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+/// Configuration of the retries of failed exports.
+///
+/// The delay before the first retry is `initial_backoff`, and is multiplied by
+/// `backoff_multiplier` for every following retry up to `max_backoff`. A delay
+/// requested by the collector, with a gRPC `RetryInfo` or an HTTP `Retry-After`
+/// header, takes precedence over the computed one.
+///
+/// Retries are never scheduled past the export timeout.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_otlp::RetryConfig;
+/// use std::time::Duration;
+///
+/// let retry_config = RetryConfig::default()
+///     .with_max_attempts(3)
+///     .with_initial_backoff(Duration::from_millis(500));
+/// # drop(retry_config);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RetryConfig {
+    /// The maximum number of attempts of an export, including the first one.
+    ///
+    /// A value of `1` or less disables retries.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry.
+    pub initial_backoff: Duration,
+
+    /// The maximum delay between two attempts.
+    pub max_backoff: Duration,
+
+    /// The factor the delay is multiplied by after every retry.
+    pub backoff_multiplier: f64,
+
+    /// The fraction of the delay that is randomized, between `0.0` and `1.0`.
+    ///
+    /// With a jitter of `0.2` a delay of 1 second becomes a random delay
+    /// between 0.8 and 1.2 seconds, which keeps exporters failing at the same
+    /// time from retrying at the same time.
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 1.5,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A configuration that disables retries.
+    pub fn disabled() -> Self {
+        RetryConfig {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of attempts of an export, including the first
+    /// one.
+    pub fn with_max_attempts(self, max_attempts: u32) -> Self {
+        RetryConfig {
+            max_attempts,
+            ..self
+        }
+    }
+
+    /// Set the delay before the first retry.
+    pub fn with_initial_backoff(self, initial_backoff: Duration) -> Self {
+        RetryConfig {
+            initial_backoff,
+            ..self
+        }
+    }
+
+    /// Set the maximum delay between two attempts.
+    pub fn with_max_backoff(self, max_backoff: Duration) -> Self {
+        RetryConfig {
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Set the factor the delay is multiplied by after every retry.
+    ///
+    /// Non-finite values are ignored.
+    pub fn with_backoff_multiplier(self, backoff_multiplier: f64) -> Self {
+        if !backoff_multiplier.is_finite() {
+            return self;
+        }
+        RetryConfig {
+            backoff_multiplier,
+            ..self
+        }
+    }
+
+    /// Set the fraction of the delay that is randomized, between `0.0` and
+    /// `1.0`.
+    ///
+    /// Non-finite values are ignored.
+    pub fn with_jitter(self, jitter: f64) -> Self {
+        if !jitter.is_finite() {
+            return self;
+        }
+        RetryConfig { jitter, ..self }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        // The public field can still be set to NaN.
+        if self.jitter.is_nan() || self.jitter <= 0.0 {
+            return delay;
+        }
+
+        let jitter = self.jitter.min(1.0);
+        let factor = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+        mul_capped(delay, factor, Duration::MAX)
+    }
+
+    /// The backoff following `backoff`, at most `max_backoff`.
+    fn next_backoff(&self, backoff: Duration) -> Duration {
+        let multiplier = if self.backoff_multiplier.is_finite() {
+            self.backoff_multiplier.max(1.0)
+        } else {
+            1.0
+        };
+        mul_capped(backoff, multiplier, self.max_backoff)
+    }
+}
+
+/// `duration` multiplied by `factor`, or `max` if the product is larger than
+/// `max` or cannot be represented.
+fn mul_capped(duration: Duration, factor: f64, max: Duration) -> Duration {
+    let secs = duration.as_secs_f64() * factor;
+    if secs >= 0.0 && secs < max.as_secs_f64() {
+        Duration::from_secs_f64(secs)
+    } else {
+        max
+    }
+}
+
+/// How a failed export should be handled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum RetryAction {
+    /// The error is permanent and the export must not be retried.
+    Fail,
+    /// The export can be retried after the backoff delay.
+    Retry,
+    /// The export can be retried after the delay requested by the collector.
+    RetryAfter(Duration),
+}
+
+/// Runs `export` until it succeeds, `classify` deems its error permanent, or
+/// the attempts allowed by `config` are used up.
+///
+/// `export` is given the time left before `timeout` elapses, which bounds the
+/// duration of the attempt. No retry is made if it could not start before
+/// then, and the last error is returned instead. Delays requested by the
+/// collector are capped at `timeout`.
+pub(crate) async fn retry_with_backoff<T, E, F, Fut>(
+    config: &RetryConfig,
+    timeout: Duration,
+    classify: impl Fn(&E) -> RetryAction,
+    mut export: F,
+) -> Result<T, E>
+where
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut backoff = config.initial_backoff;
+    let mut attempt = 1;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let err = match export(remaining).await {
+            Ok(res) => return Ok(res),
+            Err(err) => err,
+        };
+
+        let delay = match classify(&err) {
+            RetryAction::Fail => return Err(err),
+            RetryAction::Retry => config.jittered(backoff),
+            RetryAction::RetryAfter(delay) => delay.min(timeout),
+        };
+        let retry_at = Instant::now().checked_add(delay);
+        if attempt >= config.max_attempts || retry_at.map_or(true, |at| at >= deadline) {
+            return Err(err);
+        }
+
+        futures_timer::Delay::new(delay).await;
+        attempt += 1;
+        backoff = config.next_backoff(backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn config(max_attempts: u32) -> RetryConfig {
+        RetryConfig::default()
+            .with_max_attempts(max_attempts)
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_backoff(Duration::from_millis(4))
+            .with_backoff_multiplier(2.0)
+            .with_jitter(0.0)
+    }
+
+    async fn run(
+        config: &RetryConfig,
+        timeout: Duration,
+        failures: u32,
+        action: RetryAction,
+    ) -> (Result<u32, u32>, u32) {
+        let attempts = AtomicU32::new(0);
+        let res = retry_with_backoff(
+            config,
+            timeout,
+            |_| action,
+            |_| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    if attempt > failures {
+                        Ok(attempt)
+                    } else {
+                        Err(attempt)
+                    }
+                }
+            },
+        )
+        .await;
+
+        (res, attempts.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_success() {
+        let res = run(&config(5), Duration::from_secs(10), 2, RetryAction::Retry).await;
+        assert_eq!(res, (Ok(3), 3));
+    }
+
+    #[tokio::test]
+    async fn stops_after_max_attempts() {
+        let res = run(&config(3), Duration::from_secs(10), 10, RetryAction::Retry).await;
+        assert_eq!(res, (Err(3), 3));
+
+        let res = run(
+            &RetryConfig::disabled(),
+            Duration::from_secs(10),
+            10,
+            RetryAction::Retry,
+        )
+        .await;
+        assert_eq!(res, (Err(1), 1));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_errors() {
+        let res = run(&config(5), Duration::from_secs(10), 10, RetryAction::Fail).await;
+        assert_eq!(res, (Err(1), 1));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_past_timeout() {
+        let res = run(
+            &config(5),
+            Duration::from_secs(10),
+            10,
+            RetryAction::RetryAfter(Duration::from_secs(60)),
+        )
+        .await;
+        assert_eq!(res, (Err(1), 1));
+    }
+
+    #[tokio::test]
+    async fn does_not_overflow_on_large_delays() {
+        let res = run(
+            &config(5),
+            Duration::from_secs(10),
+            10,
+            RetryAction::RetryAfter(Duration::MAX),
+        )
+        .await;
+        assert_eq!(res, (Err(1), 1));
+    }
+
+    #[test]
+    fn ignores_non_finite_settings() {
+        let config = RetryConfig::default()
+            .with_jitter(f64::NAN)
+            .with_backoff_multiplier(f64::INFINITY);
+        assert_eq!(config, RetryConfig::default());
+
+        let mut config = config.with_max_backoff(Duration::MAX);
+        config.jitter = f64::NAN;
+        config.backoff_multiplier = f64::INFINITY;
+        assert_eq!(
+            config.jittered(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            config.next_backoff(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+
+        config.backoff_multiplier = 1e300;
+        assert_eq!(config.next_backoff(Duration::from_secs(1)), Duration::MAX);
+        config.jitter = 1.0;
+        config.jittered(Duration::MAX);
+    }
+
+    #[test]
+    fn backoff_is_jittered_within_bounds() {
+        let config = RetryConfig::default();
+        for _ in 0..100 {
+            let delay = config.jittered(Duration::from_secs(1));
+            assert!(delay >= Duration::from_millis(800) && delay <= Duration::from_millis(1200));
+        }
+    }
+}
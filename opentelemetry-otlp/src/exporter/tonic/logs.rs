@@ -1,4 +1,5 @@
 use core::fmt;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 use opentelemetry::logs::{LogError, LogResult};
@@ -6,17 +7,18 @@ use opentelemetry_proto::tonic::collector::logs::v1::{
     logs_service_client::LogsServiceClient, ExportLogsServiceRequest,
 };
 use opentelemetry_sdk::export::logs::{LogData, LogExporter};
-use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
+use tonic::{codegen::CompressionEncoding, transport::Channel};
 
-use super::BoxInterceptor;
+use super::{BoxInterceptor, RetryPolicy};
 
 pub(crate) struct TonicLogsClient {
     inner: Option<ClientInner>,
+    retry: RetryPolicy,
 }
 
 struct ClientInner {
     client: LogsServiceClient<Channel>,
-    interceptor: BoxInterceptor,
+    interceptor: Mutex<BoxInterceptor>,
 }
 
 impl fmt::Debug for TonicLogsClient {
@@ -30,6 +32,7 @@ impl TonicLogsClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry: RetryPolicy,
     ) -> Self {
         let mut client = LogsServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -41,8 +44,9 @@ impl TonicLogsClient {
         TonicLogsClient {
             inner: Some(ClientInner {
                 client,
-                interceptor,
+                interceptor: Mutex::new(interceptor),
             }),
+            retry,
         }
     }
 }
@@ -50,26 +54,19 @@ impl TonicLogsClient {
 #[async_trait]
 impl LogExporter for TonicLogsClient {
     async fn export(&mut self, batch: Vec<LogData>) -> LogResult<()> {
-        let (mut client, metadata, extensions) = match &mut self.inner {
-            Some(inner) => {
-                let (m, e, _) = inner
-                    .interceptor
-                    .call(Request::new(()))
-                    .map_err(|e| LogError::Other(Box::new(e)))?
-                    .into_parts();
-                (inner.client.clone(), m, e)
-            }
+        let inner = match &self.inner {
+            Some(inner) => inner,
             None => return Err(LogError::Other("exporter is already shut down".into())),
         };
 
-        client
-            .export(Request::from_parts(
-                metadata,
-                extensions,
-                ExportLogsServiceRequest {
-                    resource_logs: batch.into_iter().map(Into::into).collect(),
-                },
-            ))
+        let request = ExportLogsServiceRequest {
+            resource_logs: batch.into_iter().map(Into::into).collect(),
+        };
+        self.retry
+            .export(&inner.interceptor, request, |request| {
+                let mut client = inner.client.clone();
+                async move { client.export(request).await }
+            })
             .await
             .map_err(crate::Error::from)?;
 
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use opentelemetry::metrics::{MetricsError, Result};
@@ -7,18 +7,19 @@ use opentelemetry_proto::tonic::collector::metrics::v1::{
     metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
 };
 use opentelemetry_sdk::metrics::data::ResourceMetrics;
-use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
+use tonic::{codegen::CompressionEncoding, transport::Channel};
 
-use super::BoxInterceptor;
+use super::{BoxInterceptor, RetryPolicy};
 use crate::metric::MetricsClient;
 
 pub(crate) struct TonicMetricsClient {
     inner: Mutex<Option<ClientInner>>,
+    retry: RetryPolicy,
 }
 
 struct ClientInner {
     client: MetricsServiceClient<Channel>,
-    interceptor: BoxInterceptor,
+    interceptor: Arc<Mutex<BoxInterceptor>>,
 }
 
 impl fmt::Debug for TonicMetricsClient {
@@ -32,6 +33,7 @@ impl TonicMetricsClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry: RetryPolicy,
     ) -> Self {
         let mut client = MetricsServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -43,8 +45,9 @@ impl TonicMetricsClient {
         TonicMetricsClient {
             inner: Mutex::new(Some(ClientInner {
                 client,
-                interceptor,
+                interceptor: Arc::new(Mutex::new(interceptor)),
             })),
+            retry,
         }
     }
 }
@@ -52,32 +55,24 @@ impl TonicMetricsClient {
 #[async_trait]
 impl MetricsClient for TonicMetricsClient {
     async fn export(&self, metrics: &mut ResourceMetrics) -> Result<()> {
-        let (mut client, metadata, extensions) =
+        let (client, interceptor) =
             self.inner
                 .lock()
                 .map_err(Into::into)
-                .and_then(|mut inner| match &mut *inner {
-                    Some(inner) => {
-                        let (m, e, _) = inner
-                            .interceptor
-                            .call(Request::new(()))
-                            .map_err(|e| {
-                                MetricsError::Other(format!(
-                                    "unexpected status while exporting {e:?}"
-                                ))
-                            })?
-                            .into_parts();
-                        Ok((inner.client.clone(), m, e))
-                    }
+                .and_then(|inner| match &*inner {
+                    Some(inner) => Ok((inner.client.clone(), Arc::clone(&inner.interceptor))),
                     None => Err(MetricsError::Other("exporter is already shut down".into())),
                 })?;
 
-        client
-            .export(Request::from_parts(
-                metadata,
-                extensions,
+        self.retry
+            .export(
+                &interceptor,
                 ExportMetricsServiceRequest::from(&*metrics),
-            ))
+                |request| {
+                    let mut client = client.clone();
+                    async move { client.export(request).await }
+                },
+            )
             .await
             .map_err(crate::Error::from)?;
 
@@ -1,17 +1,22 @@
 use std::env;
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
 use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use http::{HeaderMap, HeaderName, HeaderValue};
+use prost::Message;
 use tonic::codec::CompressionEncoding;
 use tonic::metadata::{KeyAndValueRef, MetadataMap};
 use tonic::service::Interceptor;
 use tonic::transport::Channel;
 #[cfg(feature = "tls")]
 use tonic::transport::ClientTlsConfig;
+use tonic::{Code, Request, Status};
 
 use super::{default_headers, parse_header_string};
+use crate::exporter::retry::{retry_with_backoff, RetryAction, RetryConfig};
 use crate::exporter::Compression;
 use crate::{
     ExportConfig, OTEL_EXPORTER_OTLP_COMPRESSION, OTEL_EXPORTER_OTLP_ENDPOINT,
@@ -118,6 +123,7 @@ pub struct TonicExporterBuilder {
     pub(crate) tonic_config: TonicConfig,
     pub(crate) channel: Option<tonic::transport::Channel>,
     pub(crate) interceptor: Option<BoxInterceptor>,
+    pub(crate) retry_config: RetryConfig,
 }
 
 pub(crate) struct BoxInterceptor(Box<dyn Interceptor + Send + Sync>);
@@ -155,6 +161,7 @@ impl Default for TonicExporterBuilder {
             tonic_config,
             channel: Option::default(),
             interceptor: Option::default(),
+            retry_config: RetryConfig::default(),
         }
     }
 }
@@ -210,6 +217,18 @@ impl TonicExporterBuilder {
         self
     }
 
+    /// Set the [RetryConfig] of failed exports.
+    ///
+    /// Exports failing with the `CANCELLED`, `DEADLINE_EXCEEDED`, `ABORTED`,
+    /// `OUT_OF_RANGE`, `UNAVAILABLE` or `DATA_LOSS` status codes are retried,
+    /// as are exports failing with `RESOURCE_EXHAUSTED` if the server provides
+    /// a `RetryInfo`. If this option is not used, [RetryConfig::default] is
+    /// used.
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
     fn build_channel(
         self,
         signal_endpoint_var: &str,
@@ -217,7 +236,15 @@ impl TonicExporterBuilder {
         signal_timeout_var: &str,
         signal_compression_var: &str,
         signal_headers_var: &str,
-    ) -> Result<(Channel, BoxInterceptor, Option<CompressionEncoding>), crate::Error> {
+    ) -> Result<
+        (
+            Channel,
+            BoxInterceptor,
+            Option<CompressionEncoding>,
+            RetryPolicy,
+        ),
+        crate::Error,
+    > {
         let tonic_config = self.tonic_config;
         let compression = resolve_compression(&tonic_config, signal_compression_var)?;
 
@@ -249,12 +276,27 @@ impl TonicExporterBuilder {
             None => BoxInterceptor(Box::new(add_metadata)),
         };
 
+        let config = self.exporter_config;
+        let timeout = match env::var(signal_timeout_var)
+            .ok()
+            .or(env::var(OTEL_EXPORTER_OTLP_TIMEOUT).ok())
+        {
+            Some(val) => match val.parse() {
+                Ok(seconds) => Duration::from_secs(seconds),
+                Err(_) => config.timeout,
+            },
+            None => config.timeout,
+        };
+        let retry = RetryPolicy {
+            config: self.retry_config,
+            timeout,
+        };
+
         // If a custom channel was provided, use that channel instead of creating one
         if let Some(channel) = self.channel {
-            return Ok((channel, interceptor, compression));
+            return Ok((channel, interceptor, compression, retry));
         }
 
-        let config = self.exporter_config;
         let endpoint = match env::var(signal_endpoint_var)
             .ok()
             .or(env::var(OTEL_EXPORTER_OTLP_ENDPOINT).ok())
@@ -264,16 +306,6 @@ impl TonicExporterBuilder {
         };
 
         let endpoint = Channel::from_shared(endpoint).map_err(crate::Error::from)?;
-        let timeout = match env::var(signal_timeout_var)
-            .ok()
-            .or(env::var(OTEL_EXPORTER_OTLP_TIMEOUT).ok())
-        {
-            Some(val) => match val.parse() {
-                Ok(seconds) => Duration::from_secs(seconds),
-                Err(_) => config.timeout,
-            },
-            None => config.timeout,
-        };
 
         #[cfg(feature = "tls")]
         let channel = match tonic_config.tls_config {
@@ -288,7 +320,7 @@ impl TonicExporterBuilder {
         #[cfg(not(feature = "tls"))]
         let channel = endpoint.timeout(timeout).connect_lazy();
 
-        Ok((channel, interceptor, compression))
+        Ok((channel, interceptor, compression, retry))
     }
 
     /// Build a new tonic log exporter
@@ -298,7 +330,7 @@ impl TonicExporterBuilder {
     ) -> Result<crate::logs::LogExporter, opentelemetry::logs::LogError> {
         use crate::exporter::tonic::logs::TonicLogsClient;
 
-        let (channel, interceptor, compression) = self.build_channel(
+        let (channel, interceptor, compression, retry) = self.build_channel(
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_ENDPOINT,
             "/v1/logs",
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
@@ -306,7 +338,7 @@ impl TonicExporterBuilder {
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_HEADERS,
         )?;
 
-        let client = TonicLogsClient::new(channel, interceptor, compression);
+        let client = TonicLogsClient::new(channel, interceptor, compression, retry);
 
         Ok(crate::logs::LogExporter::new(client))
     }
@@ -321,7 +353,7 @@ impl TonicExporterBuilder {
         use crate::MetricsExporter;
         use metrics::TonicMetricsClient;
 
-        let (channel, interceptor, compression) = self.build_channel(
+        let (channel, interceptor, compression, retry) = self.build_channel(
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
             "/v1/metrics",
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
@@ -329,7 +361,7 @@ impl TonicExporterBuilder {
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_HEADERS,
         )?;
 
-        let client = TonicMetricsClient::new(channel, interceptor, compression);
+        let client = TonicMetricsClient::new(channel, interceptor, compression, retry);
 
         Ok(MetricsExporter::new(
             client,
@@ -345,7 +377,7 @@ impl TonicExporterBuilder {
     ) -> Result<crate::SpanExporter, opentelemetry::trace::TraceError> {
         use crate::exporter::tonic::trace::TonicTracesClient;
 
-        let (channel, interceptor, compression) = self.build_channel(
+        let (channel, interceptor, compression, retry) = self.build_channel(
             crate::span::OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
             "/v1/traces",
             crate::span::OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
@@ -353,12 +385,125 @@ impl TonicExporterBuilder {
             crate::span::OTEL_EXPORTER_OTLP_TRACES_HEADERS,
         )?;
 
-        let client = TonicTracesClient::new(channel, interceptor, compression);
+        let client = TonicTracesClient::new(channel, interceptor, compression, retry);
 
         Ok(crate::SpanExporter::new(client))
     }
 }
 
+/// Retries the failed exports of a tonic client.
+#[derive(Debug)]
+pub(crate) struct RetryPolicy {
+    config: RetryConfig,
+    timeout: Duration,
+}
+
+impl RetryPolicy {
+    /// Exports `message` with `export`, retrying the attempts failing with a
+    /// retryable status.
+    ///
+    /// The interceptor is called for every attempt, and the duration of every
+    /// attempt is bounded by the time left before the export timeout.
+    pub(crate) async fn export<T, R, F, Fut>(
+        &self,
+        interceptor: &Mutex<BoxInterceptor>,
+        message: T,
+        mut export: F,
+    ) -> Result<R, Status>
+    where
+        T: Clone,
+        F: FnMut(Request<T>) -> Fut,
+        Fut: Future<Output = Result<R, Status>>,
+    {
+        retry_with_backoff(&self.config, self.timeout, retry_action, |remaining| {
+            let intercepted = match interceptor.lock() {
+                Ok(mut interceptor) => interceptor.call(Request::new(())),
+                Err(_) => Err(Status::internal("the interceptor lock has been poisoned")),
+            };
+            let attempt = intercepted.map(|request| {
+                let (metadata, extensions, _) = request.into_parts();
+                let mut request = Request::from_parts(metadata, extensions, message.clone());
+                request.set_timeout(remaining);
+                export(request)
+            });
+            async move { attempt?.await }
+        })
+        .await
+    }
+}
+
+/// Decides whether an export failing with `status` can be retried.
+///
+/// See the [OTLP/gRPC specification] for the retryable status codes.
+///
+/// [OTLP/gRPC specification]: https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#failures
+fn retry_action(status: &Status) -> RetryAction {
+    let retry_delay = retry_delay(status);
+    match status.code() {
+        Code::Cancelled
+        | Code::DeadlineExceeded
+        | Code::Aborted
+        | Code::OutOfRange
+        | Code::Unavailable
+        | Code::DataLoss => retry_delay.map_or(RetryAction::Retry, RetryAction::RetryAfter),
+        Code::ResourceExhausted => retry_delay.map_or(RetryAction::Fail, RetryAction::RetryAfter),
+        _ => RetryAction::Fail,
+    }
+}
+
+const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// The `google.rpc.Status` message carried in the details of a [Status].
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<RpcAny>,
+}
+
+/// The `google.protobuf.Any` message.
+#[derive(Clone, PartialEq, Message)]
+struct RpcAny {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// The `google.rpc.RetryInfo` message.
+#[derive(Clone, PartialEq, Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<RpcDuration>,
+}
+
+/// The `google.protobuf.Duration` message.
+#[derive(Clone, PartialEq, Message)]
+struct RpcDuration {
+    #[prost(int64, tag = "1")]
+    seconds: i64,
+    #[prost(int32, tag = "2")]
+    nanos: i32,
+}
+
+/// Returns the retry delay of the `RetryInfo` in the details of `status`, if
+/// the server provided one.
+fn retry_delay(status: &Status) -> Option<Duration> {
+    let details = RpcStatus::decode(status.details()).ok()?;
+    details
+        .details
+        .iter()
+        .filter(|detail| detail.type_url == RETRY_INFO_TYPE_URL)
+        .find_map(|detail| RetryInfo::decode(detail.value.as_slice()).ok()?.retry_delay)
+        .map(|delay| {
+            Duration::from_secs(delay.seconds.max(0) as u64)
+                .saturating_add(Duration::from_nanos(delay.nanos.max(0) as u64))
+        })
+}
+
 fn merge_metadata_with_headers_from_env(
     metadata: MetadataMap,
     headers_from_env: HeaderMap,
@@ -439,6 +584,51 @@ mod tests {
         assert_eq!(builder.tonic_config.compression.unwrap(), Compression::Gzip);
     }
 
+    #[test]
+    fn test_retry_action() {
+        use super::{retry_action, RetryInfo, RpcAny, RpcDuration, RpcStatus, RETRY_INFO_TYPE_URL};
+        use crate::exporter::retry::RetryAction;
+        use prost::Message;
+        use std::time::Duration;
+        use tonic::{Code, Status};
+
+        let with_retry_info = |code: Code| {
+            let details = RpcStatus {
+                code: code as i32,
+                message: String::new(),
+                details: vec![RpcAny {
+                    type_url: RETRY_INFO_TYPE_URL.into(),
+                    value: RetryInfo {
+                        retry_delay: Some(RpcDuration {
+                            seconds: 2,
+                            nanos: 500_000_000,
+                        }),
+                    }
+                    .encode_to_vec(),
+                }],
+            };
+            Status::with_details(code, "", details.encode_to_vec().into())
+        };
+
+        assert_eq!(retry_action(&Status::unavailable("")), RetryAction::Retry);
+        assert_eq!(
+            retry_action(&Status::invalid_argument("")),
+            RetryAction::Fail
+        );
+        assert_eq!(
+            retry_action(&Status::resource_exhausted("")),
+            RetryAction::Fail
+        );
+        assert_eq!(
+            retry_action(&with_retry_info(Code::ResourceExhausted)),
+            RetryAction::RetryAfter(Duration::from_millis(2500))
+        );
+        assert_eq!(
+            retry_action(&with_retry_info(Code::Unavailable)),
+            RetryAction::RetryAfter(Duration::from_millis(2500))
+        );
+    }
+
     #[test]
     fn test_parse_headers_from_env() {
         run_env_test(
@@ -1,4 +1,5 @@
 use core::fmt;
+use std::sync::{Arc, Mutex};
 
 use futures_core::future::BoxFuture;
 use opentelemetry::trace::TraceError;
@@ -6,9 +7,9 @@ use opentelemetry_proto::tonic::collector::trace::v1::{
     trace_service_client::TraceServiceClient, ExportTraceServiceRequest,
 };
 use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
-use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
+use tonic::{codegen::CompressionEncoding, transport::Channel};
 
-use super::BoxInterceptor;
+use super::{BoxInterceptor, RetryPolicy};
 
 pub(crate) struct TonicTracesClient {
     inner: Option<ClientInner>,
@@ -16,7 +17,8 @@ pub(crate) struct TonicTracesClient {
 
 struct ClientInner {
     client: TraceServiceClient<Channel>,
-    interceptor: BoxInterceptor,
+    interceptor: Arc<Mutex<BoxInterceptor>>,
+    retry: Arc<RetryPolicy>,
 }
 
 impl fmt::Debug for TonicTracesClient {
@@ -30,6 +32,7 @@ impl TonicTracesClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry: RetryPolicy,
     ) -> Self {
         let mut client = TraceServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -41,7 +44,8 @@ impl TonicTracesClient {
         TonicTracesClient {
             inner: Some(ClientInner {
                 client,
-                interceptor,
+                interceptor: Arc::new(Mutex::new(interceptor)),
+                retry: Arc::new(retry),
             }),
         }
     }
@@ -49,16 +53,12 @@ impl TonicTracesClient {
 
 impl SpanExporter for TonicTracesClient {
     fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
-        let (mut client, metadata, extensions) = match &mut self.inner {
-            Some(inner) => {
-                let (m, e, _) = match inner.interceptor.call(Request::new(())) {
-                    Ok(res) => res.into_parts(),
-                    Err(e) => {
-                        return Box::pin(std::future::ready(Err(TraceError::Other(Box::new(e)))))
-                    }
-                };
-                (inner.client.clone(), m, e)
-            }
+        let (client, interceptor, retry) = match &self.inner {
+            Some(inner) => (
+                inner.client.clone(),
+                Arc::clone(&inner.interceptor),
+                Arc::clone(&inner.retry),
+            ),
             None => {
                 return Box::pin(std::future::ready(Err(TraceError::Other(
                     "exporter is already shut down".into(),
@@ -66,15 +66,16 @@ impl SpanExporter for TonicTracesClient {
             }
         };
 
+        let request = ExportTraceServiceRequest {
+            resource_spans: batch.into_iter().map(Into::into).collect(),
+        };
+
         Box::pin(async move {
-            client
-                .export(Request::from_parts(
-                    metadata,
-                    extensions,
-                    ExportTraceServiceRequest {
-                        resource_spans: batch.into_iter().map(Into::into).collect(),
-                    },
-                ))
+            retry
+                .export(&interceptor, request, |request| {
+                    let mut client = client.clone();
+                    async move { client.export(request).await }
+                })
                 .await
                 .map_err(crate::Error::from)?;
 
@@ -89,6 +89,7 @@
 //!
 //! The following feature flags generate additional code and types:
 //! * `serialize`: Enables serialization support for type defined in this create via `serde`.
+//! * `receiver`: Includes [`OtlpReceiver`], a server receiving OTLP exports over gRPC and HTTP.
 //!
 //! The following feature flags offer additional configurations on gRPC:
 //!
@@ -215,6 +216,11 @@ mod exporter;
 mod logs;
 #[cfg(feature = "metrics")]
 mod metric;
+#[cfg(all(
+    feature = "receiver",
+    any(feature = "trace", feature = "metrics", feature = "logs")
+))]
+mod receiver;
 #[cfg(feature = "trace")]
 mod span;
 
@@ -229,9 +235,10 @@ pub use crate::span::{
 
 #[cfg(feature = "metrics")]
 pub use crate::metric::{
-    MetricsExporter, MetricsExporterBuilder, OtlpMetricPipeline,
-    OTEL_EXPORTER_OTLP_METRICS_COMPRESSION, OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
-    OTEL_EXPORTER_OTLP_METRICS_HEADERS, OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
+    DeltaTemporalitySelector, LowMemoryTemporalitySelector, MetricsExporter,
+    MetricsExporterBuilder, OtlpMetricPipeline, OTEL_EXPORTER_OTLP_METRICS_COMPRESSION,
+    OTEL_EXPORTER_OTLP_METRICS_ENDPOINT, OTEL_EXPORTER_OTLP_METRICS_HEADERS,
+    OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE, OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
 };
 
 #[cfg(feature = "logs")]
@@ -256,6 +263,17 @@ pub use crate::exporter::http::HttpExporterBuilder;
 #[cfg(feature = "grpc-tonic")]
 pub use crate::exporter::tonic::{TonicConfig, TonicExporterBuilder};
 
+#[cfg(any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"))]
+pub use crate::exporter::retry::RetryConfig;
+
+#[cfg(all(
+    feature = "receiver",
+    any(feature = "trace", feature = "metrics", feature = "logs")
+))]
+pub use crate::receiver::{
+    ExportRequest, OtlpReceiver, OtlpReceiverBuilder, ReceiverError, Rejection,
+};
+
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
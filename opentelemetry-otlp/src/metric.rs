@@ -6,7 +6,10 @@
 use crate::{NoExporterConfig, OtlpPipeline};
 use async_trait::async_trait;
 use core::fmt;
-use opentelemetry::{global, metrics::Result};
+use opentelemetry::{
+    global,
+    metrics::{MetricsError, Result},
+};
 
 #[cfg(feature = "grpc-tonic")]
 use crate::exporter::tonic::TonicExporterBuilder;
@@ -42,6 +45,10 @@ pub const OTEL_EXPORTER_OTLP_METRICS_COMPRESSION: &str = "OTEL_EXPORTER_OTLP_MET
 /// Example: `k1=v1,k2=v2`
 /// Note: this is only supported for HTTP.
 pub const OTEL_EXPORTER_OTLP_METRICS_HEADERS: &str = "OTEL_EXPORTER_OTLP_METRICS_HEADERS";
+/// Temporality preference of the metrics pipeline when no temporality selector
+/// is set, one of `cumulative`, `delta` or `lowmemory`, defaults to `cumulative`.
+pub const OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE: &str =
+    "OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE";
 impl OtlpPipeline {
     /// Create a OTLP metrics pipeline.
     pub fn metrics<RT>(self, rt: RT) -> OtlpMetricPipeline<RT, NoExporterConfig>
@@ -162,6 +169,10 @@ where
     }
 
     /// Build with the given temporality selector
+    ///
+    /// By default, the temporality preference set by the
+    /// `OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE` environment variable is
+    /// used, falling back to cumulative temporality for all instruments.
     pub fn with_temporality_selector<T: TemporalitySelector + 'static>(self, selector: T) -> Self {
         OtlpMetricPipeline {
             temporality_selector: Some(Box::new(selector)),
@@ -176,7 +187,17 @@ where
     ///
     /// [exporter-docs]: https://github.com/open-telemetry/opentelemetry-specification/blob/a1c13d59bb7d0fb086df2b3e1eaec9df9efef6cc/specification/metrics/sdk_exporters/otlp.md#additional-configuration
     pub fn with_delta_temporality(self) -> Self {
-        self.with_temporality_selector(DeltaTemporalitySelector)
+        self.with_temporality_selector(DeltaTemporalitySelector::new())
+    }
+
+    /// Build with low memory temporality selector.
+    ///
+    /// This temporality selector is equivalent to OTLP Metrics Exporter's
+    /// `LowMemory` temporality preference (see [its documentation][exporter-docs]).
+    ///
+    /// [exporter-docs]: https://github.com/open-telemetry/opentelemetry-specification/blob/a1c13d59bb7d0fb086df2b3e1eaec9df9efef6cc/specification/metrics/sdk_exporters/otlp.md#additional-configuration
+    pub fn with_low_memory_temporality(self) -> Self {
+        self.with_temporality_selector(LowMemoryTemporalitySelector::new())
     }
 
     /// Build with the given aggregation selector
@@ -217,7 +238,7 @@ where
     pub fn build(self) -> Result<SdkMeterProvider> {
         let exporter = self.exporter_pipeline.build_metrics_exporter(
             self.temporality_selector
-                .unwrap_or_else(|| Box::new(DefaultTemporalitySelector::new())),
+                .unwrap_or_else(temporality_selector_from_env),
             self.aggregator_selector
                 .unwrap_or_else(|| Box::new(DefaultAggregationSelector::new())),
         )?;
@@ -265,8 +286,17 @@ impl<RT, EB: Debug> Debug for OtlpMetricPipeline<RT, EB> {
 /// `Delta` temporality preference (see [its documentation][exporter-docs]).
 ///
 /// [exporter-docs]: https://github.com/open-telemetry/opentelemetry-specification/blob/a1c13d59bb7d0fb086df2b3e1eaec9df9efef6cc/specification/metrics/sdk_exporters/otlp.md#additional-configuration
-#[derive(Debug)]
-struct DeltaTemporalitySelector;
+#[derive(Clone, Default, Debug)]
+pub struct DeltaTemporalitySelector {
+    _private: (),
+}
+
+impl DeltaTemporalitySelector {
+    /// Create a new delta temporality selector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
 impl TemporalitySelector for DeltaTemporalitySelector {
     #[rustfmt::skip]
@@ -287,6 +317,65 @@ impl TemporalitySelector for DeltaTemporalitySelector {
     }
 }
 
+/// A temporality selector that returns [`Delta`][Temporality::Delta] for
+/// synchronous `Counter` and `Histogram` instruments, and
+/// [`Cumulative`][Temporality::Cumulative] for the others.
+///
+/// This temporality selector is equivalent to OTLP Metrics Exporter's
+/// `LowMemory` temporality preference (see [its documentation][exporter-docs]).
+///
+/// [exporter-docs]: https://github.com/open-telemetry/opentelemetry-specification/blob/a1c13d59bb7d0fb086df2b3e1eaec9df9efef6cc/specification/metrics/sdk_exporters/otlp.md#additional-configuration
+#[derive(Clone, Default, Debug)]
+pub struct LowMemoryTemporalitySelector {
+    _private: (),
+}
+
+impl LowMemoryTemporalitySelector {
+    /// Create a new low memory temporality selector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TemporalitySelector for LowMemoryTemporalitySelector {
+    #[rustfmt::skip]
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        match kind {
+            InstrumentKind::Counter
+            | InstrumentKind::Histogram => {
+                Temporality::Delta
+            }
+            InstrumentKind::UpDownCounter
+            | InstrumentKind::ObservableCounter
+            | InstrumentKind::ObservableUpDownCounter
+            | InstrumentKind::Gauge
+            | InstrumentKind::ObservableGauge => {
+                Temporality::Cumulative
+            }
+        }
+    }
+}
+
+/// The temporality selector of the preference set by the
+/// `OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE` environment variable.
+fn temporality_selector_from_env() -> Box<dyn TemporalitySelector> {
+    match std::env::var(OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE) {
+        Ok(preference) => match preference.trim().to_ascii_lowercase().as_str() {
+            "cumulative" => Box::new(DefaultTemporalitySelector::new()),
+            "delta" => Box::new(DeltaTemporalitySelector::new()),
+            "lowmemory" => Box::new(LowMemoryTemporalitySelector::new()),
+            other => {
+                global::handle_error(MetricsError::Config(format!(
+                    "Unrecognised {} value: {}. Falling back to default: cumulative",
+                    OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE, other
+                )));
+                Box::new(DefaultTemporalitySelector::new())
+            }
+        },
+        Err(_) => Box::new(DefaultTemporalitySelector::new()),
+    }
+}
+
 /// An interface for OTLP metrics clients
 #[async_trait]
 pub trait MetricsClient: fmt::Debug + Send + Sync + 'static {
@@ -349,3 +438,44 @@ impl MetricsExporter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temporality_selector_from_env() {
+        let temporality = |preference: Option<&str>, kind: InstrumentKind| {
+            temp_env::with_var(
+                OTEL_EXPORTER_OTLP_METRICS_TEMPORALITY_PREFERENCE,
+                preference,
+                || temporality_selector_from_env().temporality(kind),
+            )
+        };
+
+        assert_eq!(
+            temporality(None, InstrumentKind::Counter),
+            Temporality::Cumulative
+        );
+        assert_eq!(
+            temporality(Some("delta"), InstrumentKind::ObservableCounter),
+            Temporality::Delta
+        );
+        assert_eq!(
+            temporality(Some("LowMemory"), InstrumentKind::ObservableCounter),
+            Temporality::Cumulative
+        );
+        assert_eq!(
+            temporality(Some("lowmemory"), InstrumentKind::Histogram),
+            Temporality::Delta
+        );
+        assert_eq!(
+            temporality(Some("lowmemory"), InstrumentKind::Gauge),
+            Temporality::Cumulative
+        );
+        assert_eq!(
+            temporality(Some("unknown"), InstrumentKind::Counter),
+            Temporality::Cumulative
+        );
+    }
+}
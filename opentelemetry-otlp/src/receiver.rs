@@ -0,0 +1,738 @@
+//! # OTLP - Receiver
+//!
+//! A receiver accepting OTLP exports over gRPC, HTTP/protobuf and HTTP/JSON
+//! on a single local port, and handing the decoded requests to a callback or
+//! a channel.
+//!
+//! It is meant for tests and local pipelines that need to inspect exported
+//! telemetry without running a collector.
+//!
+//! ```no_run
+//! # #[cfg(feature = "trace")]
+//! # async fn example() -> Result<(), opentelemetry_otlp::ReceiverError> {
+//! use opentelemetry_otlp::{ExportRequest, OtlpReceiver, WithExportConfig};
+//!
+//! let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+//! let receiver = OtlpReceiver::builder().with_channel(tx).start()?;
+//!
+//! let exporter = opentelemetry_otlp::new_exporter()
+//!     .tonic()
+//!     .with_endpoint(receiver.endpoint());
+//! // export some spans, then:
+//! if let Some(ExportRequest::Traces(request)) = rx.recv().await {
+//!     println!("received {} resource spans", request.resource_spans.len());
+//! }
+//!
+//! receiver.shutdown().await
+//! # }
+//! ```
+use std::convert::Infallible;
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Read};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Method, Request, Response, StatusCode,
+};
+use http_body::{Body as _, LengthLimitError, Limited};
+use hyper::{server::conn::AddrIncoming, service::service_fn, Body};
+use prost::Message;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+#[cfg(feature = "gzip-tonic")]
+use tonic::codec::CompressionEncoding;
+use tonic::{
+    body::BoxBody,
+    codegen::Service,
+    transport::server::{Routes, RoutesBuilder},
+};
+
+#[cfg(feature = "logs")]
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    logs_service_server::{LogsService, LogsServiceServer},
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+#[cfg(feature = "metrics")]
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    metrics_service_server::{MetricsService, MetricsServiceServer},
+    ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+#[cfg(feature = "trace")]
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    trace_service_server::{TraceService, TraceServiceServer},
+    ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+
+/// An export received by an [`OtlpReceiver`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ExportRequest {
+    /// A batch of spans.
+    #[cfg(feature = "trace")]
+    Traces(ExportTraceServiceRequest),
+    /// A batch of metrics.
+    #[cfg(feature = "metrics")]
+    Metrics(ExportMetricsServiceRequest),
+    /// A batch of logs.
+    #[cfg(feature = "logs")]
+    Logs(ExportLogsServiceRequest),
+}
+
+/// The reason an export handler rejected an export, reported to the exporter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rejection {
+    /// The export can be retried later, e.g. because a queue is full.
+    Unavailable(String),
+    /// The export is invalid and must not be retried.
+    InvalidArgument(String),
+}
+
+impl From<Rejection> for tonic::Status {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::Unavailable(message) => tonic::Status::unavailable(message),
+            Rejection::InvalidArgument(message) => tonic::Status::invalid_argument(message),
+        }
+    }
+}
+
+type Handler = Arc<dyn Fn(ExportRequest) -> Result<(), Rejection> + Send + Sync>;
+
+/// The default maximum size of an export, the default maximum message size of
+/// gRPC servers.
+const DEFAULT_MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+/// Builder of an [`OtlpReceiver`].
+pub struct OtlpReceiverBuilder {
+    address: SocketAddr,
+    handler: Handler,
+    max_body_size: usize,
+}
+
+impl Debug for OtlpReceiverBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtlpReceiverBuilder")
+            .field("address", &self.address)
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+impl Default for OtlpReceiverBuilder {
+    fn default() -> Self {
+        OtlpReceiverBuilder {
+            address: SocketAddr::from(([127, 0, 0, 1], 0)),
+            handler: Arc::new(|_| Ok(())),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+impl OtlpReceiverBuilder {
+    /// Set the address to listen on.
+    ///
+    /// Defaults to `127.0.0.1:0`, i.e. a free port of the loopback interface.
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Set the maximum size of an export in bytes, once decompressed.
+    ///
+    /// Larger exports are rejected, with a `413 Payload Too Large` status over
+    /// HTTP and a `RESOURCE_EXHAUSTED` status over gRPC. Defaults to 4 MiB.
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Set the callback called with every received export.
+    ///
+    /// The callback runs on the task serving the export, and an error it
+    /// returns is reported to the exporter. By default exports are discarded.
+    pub fn with_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(ExportRequest) -> Result<(), Rejection> + Send + Sync + 'static,
+    {
+        self.handler = Arc::new(handler);
+        self
+    }
+
+    /// Send every received export to a channel.
+    ///
+    /// Exports received while the channel is full or closed are rejected as
+    /// unavailable, which lets exporters retry them.
+    pub fn with_channel(self, sender: mpsc::Sender<ExportRequest>) -> Self {
+        self.with_handler(move |request| {
+            sender.try_send(request).map_err(|err| match err {
+                mpsc::error::TrySendError::Full(_) => {
+                    Rejection::Unavailable("the receiver channel is full".into())
+                }
+                mpsc::error::TrySendError::Closed(_) => {
+                    Rejection::Unavailable("the receiver channel is closed".into())
+                }
+            })
+        })
+    }
+
+    /// Bind the address and start serving exports on the current Tokio runtime.
+    pub fn start(self) -> Result<OtlpReceiver, ReceiverError> {
+        let incoming = AddrIncoming::bind(&self.address).map_err(ReceiverError::Bind)?;
+        let local_addr = incoming.local_addr();
+
+        let handler = self.handler;
+        let max_body_size = self.max_body_size;
+        let grpc = grpc_routes(handler.clone(), max_body_size);
+        let make_service = hyper::service::make_service_fn(move |_| {
+            let handler = handler.clone();
+            let grpc = grpc.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let handler = handler.clone();
+                    let mut grpc = grpc.clone();
+                    async move {
+                        if is_grpc(&req) {
+                            grpc.call(req).await
+                        } else {
+                            Ok(handle_http(&handler, max_body_size, req).await)
+                        }
+                    }
+                }))
+            }
+        });
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server = hyper::Server::builder(incoming)
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                // a dropped sender also stops the server
+                let _ = shutdown_rx.await;
+            });
+
+        Ok(OtlpReceiver {
+            local_addr,
+            shutdown: Some(shutdown_tx),
+            server: Some(tokio::spawn(server)),
+        })
+    }
+}
+
+/// Errors of an [`OtlpReceiver`].
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum ReceiverError {
+    /// The receiver failed to bind its address.
+    #[error("the receiver failed to bind its address: {0}")]
+    Bind(#[source] hyper::Error),
+
+    /// The receiver failed while serving exports.
+    #[error("the receiver failed with {0}")]
+    Serve(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// A server receiving OTLP exports.
+///
+/// The server stops when [`OtlpReceiver::shutdown`] is called or the receiver
+/// is dropped.
+pub struct OtlpReceiver {
+    local_addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    server: Option<JoinHandle<Result<(), hyper::Error>>>,
+}
+
+impl Debug for OtlpReceiver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtlpReceiver")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+impl OtlpReceiver {
+    /// Create a builder of a receiver.
+    pub fn builder() -> OtlpReceiverBuilder {
+        OtlpReceiverBuilder::default()
+    }
+
+    /// The address the receiver listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The endpoint exporters can be configured with to export to this receiver.
+    ///
+    /// It is suitable for both the gRPC and HTTP exporters.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    /// Stop the receiver, waiting for the exports in progress to be handled.
+    pub async fn shutdown(mut self) -> Result<(), ReceiverError> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        match self.server.take() {
+            Some(server) => match server.await {
+                Ok(res) => res.map_err(|err| ReceiverError::Serve(err.into())),
+                Err(err) => Err(ReceiverError::Serve(err.into())),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+fn is_grpc<B>(req: &Request<B>) -> bool {
+    req.headers()
+        .get(CONTENT_TYPE)
+        .map_or(false, |content_type| {
+            content_type.as_bytes().starts_with(b"application/grpc")
+        })
+}
+
+#[derive(Clone)]
+struct GrpcService {
+    handler: Handler,
+}
+
+fn grpc_routes(handler: Handler, max_body_size: usize) -> Routes {
+    let service = GrpcService { handler };
+    let mut routes = RoutesBuilder::default();
+
+    #[cfg(feature = "trace")]
+    {
+        let server =
+            TraceServiceServer::new(service.clone()).max_decoding_message_size(max_body_size);
+        #[cfg(feature = "gzip-tonic")]
+        let server = server.accept_compressed(CompressionEncoding::Gzip);
+        routes.add_service(server);
+    }
+    #[cfg(feature = "metrics")]
+    {
+        let server =
+            MetricsServiceServer::new(service.clone()).max_decoding_message_size(max_body_size);
+        #[cfg(feature = "gzip-tonic")]
+        let server = server.accept_compressed(CompressionEncoding::Gzip);
+        routes.add_service(server);
+    }
+    #[cfg(feature = "logs")]
+    {
+        let server =
+            LogsServiceServer::new(service.clone()).max_decoding_message_size(max_body_size);
+        #[cfg(feature = "gzip-tonic")]
+        let server = server.accept_compressed(CompressionEncoding::Gzip);
+        routes.add_service(server);
+    }
+
+    routes.routes()
+}
+
+#[cfg(feature = "trace")]
+#[tonic::async_trait]
+impl TraceService for GrpcService {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportTraceServiceRequest>,
+    ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+        (self.handler)(ExportRequest::Traces(request.into_inner()))?;
+        Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[tonic::async_trait]
+impl MetricsService for GrpcService {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportMetricsServiceRequest>,
+    ) -> Result<tonic::Response<ExportMetricsServiceResponse>, tonic::Status> {
+        (self.handler)(ExportRequest::Metrics(request.into_inner()))?;
+        Ok(tonic::Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+#[cfg(feature = "logs")]
+#[tonic::async_trait]
+impl LogsService for GrpcService {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportLogsServiceRequest>,
+    ) -> Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+        (self.handler)(ExportRequest::Logs(request.into_inner()))?;
+        Ok(tonic::Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+/// The encodings of OTLP/HTTP payloads.
+#[derive(Clone, Copy)]
+enum Encoding {
+    Protobuf,
+    Json,
+}
+
+impl Encoding {
+    fn content_type(self) -> &'static str {
+        match self {
+            Encoding::Protobuf => "application/x-protobuf",
+            Encoding::Json => "application/json",
+        }
+    }
+}
+
+async fn handle_http(
+    handler: &Handler,
+    max_body_size: usize,
+    req: Request<Body>,
+) -> Response<BoxBody> {
+    if req.method() != Method::POST {
+        return text_response(StatusCode::METHOD_NOT_ALLOWED, "only POST is allowed");
+    }
+
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    let encoding = match content_type {
+        Some("application/x-protobuf") => Encoding::Protobuf,
+        Some("application/json") => Encoding::Json,
+        _ => {
+            return text_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "the content type must be application/x-protobuf or application/json",
+            )
+        }
+    };
+
+    let gzip = match req
+        .headers()
+        .get(CONTENT_ENCODING)
+        .map(|value| value.to_str())
+    {
+        None | Some(Ok("identity")) => false,
+        Some(Ok("gzip")) => true,
+        _ => {
+            return text_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "the content encoding must be gzip or identity",
+            )
+        }
+    };
+
+    let path = req.uri().path().to_string();
+    let body = match hyper::body::to_bytes(Limited::new(req.into_body(), max_body_size)).await {
+        Ok(body) => body,
+        Err(err) if err.downcast_ref::<LengthLimitError>().is_some() => {
+            return payload_too_large(max_body_size)
+        }
+        Err(err) => return text_response(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let body = if gzip {
+        match gunzip(&body, max_body_size) {
+            Ok(Some(body)) => body,
+            Ok(None) => return payload_too_large(max_body_size),
+            Err(err) => return text_response(StatusCode::BAD_REQUEST, &err.to_string()),
+        }
+    } else {
+        body
+    };
+
+    match path.as_str() {
+        #[cfg(feature = "trace")]
+        "/v1/traces" => export::<ExportTraceServiceRequest, ExportTraceServiceResponse>(
+            handler,
+            encoding,
+            &body,
+            ExportRequest::Traces,
+        ),
+        #[cfg(feature = "metrics")]
+        "/v1/metrics" => export::<ExportMetricsServiceRequest, ExportMetricsServiceResponse>(
+            handler,
+            encoding,
+            &body,
+            ExportRequest::Metrics,
+        ),
+        #[cfg(feature = "logs")]
+        "/v1/logs" => export::<ExportLogsServiceRequest, ExportLogsServiceResponse>(
+            handler,
+            encoding,
+            &body,
+            ExportRequest::Logs,
+        ),
+        _ => text_response(StatusCode::NOT_FOUND, "unknown signal"),
+    }
+}
+
+fn export<Req, Res>(
+    handler: &Handler,
+    encoding: Encoding,
+    body: &[u8],
+    into_request: fn(Req) -> ExportRequest,
+) -> Response<BoxBody>
+where
+    Req: Message + Default + DeserializeOwned,
+    Res: Message + Default + Serialize,
+{
+    let request = match encoding {
+        Encoding::Protobuf => Req::decode(body).map_err(|err| err.to_string()),
+        Encoding::Json => serde_json::from_slice(body).map_err(|err| err.to_string()),
+    };
+    let request = match request {
+        Ok(request) => request,
+        Err(err) => return text_response(StatusCode::BAD_REQUEST, &err),
+    };
+
+    match handler(into_request(request)) {
+        Ok(()) => {
+            let body = match encoding {
+                Encoding::Protobuf => Res::default().encode_to_vec(),
+                Encoding::Json => serde_json::to_vec(&Res::default()).unwrap_or_default(),
+            };
+            response(StatusCode::OK, encoding.content_type(), body)
+        }
+        Err(Rejection::Unavailable(message)) => {
+            text_response(StatusCode::SERVICE_UNAVAILABLE, &message)
+        }
+        Err(Rejection::InvalidArgument(message)) => {
+            text_response(StatusCode::BAD_REQUEST, &message)
+        }
+    }
+}
+
+/// Decompress a gzip body, or return `None` if it is larger than `max_size`
+/// once decompressed.
+fn gunzip(body: &[u8], max_size: usize) -> io::Result<Option<Bytes>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(body)
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decoded)?;
+    Ok((decoded.len() <= max_size).then(|| Bytes::from(decoded)))
+}
+
+fn payload_too_large(max_body_size: usize) -> Response<BoxBody> {
+    text_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        &format!("the export is larger than {} bytes", max_body_size),
+    )
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<BoxBody> {
+    response(status, "text/plain", message.as_bytes().to_vec())
+}
+
+fn response(status: StatusCode, content_type: &'static str, body: Vec<u8>) -> Response<BoxBody> {
+    let body = http_body::Full::new(Bytes::from(body))
+        .map_err(|never| match never {})
+        .boxed_unsync();
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, http::HeaderValue::from_static(content_type));
+    response
+}
+
+#[cfg(test)]
+#[cfg(feature = "trace")]
+mod tests {
+    use super::*;
+    use crate::WithExportConfig;
+    use opentelemetry_sdk::export::trace::SpanExporter;
+    use opentelemetry_sdk::testing::trace::new_test_export_span_data;
+
+    async fn post(
+        receiver: &OtlpReceiver,
+        path: &str,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> StatusCode {
+        post_encoded(receiver, path, content_type, "identity", body).await
+    }
+
+    async fn post_encoded(
+        receiver: &OtlpReceiver,
+        path: &str,
+        content_type: &str,
+        content_encoding: &str,
+        body: Vec<u8>,
+    ) -> StatusCode {
+        let request = Request::post(format!("{}{}", receiver.endpoint(), path))
+            .header(CONTENT_TYPE, content_type)
+            .header(CONTENT_ENCODING, content_encoding)
+            .body(Body::from(body))
+            .unwrap();
+        hyper::Client::new()
+            .request(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn traces_request() -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: vec![new_test_export_span_data().into()],
+        }
+    }
+
+    #[tokio::test]
+    async fn receives_grpc_exports() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let receiver = OtlpReceiver::builder().with_channel(tx).start().unwrap();
+
+        let mut exporter = crate::new_exporter()
+            .tonic()
+            .with_endpoint(receiver.endpoint())
+            .build_span_exporter()
+            .unwrap();
+        exporter
+            .export(vec![new_test_export_span_data()])
+            .await
+            .unwrap();
+
+        match rx.recv().await {
+            Some(ExportRequest::Traces(request)) => {
+                let span = &request.resource_spans[0].scope_spans[0].spans[0];
+                assert_eq!(span.name, "opentelemetry");
+            }
+            other => panic!("unexpected export {:?}", other),
+        }
+        receiver.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn receives_http_exports() {
+        let (tx, mut rx) = mpsc::channel(2);
+        let receiver = OtlpReceiver::builder().with_channel(tx).start().unwrap();
+        let request = traces_request();
+
+        let status = post(
+            &receiver,
+            "/v1/traces",
+            "application/x-protobuf",
+            request.encode_to_vec(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let status = post(
+            &receiver,
+            "/v1/traces",
+            "application/json",
+            serde_json::to_vec(&request).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        for _ in 0..2 {
+            assert_eq!(
+                rx.recv().await,
+                Some(ExportRequest::Traces(request.clone()))
+            );
+        }
+        receiver.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_http_exports() {
+        let receiver = OtlpReceiver::builder()
+            .with_handler(|_| Err(Rejection::Unavailable("busy".into())))
+            .start()
+            .unwrap();
+
+        let body = traces_request().encode_to_vec();
+        assert_eq!(
+            post(&receiver, "/v1/traces", "text/plain", body.clone()).await,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        assert_eq!(
+            post(
+                &receiver,
+                "/v1/unknown",
+                "application/x-protobuf",
+                body.clone()
+            )
+            .await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            post(&receiver, "/v1/traces", "application/json", b"{".to_vec()).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            post(&receiver, "/v1/traces", "application/x-protobuf", body).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        receiver.shutdown().await.unwrap();
+    }
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(body).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn decompresses_and_limits_http_exports() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let request = traces_request();
+        let body = request.encode_to_vec();
+        let receiver = OtlpReceiver::builder()
+            .with_channel(tx)
+            .with_max_body_size(body.len())
+            .start()
+            .unwrap();
+
+        let status = post_encoded(
+            &receiver,
+            "/v1/traces",
+            "application/x-protobuf",
+            "gzip",
+            gzip(&body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(rx.recv().await, Some(ExportRequest::Traces(request)));
+
+        let mut larger = body.clone();
+        larger.push(0);
+        assert_eq!(
+            post(
+                &receiver,
+                "/v1/traces",
+                "application/x-protobuf",
+                larger.clone()
+            )
+            .await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            post_encoded(
+                &receiver,
+                "/v1/traces",
+                "application/x-protobuf",
+                "gzip",
+                gzip(&larger)
+            )
+            .await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            post_encoded(
+                &receiver,
+                "/v1/traces",
+                "application/x-protobuf",
+                "br",
+                body
+            )
+            .await,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        receiver.shutdown().await.unwrap();
+    }
+}
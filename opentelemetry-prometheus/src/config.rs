@@ -1,5 +1,4 @@
 use core::fmt;
-use once_cell::sync::OnceCell;
 use opentelemetry::metrics::{MetricsError, Result};
 use opentelemetry_sdk::metrics::{
     reader::{AggregationSelector, MetricProducer},
@@ -7,7 +6,10 @@ use opentelemetry_sdk::metrics::{
 };
 use std::sync::{Arc, Mutex};
 
-use crate::{Collector, PrometheusExporter, ResourceSelector};
+use crate::{
+    Collector, Converter, OpenMetricsEncoder, PrometheusExporter, RegisteredCollector,
+    ResourceSelector,
+};
 
 /// [PrometheusExporter] configuration options
 #[derive(Default)]
@@ -20,6 +22,8 @@ pub struct ExporterBuilder {
     disable_scope_info: bool,
     reader: ManualReaderBuilder,
     resource_selector: ResourceSelector,
+    #[cfg(feature = "server")]
+    server_addr: Option<std::net::SocketAddr>,
 }
 
 impl fmt::Debug for ExporterBuilder {
@@ -31,7 +35,7 @@ impl fmt::Debug for ExporterBuilder {
             .field("without_counter_suffixes", &self.without_counter_suffixes)
             .field("namespace", &self.namespace)
             .field("disable_scope_info", &self.disable_scope_info)
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
@@ -137,28 +141,73 @@ impl ExporterBuilder {
         self
     }
 
+    /// Serves the metrics of the exporter's registry over HTTP on the given address.
+    ///
+    /// The server is started by [ExporterBuilder::build] on a dedicated thread,
+    /// and stopped when the exporter is shut down, along with the meter provider
+    /// it is registered with. It serves:
+    ///
+    /// * `/metrics`, in the text, protobuf or OpenMetrics format depending on
+    ///   the `Accept` header of the request, gzip compressed if the
+    ///   `Accept-Encoding` header allows it. Only the exporter's metrics are
+    ///   served in the OpenMetrics format, see [OpenMetricsEncoder],
+    /// * `/health`, answering `200 OK` while the server is running.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use opentelemetry_sdk::metrics::SdkMeterProvider;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let exporter = opentelemetry_prometheus::exporter()
+    ///     .with_server(([0, 0, 0, 0], 9464))
+    ///     .build()?;
+    /// let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    /// # drop(provider);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "server")]
+    pub fn with_server(mut self, addr: impl Into<std::net::SocketAddr>) -> Self {
+        self.server_addr = Some(addr.into());
+        self
+    }
+
     /// Creates a new [PrometheusExporter] from this configuration.
     pub fn build(self) -> Result<PrometheusExporter> {
         let reader = Arc::new(self.reader.build());
 
-        let collector = Collector {
+        let collector = Arc::new(Collector {
             reader: Arc::clone(&reader),
-            disable_target_info: self.disable_target_info,
-            without_units: self.without_units,
-            without_counter_suffixes: self.without_counter_suffixes,
-            disable_scope_info: self.disable_scope_info,
-            create_target_info_once: OnceCell::new(),
-            namespace: self.namespace,
+            converter: Converter {
+                namespace: self.namespace,
+                without_units: self.without_units,
+                without_counter_suffixes: self.without_counter_suffixes,
+                disable_target_info: self.disable_target_info,
+                disable_scope_info: self.disable_scope_info,
+                resource_selector: self.resource_selector,
+                ..Default::default()
+            },
             inner: Mutex::new(Default::default()),
-            resource_selector: self.resource_selector,
-            resource_labels_once: OnceCell::new(),
-        };
+        });
+        let openmetrics = OpenMetricsEncoder::new(Arc::clone(&collector));
 
         let registry = self.registry.unwrap_or_default();
         registry
-            .register(Box::new(collector))
+            .register(Box::new(RegisteredCollector(collector)))
             .map_err(|e| MetricsError::Other(e.to_string()))?;
 
-        Ok(PrometheusExporter { reader })
+        #[cfg(feature = "server")]
+        let server = self
+            .server_addr
+            .map(|addr| crate::server::ScrapeServer::start(addr, registry, openmetrics.clone()))
+            .transpose()?;
+
+        Ok(PrometheusExporter {
+            reader,
+            openmetrics,
+            #[cfg(feature = "server")]
+            server,
+        })
     }
 }
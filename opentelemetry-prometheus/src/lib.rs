@@ -132,10 +132,19 @@ const SCOPE_INFO_KEYS: [&str; 2] = ["otel_scope_name", "otel_scope_version"];
 const COUNTER_SUFFIX: &str = "_total";
 
 mod config;
+mod native_histogram;
+mod openmetrics;
+#[cfg(feature = "remote-write")]
+mod remote_write;
 mod resource_selector;
+#[cfg(feature = "server")]
+mod server;
 mod utils;
 
 pub use config::ExporterBuilder;
+pub use openmetrics::{OpenMetricsEncoder, OPENMETRICS_FORMAT};
+#[cfg(feature = "remote-write")]
+pub use remote_write::{RemoteWriteExporter, RemoteWriteExporterBuilder};
 pub use resource_selector::ResourceSelector;
 
 /// Creates a builder to configure a [PrometheusExporter]
@@ -147,6 +156,27 @@ pub fn exporter() -> ExporterBuilder {
 #[derive(Debug)]
 pub struct PrometheusExporter {
     reader: Arc<ManualReader>,
+    openmetrics: OpenMetricsEncoder,
+    #[cfg(feature = "server")]
+    server: Option<server::ScrapeServer>,
+}
+
+impl PrometheusExporter {
+    /// An encoder of the exporter's metrics in the OpenMetrics text format.
+    pub fn openmetrics_encoder(&self) -> OpenMetricsEncoder {
+        self.openmetrics.clone()
+    }
+}
+
+#[cfg(feature = "server")]
+impl PrometheusExporter {
+    /// The address the scrape server configured with [ExporterBuilder::with_server]
+    /// is listening on.
+    ///
+    /// This is the bound port when the server was configured with port `0`.
+    pub fn server_addr(&self) -> Option<std::net::SocketAddr> {
+        self.server.as_ref().map(server::ScrapeServer::local_addr)
+    }
 }
 
 impl TemporalitySelector for PrometheusExporter {
@@ -177,21 +207,40 @@ impl MetricReader for PrometheusExporter {
     }
 
     fn shutdown(&self) -> Result<()> {
-        self.reader.shutdown()
+        // The reader is shut down even if stopping the server fails
+        #[cfg(feature = "server")]
+        let server = self
+            .server
+            .as_ref()
+            .map_or(Ok(()), |server| server.shutdown());
+        #[cfg(not(feature = "server"))]
+        let server = Ok(());
+
+        let reader = self.reader.shutdown();
+        server.and(reader)
     }
 }
 
 struct Collector {
     reader: Arc<ManualReader>,
-    disable_target_info: bool,
+    converter: Converter,
+    inner: Mutex<CollectorInner>,
+}
+
+/// Converts metrics to Prometheus metric families, shared by the
+/// [PrometheusExporter] and the remote-write exporter.
+#[derive(Default)]
+struct Converter {
+    namespace: Option<String>,
     without_units: bool,
     without_counter_suffixes: bool,
+    disable_target_info: bool,
     disable_scope_info: bool,
+    resource_selector: ResourceSelector,
+    /// Whether metrics are timestamped with the time of their data points.
+    with_timestamps: bool,
     create_target_info_once: OnceCell<MetricFamily>,
     resource_labels_once: OnceCell<Vec<LabelPair>>,
-    namespace: Option<String>,
-    inner: Mutex<CollectorInner>,
-    resource_selector: ResourceSelector,
 }
 
 #[derive(Default)]
@@ -209,6 +258,13 @@ static HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
         TypeId::of::<data::Histogram<f64>>(),
     ]
 });
+static EXPONENTIAL_HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
+    [
+        TypeId::of::<data::ExponentialHistogram<i64>>(),
+        TypeId::of::<data::ExponentialHistogram<u64>>(),
+        TypeId::of::<data::ExponentialHistogram<f64>>(),
+    ]
+});
 static SUM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
     [
         TypeId::of::<data::Sum<i64>>(),
@@ -224,14 +280,14 @@ static GAUGE_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
     ]
 });
 
-impl Collector {
+impl Converter {
     fn metric_type_and_name(&self, m: &data::Metric) -> Option<(MetricType, Cow<'static, str>)> {
         let mut name = self.get_name(m);
 
         let data = m.data.as_any();
         let type_id = data.type_id();
 
-        if HISTOGRAM_TYPES.contains(&type_id) {
+        if HISTOGRAM_TYPES.contains(&type_id) || EXPONENTIAL_HISTOGRAM_TYPES.contains(&type_id) {
             Some((MetricType::HISTOGRAM, name))
         } else if GAUGE_TYPES.contains(&type_id) {
             Some((MetricType::GAUGE, name))
@@ -260,43 +316,16 @@ impl Collector {
     }
 
     fn get_name(&self, m: &data::Metric) -> Cow<'static, str> {
-        let name = utils::sanitize_name(&m.name);
-        let unit_suffixes = if self.without_units {
-            None
-        } else {
-            utils::get_unit_suffixes(&m.unit)
-        };
-        match (&self.namespace, unit_suffixes) {
-            (Some(namespace), Some(suffix)) => Cow::Owned(format!("{namespace}{name}_{suffix}")),
-            (Some(namespace), None) => Cow::Owned(format!("{namespace}{name}")),
-            (None, Some(suffix)) => Cow::Owned(format!("{name}_{suffix}")),
-            (None, None) => name,
-        }
-    }
-}
-
-impl prometheus::core::Collector for Collector {
-    fn desc(&self) -> Vec<&Desc> {
-        Vec::new()
+        utils::get_name(m, self.namespace.as_deref(), self.without_units)
     }
 
-    fn collect(&self) -> Vec<MetricFamily> {
-        let mut inner = match self.inner.lock() {
-            Ok(guard) => guard,
-            Err(err) => {
-                global::handle_error(err);
-                return Vec::new();
-            }
-        };
-
-        let mut metrics = ResourceMetrics {
-            resource: Resource::empty(),
-            scope_metrics: vec![],
-        };
-        if let Err(err) = self.reader.collect(&mut metrics) {
-            global::handle_error(err);
-            return vec![];
-        }
+    /// Converts `metrics` to metric families, dropping the ones conflicting
+    /// with the families previously converted with `inner`.
+    fn metric_families(
+        &self,
+        metrics: &ResourceMetrics,
+        inner: &mut CollectorInner,
+    ) -> Vec<MetricFamily> {
         let mut res = Vec::with_capacity(metrics.scope_metrics.len() + 1);
 
         let target_info = self.create_target_info_once.get_or_init(|| {
@@ -312,7 +341,7 @@ impl prometheus::core::Collector for Collector {
             .resource_labels_once
             .get_or_init(|| self.resource_selector.select(&metrics.resource));
 
-        for scope_metrics in metrics.scope_metrics {
+        for scope_metrics in &metrics.scope_metrics {
             let scope_labels = if !self.disable_scope_info {
                 if !scope_metrics.scope.attributes.is_empty() {
                     let scope_info = inner
@@ -322,60 +351,93 @@ impl prometheus::core::Collector for Collector {
                     res.push(scope_info.clone());
                 }
 
-                let mut labels =
-                    Vec::with_capacity(1 + scope_metrics.scope.version.is_some() as usize);
-                let mut name = LabelPair::new();
-                name.set_name(SCOPE_INFO_KEYS[0].into());
-                name.set_value(scope_metrics.scope.name.to_string());
-                labels.push(name);
-                if let Some(version) = &scope_metrics.scope.version {
-                    let mut l_version = LabelPair::new();
-                    l_version.set_name(SCOPE_INFO_KEYS[1].into());
-                    l_version.set_value(version.to_string());
-                    labels.push(l_version);
-                }
-
-                if !resource_labels.is_empty() {
-                    labels.extend(resource_labels.iter().cloned());
-                }
-                labels
+                scope_labels(&scope_metrics.scope, resource_labels)
             } else {
                 Vec::new()
             };
 
-            for metrics in scope_metrics.metrics {
-                let (metric_type, name) = match self.metric_type_and_name(&metrics) {
+            for metric in &scope_metrics.metrics {
+                let (metric_type, name) = match self.metric_type_and_name(metric) {
                     Some((metric_type, name)) => (metric_type, name),
                     _ => continue,
                 };
 
                 let mfs = &mut inner.metric_families;
-                let (drop, help) = validate_metrics(&name, &metrics.description, metric_type, mfs);
+                let (drop, help) = validate_metrics(&name, &metric.description, metric_type, mfs);
                 if drop {
                     continue;
                 }
 
-                let description = help.unwrap_or_else(|| metrics.description.into());
-                let data = metrics.data.as_any();
+                let description = help.unwrap_or_else(|| metric.description.to_string());
+                let data = metric.data.as_any();
+                let timestamps = self.with_timestamps;
 
                 if let Some(hist) = data.downcast_ref::<data::Histogram<i64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
                 } else if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
                 } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<i64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                        timestamps,
+                    );
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
-                    add_sum_metric(&mut res, sum, description, &scope_labels, name);
+                    add_sum_metric(&mut res, sum, description, &scope_labels, name, timestamps);
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
-                    add_sum_metric(&mut res, sum, description, &scope_labels, name);
+                    add_sum_metric(&mut res, sum, description, &scope_labels, name, timestamps);
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
-                    add_sum_metric(&mut res, sum, description, &scope_labels, name);
+                    add_sum_metric(&mut res, sum, description, &scope_labels, name, timestamps);
                 } else if let Some(g) = data.downcast_ref::<data::Gauge<u64>>() {
-                    add_gauge_metric(&mut res, g, description, &scope_labels, name);
+                    add_gauge_metric(&mut res, g, description, &scope_labels, name, timestamps);
                 } else if let Some(g) = data.downcast_ref::<data::Gauge<i64>>() {
-                    add_gauge_metric(&mut res, g, description, &scope_labels, name);
+                    add_gauge_metric(&mut res, g, description, &scope_labels, name, timestamps);
                 } else if let Some(g) = data.downcast_ref::<data::Gauge<f64>>() {
-                    add_gauge_metric(&mut res, g, description, &scope_labels, name);
+                    add_gauge_metric(&mut res, g, description, &scope_labels, name, timestamps);
                 }
             }
         }
@@ -384,6 +446,66 @@ impl prometheus::core::Collector for Collector {
     }
 }
 
+/// The [Collector] registered with the registry, sharing it with the
+/// [OpenMetricsEncoder].
+struct RegisteredCollector(Arc<Collector>);
+
+impl prometheus::core::Collector for RegisteredCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        prometheus::core::Collector::desc(self.0.as_ref())
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        prometheus::core::Collector::collect(self.0.as_ref())
+    }
+}
+
+impl prometheus::core::Collector for Collector {
+    fn desc(&self) -> Vec<&Desc> {
+        Vec::new()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let mut inner = match self.inner.lock() {
+            Ok(guard) => guard,
+            Err(err) => {
+                global::handle_error(err);
+                return Vec::new();
+            }
+        };
+
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![],
+        };
+        if let Err(err) = self.reader.collect(&mut metrics) {
+            global::handle_error(err);
+            return vec![];
+        }
+        self.converter.metric_families(&metrics, &mut inner)
+    }
+}
+
+/// The labels identifying `scope`, followed by `resource_labels`.
+fn scope_labels(scope: &Scope, resource_labels: &[LabelPair]) -> Vec<LabelPair> {
+    let mut labels = Vec::with_capacity(1 + scope.version.is_some() as usize);
+    let mut name = LabelPair::new();
+    name.set_name(SCOPE_INFO_KEYS[0].into());
+    name.set_value(scope.name.to_string());
+    labels.push(name);
+    if let Some(version) = &scope.version {
+        let mut l_version = LabelPair::new();
+        l_version.set_name(SCOPE_INFO_KEYS[1].into());
+        l_version.set_value(version.to_string());
+        labels.push(l_version);
+    }
+
+    if !resource_labels.is_empty() {
+        labels.extend(resource_labels.iter().cloned());
+    }
+    labels
+}
+
 /// Maps attributes into Prometheus-style label pairs.
 ///
 /// It sanitizes invalid characters and handles duplicate keys (due to
@@ -449,6 +571,7 @@ fn add_histogram_metric<T: Numeric>(
     description: String,
     extra: &[LabelPair],
     name: Cow<'static, str>,
+    timestamps: bool,
 ) {
     // Consider supporting exemplars when `prometheus` crate has the feature
     // See: https://github.com/tikv/rust-prometheus/issues/393
@@ -476,6 +599,36 @@ fn add_histogram_metric<T: Numeric>(
         let mut pm = prometheus::proto::Metric::default();
         pm.set_label(protobuf::RepeatedField::from_vec(kvs));
         pm.set_histogram(h);
+        if timestamps {
+            pm.set_timestamp_ms(utils::timestamp_millis(dp.time));
+        }
+
+        let mut mf = prometheus::proto::MetricFamily::default();
+        mf.set_name(name.to_string());
+        mf.set_help(description.clone());
+        mf.set_field_type(prometheus::proto::MetricType::HISTOGRAM);
+        mf.set_metric(protobuf::RepeatedField::from_vec(vec![pm]));
+        res.push(mf);
+    }
+}
+
+fn add_exponential_histogram_metric<T: Numeric>(
+    res: &mut Vec<MetricFamily>,
+    histogram: &data::ExponentialHistogram<T>,
+    description: String,
+    extra: &[LabelPair],
+    name: Cow<'static, str>,
+    timestamps: bool,
+) {
+    for dp in &histogram.data_points {
+        let kvs = get_attrs(&mut dp.attributes.iter(), extra);
+
+        let mut pm = prometheus::proto::Metric::default();
+        pm.set_label(protobuf::RepeatedField::from_vec(kvs));
+        pm.set_histogram(native_histogram::histogram(dp));
+        if timestamps {
+            pm.set_timestamp_ms(utils::timestamp_millis(dp.time));
+        }
 
         let mut mf = prometheus::proto::MetricFamily::default();
         mf.set_name(name.to_string());
@@ -492,6 +645,7 @@ fn add_sum_metric<T: Numeric>(
     description: String,
     extra: &[LabelPair],
     name: Cow<'static, str>,
+    timestamps: bool,
 ) {
     let metric_type = if sum.is_monotonic {
         MetricType::COUNTER
@@ -504,6 +658,9 @@ fn add_sum_metric<T: Numeric>(
 
         let mut pm = prometheus::proto::Metric::default();
         pm.set_label(protobuf::RepeatedField::from_vec(kvs));
+        if let Some(time) = dp.time.filter(|_| timestamps) {
+            pm.set_timestamp_ms(utils::timestamp_millis(time));
+        }
 
         if sum.is_monotonic {
             let mut c = prometheus::proto::Counter::default();
@@ -530,6 +687,7 @@ fn add_gauge_metric<T: Numeric>(
     description: String,
     extra: &[LabelPair],
     name: Cow<'static, str>,
+    timestamps: bool,
 ) {
     for dp in &gauge.data_points {
         let kvs = get_attrs(&mut dp.attributes.iter(), extra);
@@ -539,6 +697,9 @@ fn add_gauge_metric<T: Numeric>(
         let mut pm = prometheus::proto::Metric::default();
         pm.set_label(protobuf::RepeatedField::from_vec(kvs));
         pm.set_gauge(g);
+        if let Some(time) = dp.time.filter(|_| timestamps) {
+            pm.set_timestamp_ms(utils::timestamp_millis(time));
+        }
 
         let mut mf = prometheus::proto::MetricFamily::default();
         mf.set_name(name.to_string());
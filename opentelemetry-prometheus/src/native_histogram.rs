@@ -0,0 +1,313 @@
+//! Conversion of exponential histograms to Prometheus histograms.
+//!
+//! Exponential histograms map to Prometheus native histograms, which share the
+//! same base-2 bucket layout. The `prometheus` crate's protobuf types predate
+//! native histograms, so their fields are written as unknown fields of the
+//! classic histogram message, which the protobuf encoder serializes as is. Classic
+//! buckets are set as well, for the text format and for servers not scraping
+//! native histograms.
+use opentelemetry_sdk::metrics::data::{ExponentialBucket, ExponentialHistogramDataPoint};
+use prometheus::proto::{Bucket, Histogram};
+use protobuf::{CodedOutputStream, RepeatedField};
+
+use crate::Numeric;
+
+// Field numbers of the native histogram fields of `io.prometheus.client.Histogram`.
+const SCHEMA_FIELD: u32 = 5;
+const ZERO_THRESHOLD_FIELD: u32 = 6;
+const ZERO_COUNT_FIELD: u32 = 7;
+const NEGATIVE_SPAN_FIELD: u32 = 9;
+const NEGATIVE_DELTA_FIELD: u32 = 10;
+const POSITIVE_SPAN_FIELD: u32 = 12;
+const POSITIVE_DELTA_FIELD: u32 = 13;
+
+// Field numbers of `io.prometheus.client.BucketSpan`.
+const SPAN_OFFSET_FIELD: u32 = 1;
+const SPAN_LENGTH_FIELD: u32 = 2;
+
+/// The range of schemas, the Prometheus name of the scale, native histograms support.
+const MIN_SCHEMA: i8 = -4;
+const MAX_SCHEMA: i8 = 8;
+
+/// The number of consecutive empty buckets kept within a span, more start a new span.
+const MAX_EMPTY_BUCKETS_IN_SPAN: usize = 2;
+
+/// Converts an exponential histogram data point to a Prometheus histogram with
+/// both native and classic buckets.
+///
+/// Data points with a scale above the maximum native histogram schema are
+/// downscaled, and the ones below the minimum schema only have classic buckets.
+pub(crate) fn histogram<T: Numeric>(dp: &ExponentialHistogramDataPoint<T>) -> Histogram {
+    let mut h = Histogram::default();
+    h.set_sample_sum(dp.sum.as_f64());
+    h.set_sample_count(dp.count as u64);
+    h.set_bucket(RepeatedField::from_vec(classic_buckets(dp)));
+
+    if dp.scale >= MIN_SCHEMA {
+        let schema = dp.scale.min(MAX_SCHEMA);
+        let shift = (dp.scale - schema) as u32;
+
+        let fields = &mut h.unknown_fields;
+        fields.add_varint(SCHEMA_FIELD, zigzag32(schema.into()));
+        fields.add_fixed64(ZERO_THRESHOLD_FIELD, dp.zero_threshold.to_bits());
+        fields.add_varint(ZERO_COUNT_FIELD, dp.zero_count);
+
+        let mut has_spans = false;
+        for (span_field, delta_field, bucket) in [
+            (
+                NEGATIVE_SPAN_FIELD,
+                NEGATIVE_DELTA_FIELD,
+                &dp.negative_bucket,
+            ),
+            (
+                POSITIVE_SPAN_FIELD,
+                POSITIVE_DELTA_FIELD,
+                &dp.positive_bucket,
+            ),
+        ] {
+            let (offset, counts) = downscale(bucket, shift);
+            // OpenTelemetry bucket `i` is the Prometheus bucket `i + 1`, both
+            // holding values in (base^i, base^(i+1)].
+            let (spans, deltas) = spans_and_deltas(offset + 1, &counts);
+            has_spans |= !spans.is_empty();
+            for (offset, length) in spans {
+                fields.add_length_delimited(span_field, encode_span(offset, length));
+            }
+            for delta in deltas {
+                fields.add_varint(delta_field, zigzag64(delta));
+            }
+        }
+
+        // A histogram without observations needs an empty span to be recognized
+        // as a native histogram.
+        if !has_spans && dp.zero_threshold == 0.0 && dp.zero_count == 0 {
+            fields.add_length_delimited(POSITIVE_SPAN_FIELD, encode_span(0, 0));
+        }
+    }
+
+    h
+}
+
+/// Merges the buckets of `bucket` into the buckets of a scale `shift` lower.
+fn downscale(bucket: &ExponentialBucket, shift: u32) -> (i32, Vec<u64>) {
+    if shift == 0 || bucket.counts.is_empty() {
+        return (bucket.offset, bucket.counts.clone());
+    }
+
+    let offset = bucket.offset >> shift;
+    let mut counts = Vec::new();
+    for (i, count) in bucket.counts.iter().enumerate() {
+        let index = (((bucket.offset + i as i32) >> shift) - offset) as usize;
+        if counts.len() <= index {
+            counts.resize(index + 1, 0);
+        }
+        counts[index] += count;
+    }
+    (offset, counts)
+}
+
+/// The spans and delta encoded counts of the non-empty buckets in `counts`,
+/// starting at the bucket index `offset`.
+///
+/// The offset of the first span is the index of its first bucket, the offset
+/// of the other spans is the number of buckets since the end of the previous
+/// span.
+fn spans_and_deltas(offset: i32, counts: &[u64]) -> (Vec<(i32, u32)>, Vec<i64>) {
+    let mut spans: Vec<(i32, u32)> = Vec::new();
+    let mut deltas = Vec::new();
+    let mut previous_count = 0;
+    let mut next_index = 0;
+    let mut empty_buckets = 0;
+
+    for (i, count) in counts.iter().enumerate() {
+        if *count == 0 {
+            empty_buckets += 1;
+            continue;
+        }
+
+        let index = offset + i as i32;
+        match spans.last_mut() {
+            Some((_, length)) if empty_buckets <= MAX_EMPTY_BUCKETS_IN_SPAN => {
+                for _ in 0..empty_buckets {
+                    deltas.push(-previous_count);
+                    previous_count = 0;
+                }
+                *length += empty_buckets as u32 + 1;
+            }
+            Some(_) => spans.push((index - next_index, 1)),
+            None => spans.push((index, 1)),
+        }
+
+        let count = *count as i64;
+        deltas.push(count - previous_count);
+        previous_count = count;
+        next_index = index + 1;
+        empty_buckets = 0;
+    }
+
+    (spans, deltas)
+}
+
+/// The cumulative classic buckets of an exponential histogram data point.
+///
+/// There is a bucket per exponential bucket, with the bucket's upper boundary,
+/// and a bucket for the zero region, with the zero threshold as upper bound.
+pub(crate) fn classic_buckets<T>(dp: &ExponentialHistogramDataPoint<T>) -> Vec<Bucket> {
+    let mut buckets =
+        Vec::with_capacity(dp.negative_bucket.counts.len() + dp.positive_bucket.counts.len() + 1);
+    let mut cumulative_count = 0;
+    let mut push = |upper_bound: f64, count: u64| {
+        cumulative_count += count;
+        let mut b = Bucket::default();
+        b.set_upper_bound(upper_bound);
+        b.set_cumulative_count(cumulative_count);
+        buckets.push(b);
+    };
+
+    // Negative bucket `i` holds values in [-base^(i+1), -base^i).
+    let negative = &dp.negative_bucket;
+    for (i, count) in negative.counts.iter().enumerate().rev() {
+        push(
+            -lower_boundary(negative.offset + i as i32, dp.scale),
+            *count,
+        );
+    }
+
+    push(dp.zero_threshold, dp.zero_count);
+
+    let positive = &dp.positive_bucket;
+    for (i, count) in positive.counts.iter().enumerate() {
+        push(
+            lower_boundary(positive.offset + i as i32 + 1, dp.scale),
+            *count,
+        );
+    }
+
+    buckets
+}
+
+/// The lower boundary of the positive bucket `index`, base^index.
+fn lower_boundary(index: i32, scale: i8) -> f64 {
+    (f64::from(index) * 2f64.powi(-i32::from(scale))).exp2()
+}
+
+fn encode_span(offset: i32, length: u32) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let mut os = CodedOutputStream::vec(&mut buf);
+    // Writing to a vec can't fail
+    let _ = os
+        .write_sint32(SPAN_OFFSET_FIELD, offset)
+        .and_then(|_| os.write_uint32(SPAN_LENGTH_FIELD, length))
+        .and_then(|_| os.flush());
+    drop(os);
+    buf
+}
+
+fn zigzag32(value: i32) -> u64 {
+    ((value << 1) ^ (value >> 31)) as u32 as u64
+}
+
+fn zigzag64(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_sdk::AttributeSet;
+    use std::time::SystemTime;
+
+    fn data_point(scale: i8, offset: i32, counts: Vec<u64>) -> ExponentialHistogramDataPoint<f64> {
+        ExponentialHistogramDataPoint {
+            attributes: AttributeSet::default(),
+            start_time: SystemTime::now(),
+            time: SystemTime::now(),
+            count: counts.iter().sum::<u64>() as usize,
+            min: None,
+            max: None,
+            sum: 0.0,
+            scale,
+            zero_count: 0,
+            positive_bucket: ExponentialBucket { offset, counts },
+            negative_bucket: ExponentialBucket {
+                offset: 0,
+                counts: vec![],
+            },
+            zero_threshold: 0.0,
+            exemplars: vec![],
+        }
+    }
+
+    #[test]
+    fn spans_split_on_empty_buckets() {
+        assert_eq!(
+            spans_and_deltas(2, &[1, 2, 0, 0, 1, 0, 0, 0, 3, 0]),
+            (vec![(2, 5), (3, 1)], vec![1, 1, -2, 0, 1, 2])
+        );
+        assert_eq!(spans_and_deltas(-3, &[0, 0, 4]), (vec![(-1, 1)], vec![4]));
+        assert_eq!(spans_and_deltas(0, &[]), (vec![], vec![]));
+    }
+
+    #[test]
+    fn downscale_merges_buckets() {
+        let bucket = ExponentialBucket {
+            offset: -3,
+            counts: vec![1, 2, 3, 4, 5],
+        };
+        // indexes -3..=1 map to -2, -1, -1, 0, 0 at a scale one lower
+        assert_eq!(downscale(&bucket, 1), (-2, vec![1, 5, 9]));
+        assert_eq!(downscale(&bucket, 0), (-3, vec![1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn native_fields() {
+        let h = histogram(&data_point(10, 1020, vec![1, 1, 1, 1, 1]));
+
+        let schema = h.unknown_fields.get(SCHEMA_FIELD).expect("schema");
+        assert_eq!(schema.varint, vec![zigzag32(8)]);
+        // buckets 1020..=1024 at scale 10 are buckets 255 and 256 at scale 8
+        let spans = h.unknown_fields.get(POSITIVE_SPAN_FIELD).expect("spans");
+        assert_eq!(spans.length_delimited, vec![encode_span(256, 2)]);
+        let deltas = h.unknown_fields.get(POSITIVE_DELTA_FIELD).expect("deltas");
+        assert_eq!(deltas.varint, vec![zigzag64(4), zigzag64(-3)]);
+
+        let empty = histogram(&data_point(0, 0, vec![]));
+        let spans = empty
+            .unknown_fields
+            .get(POSITIVE_SPAN_FIELD)
+            .expect("spans");
+        assert_eq!(spans.length_delimited, vec![encode_span(0, 0)]);
+
+        let coarse = histogram(&data_point(-5, 0, vec![1]));
+        assert!(coarse.unknown_fields.get(SCHEMA_FIELD).is_none());
+        assert_eq!(coarse.get_bucket().len(), 2);
+    }
+
+    #[test]
+    fn classic_bucket_bounds() {
+        let mut dp = data_point(0, 1, vec![1, 2]);
+        dp.negative_bucket = ExponentialBucket {
+            offset: 0,
+            counts: vec![3],
+        };
+        dp.zero_count = 4;
+
+        let buckets = classic_buckets(&dp)
+            .iter()
+            .map(|b| (b.get_upper_bound(), b.get_cumulative_count()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![(-1.0, 3), (0.0, 7), (4.0, 8), (8.0, 10)],
+            "negative, zero and positive buckets expected in order"
+        );
+    }
+
+    #[test]
+    fn encodes_zigzag() {
+        assert_eq!(zigzag32(0), 0);
+        assert_eq!(zigzag32(-1), 1);
+        assert_eq!(zigzag32(1), 2);
+        assert_eq!(zigzag64(-2), 3);
+    }
+}
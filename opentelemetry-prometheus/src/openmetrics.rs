@@ -0,0 +1,728 @@
+//! Encoding of metrics in the [OpenMetrics] 1.0 text format.
+//!
+//! Unlike the Prometheus text format, OpenMetrics exposes when counters and
+//! histograms were created, the unit of metric families and exemplars. Metrics
+//! are rendered from [ResourceMetrics] directly since the `prometheus` crate has
+//! no OpenMetrics support.
+//!
+//! [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::{self, Write as _},
+    io,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use opentelemetry::{
+    global,
+    metrics::{MetricsError, Result},
+};
+use opentelemetry_sdk::{
+    metrics::{
+        data::{self, Exemplar, ResourceMetrics},
+        reader::MetricReader,
+    },
+    Resource,
+};
+use prometheus::proto::LabelPair;
+
+use crate::{
+    get_attrs, native_histogram, scope_labels, utils, Collector, Numeric, COUNTER_SUFFIX,
+    EXPONENTIAL_HISTOGRAM_TYPES, HISTOGRAM_TYPES, SCOPE_INFO_DESCRIPTION, TARGET_INFO_DESCRIPTION,
+};
+
+/// The content type of the OpenMetrics text format.
+pub const OPENMETRICS_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+const TARGET_INFO_FAMILY: &str = "target";
+const SCOPE_INFO_FAMILY: &str = "otel_scope";
+const INFO_SUFFIX: &str = "_info";
+const CREATED_SUFFIX: &str = "_created";
+
+/// The maximum combined length of the names and values of exemplar labels.
+const MAX_EXEMPLAR_LABELS_LENGTH: usize = 128;
+
+/// Encodes the metrics of a [PrometheusExporter] in the OpenMetrics text format.
+///
+/// Metrics are named, labeled and filtered with the exporter's configuration,
+/// except for [ExporterBuilder::without_counter_suffixes], as OpenMetrics
+/// counter samples always have the `_total` suffix.
+///
+/// # Example
+///
+/// ```
+/// use opentelemetry::metrics::MeterProvider;
+/// use opentelemetry_sdk::metrics::SdkMeterProvider;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = opentelemetry_prometheus::exporter()
+///     .with_registry(prometheus::Registry::new())
+///     .build()?;
+/// let encoder = exporter.openmetrics_encoder();
+/// let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+///
+/// let counter = provider.meter("my-app").u64_counter("a.counter").init();
+/// counter.add(100, &[]);
+///
+/// let mut result = Vec::new();
+/// encoder.encode(&mut result)?;
+///
+/// // result now contains encoded metrics:
+/// //
+/// // # TYPE a_counter counter
+/// // a_counter_total{otel_scope_name="my-app"} 100.0
+/// // a_counter_created{otel_scope_name="my-app"} 1712345678.123456
+/// // ...
+/// // # EOF
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [PrometheusExporter]: crate::PrometheusExporter
+/// [ExporterBuilder::without_counter_suffixes]: crate::ExporterBuilder::without_counter_suffixes
+#[derive(Clone)]
+pub struct OpenMetricsEncoder {
+    collector: Arc<Collector>,
+}
+
+impl fmt::Debug for OpenMetricsEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpenMetricsEncoder").finish_non_exhaustive()
+    }
+}
+
+impl OpenMetricsEncoder {
+    pub(crate) fn new(collector: Arc<Collector>) -> Self {
+        OpenMetricsEncoder { collector }
+    }
+
+    /// Collects the metrics of the exporter and writes them to `writer`.
+    pub fn encode(&self, writer: &mut dyn io::Write) -> Result<()> {
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![],
+        };
+        self.collector.reader.collect(&mut metrics)?;
+        self.encode_resource_metrics(&metrics, writer)
+    }
+
+    /// Writes the given metrics to `writer`.
+    pub fn encode_resource_metrics(
+        &self,
+        metrics: &ResourceMetrics,
+        writer: &mut dyn io::Write,
+    ) -> Result<()> {
+        let mut families = Families::default();
+        self.add_metrics(metrics, &mut families);
+
+        let mut out = String::new();
+        for family in families.families {
+            family.write(&mut out);
+        }
+        out.push_str("# EOF\n");
+
+        writer
+            .write_all(out.as_bytes())
+            .map_err(|err| MetricsError::Other(err.to_string()))
+    }
+
+    fn add_metrics(&self, metrics: &ResourceMetrics, families: &mut Families) {
+        let converter = &self.collector.converter;
+
+        if !converter.disable_target_info && !metrics.resource.is_empty() {
+            let labels = get_attrs(&mut metrics.resource.iter(), &[]);
+            if let Some(samples) = families.get(
+                TARGET_INFO_FAMILY,
+                FamilyType::Info,
+                TARGET_INFO_DESCRIPTION,
+                None,
+            ) {
+                write_sample(
+                    samples,
+                    TARGET_INFO_FAMILY,
+                    INFO_SUFFIX,
+                    &labels,
+                    None,
+                    "1",
+                    None,
+                );
+            }
+        }
+
+        let resource_labels = converter.resource_selector.select(&metrics.resource);
+        let mut scopes = HashSet::new();
+        for scope_metrics in &metrics.scope_metrics {
+            let scope = &scope_metrics.scope;
+            if !converter.disable_scope_info && !scope.attributes.is_empty() && scopes.insert(scope)
+            {
+                let labels = scope_labels(scope, &[]);
+                if let Some(samples) = families.get(
+                    SCOPE_INFO_FAMILY,
+                    FamilyType::Info,
+                    SCOPE_INFO_DESCRIPTION,
+                    None,
+                ) {
+                    write_sample(
+                        samples,
+                        SCOPE_INFO_FAMILY,
+                        INFO_SUFFIX,
+                        &labels,
+                        None,
+                        "1",
+                        None,
+                    );
+                }
+            }
+
+            let scope_labels = if converter.disable_scope_info {
+                Vec::new()
+            } else {
+                scope_labels(scope, &resource_labels)
+            };
+
+            for metric in &scope_metrics.metrics {
+                self.add_metric(metric, &scope_labels, families);
+            }
+        }
+    }
+
+    fn add_metric(&self, metric: &data::Metric, extra: &[LabelPair], families: &mut Families) {
+        let mut name = self.collector.converter.get_name(metric);
+        let unit = if self.collector.converter.without_units {
+            None
+        } else {
+            utils::get_unit_suffixes(&metric.unit)
+        };
+
+        let data = metric.data.as_any();
+        let family_type = if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+            FamilyType::of_sum(sum)
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+            FamilyType::of_sum(sum)
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+            FamilyType::of_sum(sum)
+        } else if data.is::<data::Gauge<u64>>()
+            || data.is::<data::Gauge<i64>>()
+            || data.is::<data::Gauge<f64>>()
+        {
+            FamilyType::Gauge
+        } else if HISTOGRAM_TYPES.contains(&data.type_id())
+            || EXPONENTIAL_HISTOGRAM_TYPES.contains(&data.type_id())
+        {
+            FamilyType::Histogram
+        } else {
+            return;
+        };
+
+        // The name of counter families excludes the suffix of their samples
+        if family_type == FamilyType::Counter {
+            if let Some(stripped) = name.strip_suffix(COUNTER_SUFFIX) {
+                name = stripped.to_string().into();
+            }
+        }
+        let unit = unit.filter(|unit| name.ends_with(&format!("_{unit}")));
+
+        let samples = match families.get(&name, family_type, &metric.description, unit) {
+            Some(samples) => samples,
+            None => return,
+        };
+
+        if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+            write_sum(samples, &name, sum, extra);
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
+            write_sum(samples, &name, sum, extra);
+        } else if let Some(sum) = data.downcast_ref::<data::Sum<f64>>() {
+            write_sum(samples, &name, sum, extra);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<u64>>() {
+            write_gauge(samples, &name, &gauge.data_points, extra);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<i64>>() {
+            write_gauge(samples, &name, &gauge.data_points, extra);
+        } else if let Some(gauge) = data.downcast_ref::<data::Gauge<f64>>() {
+            write_gauge(samples, &name, &gauge.data_points, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
+            write_histogram(samples, &name, hist, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::Histogram<i64>>() {
+            write_histogram(samples, &name, hist, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
+            write_histogram(samples, &name, hist, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+            write_exponential_histogram(samples, &name, hist, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<i64>>() {
+            write_exponential_histogram(samples, &name, hist, extra);
+        } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+            write_exponential_histogram(samples, &name, hist, extra);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FamilyType {
+    Counter,
+    Gauge,
+    Histogram,
+    Info,
+}
+
+impl FamilyType {
+    fn of_sum<T>(sum: &data::Sum<T>) -> Self {
+        if sum.is_monotonic {
+            FamilyType::Counter
+        } else {
+            FamilyType::Gauge
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            FamilyType::Counter => "counter",
+            FamilyType::Gauge => "gauge",
+            FamilyType::Histogram => "histogram",
+            FamilyType::Info => "info",
+        }
+    }
+}
+
+struct Family {
+    name: String,
+    family_type: FamilyType,
+    help: String,
+    unit: Option<Cow<'static, str>>,
+    samples: String,
+}
+
+impl Family {
+    fn write(&self, out: &mut String) {
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.family_type.as_str());
+        if let Some(unit) = &self.unit {
+            let _ = writeln!(out, "# UNIT {} {unit}", self.name);
+        }
+        if !self.help.is_empty() {
+            let _ = writeln!(out, "# HELP {} {}", self.name, escape(&self.help));
+        }
+        out.push_str(&self.samples);
+    }
+}
+
+/// The metric families in the order they are first seen, as OpenMetrics
+/// requires the samples of a family to be contiguous.
+#[derive(Default)]
+struct Families {
+    families: Vec<Family>,
+    index: HashMap<String, usize>,
+}
+
+impl Families {
+    /// The samples of the family `name`, `None` if its metrics are dropped
+    /// because they conflict with the existing family type.
+    fn get(
+        &mut self,
+        name: &str,
+        family_type: FamilyType,
+        help: &str,
+        unit: Option<Cow<'static, str>>,
+    ) -> Option<&mut String> {
+        if let Some(&i) = self.index.get(name) {
+            let existing = &mut self.families[i];
+            if existing.family_type != family_type {
+                global::handle_error(MetricsError::Other(format!(
+                    "Instrument type conflict, using existing type definition. Instrument {name}, Existing: {:?}, dropped: {:?}",
+                    existing.family_type, family_type
+                )));
+                return None;
+            }
+            if existing.help != help {
+                global::handle_error(MetricsError::Other(format!(
+                    "Instrument description conflict, using existing. Instrument {name}, Existing: {:?}, dropped: {:?}",
+                    existing.help, help
+                )));
+            }
+            return Some(&mut existing.samples);
+        }
+
+        self.index.insert(name.to_string(), self.families.len());
+        self.families.push(Family {
+            name: name.to_string(),
+            family_type,
+            help: help.to_string(),
+            unit,
+            samples: String::new(),
+        });
+        self.families.last_mut().map(|family| &mut family.samples)
+    }
+}
+
+fn write_sum<T: Numeric>(out: &mut String, name: &str, sum: &data::Sum<T>, extra: &[LabelPair]) {
+    if !sum.is_monotonic {
+        return write_gauge(out, name, &sum.data_points, extra);
+    }
+
+    for dp in &sum.data_points {
+        let labels = get_attrs(&mut dp.attributes.iter(), extra);
+        let exemplar = latest(dp.exemplars.iter()).map(format_exemplar);
+        let value = format_float(dp.value.as_f64());
+        write_sample(out, name, COUNTER_SUFFIX, &labels, None, &value, exemplar);
+        if let Some(start_time) = dp.start_time {
+            let created = format_timestamp(start_time);
+            write_sample(out, name, CREATED_SUFFIX, &labels, None, &created, None);
+        }
+    }
+}
+
+fn write_gauge<T: Numeric>(
+    out: &mut String,
+    name: &str,
+    data_points: &[data::DataPoint<T>],
+    extra: &[LabelPair],
+) {
+    for dp in data_points {
+        let labels = get_attrs(&mut dp.attributes.iter(), extra);
+        let value = format_float(dp.value.as_f64());
+        write_sample(out, name, "", &labels, None, &value, None);
+    }
+}
+
+fn write_histogram<T: Numeric>(
+    out: &mut String,
+    name: &str,
+    histogram: &data::Histogram<T>,
+    extra: &[LabelPair],
+) {
+    for dp in &histogram.data_points {
+        let labels = get_attrs(&mut dp.attributes.iter(), extra);
+        let buckets = dp
+            .bounds
+            .iter()
+            .zip(&dp.bucket_counts)
+            .scan(0, |cumulative_count, (bound, count)| {
+                *cumulative_count += count;
+                Some((*bound, *cumulative_count))
+            })
+            .collect::<Vec<_>>();
+        let histogram = HistogramSamples {
+            buckets,
+            count: dp.count,
+            sum: dp.sum.as_f64(),
+            start_time: dp.start_time,
+            exemplars: &dp.exemplars,
+        };
+        histogram.write(out, name, &labels);
+    }
+}
+
+fn write_exponential_histogram<T: Numeric>(
+    out: &mut String,
+    name: &str,
+    histogram: &data::ExponentialHistogram<T>,
+    extra: &[LabelPair],
+) {
+    for dp in &histogram.data_points {
+        let labels = get_attrs(&mut dp.attributes.iter(), extra);
+        let buckets = native_histogram::classic_buckets(dp)
+            .iter()
+            .map(|b| (b.get_upper_bound(), b.get_cumulative_count()))
+            .collect();
+        let histogram = HistogramSamples {
+            buckets,
+            count: dp.count as u64,
+            sum: dp.sum.as_f64(),
+            start_time: dp.start_time,
+            exemplars: &dp.exemplars,
+        };
+        histogram.write(out, name, &labels);
+    }
+}
+
+/// The samples of a histogram data point, with its buckets as upper bounds
+/// and cumulative counts, excluding the `+Inf` bucket.
+struct HistogramSamples<'a, T> {
+    buckets: Vec<(f64, u64)>,
+    count: u64,
+    sum: f64,
+    start_time: SystemTime,
+    exemplars: &'a [Exemplar<T>],
+}
+
+impl<T: Numeric> HistogramSamples<'_, T> {
+    fn write(&self, out: &mut String, name: &str, labels: &[LabelPair]) {
+        // Each bucket has the latest exemplar of the values it holds
+        let mut exemplars: Vec<Option<&Exemplar<T>>> = vec![None; self.buckets.len() + 1];
+        for exemplar in self.exemplars {
+            let value = exemplar.value.as_f64();
+            let i = self
+                .buckets
+                .iter()
+                .position(|(bound, _)| value <= *bound)
+                .unwrap_or(self.buckets.len());
+            exemplars[i] = latest(exemplars[i].into_iter().chain(Some(exemplar)));
+        }
+
+        let buckets = self
+            .buckets
+            .iter()
+            .copied()
+            .chain(Some((f64::INFINITY, self.count)));
+        for ((bound, cumulative_count), exemplar) in buckets.zip(exemplars) {
+            let le = format_float(bound);
+            write_sample(
+                out,
+                name,
+                "_bucket",
+                labels,
+                Some(("le", &le)),
+                &cumulative_count.to_string(),
+                exemplar.map(format_exemplar),
+            );
+        }
+
+        let count = self.count.to_string();
+        write_sample(out, name, "_count", labels, None, &count, None);
+        // The sum is only a counter, and exposed, without negative buckets
+        if self
+            .buckets
+            .first()
+            .map_or(true, |(bound, _)| *bound >= 0.0)
+        {
+            let sum = format_float(self.sum);
+            write_sample(out, name, "_sum", labels, None, &sum, None);
+        }
+        let created = format_timestamp(self.start_time);
+        write_sample(out, name, CREATED_SUFFIX, labels, None, &created, None);
+    }
+}
+
+fn latest<'a, T: 'a>(exemplars: impl Iterator<Item = &'a Exemplar<T>>) -> Option<&'a Exemplar<T>> {
+    exemplars.max_by_key(|exemplar| exemplar.time)
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    suffix: &str,
+    labels: &[LabelPair],
+    extra_label: Option<(&str, &str)>,
+    value: &str,
+    exemplar: Option<String>,
+) {
+    out.push_str(name);
+    out.push_str(suffix);
+    let labels = labels
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .chain(extra_label);
+    write_labels(out, labels);
+    out.push(' ');
+    out.push_str(value);
+    if let Some(exemplar) = exemplar {
+        out.push_str(" # ");
+        out.push_str(&exemplar);
+    }
+    out.push('\n');
+}
+
+fn write_labels<'a>(out: &mut String, labels: impl Iterator<Item = (&'a str, &'a str)>) {
+    let mut labels = labels.peekable();
+    if labels.peek().is_none() {
+        return;
+    }
+    out.push('{');
+    for (i, (name, value)) in labels.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{name}=\"{}\"", escape(value));
+    }
+    out.push('}');
+}
+
+/// Formats an exemplar as its label set, value and timestamp.
+///
+/// The filtered attributes are left out when the labels would exceed the
+/// length OpenMetrics allows.
+fn format_exemplar<T: Numeric>(exemplar: &Exemplar<T>) -> String {
+    let mut labels = Vec::with_capacity(2);
+    if exemplar.trace_id != [0; 16] {
+        labels.push(("trace_id".to_string(), hex(&exemplar.trace_id)));
+        labels.push(("span_id".to_string(), hex(&exemplar.span_id)));
+    }
+    let length = |labels: &[(String, String)]| {
+        labels
+            .iter()
+            .map(|(name, value)| name.chars().count() + value.chars().count())
+            .sum::<usize>()
+    };
+
+    let mut all_labels = labels.clone();
+    let attrs = get_attrs(
+        &mut exemplar
+            .filtered_attributes
+            .iter()
+            .map(|kv| (&kv.key, &kv.value)),
+        &[],
+    );
+    all_labels.extend(
+        attrs
+            .into_iter()
+            .map(|mut label| (label.take_name(), label.take_value())),
+    );
+    if length(&all_labels) <= MAX_EXEMPLAR_LABELS_LENGTH {
+        labels = all_labels;
+    }
+
+    let mut out = String::new();
+    out.push('{');
+    for (i, (name, value)) in labels.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{name}=\"{}\"", escape(value));
+    }
+    out.push('}');
+    let _ = write!(
+        out,
+        " {} {}",
+        format_float(exemplar.value.as_f64()),
+        format_timestamp(exemplar.time)
+    );
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+/// Escapes label values and help texts.
+fn escape(s: &str) -> Cow<'_, str> {
+    if !s.contains(['\\', '"', '\n']) {
+        return Cow::Borrowed(s);
+    }
+    let mut escaped = String::with_capacity(s.len() + 2);
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Formats a number, integral values keeping a fractional part as OpenMetrics
+/// requires for `le` label values.
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.1}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats a time as seconds since the Unix epoch.
+fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    format_float(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn formats_floats() {
+        assert_eq!(format_float(1.0), "1.0");
+        assert_eq!(format_float(-0.25), "-0.25");
+        assert_eq!(format_float(1e20), "100000000000000000000");
+        assert_eq!(format_float(f64::INFINITY), "+Inf");
+        assert_eq!(format_float(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_float(f64::NAN), "NaN");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_millis(1_500)),
+            "1.5"
+        );
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape("plain"), Cow::Borrowed("plain"));
+        assert_eq!(escape("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+
+    #[test]
+    fn exemplar_attributes_dropped_when_too_long() {
+        let mut exemplar = Exemplar {
+            filtered_attributes: vec![opentelemetry::KeyValue::new("user", "alice")],
+            time: UNIX_EPOCH + Duration::from_secs(2),
+            value: 3u64,
+            span_id: [1; 8],
+            trace_id: [2; 16],
+        };
+        assert_eq!(
+            format_exemplar(&exemplar),
+            concat!(
+                "{trace_id=\"02020202020202020202020202020202\",",
+                "span_id=\"0101010101010101\",user=\"alice\"} 3.0 2.0"
+            )
+        );
+
+        exemplar.filtered_attributes = vec![opentelemetry::KeyValue::new("user", "a".repeat(100))];
+        assert_eq!(
+            format_exemplar(&exemplar),
+            concat!(
+                "{trace_id=\"02020202020202020202020202020202\",",
+                "span_id=\"0101010101010101\"} 3.0 2.0"
+            )
+        );
+
+        exemplar.trace_id = [0; 16];
+        exemplar.filtered_attributes.clear();
+        assert_eq!(format_exemplar(&exemplar), "{} 3.0 2.0");
+    }
+
+    #[test]
+    fn histogram_exemplars_in_buckets() {
+        let exemplar = |value: f64, secs: u64| Exemplar {
+            filtered_attributes: vec![],
+            time: UNIX_EPOCH + Duration::from_secs(secs),
+            value,
+            span_id: [0; 8],
+            trace_id: [0; 16],
+        };
+        let exemplars = [exemplar(1.0, 1), exemplar(0.5, 2), exemplar(20.0, 3)];
+        let samples = HistogramSamples {
+            buckets: vec![(1.0, 2), (10.0, 2)],
+            count: 3,
+            sum: 21.5,
+            start_time: UNIX_EPOCH,
+            exemplars: &exemplars,
+        };
+
+        let mut out = String::new();
+        samples.write(&mut out, "h", &[]);
+        assert_eq!(
+            out,
+            concat!(
+                "h_bucket{le=\"1.0\"} 2 # {} 0.5 2.0\n",
+                "h_bucket{le=\"10.0\"} 2\n",
+                "h_bucket{le=\"+Inf\"} 3 # {} 20.0 3.0\n",
+                "h_count 3\n",
+                "h_sum 21.5\n",
+                "h_created 0.0\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,655 @@
+//! An exporter pushing metrics with the Prometheus [remote-write] protocol.
+//!
+//! [remote-write]: https://prometheus.io/docs/concepts/remote_write_spec/
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use http::{
+    header::{HeaderName, HeaderValue, CONTENT_ENCODING, CONTENT_TYPE},
+    HeaderMap, Method, Uri,
+};
+use opentelemetry::metrics::{MetricsError, Result};
+use opentelemetry_http::{HttpClient, ResponseExt};
+use opentelemetry_sdk::metrics::{
+    data::{ResourceMetrics, Temporality},
+    exporter::PushMetricsExporter,
+    reader::{AggregationSelector, DefaultAggregationSelector, TemporalitySelector},
+    Aggregation, InstrumentKind,
+};
+use prometheus::proto::{Histogram, LabelPair, MetricFamily};
+use prost::Message;
+
+use crate::{utils, CollectorInner, Converter, ResourceSelector};
+
+mod proto;
+
+use proto::{Label, MetricMetadata, MetricType, Sample, TimeSeries, WriteRequest};
+
+const NAME_LABEL: &str = "__name__";
+const REMOTE_WRITE_VERSION_HEADER: &str = "x-prometheus-remote-write-version";
+const REMOTE_WRITE_VERSION: &str = "0.1.0";
+
+/// An exporter pushing metrics to a Prometheus [remote-write] endpoint, such as
+/// the ones of Prometheus, Mimir, Thanos or Cortex.
+///
+/// Metrics are named and labeled as by the [PrometheusExporter], and sent as
+/// cumulative series, snappy compressed, with the given [HttpClient].
+///
+/// The exporter is typically registered with a meter provider through a
+/// [PeriodicReader], which exports metrics at a fixed interval and when the
+/// provider is shut down, making it suitable for short-lived jobs.
+///
+/// # Example
+///
+/// ```no_run
+/// use opentelemetry_http::HttpClient;
+/// use opentelemetry_prometheus::RemoteWriteExporter;
+///
+/// # fn build(client: impl HttpClient + 'static) -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = RemoteWriteExporter::builder()
+///     .with_endpoint("http://localhost:9009/api/v1/push")
+///     .with_http_client(client)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [remote-write]: https://prometheus.io/docs/concepts/remote_write_spec/
+/// [PrometheusExporter]: crate::PrometheusExporter
+/// [PeriodicReader]: opentelemetry_sdk::metrics::PeriodicReader
+pub struct RemoteWriteExporter {
+    endpoint: Uri,
+    headers: HeaderMap,
+    client: Mutex<Option<Arc<dyn HttpClient>>>,
+    converter: Converter,
+    inner: Mutex<CollectorInner>,
+    aggregation_selector: Box<dyn AggregationSelector>,
+}
+
+impl RemoteWriteExporter {
+    /// Creates a builder to configure a [RemoteWriteExporter].
+    pub fn builder() -> RemoteWriteExporterBuilder {
+        RemoteWriteExporterBuilder::default()
+    }
+}
+
+impl fmt::Debug for RemoteWriteExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWriteExporter")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TemporalitySelector for RemoteWriteExporter {
+    /// Note: Prometheus only supports cumulative temporality so this will always be
+    /// [Temporality::Cumulative].
+    fn temporality(&self, _kind: InstrumentKind) -> Temporality {
+        Temporality::Cumulative
+    }
+}
+
+impl AggregationSelector for RemoteWriteExporter {
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.aggregation_selector.aggregation(kind)
+    }
+}
+
+#[async_trait]
+impl PushMetricsExporter for RemoteWriteExporter {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> Result<()> {
+        let client = self
+            .client
+            .lock()?
+            .clone()
+            .ok_or_else(|| MetricsError::Other("exporter is already shut down".into()))?;
+
+        let write_request = {
+            let mut inner = self.inner.lock()?;
+            write_request(&self.converter, metrics, &mut inner, SystemTime::now())
+        };
+        if write_request.timeseries.is_empty() {
+            return Ok(());
+        }
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&write_request.encode_to_vec())
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+
+        let mut request = http::Request::builder()
+            .method(Method::POST)
+            .uri(&self.endpoint)
+            .header(CONTENT_TYPE, "application/x-protobuf")
+            .header(CONTENT_ENCODING, "snappy")
+            .header(REMOTE_WRITE_VERSION_HEADER, REMOTE_WRITE_VERSION)
+            .body(body)
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+        request.headers_mut().extend(self.headers.clone());
+
+        client
+            .send(request)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| MetricsError::Other(format!("remote write failed: {err}")))?;
+        Ok(())
+    }
+
+    async fn force_flush(&self) -> Result<()> {
+        // exporter holds no state, nothing to flush
+        Ok(())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        let _ = self.client.lock()?.take();
+        Ok(())
+    }
+}
+
+/// [RemoteWriteExporter] configuration options
+#[derive(Default)]
+pub struct RemoteWriteExporterBuilder {
+    endpoint: Option<String>,
+    client: Option<Arc<dyn HttpClient>>,
+    headers: HashMap<String, String>,
+    converter: Converter,
+    aggregation_selector: Option<Box<dyn AggregationSelector>>,
+}
+
+impl fmt::Debug for RemoteWriteExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWriteExporterBuilder")
+            .field("endpoint", &self.endpoint)
+            .field("client", &self.client)
+            .field("namespace", &self.converter.namespace)
+            .field("without_units", &self.converter.without_units)
+            .field(
+                "without_counter_suffixes",
+                &self.converter.without_counter_suffixes,
+            )
+            .field("disable_target_info", &self.converter.disable_target_info)
+            .field("disable_scope_info", &self.converter.disable_scope_info)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RemoteWriteExporterBuilder {
+    /// The URL of the remote-write endpoint, e.g. `http://localhost:9090/api/v1/write`.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// The [HttpClient] sending the requests.
+    pub fn with_http_client(mut self, client: impl HttpClient + 'static) -> Self {
+        self.client = Some(Arc::new(client));
+        self
+    }
+
+    /// Headers added to every request, e.g. for authentication or tenancy.
+    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Prefixes metrics with the given namespace, as with
+    /// [ExporterBuilder::with_namespace].
+    ///
+    /// [ExporterBuilder::with_namespace]: crate::ExporterBuilder::with_namespace
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        let mut namespace = namespace.into();
+        if !namespace.ends_with('_') {
+            namespace.push('_')
+        }
+        self.converter.namespace = Some(namespace);
+        self
+    }
+
+    /// Disables the addition of unit suffixes to metric names, as with
+    /// [ExporterBuilder::without_units].
+    ///
+    /// [ExporterBuilder::without_units]: crate::ExporterBuilder::without_units
+    pub fn without_units(mut self) -> Self {
+        self.converter.without_units = true;
+        self
+    }
+
+    /// Disables the addition of `_total` suffixes to counters, as with
+    /// [ExporterBuilder::without_counter_suffixes].
+    ///
+    /// [ExporterBuilder::without_counter_suffixes]: crate::ExporterBuilder::without_counter_suffixes
+    pub fn without_counter_suffixes(mut self) -> Self {
+        self.converter.without_counter_suffixes = true;
+        self
+    }
+
+    /// Disables the `target_info` series of the resource attributes.
+    pub fn without_target_info(mut self) -> Self {
+        self.converter.disable_target_info = true;
+        self
+    }
+
+    /// Disables the `otel_scope_info` series and the scope labels of all series.
+    pub fn without_scope_info(mut self) -> Self {
+        self.converter.disable_scope_info = true;
+        self
+    }
+
+    /// Configures which resource attributes are added as labels of every
+    /// series, see [ResourceSelector].
+    pub fn with_resource_selector(
+        mut self,
+        resource_selector: impl Into<ResourceSelector>,
+    ) -> Self {
+        self.converter.resource_selector = resource_selector.into();
+        self
+    }
+
+    /// Configure the [AggregationSelector] the exporter will use.
+    ///
+    /// If no selector is provided, the [DefaultAggregationSelector] is used.
+    pub fn with_aggregation_selector(mut self, agg: impl AggregationSelector + 'static) -> Self {
+        self.aggregation_selector = Some(Box::new(agg));
+        self
+    }
+
+    /// Creates a new [RemoteWriteExporter] from this configuration.
+    ///
+    /// Fails if the endpoint or the HTTP client are missing, or if the endpoint
+    /// or a header is invalid.
+    pub fn build(self) -> Result<RemoteWriteExporter> {
+        let endpoint = self
+            .endpoint
+            .ok_or_else(|| MetricsError::Config("remote write endpoint is required".into()))?
+            .parse::<Uri>()
+            .map_err(|err| MetricsError::Config(format!("invalid remote write endpoint: {err}")))?;
+        let client = self
+            .client
+            .ok_or_else(|| MetricsError::Config("remote write HTTP client is required".into()))?;
+
+        let mut headers = HeaderMap::with_capacity(self.headers.len());
+        for (name, value) in self.headers {
+            let name = HeaderName::try_from(name)
+                .map_err(|err| MetricsError::Config(format!("invalid header name: {err}")))?;
+            let value = HeaderValue::try_from(value)
+                .map_err(|err| MetricsError::Config(format!("invalid header value: {err}")))?;
+            headers.insert(name, value);
+        }
+
+        Ok(RemoteWriteExporter {
+            endpoint,
+            headers,
+            client: Mutex::new(Some(client)),
+            converter: Converter {
+                with_timestamps: true,
+                ..self.converter
+            },
+            inner: Mutex::new(Default::default()),
+            aggregation_selector: self
+                .aggregation_selector
+                .unwrap_or_else(|| Box::new(DefaultAggregationSelector::new())),
+        })
+    }
+}
+
+/// Converts metrics to the series of a [WriteRequest], with the metric
+/// families the `converter` converts them to for the [PrometheusExporter].
+///
+/// Series without timestamps are timestamped with `now`.
+///
+/// [PrometheusExporter]: crate::PrometheusExporter
+fn write_request(
+    converter: &Converter,
+    metrics: &ResourceMetrics,
+    inner: &mut CollectorInner,
+    now: SystemTime,
+) -> WriteRequest {
+    let units = units(converter, metrics);
+    let mut request = RequestBuilder {
+        request: WriteRequest::default(),
+        families: HashSet::new(),
+        now: utils::timestamp_millis(now),
+    };
+    for family in converter.metric_families(metrics, inner) {
+        let unit = units.get(family.get_name()).map_or("", |unit| unit);
+        request.add_family(&family, unit);
+    }
+    request.request
+}
+
+/// The unit suffixes of the metric families of `metrics`, by family name.
+fn units(converter: &Converter, metrics: &ResourceMetrics) -> HashMap<String, Cow<'static, str>> {
+    if converter.without_units {
+        return HashMap::new();
+    }
+    metrics
+        .scope_metrics
+        .iter()
+        .flat_map(|scope_metrics| &scope_metrics.metrics)
+        .filter_map(|metric| {
+            let (_, name) = converter.metric_type_and_name(metric)?;
+            let unit = utils::get_unit_suffixes(&metric.unit)?;
+            Some((name.into_owned(), unit))
+        })
+        .collect()
+}
+
+struct RequestBuilder {
+    request: WriteRequest,
+    families: HashSet<String>,
+    now: i64,
+}
+
+impl RequestBuilder {
+    /// Adds the series of `family`, and its metadata if it is the first
+    /// family of its name.
+    fn add_family(&mut self, family: &MetricFamily, unit: &str) {
+        let name = family.get_name();
+        let metric_type = match family.get_field_type() {
+            prometheus::proto::MetricType::COUNTER => MetricType::Counter,
+            prometheus::proto::MetricType::GAUGE => MetricType::Gauge,
+            prometheus::proto::MetricType::HISTOGRAM => MetricType::Histogram,
+            _ => MetricType::Unknown,
+        };
+        if self.families.insert(name.to_string()) {
+            self.request.metadata.push(MetricMetadata {
+                r#type: metric_type.into(),
+                metric_family_name: name.to_string(),
+                help: family.get_help().to_string(),
+                unit: unit.to_string(),
+            });
+        }
+
+        for metric in family.get_metric() {
+            let labels = metric.get_label();
+            let time = if metric.has_timestamp_ms() {
+                metric.get_timestamp_ms()
+            } else {
+                self.now
+            };
+            match metric_type {
+                MetricType::Counter => {
+                    let value = metric.get_counter().get_value();
+                    self.push(name, labels, None, value, time);
+                }
+                MetricType::Gauge => {
+                    let value = metric.get_gauge().get_value();
+                    self.push(name, labels, None, value, time);
+                }
+                MetricType::Histogram => {
+                    self.push_histogram(name, labels, metric.get_histogram(), time)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Pushes the series of a histogram: its cumulative buckets, followed by
+    /// the `+Inf` bucket, its count and its sum.
+    fn push_histogram(
+        &mut self,
+        name: &str,
+        labels: &[LabelPair],
+        histogram: &Histogram,
+        time: i64,
+    ) {
+        let count = histogram.get_sample_count();
+        let buckets = histogram
+            .get_bucket()
+            .iter()
+            .map(|b| (b.get_upper_bound(), b.get_cumulative_count()));
+
+        let bucket_name = format!("{name}_bucket");
+        for (bound, cumulative_count) in buckets.chain(Some((f64::INFINITY, count))) {
+            let le = if bound == f64::INFINITY {
+                "+Inf".to_string()
+            } else {
+                bound.to_string()
+            };
+            let value = cumulative_count as f64;
+            self.push(&bucket_name, labels, Some(("le", le)), value, time);
+        }
+        self.push(&format!("{name}_count"), labels, None, count as f64, time);
+        let sum = histogram.get_sample_sum();
+        self.push(&format!("{name}_sum"), labels, None, sum, time);
+    }
+
+    fn push(
+        &mut self,
+        name: &str,
+        labels: &[LabelPair],
+        extra_label: Option<(&str, String)>,
+        value: f64,
+        timestamp: i64,
+    ) {
+        let mut series_labels = Vec::with_capacity(labels.len() + 2);
+        series_labels.push(Label {
+            name: NAME_LABEL.to_string(),
+            value: name.to_string(),
+        });
+        series_labels.extend(labels.iter().map(|label| Label {
+            name: label.get_name().to_string(),
+            value: label.get_value().to_string(),
+        }));
+        if let Some((name, value)) = extra_label {
+            series_labels.push(Label {
+                name: name.to_string(),
+                value,
+            });
+        }
+        // Remote-write requires labels sorted by name
+        series_labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.request.timeseries.push(TimeSeries {
+            labels: series_labels,
+            samples: vec![Sample { value, timestamp }],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::KeyValue;
+    use opentelemetry_http::{Bytes, HttpError, Request, Response};
+    use opentelemetry_sdk::{
+        metrics::data::{self, DataPoint, HistogramDataPoint, Metric, ScopeMetrics},
+        AttributeSet, Resource, Scope,
+    };
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingClient {
+        requests: Arc<Mutex<Vec<Request<Vec<u8>>>>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for RecordingClient {
+        async fn send(
+            &self,
+            request: Request<Vec<u8>>,
+        ) -> std::result::Result<Response<Bytes>, HttpError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(Response::new(Bytes::new()))
+        }
+    }
+
+    fn resource_metrics(time: SystemTime) -> ResourceMetrics {
+        let attributes = AttributeSet::from(&[KeyValue::new("A", "B")][..]);
+        ResourceMetrics {
+            resource: Resource::new(vec![KeyValue::new("service.name", "batch_job")]),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Scope::builder("testmeter").build(),
+                metrics: vec![
+                    Metric {
+                        name: "foo".into(),
+                        description: "a simple counter".into(),
+                        unit: opentelemetry::metrics::Unit::new("ms"),
+                        data: Box::new(data::Sum {
+                            data_points: vec![DataPoint {
+                                attributes: attributes.clone(),
+                                start_time: Some(time),
+                                time: Some(time),
+                                value: 5u64,
+                                exemplars: vec![],
+                            }],
+                            temporality: Temporality::Cumulative,
+                            is_monotonic: true,
+                        }),
+                    },
+                    Metric {
+                        name: "baz".into(),
+                        description: "a histogram".into(),
+                        unit: opentelemetry::metrics::Unit::new(""),
+                        data: Box::new(data::Histogram {
+                            data_points: vec![HistogramDataPoint {
+                                attributes,
+                                start_time: time,
+                                time,
+                                count: 2,
+                                bounds: vec![1.5],
+                                bucket_counts: vec![1, 1],
+                                min: None,
+                                max: None,
+                                sum: 4.0,
+                                exemplars: vec![],
+                            }],
+                            temporality: Temporality::Cumulative,
+                        }),
+                    },
+                ],
+            }],
+        }
+    }
+
+    fn series(request: &WriteRequest) -> Vec<(String, f64, i64)> {
+        request
+            .timeseries
+            .iter()
+            .map(|ts| {
+                let labels = ts
+                    .labels
+                    .iter()
+                    .map(|l| format!("{}={}", l.name, l.value))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                (labels, ts.samples[0].value, ts.samples[0].timestamp)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pushes_snappy_compressed_write_request() {
+        let client = RecordingClient::default();
+        let exporter = RemoteWriteExporter::builder()
+            .with_endpoint("http://localhost:9090/api/v1/write")
+            .with_http_client(client.clone())
+            .with_headers(HashMap::from([(
+                "X-Scope-OrgID".to_string(),
+                "tenant".to_string(),
+            )]))
+            .build()
+            .unwrap();
+
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        exporter.export(&mut resource_metrics(time)).await.unwrap();
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(request.uri(), "http://localhost:9090/api/v1/write");
+        assert_eq!(request.headers()[CONTENT_ENCODING], "snappy");
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/x-protobuf");
+        assert_eq!(request.headers()[REMOTE_WRITE_VERSION_HEADER], "0.1.0");
+        assert_eq!(request.headers()["x-scope-orgid"], "tenant");
+
+        let body = snap::raw::Decoder::new()
+            .decompress_vec(request.body())
+            .unwrap();
+        let write_request = WriteRequest::decode(body.as_slice()).unwrap();
+        let ms = 1_700_000_000_123;
+        let scope = "otel_scope_name=testmeter";
+        assert_eq!(
+            series(&write_request)[1..],
+            [
+                (
+                    format!("A=B,__name__=foo_milliseconds_total,{scope}"),
+                    5.0,
+                    ms
+                ),
+                (format!("A=B,__name__=baz_bucket,le=1.5,{scope}"), 1.0, ms),
+                (format!("A=B,__name__=baz_bucket,le=+Inf,{scope}"), 2.0, ms),
+                (format!("A=B,__name__=baz_count,{scope}"), 2.0, ms),
+                (format!("A=B,__name__=baz_sum,{scope}"), 4.0, ms),
+            ]
+        );
+        let (target_info, value, _) = &series(&write_request)[0];
+        assert_eq!(target_info, "__name__=target_info,service_name=batch_job");
+        assert_eq!(*value, 1.0);
+
+        let metadata = &write_request.metadata[1];
+        assert_eq!(metadata.r#type, MetricType::Counter as i32);
+        assert_eq!(metadata.metric_family_name, "foo_milliseconds_total");
+        assert_eq!(metadata.help, "a simple counter");
+        assert_eq!(metadata.unit, "milliseconds");
+    }
+
+    #[test]
+    fn applies_naming_options() {
+        let converter = Converter {
+            namespace: Some("job_".into()),
+            without_units: true,
+            without_counter_suffixes: true,
+            disable_target_info: true,
+            resource_selector: ResourceSelector::All,
+            with_timestamps: true,
+            ..Default::default()
+        };
+
+        let time = UNIX_EPOCH;
+        let write_request = write_request(
+            &converter,
+            &resource_metrics(time),
+            &mut CollectorInner::default(),
+            time,
+        );
+        assert_eq!(
+            series(&write_request)[0].0,
+            "A=B,__name__=job_foo,otel_scope_name=testmeter,service_name=batch_job"
+        );
+        assert_eq!(write_request.timeseries.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn fails_after_shutdown() {
+        let client = RecordingClient::default();
+        let exporter = RemoteWriteExporter::builder()
+            .with_endpoint("http://localhost:9090/api/v1/write")
+            .with_http_client(client.clone())
+            .build()
+            .unwrap();
+        exporter.shutdown().unwrap();
+
+        assert!(exporter
+            .export(&mut resource_metrics(UNIX_EPOCH))
+            .await
+            .is_err());
+        assert!(client.requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn requires_endpoint_and_client() {
+        assert!(RemoteWriteExporter::builder()
+            .with_http_client(RecordingClient::default())
+            .build()
+            .is_err());
+        assert!(RemoteWriteExporter::builder()
+            .with_endpoint("http://localhost:9090/api/v1/write")
+            .build()
+            .is_err());
+    }
+}
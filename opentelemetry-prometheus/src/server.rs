@@ -0,0 +1,301 @@
+//! A built-in HTTP server exposing the metrics of a registry to be scraped.
+use std::{
+    convert::Infallible,
+    io::Write,
+    net::{SocketAddr, TcpListener},
+    sync::Mutex,
+    thread,
+};
+
+use flate2::{write::GzEncoder, Compression};
+use hyper::{
+    header::{HeaderValue, ACCEPT, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use opentelemetry::{
+    global,
+    metrics::{MetricsError, Result},
+};
+use prometheus::{Encoder, ProtobufEncoder, Registry, TextEncoder};
+use tokio::sync::oneshot;
+
+use crate::OpenMetricsEncoder;
+
+/// The path metrics are served on.
+pub(crate) const METRICS_PATH: &str = "/metrics";
+/// The path of the health check, answering `200 OK` while the server runs.
+pub(crate) const HEALTH_PATH: &str = "/health";
+
+/// A scrape server running on its own thread until it is shut down or dropped.
+#[derive(Debug)]
+pub(crate) struct ScrapeServer {
+    local_addr: SocketAddr,
+    running: Mutex<Option<(oneshot::Sender<()>, thread::JoinHandle<()>)>>,
+}
+
+impl ScrapeServer {
+    /// Binds `addr` and starts serving the metrics of `registry`, or the ones
+    /// of `openmetrics` in the OpenMetrics format.
+    pub(crate) fn start(
+        addr: SocketAddr,
+        registry: Registry,
+        openmetrics: OpenMetricsEncoder,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|err| {
+                MetricsError::Other(format!("failed to bind scrape server to {addr}: {err}"))
+            })?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = {
+            let _guard = runtime.enter();
+            let make_service = make_service_fn(move |_conn| {
+                let (registry, openmetrics) = (registry.clone(), openmetrics.clone());
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req| {
+                        let response = handle(&req, &registry, &openmetrics);
+                        async move { Ok::<_, Infallible>(response) }
+                    }))
+                }
+            });
+            Server::from_tcp(listener)
+                .map_err(|err| MetricsError::Other(err.to_string()))?
+                .serve(make_service)
+                .with_graceful_shutdown(async {
+                    // Also stops the server when the sender is dropped
+                    let _ = shutdown_rx.await;
+                })
+        };
+
+        let handle = thread::Builder::new()
+            .name("opentelemetry-prometheus-server".to_string())
+            .spawn(move || {
+                if let Err(err) = runtime.block_on(server) {
+                    global::handle_error(MetricsError::Other(format!(
+                        "scrape server failed: {err}"
+                    )));
+                }
+            })
+            .map_err(|err| MetricsError::Other(err.to_string()))?;
+
+        Ok(ScrapeServer {
+            local_addr,
+            running: Mutex::new(Some((shutdown_tx, handle))),
+        })
+    }
+
+    /// The address the server is listening on.
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops the server once the requests in progress are answered.
+    pub(crate) fn shutdown(&self) -> Result<()> {
+        let running = self.running.lock()?.take();
+        if let Some((shutdown_tx, handle)) = running {
+            let _ = shutdown_tx.send(());
+            handle
+                .join()
+                .map_err(|_| MetricsError::Other("scrape server thread panicked".into()))?;
+        }
+        Ok(())
+    }
+}
+
+fn handle(
+    req: &Request<Body>,
+    registry: &Registry,
+    openmetrics: &OpenMetricsEncoder,
+) -> Response<Body> {
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, METRICS_PATH) => metrics(req, registry, openmetrics),
+        (&Method::GET, HEALTH_PATH) => Response::new(Body::from("OK")),
+        _ => status(StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
+
+fn metrics(
+    req: &Request<Body>,
+    registry: &Registry,
+    openmetrics: &OpenMetricsEncoder,
+) -> Response<Body> {
+    let header = |name| req.headers().get(name).and_then(|v| v.to_str().ok());
+    let format = Format::negotiate(header(ACCEPT));
+
+    let mut buffer = Vec::new();
+    let encoded = match format {
+        Format::Text => TextEncoder::new()
+            .encode(&registry.gather(), &mut buffer)
+            .map_err(|err| MetricsError::Other(err.to_string())),
+        Format::Protobuf => ProtobufEncoder::new()
+            .encode(&registry.gather(), &mut buffer)
+            .map_err(|err| MetricsError::Other(err.to_string())),
+        Format::OpenMetrics => openmetrics.encode(&mut buffer),
+    };
+    if let Err(err) = encoded {
+        global::handle_error(err);
+        return status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to encode metrics",
+        );
+    }
+
+    let gzip = accepts_gzip(header(ACCEPT_ENCODING));
+    if gzip {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        match encoder.write_all(&buffer).and_then(|_| encoder.finish()) {
+            Ok(compressed) => buffer = compressed,
+            Err(err) => {
+                global::handle_error(MetricsError::Other(err.to_string()));
+                return status(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to compress metrics",
+                );
+            }
+        }
+    }
+
+    let mut response = Response::new(Body::from(buffer));
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if gzip {
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    }
+    response
+}
+
+fn status(code: StatusCode, message: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message));
+    *response.status_mut() = code;
+    response
+}
+
+/// The exposition formats served.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Text,
+    Protobuf,
+    OpenMetrics,
+}
+
+impl Format {
+    /// The format with the highest quality in an `Accept` header, text if none
+    /// of the accepted media types is supported.
+    fn negotiate(accept: Option<&str>) -> Format {
+        let mut best = (Format::Text, 0.0);
+        for media_range in accept.unwrap_or_default().split(',') {
+            let mut parts = media_range.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default();
+            let (mut quality, mut proto, mut encoding, mut version) = (1.0, None, None, None);
+            for (name, value) in parts.filter_map(|param| param.split_once('=')) {
+                match name.trim() {
+                    "q" => quality = value.trim().parse().unwrap_or(0.0),
+                    "proto" => proto = Some(value.trim()),
+                    "encoding" => encoding = Some(value.trim()),
+                    "version" => version = Some(value.trim()),
+                    _ => {}
+                }
+            }
+
+            let format = match media_type {
+                "application/vnd.google.protobuf"
+                    if proto == Some("io.prometheus.client.MetricFamily")
+                        && encoding == Some("delimited") =>
+                {
+                    Format::Protobuf
+                }
+                "application/openmetrics-text" if matches!(version, None | Some("1.0.0")) => {
+                    Format::OpenMetrics
+                }
+                "text/plain" | "text/*" | "*/*" => Format::Text,
+                _ => continue,
+            };
+            if quality > best.1 {
+                best = (format, quality);
+            }
+        }
+        best.0
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Text => prometheus::TEXT_FORMAT,
+            Format::Protobuf => prometheus::PROTOBUF_FORMAT,
+            Format::OpenMetrics => crate::OPENMETRICS_FORMAT,
+        }
+    }
+}
+
+/// Whether an `Accept-Encoding` header accepts gzip.
+fn accepts_gzip(accept_encoding: Option<&str>) -> bool {
+    accept_encoding
+        .unwrap_or_default()
+        .split(',')
+        .any(|coding| {
+            let mut parts = coding.split(';').map(str::trim);
+            parts.next() == Some("gzip")
+                && !parts.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .map_or(false, |q| q.trim().parse() == Ok(0.0))
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_format() {
+        let prometheus_accept = concat!(
+            "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;",
+            "encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3,*/*;q=0.1"
+        );
+        assert_eq!(Format::negotiate(Some(prometheus_accept)), Format::Protobuf);
+        let text_preferred = concat!(
+            "text/plain;q=0.9,application/vnd.google.protobuf;",
+            "proto=io.prometheus.client.MetricFamily;encoding=delimited;q=0.5"
+        );
+        assert_eq!(Format::negotiate(Some(text_preferred)), Format::Text);
+        assert_eq!(
+            Format::negotiate(Some("application/vnd.google.protobuf;proto=other")),
+            Format::Text
+        );
+        let openmetrics_accept = concat!(
+            "application/openmetrics-text;version=1.0.0,application/openmetrics-text;",
+            "version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1"
+        );
+        assert_eq!(
+            Format::negotiate(Some(openmetrics_accept)),
+            Format::OpenMetrics
+        );
+        assert_eq!(
+            Format::negotiate(Some("application/openmetrics-text;version=0.0.1")),
+            Format::Text
+        );
+        assert_eq!(Format::negotiate(None), Format::Text);
+    }
+
+    #[test]
+    fn negotiate_gzip() {
+        assert!(accepts_gzip(Some("gzip")));
+        assert!(accepts_gzip(Some("deflate, gzip;q=0.5")));
+        assert!(!accepts_gzip(Some("gzip;q=0")));
+        assert!(!accepts_gzip(Some("identity")));
+        assert!(!accepts_gzip(None));
+    }
+}
@@ -1,8 +1,31 @@
 use opentelemetry::metrics::Unit;
+use opentelemetry_sdk::metrics::data;
 use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const NON_APPLICABLE_ON_PER_UNIT: [&str; 8] = ["1", "d", "h", "min", "s", "ms", "us", "ns"];
 
+/// The Prometheus name of a metric, prefixed with `namespace` and, unless
+/// `without_units` is set, suffixed with its unit.
+pub(crate) fn get_name(
+    m: &data::Metric,
+    namespace: Option<&str>,
+    without_units: bool,
+) -> Cow<'static, str> {
+    let name = sanitize_name(&m.name);
+    let unit_suffixes = if without_units {
+        None
+    } else {
+        get_unit_suffixes(&m.unit)
+    };
+    match (namespace, unit_suffixes) {
+        (Some(namespace), Some(suffix)) => Cow::Owned(format!("{namespace}{name}_{suffix}")),
+        (Some(namespace), None) => Cow::Owned(format!("{namespace}{name}")),
+        (None, Some(suffix)) => Cow::Owned(format!("{name}_{suffix}")),
+        (None, None) => name,
+    }
+}
+
 pub(crate) fn get_unit_suffixes(unit: &Unit) -> Option<Cow<'static, str>> {
     // no unit return early
     if unit.as_str().is_empty() {
@@ -146,6 +169,13 @@ pub(crate) fn sanitize_prom_kv(s: &str) -> String {
         .collect()
 }
 
+/// The milliseconds since the Unix epoch of `time`, as Prometheus timestamps.
+pub(crate) fn timestamp_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,19 +1,24 @@
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 use opentelemetry::metrics::{Meter, MeterProvider as _, Unit};
 use opentelemetry::Key;
 use opentelemetry::KeyValue;
 use opentelemetry_prometheus::{ExporterBuilder, ResourceSelector};
+use opentelemetry_sdk::metrics::data::{
+    self, DataPoint, Exemplar, HistogramDataPoint, Metric, ResourceMetrics, ScopeMetrics,
+    Temporality,
+};
 use opentelemetry_sdk::metrics::{new_view, Aggregation, Instrument, SdkMeterProvider, Stream};
 use opentelemetry_sdk::resource::{
     EnvResourceDetector, SdkProvidedResourceDetector, TelemetryResourceDetector,
 };
-use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::{AttributeSet, Resource, Scope};
 use opentelemetry_semantic_conventions::resource::{SERVICE_NAME, TELEMETRY_SDK_VERSION};
 use prometheus::{Encoder, TextEncoder};
+use protobuf::Message;
 
 #[test]
 fn prometheus_exporter_integration() {
@@ -468,6 +473,169 @@ fn multiple_scopes() {
     gather_and_compare(registry, content, "multi_scope");
 }
 
+#[test]
+fn exponential_histogram() {
+    let registry = prometheus::Registry::new();
+    let exporter = ExporterBuilder::default()
+        .with_registry(registry.clone())
+        .without_scope_info()
+        .without_target_info()
+        .build()
+        .unwrap();
+
+    let provider = SdkMeterProvider::builder()
+        .with_reader(exporter)
+        .with_view(
+            new_view(
+                Instrument::new().name("exp_histogram"),
+                Stream::new().aggregation(Aggregation::Base2ExponentialHistogram {
+                    max_size: 160,
+                    max_scale: 0,
+                    record_min_max: true,
+                }),
+            )
+            .unwrap(),
+        )
+        .build();
+    let histogram = provider
+        .meter("testmeter")
+        .f64_histogram("exp_histogram")
+        .with_unit(Unit::new("By"))
+        .with_description("an exponential histogram")
+        .init();
+    for value in [3.0, 5.0, 7.0, 100.0] {
+        histogram.record(value, &[KeyValue::new("A", "B")]);
+    }
+
+    let content = fs::read_to_string("./tests/data/exponential_histogram.txt").unwrap();
+    gather_and_compare(registry.clone(), content, "exponential_histogram");
+
+    // The native histogram fields survive the protobuf encoding.
+    let metric_families = registry.gather();
+    let encoded = metric_families[0].get_metric()[0]
+        .get_histogram()
+        .write_to_bytes()
+        .unwrap();
+    let decoded = prometheus::proto::Histogram::parse_from_bytes(&encoded).unwrap();
+    let schema = decoded
+        .unknown_fields
+        .get(5)
+        .expect("native histogram schema");
+    assert_eq!(schema.varint, vec![0]);
+    let positive_spans = decoded
+        .unknown_fields
+        .get(12)
+        .expect("native histogram positive spans");
+    assert_eq!(positive_spans.length_delimited.len(), 2);
+    let positive_deltas = decoded
+        .unknown_fields
+        .get(13)
+        .expect("native histogram positive deltas");
+    // counts 1, 2 and 1, zigzag encoded
+    assert_eq!(positive_deltas.varint, vec![2, 2, 1]);
+}
+
+#[test]
+fn openmetrics() {
+    let exporter = ExporterBuilder::default()
+        .with_registry(prometheus::Registry::new())
+        .build()
+        .unwrap();
+    let encoder = exporter.openmetrics_encoder();
+
+    let start_time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let time = start_time + Duration::from_millis(1_500);
+    let exemplar = |value| Exemplar {
+        filtered_attributes: vec![KeyValue::new("user", "alice")],
+        time,
+        value,
+        span_id: [1; 8],
+        trace_id: [2; 16],
+    };
+    let attributes = AttributeSet::from(&[KeyValue::new("A", "B\"C")][..]);
+
+    let metrics = ResourceMetrics {
+        resource: Resource::new(vec![KeyValue::new(SERVICE_NAME, "prometheus_test")]),
+        scope_metrics: vec![ScopeMetrics {
+            scope: Scope::builder("testmeter")
+                .with_version("v0.1.0")
+                .with_attributes(vec![KeyValue::new("k", "v")])
+                .build(),
+            metrics: vec![
+                Metric {
+                    name: "foo".into(),
+                    description: "a simple counter".into(),
+                    unit: Unit::new("ms"),
+                    data: Box::new(data::Sum {
+                        data_points: vec![DataPoint {
+                            attributes: attributes.clone(),
+                            start_time: Some(start_time),
+                            time: Some(time),
+                            value: 5.5,
+                            exemplars: vec![exemplar(2.5)],
+                        }],
+                        temporality: Temporality::Cumulative,
+                        is_monotonic: true,
+                    }),
+                },
+                Metric {
+                    name: "bar".into(),
+                    description: "a fun little gauge".into(),
+                    unit: Unit::new("1"),
+                    data: Box::new(data::Gauge {
+                        data_points: vec![DataPoint {
+                            attributes: attributes.clone(),
+                            start_time: None,
+                            time: Some(time),
+                            value: -1i64,
+                            exemplars: vec![],
+                        }],
+                    }),
+                },
+                Metric {
+                    name: "histogram_baz".into(),
+                    description: "a very nice histogram".into(),
+                    unit: Unit::new("By"),
+                    data: Box::new(data::Histogram {
+                        data_points: vec![HistogramDataPoint {
+                            attributes,
+                            start_time,
+                            time,
+                            count: 3,
+                            bounds: vec![0.0, 5.0, 10.0],
+                            bucket_counts: vec![0, 1, 1, 1],
+                            min: Some(4.0),
+                            max: Some(23.0),
+                            sum: 34.0,
+                            exemplars: vec![exemplar(7.0), exemplar(23.0)],
+                        }],
+                        temporality: Temporality::Cumulative,
+                    }),
+                },
+            ],
+        }],
+    };
+
+    let mut output = Vec::new();
+    encoder
+        .encode_resource_metrics(&metrics, &mut output)
+        .unwrap();
+    let expected = fs::read_to_string("./tests/data/openmetrics.txt").unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+    // Metrics collected from the exporter are encoded as well
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    let counter = provider.meter("testmeter").u64_counter("foo").init();
+    counter.add(1, &[]);
+    let mut output = Vec::new();
+    encoder.encode(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("\n# TYPE foo counter\n"), "{output}");
+    assert!(output.contains("\nfoo_total{otel_scope_name=\"testmeter\"} 1.0\n"));
+    assert!(output.contains("\nfoo_created{otel_scope_name=\"testmeter\"} "));
+    assert!(output.ends_with("\n# EOF\n"));
+}
+
 #[test]
 fn duplicate_metrics() {
     struct TestCase {
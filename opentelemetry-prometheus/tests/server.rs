@@ -0,0 +1,91 @@
+#![cfg(feature = "server")]
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use hyper::{body::to_bytes, header, Body, Client, Request, StatusCode};
+use opentelemetry::{metrics::MeterProvider as _, KeyValue};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+#[test]
+fn serves_metrics_until_shutdown() {
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_registry(prometheus::Registry::new())
+        .with_server(([127, 0, 0, 1], 0))
+        .build()
+        .unwrap();
+    let addr = exporter.server_addr().expect("server address");
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    let counter = provider.meter("testmeter").u64_counter("foo").init();
+    counter.add(5, &[KeyValue::new("A", "B")]);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let get = |path: &str, headers: &[(header::HeaderName, &str)]| {
+        let mut req = Request::get(format!("http://{addr}{path}"));
+        for (name, value) in headers {
+            req = req.header(name, *value);
+        }
+        runtime.block_on(async {
+            let res = Client::new()
+                .request(req.body(Body::empty()).unwrap())
+                .await?;
+            let (parts, body) = res.into_parts();
+            to_bytes(body).await.map(|body| (parts, body.to_vec()))
+        })
+    };
+
+    let (parts, body) = get("/metrics", &[]).unwrap();
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(parts.headers[header::CONTENT_TYPE], prometheus::TEXT_FORMAT);
+    let text = String::from_utf8(body).unwrap();
+    assert!(text.contains("foo_total{A=\"B\",otel_scope_name=\"testmeter\"} 5"));
+
+    let (parts, _) = get(
+        "/metrics",
+        &[(
+            header::ACCEPT,
+            "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited",
+        )],
+    )
+    .unwrap();
+    assert_eq!(
+        parts.headers[header::CONTENT_TYPE],
+        prometheus::PROTOBUF_FORMAT
+    );
+
+    let (parts, body) = get(
+        "/metrics",
+        &[(header::ACCEPT, "application/openmetrics-text;version=1.0.0")],
+    )
+    .unwrap();
+    assert_eq!(
+        parts.headers[header::CONTENT_TYPE],
+        opentelemetry_prometheus::OPENMETRICS_FORMAT
+    );
+    let openmetrics = String::from_utf8(body).unwrap();
+    assert!(openmetrics.contains("foo_total{A=\"B\",otel_scope_name=\"testmeter\"} 5.0\n"));
+    assert!(openmetrics.ends_with("# EOF\n"));
+
+    let (parts, body) = get("/metrics", &[(header::ACCEPT_ENCODING, "gzip")]).unwrap();
+    assert_eq!(parts.headers[header::CONTENT_ENCODING], "gzip");
+    let mut decompressed = String::new();
+    GzDecoder::new(body.as_slice())
+        .read_to_string(&mut decompressed)
+        .unwrap();
+    assert_eq!(decompressed, text);
+
+    let (parts, body) = get("/health", &[]).unwrap();
+    assert_eq!(parts.status, StatusCode::OK);
+    assert_eq!(body, b"OK");
+
+    let (parts, _) = get("/unknown", &[]).unwrap();
+    assert_eq!(parts.status, StatusCode::NOT_FOUND);
+
+    provider.shutdown().unwrap();
+    assert!(
+        get("/health", &[]).is_err(),
+        "the server should stop with the meter provider"
+    );
+}
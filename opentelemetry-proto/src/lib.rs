@@ -22,6 +22,7 @@
 //!
 //! ## Misc
 //! - `full`: enabled all features above.
+//! - `persistence`: codecs storing spans and logs as OTLP messages in the SDK's persistent queue.
 //!
 //! By default, no feature is enabled.
 
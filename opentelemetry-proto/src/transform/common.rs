@@ -14,6 +14,14 @@ pub(crate) fn to_nanos(time: SystemTime) -> u64 {
         .as_nanos() as u64
 }
 
+#[cfg(all(
+    feature = "gen-tonic-messages",
+    any(feature = "trace", feature = "metrics", feature = "logs")
+))]
+pub(crate) fn from_nanos(nanos: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_nanos(nanos)
+}
+
 #[cfg(feature = "gen-tonic-messages")]
 pub mod tonic {
     use crate::proto::tonic::common::v1::{
@@ -21,10 +29,81 @@ pub mod tonic {
     };
     use opentelemetry::{Array, Value};
     use std::borrow::Cow;
+    use std::fmt;
 
-    #[cfg(any(feature = "trace", feature = "logs"))]
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    use opentelemetry::trace::{SpanId, TraceId};
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
     use opentelemetry_sdk::Resource;
 
+    /// Errors returned when converting OTLP messages back into SDK types.
+    #[derive(Clone, Debug, PartialEq)]
+    #[non_exhaustive]
+    pub enum ConversionError {
+        /// A trace id is not 16 bytes long.
+        InvalidTraceId(Vec<u8>),
+        /// A span id is not 8 bytes long.
+        InvalidSpanId(Vec<u8>),
+        /// A trace state is not a valid W3C `tracestate` header.
+        InvalidTraceState(String),
+        /// An enum field holds a value unknown to this version of the protocol.
+        UnknownEnumValue {
+            /// The name of the field.
+            field: &'static str,
+            /// The value of the field.
+            value: i32,
+        },
+        /// A required field is not set.
+        MissingField(&'static str),
+        /// A value cannot be represented by the SDK types.
+        UnsupportedValue(String),
+    }
+
+    impl fmt::Display for ConversionError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ConversionError::InvalidTraceId(bytes) => {
+                    write!(f, "invalid trace id of {} bytes", bytes.len())
+                }
+                ConversionError::InvalidSpanId(bytes) => {
+                    write!(f, "invalid span id of {} bytes", bytes.len())
+                }
+                ConversionError::InvalidTraceState(state) => {
+                    write!(f, "invalid trace state {:?}", state)
+                }
+                ConversionError::UnknownEnumValue { field, value } => {
+                    write!(f, "unknown value {} for {}", value, field)
+                }
+                ConversionError::MissingField(field) => write!(f, "missing field {}", field),
+                ConversionError::UnsupportedValue(msg) => write!(f, "unsupported value: {}", msg),
+            }
+        }
+    }
+
+    impl std::error::Error for ConversionError {}
+
+    /// Parses a trace id, an empty one being [`TraceId::INVALID`].
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    pub(crate) fn trace_id_from_bytes(bytes: &[u8]) -> Result<TraceId, ConversionError> {
+        if bytes.is_empty() {
+            return Ok(TraceId::INVALID);
+        }
+        <[u8; 16]>::try_from(bytes)
+            .map(TraceId::from_bytes)
+            .map_err(|_| ConversionError::InvalidTraceId(bytes.to_vec()))
+    }
+
+    /// Parses a span id, an empty one being [`SpanId::INVALID`].
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    pub(crate) fn span_id_from_bytes(bytes: &[u8]) -> Result<SpanId, ConversionError> {
+        if bytes.is_empty() {
+            return Ok(SpanId::INVALID);
+        }
+        <[u8; 8]>::try_from(bytes)
+            .map(SpanId::from_bytes)
+            .map_err(|_| ConversionError::InvalidSpanId(bytes.to_vec()))
+    }
+
     impl From<opentelemetry_sdk::InstrumentationLibrary> for InstrumentationScope {
         fn from(library: opentelemetry_sdk::InstrumentationLibrary) -> Self {
             InstrumentationScope {
@@ -113,6 +192,125 @@ pub mod tonic {
         ArrayValue { values }
     }
 
+    impl TryFrom<KeyValue> for opentelemetry::KeyValue {
+        type Error = ConversionError;
+
+        fn try_from(kv: KeyValue) -> Result<Self, Self::Error> {
+            let value = kv
+                .value
+                .ok_or(ConversionError::MissingField("KeyValue.value"))?;
+            Ok(opentelemetry::KeyValue::new(
+                kv.key,
+                Value::try_from(value)?,
+            ))
+        }
+    }
+
+    impl TryFrom<AnyValue> for Value {
+        type Error = ConversionError;
+
+        fn try_from(value: AnyValue) -> Result<Self, Self::Error> {
+            match value.value {
+                Some(any_value::Value::BoolValue(val)) => Ok(Value::Bool(val)),
+                Some(any_value::Value::IntValue(val)) => Ok(Value::I64(val)),
+                Some(any_value::Value::DoubleValue(val)) => Ok(Value::F64(val)),
+                Some(any_value::Value::StringValue(val)) => Ok(Value::String(val.into())),
+                Some(any_value::Value::ArrayValue(array)) => array_from_proto(array),
+                Some(any_value::Value::KvlistValue(_)) => Err(ConversionError::UnsupportedValue(
+                    "attribute values cannot be maps".into(),
+                )),
+                Some(any_value::Value::BytesValue(_)) => Err(ConversionError::UnsupportedValue(
+                    "attribute values cannot be bytes".into(),
+                )),
+                None => Err(ConversionError::MissingField("AnyValue.value")),
+            }
+        }
+    }
+
+    fn array_from_proto(array: ArrayValue) -> Result<Value, ConversionError> {
+        let values = array
+            .values
+            .into_iter()
+            .map(Value::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // attribute arrays are homogeneous, their type is the one of the first element
+        let array = match values.first() {
+            None => Array::String(vec![]),
+            Some(Value::Bool(_)) => Array::Bool(homogeneous(values, |v| match v {
+                Value::Bool(val) => Some(val),
+                _ => None,
+            })?),
+            Some(Value::I64(_)) => Array::I64(homogeneous(values, |v| match v {
+                Value::I64(val) => Some(val),
+                _ => None,
+            })?),
+            Some(Value::F64(_)) => Array::F64(homogeneous(values, |v| match v {
+                Value::F64(val) => Some(val),
+                _ => None,
+            })?),
+            Some(Value::String(_)) => Array::String(homogeneous(values, |v| match v {
+                Value::String(val) => Some(val),
+                _ => None,
+            })?),
+            Some(Value::Array(_)) => {
+                return Err(ConversionError::UnsupportedValue(
+                    "attribute arrays cannot be nested".into(),
+                ))
+            }
+        };
+
+        Ok(Value::Array(array))
+    }
+
+    fn homogeneous<T>(
+        values: Vec<Value>,
+        f: impl Fn(Value) -> Option<T>,
+    ) -> Result<Vec<T>, ConversionError> {
+        values
+            .into_iter()
+            .map(f)
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                ConversionError::UnsupportedValue("attribute arrays must be homogeneous".into())
+            })
+    }
+
+    /// Converts a list of attributes, failing on the first invalid one.
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    pub(crate) fn attributes_from_proto(
+        attributes: Vec<KeyValue>,
+    ) -> Result<Vec<opentelemetry::KeyValue>, ConversionError> {
+        attributes.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    pub(crate) fn instrumentation_library_from_proto(
+        scope: Option<InstrumentationScope>,
+        schema_url: String,
+    ) -> Result<opentelemetry_sdk::InstrumentationLibrary, ConversionError> {
+        let scope = scope.unwrap_or_default();
+        let mut builder = opentelemetry_sdk::InstrumentationLibrary::builder(scope.name)
+            .with_attributes(attributes_from_proto(scope.attributes)?);
+        if !scope.version.is_empty() {
+            builder = builder.with_version(scope.version);
+        }
+        if !schema_url.is_empty() {
+            builder = builder.with_schema_url(schema_url);
+        }
+
+        Ok(builder.build())
+    }
+
+    #[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+    pub(crate) fn resource_from_proto(
+        resource: Option<crate::proto::tonic::resource::v1::Resource>,
+        schema_url: String,
+    ) -> Result<Resource, ConversionError> {
+        let attributes = attributes_from_proto(resource.unwrap_or_default().attributes)?;
+        Ok(Resource::from_schema_url(attributes, schema_url))
+    }
+
     #[cfg(any(feature = "trace", feature = "logs"))]
     pub(crate) fn resource_attributes(resource: &Resource) -> Attributes {
         resource
@@ -2,14 +2,29 @@
 pub mod tonic {
     use crate::{
         tonic::{
+            collector::logs::v1::ExportLogsServiceRequest,
             common::v1::{any_value::Value, AnyValue, ArrayValue, KeyValue, KeyValueList},
             logs::v1::{LogRecord, ResourceLogs, ScopeLogs, SeverityNumber},
             resource::v1::Resource,
             Attributes,
         },
-        transform::common::{to_nanos, tonic::resource_attributes},
+        transform::common::{
+            from_nanos, to_nanos,
+            tonic::{
+                instrumentation_library_from_proto, resource_attributes, resource_from_proto,
+                span_id_from_bytes, trace_id_from_bytes, ConversionError,
+            },
+        },
     };
-    use opentelemetry::logs::{AnyValue as LogsAnyValue, Severity};
+    use opentelemetry::logs::{AnyValue as LogsAnyValue, Severity, TraceContext};
+    use opentelemetry::trace::{SpanContext, TraceFlags, TraceState};
+    use opentelemetry::Key;
+    use opentelemetry_sdk::export::logs::LogData;
+    #[cfg(feature = "persistence")]
+    use opentelemetry_sdk::persistence::{BatchCodec, PersistenceError};
+    #[cfg(feature = "persistence")]
+    use prost::Message;
+    use std::borrow::Cow;
 
     impl From<LogsAnyValue> for AnyValue {
         fn from(value: LogsAnyValue) -> Self {
@@ -92,7 +107,7 @@ pub mod tonic {
                     .map(Attributes::from_iter)
                     .unwrap_or_default()
                     .0,
-                dropped_attributes_count: 0,
+                dropped_attributes_count: log_record.dropped_attributes_count,
                 flags: trace_context
                     .map(|ctx| {
                         ctx.trace_flags
@@ -135,4 +150,269 @@ pub mod tonic {
             }
         }
     }
+
+    impl TryFrom<AnyValue> for LogsAnyValue {
+        type Error = ConversionError;
+
+        fn try_from(value: AnyValue) -> Result<Self, Self::Error> {
+            value
+                .value
+                .ok_or(ConversionError::MissingField("AnyValue.value"))?
+                .try_into()
+        }
+    }
+
+    impl TryFrom<Value> for LogsAnyValue {
+        type Error = ConversionError;
+
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+            Ok(match value {
+                Value::DoubleValue(f) => LogsAnyValue::Double(f),
+                Value::IntValue(i) => LogsAnyValue::Int(i),
+                Value::StringValue(s) => LogsAnyValue::String(s.into()),
+                Value::BoolValue(b) => LogsAnyValue::Boolean(b),
+                Value::ArrayValue(array) => LogsAnyValue::ListAny(
+                    array
+                        .values
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?,
+                ),
+                Value::KvlistValue(list) => LogsAnyValue::Map(key_values(list.values)?),
+                Value::BytesValue(v) => LogsAnyValue::Bytes(v),
+            })
+        }
+    }
+
+    fn key_values<C>(values: Vec<KeyValue>) -> Result<C, ConversionError>
+    where
+        C: FromIterator<(Key, LogsAnyValue)>,
+    {
+        values
+            .into_iter()
+            .map(|kv| {
+                let value = kv
+                    .value
+                    .ok_or(ConversionError::MissingField("KeyValue.value"))?;
+                Ok((kv.key.into(), value.try_into()?))
+            })
+            .collect()
+    }
+
+    fn severity_from_proto(severity_number: SeverityNumber) -> Option<Severity> {
+        match severity_number {
+            SeverityNumber::Unspecified => None,
+            SeverityNumber::Trace => Some(Severity::Trace),
+            SeverityNumber::Trace2 => Some(Severity::Trace2),
+            SeverityNumber::Trace3 => Some(Severity::Trace3),
+            SeverityNumber::Trace4 => Some(Severity::Trace4),
+            SeverityNumber::Debug => Some(Severity::Debug),
+            SeverityNumber::Debug2 => Some(Severity::Debug2),
+            SeverityNumber::Debug3 => Some(Severity::Debug3),
+            SeverityNumber::Debug4 => Some(Severity::Debug4),
+            SeverityNumber::Info => Some(Severity::Info),
+            SeverityNumber::Info2 => Some(Severity::Info2),
+            SeverityNumber::Info3 => Some(Severity::Info3),
+            SeverityNumber::Info4 => Some(Severity::Info4),
+            SeverityNumber::Warn => Some(Severity::Warn),
+            SeverityNumber::Warn2 => Some(Severity::Warn2),
+            SeverityNumber::Warn3 => Some(Severity::Warn3),
+            SeverityNumber::Warn4 => Some(Severity::Warn4),
+            SeverityNumber::Error => Some(Severity::Error),
+            SeverityNumber::Error2 => Some(Severity::Error2),
+            SeverityNumber::Error3 => Some(Severity::Error3),
+            SeverityNumber::Error4 => Some(Severity::Error4),
+            SeverityNumber::Fatal => Some(Severity::Fatal),
+            SeverityNumber::Fatal2 => Some(Severity::Fatal2),
+            SeverityNumber::Fatal3 => Some(Severity::Fatal3),
+            SeverityNumber::Fatal4 => Some(Severity::Fatal4),
+        }
+    }
+
+    impl TryFrom<LogRecord> for opentelemetry::logs::LogRecord {
+        type Error = ConversionError;
+
+        fn try_from(log_record: LogRecord) -> Result<Self, Self::Error> {
+            let severity_number =
+                SeverityNumber::try_from(log_record.severity_number).map_err(|_| {
+                    ConversionError::UnknownEnumValue {
+                        field: "LogRecord.severity_number",
+                        value: log_record.severity_number,
+                    }
+                })?;
+
+            let mut record = opentelemetry::logs::LogRecord::default();
+            record.timestamp =
+                (log_record.time_unix_nano != 0).then(|| from_nanos(log_record.time_unix_nano));
+            record.observed_timestamp = from_nanos(log_record.observed_time_unix_nano);
+            record.severity_number = severity_from_proto(severity_number);
+            record.severity_text =
+                (!log_record.severity_text.is_empty()).then(|| log_record.severity_text.into());
+            record.body = log_record.body.map(TryInto::try_into).transpose()?;
+            record.attributes = (!log_record.attributes.is_empty())
+                .then(|| key_values(log_record.attributes))
+                .transpose()?;
+            record.trace_context = if log_record.trace_id.is_empty() {
+                None
+            } else {
+                let mut trace_context = TraceContext::from(&SpanContext::new(
+                    trace_id_from_bytes(&log_record.trace_id)?,
+                    span_id_from_bytes(&log_record.span_id)?,
+                    TraceFlags::new(log_record.flags as u8),
+                    false,
+                    TraceState::default(),
+                ));
+                if log_record.flags == 0 {
+                    trace_context.trace_flags = None;
+                }
+                Some(trace_context)
+            };
+
+            Ok(record)
+        }
+    }
+
+    impl TryFrom<ResourceLogs> for Vec<LogData> {
+        type Error = ConversionError;
+
+        fn try_from(resource_logs: ResourceLogs) -> Result<Self, Self::Error> {
+            let resource = resource_from_proto(resource_logs.resource, resource_logs.schema_url)?;
+            let mut logs = Vec::new();
+
+            for scope_logs in resource_logs.scope_logs {
+                let instrumentation =
+                    instrumentation_library_from_proto(scope_logs.scope, scope_logs.schema_url)?;
+
+                for log_record in scope_logs.log_records {
+                    logs.push(LogData {
+                        record: log_record.try_into()?,
+                        resource: Cow::Owned(resource.clone()),
+                        instrumentation: instrumentation.clone(),
+                    });
+                }
+            }
+
+            Ok(logs)
+        }
+    }
+
+    impl TryFrom<ExportLogsServiceRequest> for Vec<LogData> {
+        type Error = ConversionError;
+
+        fn try_from(request: ExportLogsServiceRequest) -> Result<Self, Self::Error> {
+            let mut logs = Vec::new();
+            for resource_logs in request.resource_logs {
+                logs.extend(Vec::<LogData>::try_from(resource_logs)?);
+            }
+
+            Ok(logs)
+        }
+    }
+
+    /// Encodes batches of log records in a [persistent queue] as
+    /// [`ExportLogsServiceRequest`] protobuf messages.
+    ///
+    /// [persistent queue]: opentelemetry_sdk::persistence::PersistentQueue
+    #[cfg(feature = "persistence")]
+    #[derive(Clone, Debug, Default)]
+    pub struct OtlpLogCodec;
+
+    #[cfg(feature = "persistence")]
+    impl BatchCodec<LogData> for OtlpLogCodec {
+        fn encode(&self, batch: &[LogData]) -> Result<Vec<u8>, PersistenceError> {
+            let request = ExportLogsServiceRequest {
+                resource_logs: batch.iter().cloned().map(Into::into).collect(),
+            };
+            Ok(request.encode_to_vec())
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<Vec<LogData>, PersistenceError> {
+            let request = ExportLogsServiceRequest::decode(bytes)
+                .map_err(|err| PersistenceError::Codec(err.to_string()))?;
+            Vec::<LogData>::try_from(request)
+                .map_err(|err| PersistenceError::Codec(err.to_string()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use opentelemetry::trace::{SpanId, TraceId};
+        use opentelemetry_sdk::{InstrumentationLibrary, Resource as SdkResource};
+        use std::collections::HashMap;
+        use std::time::{Duration, SystemTime};
+
+        fn log_data() -> LogData {
+            let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+            let mut record = opentelemetry::logs::LogRecord::default();
+            record.timestamp = Some(timestamp);
+            record.observed_timestamp = timestamp + Duration::from_millis(5);
+            record.severity_number = Some(Severity::Warn);
+            record.severity_text = Some("WARN".into());
+            record.body = Some(LogsAnyValue::Map(HashMap::from([(
+                Key::new("nested"),
+                LogsAnyValue::ListAny(vec![LogsAnyValue::Int(1), LogsAnyValue::Bytes(vec![2])]),
+            )])));
+            record.attributes = Some(vec![(Key::new("key"), LogsAnyValue::Boolean(true))]);
+            record.trace_context = Some(TraceContext::from(&SpanContext::new(
+                TraceId::from_bytes([1; 16]),
+                SpanId::from_bytes([2; 8]),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            )));
+
+            LogData {
+                record,
+                resource: Cow::Owned(SdkResource::new([opentelemetry::KeyValue::new(
+                    "service.name",
+                    "test",
+                )])),
+                instrumentation: InstrumentationLibrary::builder("logger").build(),
+            }
+        }
+
+        #[test]
+        fn resource_logs_round_trip() {
+            let resource_logs = ResourceLogs::from(log_data());
+            let logs = Vec::<LogData>::try_from(resource_logs.clone()).unwrap();
+
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].record.severity_number, Some(Severity::Warn));
+            assert_eq!(ResourceLogs::from(logs[0].clone()), resource_logs);
+        }
+
+        #[test]
+        #[cfg(feature = "persistence")]
+        fn log_codec_round_trip() {
+            let bytes = OtlpLogCodec.encode(&[log_data(), log_data()]).unwrap();
+            let logs = OtlpLogCodec.decode(&bytes).unwrap();
+
+            assert_eq!(logs.len(), 2);
+            assert_eq!(
+                ResourceLogs::from(logs[1].clone()),
+                ResourceLogs::from(log_data())
+            );
+        }
+
+        #[test]
+        fn invalid_records_are_rejected() {
+            let mut resource_logs = ResourceLogs::from(log_data());
+            resource_logs.scope_logs[0].log_records[0].severity_number = 100;
+            assert_eq!(
+                Vec::<LogData>::try_from(resource_logs).unwrap_err(),
+                ConversionError::UnknownEnumValue {
+                    field: "LogRecord.severity_number",
+                    value: 100
+                }
+            );
+
+            let mut resource_logs = ResourceLogs::from(log_data());
+            resource_logs.scope_logs[0].log_records[0].trace_id = vec![1; 15];
+            assert_eq!(
+                Vec::<LogData>::try_from(resource_logs).unwrap_err(),
+                ConversionError::InvalidTraceId(vec![1; 15])
+            );
+        }
+    }
 }
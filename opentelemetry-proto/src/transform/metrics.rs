@@ -8,12 +8,19 @@ pub mod tonic {
     use std::any::Any;
     use std::fmt;
 
-    use opentelemetry::{global, metrics::MetricsError, Key, Value};
+    use opentelemetry::{
+        global,
+        metrics::{MetricsError, Unit},
+        Key, Value,
+    };
     use opentelemetry_sdk::metrics::data::{
-        self, Exemplar as SdkExemplar, ExponentialHistogram as SdkExponentialHistogram,
-        Gauge as SdkGauge, Histogram as SdkHistogram, Metric as SdkMetric,
-        ScopeMetrics as SdkScopeMetrics, Sum as SdkSum, Temporality,
+        self, Aggregation as SdkAggregation, DataPoint as SdkDataPoint, Exemplar as SdkExemplar,
+        ExponentialBucket as SdkExponentialBucket, ExponentialHistogram as SdkExponentialHistogram,
+        ExponentialHistogramDataPoint as SdkExponentialHistogramDataPoint, Gauge as SdkGauge,
+        Histogram as SdkHistogram, HistogramDataPoint as SdkHistogramDataPoint,
+        Metric as SdkMetric, ScopeMetrics as SdkScopeMetrics, Sum as SdkSum, Temporality,
     };
+    use opentelemetry_sdk::AttributeSet;
     use opentelemetry_sdk::Resource as SdkResource;
 
     use crate::proto::tonic::{
@@ -35,7 +42,13 @@ pub mod tonic {
         },
         resource::v1::Resource as TonicResource,
     };
-    use crate::transform::common::to_nanos;
+    use crate::transform::common::{
+        from_nanos, to_nanos,
+        tonic::{
+            attributes_from_proto, instrumentation_library_from_proto, resource_from_proto,
+            span_id_from_bytes, trace_id_from_bytes, ConversionError,
+        },
+    };
 
     impl From<u64> for exemplar::Value {
         fn from(value: u64) -> Self {
@@ -342,4 +355,519 @@ pub mod tonic {
             }
         }
     }
+
+    impl TryFrom<ExportMetricsServiceRequest> for Vec<data::ResourceMetrics> {
+        type Error = ConversionError;
+
+        fn try_from(request: ExportMetricsServiceRequest) -> Result<Self, Self::Error> {
+            request
+                .resource_metrics
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect()
+        }
+    }
+
+    impl TryFrom<TonicResourceMetrics> for data::ResourceMetrics {
+        type Error = ConversionError;
+
+        fn try_from(rm: TonicResourceMetrics) -> Result<Self, Self::Error> {
+            Ok(data::ResourceMetrics {
+                resource: resource_from_proto(rm.resource, rm.schema_url)?,
+                scope_metrics: rm
+                    .scope_metrics
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+    }
+
+    impl TryFrom<TonicScopeMetrics> for SdkScopeMetrics {
+        type Error = ConversionError;
+
+        fn try_from(sm: TonicScopeMetrics) -> Result<Self, Self::Error> {
+            Ok(SdkScopeMetrics {
+                scope: instrumentation_library_from_proto(sm.scope, sm.schema_url)?,
+                metrics: sm
+                    .metrics
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+    }
+
+    impl TryFrom<TonicMetric> for SdkMetric {
+        type Error = ConversionError;
+
+        fn try_from(metric: TonicMetric) -> Result<Self, Self::Error> {
+            let data: Box<dyn SdkAggregation> = match metric
+                .data
+                .ok_or(ConversionError::MissingField("Metric.data"))?
+            {
+                TonicMetricData::Gauge(gauge) => {
+                    if is_integer(&gauge.data_points) {
+                        Box::new(SdkGauge::<i64> {
+                            data_points: number_data_points(gauge.data_points)?,
+                        })
+                    } else {
+                        Box::new(SdkGauge::<f64> {
+                            data_points: number_data_points(gauge.data_points)?,
+                        })
+                    }
+                }
+                TonicMetricData::Sum(sum) => {
+                    let temporality = temporality_from_proto(sum.aggregation_temporality)?;
+                    if is_integer(&sum.data_points) {
+                        Box::new(SdkSum::<i64> {
+                            data_points: number_data_points(sum.data_points)?,
+                            temporality,
+                            is_monotonic: sum.is_monotonic,
+                        })
+                    } else {
+                        Box::new(SdkSum::<f64> {
+                            data_points: number_data_points(sum.data_points)?,
+                            temporality,
+                            is_monotonic: sum.is_monotonic,
+                        })
+                    }
+                }
+                TonicMetricData::Histogram(hist) => Box::new(SdkHistogram::<f64>::try_from(hist)?),
+                TonicMetricData::ExponentialHistogram(hist) => {
+                    Box::new(SdkExponentialHistogram::<f64>::try_from(hist)?)
+                }
+                TonicMetricData::Summary(_) => {
+                    return Err(ConversionError::UnsupportedValue(format!(
+                        "summary data of metric {} has no SDK equivalent",
+                        metric.name
+                    )))
+                }
+            };
+
+            Ok(SdkMetric {
+                name: metric.name.into(),
+                description: metric.description.into(),
+                unit: Unit::new(metric.unit),
+                data,
+            })
+        }
+    }
+
+    fn temporality_from_proto(value: i32) -> Result<Temporality, ConversionError> {
+        match TonicTemporality::try_from(value) {
+            Ok(TonicTemporality::Cumulative) => Ok(Temporality::Cumulative),
+            Ok(TonicTemporality::Delta) => Ok(Temporality::Delta),
+            Ok(TonicTemporality::Unspecified) => {
+                Err(ConversionError::MissingField("aggregation_temporality"))
+            }
+            Err(_) => Err(ConversionError::UnknownEnumValue {
+                field: "aggregation_temporality",
+                value,
+            }),
+        }
+    }
+
+    // Number data points of a metric are converted into `i64` values when they
+    // and their exemplars all hold integers, and into `f64` values otherwise.
+    // Values that cannot be represented exactly are rejected.
+    trait FromNumber: Sized {
+        fn from_i64(value: i64) -> Result<Self, ConversionError>;
+        fn from_f64(value: f64) -> Result<Self, ConversionError>;
+    }
+
+    impl FromNumber for i64 {
+        fn from_i64(value: i64) -> Result<Self, ConversionError> {
+            Ok(value)
+        }
+
+        fn from_f64(value: f64) -> Result<Self, ConversionError> {
+            // `i64::MAX as f64` is 2^63, which is out of range
+            if value.fract() == 0.0 && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+                Ok(value as i64)
+            } else {
+                Err(ConversionError::UnsupportedValue(format!(
+                    "{} is not an integer value",
+                    value
+                )))
+            }
+        }
+    }
+
+    impl FromNumber for f64 {
+        fn from_i64(value: i64) -> Result<Self, ConversionError> {
+            let converted = value as f64;
+            if converted as i128 == i128::from(value) {
+                Ok(converted)
+            } else {
+                Err(ConversionError::UnsupportedValue(format!(
+                    "{} cannot be represented exactly as a double value",
+                    value
+                )))
+            }
+        }
+
+        fn from_f64(value: f64) -> Result<Self, ConversionError> {
+            Ok(value)
+        }
+    }
+
+    fn is_integer(data_points: &[TonicNumberDataPoint]) -> bool {
+        data_points.iter().all(|dp| {
+            !matches!(dp.value, Some(TonicDataPointValue::AsDouble(_)))
+                && dp
+                    .exemplars
+                    .iter()
+                    .all(|ex| !matches!(ex.value, Some(TonicExemplarValue::AsDouble(_))))
+        })
+    }
+
+    fn optional_time(nanos: u64) -> Option<std::time::SystemTime> {
+        (nanos != 0).then(|| from_nanos(nanos))
+    }
+
+    fn number_data_points<T: FromNumber>(
+        data_points: Vec<TonicNumberDataPoint>,
+    ) -> Result<Vec<SdkDataPoint<T>>, ConversionError> {
+        data_points
+            .into_iter()
+            .map(|dp| {
+                let value = match dp.value {
+                    Some(TonicDataPointValue::AsInt(value)) => T::from_i64(value)?,
+                    Some(TonicDataPointValue::AsDouble(value)) => T::from_f64(value)?,
+                    None => return Err(ConversionError::MissingField("NumberDataPoint.value")),
+                };
+
+                Ok(SdkDataPoint {
+                    attributes: attribute_set(dp.attributes)?,
+                    start_time: optional_time(dp.start_time_unix_nano),
+                    time: optional_time(dp.time_unix_nano),
+                    value,
+                    exemplars: exemplars(dp.exemplars)?,
+                })
+            })
+            .collect()
+    }
+
+    fn attribute_set(attributes: Vec<KeyValue>) -> Result<AttributeSet, ConversionError> {
+        Ok(AttributeSet::from(&attributes_from_proto(attributes)?[..]))
+    }
+
+    fn exemplars<T: FromNumber>(
+        exemplars: Vec<TonicExemplar>,
+    ) -> Result<Vec<SdkExemplar<T>>, ConversionError> {
+        exemplars
+            .into_iter()
+            .map(|ex| {
+                let value = match ex.value {
+                    Some(TonicExemplarValue::AsInt(value)) => T::from_i64(value)?,
+                    Some(TonicExemplarValue::AsDouble(value)) => T::from_f64(value)?,
+                    None => return Err(ConversionError::MissingField("Exemplar.value")),
+                };
+
+                Ok(SdkExemplar {
+                    filtered_attributes: attributes_from_proto(ex.filtered_attributes)?,
+                    time: from_nanos(ex.time_unix_nano),
+                    value,
+                    span_id: span_id_from_bytes(&ex.span_id)?.to_bytes(),
+                    trace_id: trace_id_from_bytes(&ex.trace_id)?.to_bytes(),
+                })
+            })
+            .collect()
+    }
+
+    impl TryFrom<TonicHistogram> for SdkHistogram<f64> {
+        type Error = ConversionError;
+
+        fn try_from(hist: TonicHistogram) -> Result<Self, Self::Error> {
+            Ok(SdkHistogram {
+                temporality: temporality_from_proto(hist.aggregation_temporality)?,
+                data_points: hist
+                    .data_points
+                    .into_iter()
+                    .map(|dp| {
+                        Ok(SdkHistogramDataPoint {
+                            attributes: attribute_set(dp.attributes)?,
+                            start_time: from_nanos(dp.start_time_unix_nano),
+                            time: from_nanos(dp.time_unix_nano),
+                            count: dp.count,
+                            bounds: dp.explicit_bounds,
+                            bucket_counts: dp.bucket_counts,
+                            min: dp.min,
+                            max: dp.max,
+                            sum: dp.sum.unwrap_or_default(),
+                            exemplars: exemplars(dp.exemplars)?,
+                        })
+                    })
+                    .collect::<Result<_, ConversionError>>()?,
+            })
+        }
+    }
+
+    impl TryFrom<TonicExponentialHistogram> for SdkExponentialHistogram<f64> {
+        type Error = ConversionError;
+
+        fn try_from(hist: TonicExponentialHistogram) -> Result<Self, Self::Error> {
+            Ok(SdkExponentialHistogram {
+                temporality: temporality_from_proto(hist.aggregation_temporality)?,
+                data_points: hist
+                    .data_points
+                    .into_iter()
+                    .map(|dp| {
+                        let scale = i8::try_from(dp.scale).map_err(|_| {
+                            ConversionError::UnsupportedValue(format!(
+                                "exponential histogram scale {} is out of range",
+                                dp.scale
+                            ))
+                        })?;
+
+                        Ok(SdkExponentialHistogramDataPoint {
+                            attributes: attribute_set(dp.attributes)?,
+                            start_time: from_nanos(dp.start_time_unix_nano),
+                            time: from_nanos(dp.time_unix_nano),
+                            count: dp.count as usize,
+                            min: dp.min,
+                            max: dp.max,
+                            sum: dp.sum.unwrap_or_default(),
+                            scale,
+                            zero_count: dp.zero_count,
+                            positive_bucket: exponential_bucket(dp.positive),
+                            negative_bucket: exponential_bucket(dp.negative),
+                            zero_threshold: dp.zero_threshold,
+                            exemplars: exemplars(dp.exemplars)?,
+                        })
+                    })
+                    .collect::<Result<_, ConversionError>>()?,
+            })
+        }
+    }
+
+    fn exponential_bucket(buckets: Option<TonicBuckets>) -> SdkExponentialBucket {
+        let buckets = buckets.unwrap_or_default();
+        SdkExponentialBucket {
+            offset: buckets.offset,
+            counts: buckets.bucket_counts,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use opentelemetry_sdk::InstrumentationLibrary;
+        use std::time::{Duration, SystemTime};
+
+        fn resource_metrics() -> data::ResourceMetrics {
+            let start_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+            let time = start_time + Duration::from_secs(10);
+            let attributes =
+                AttributeSet::from(&[opentelemetry::KeyValue::new("key", "value")][..]);
+
+            data::ResourceMetrics {
+                resource: SdkResource::new([opentelemetry::KeyValue::new("service.name", "test")]),
+                scope_metrics: vec![SdkScopeMetrics {
+                    scope: InstrumentationLibrary::builder("meter")
+                        .with_version("1.0")
+                        .build(),
+                    metrics: vec![
+                        SdkMetric {
+                            name: "counter".into(),
+                            description: "a counter".into(),
+                            unit: Unit::new("1"),
+                            data: Box::new(SdkSum::<i64> {
+                                data_points: vec![SdkDataPoint {
+                                    attributes: attributes.clone(),
+                                    start_time: Some(start_time),
+                                    time: Some(time),
+                                    value: 42,
+                                    exemplars: vec![SdkExemplar {
+                                        filtered_attributes: vec![opentelemetry::KeyValue::new(
+                                            "filtered", true,
+                                        )],
+                                        time,
+                                        value: 7,
+                                        span_id: [1; 8],
+                                        trace_id: [2; 16],
+                                    }],
+                                }],
+                                temporality: Temporality::Delta,
+                                is_monotonic: true,
+                            }),
+                        },
+                        SdkMetric {
+                            name: "gauge".into(),
+                            description: "".into(),
+                            unit: Unit::default(),
+                            data: Box::new(SdkGauge::<f64> {
+                                data_points: vec![SdkDataPoint {
+                                    attributes: attributes.clone(),
+                                    start_time: None,
+                                    time: Some(time),
+                                    value: 1.5,
+                                    exemplars: vec![],
+                                }],
+                            }),
+                        },
+                        SdkMetric {
+                            name: "histogram".into(),
+                            description: "".into(),
+                            unit: Unit::new("ms"),
+                            data: Box::new(SdkHistogram::<f64> {
+                                data_points: vec![SdkHistogramDataPoint {
+                                    attributes: attributes.clone(),
+                                    start_time,
+                                    time,
+                                    count: 3,
+                                    bounds: vec![1.0, 5.0],
+                                    bucket_counts: vec![1, 1, 1],
+                                    min: Some(0.5),
+                                    max: Some(10.0),
+                                    sum: 13.5,
+                                    exemplars: vec![],
+                                }],
+                                temporality: Temporality::Cumulative,
+                            }),
+                        },
+                        SdkMetric {
+                            name: "exponential_histogram".into(),
+                            description: "".into(),
+                            unit: Unit::new("ms"),
+                            data: Box::new(SdkExponentialHistogram::<f64> {
+                                data_points: vec![SdkExponentialHistogramDataPoint {
+                                    attributes,
+                                    start_time,
+                                    time,
+                                    count: 3,
+                                    min: Some(1.0),
+                                    max: Some(4.0),
+                                    sum: 7.0,
+                                    scale: 2,
+                                    zero_count: 0,
+                                    positive_bucket: SdkExponentialBucket {
+                                        offset: -1,
+                                        counts: vec![1, 0, 2],
+                                    },
+                                    negative_bucket: SdkExponentialBucket {
+                                        offset: 0,
+                                        counts: vec![],
+                                    },
+                                    zero_threshold: 0.0,
+                                    exemplars: vec![],
+                                }],
+                                temporality: Temporality::Cumulative,
+                            }),
+                        },
+                    ],
+                }],
+            }
+        }
+
+        #[test]
+        fn export_request_round_trip() {
+            let request = ExportMetricsServiceRequest::from(&resource_metrics());
+            let resource_metrics = Vec::<data::ResourceMetrics>::try_from(request.clone()).unwrap();
+
+            assert_eq!(resource_metrics.len(), 1);
+            let metrics = &resource_metrics[0].scope_metrics[0].metrics;
+            let sum = metrics[0]
+                .data
+                .as_any()
+                .downcast_ref::<SdkSum<i64>>()
+                .unwrap();
+            assert_eq!(sum.data_points[0].value, 42);
+            assert_eq!(sum.data_points[0].exemplars[0].trace_id, [2; 16]);
+            assert!(metrics[1]
+                .data
+                .as_any()
+                .downcast_ref::<SdkGauge<f64>>()
+                .is_some());
+
+            assert_eq!(
+                ExportMetricsServiceRequest::from(&resource_metrics[0]),
+                request
+            );
+        }
+
+        #[test]
+        fn unspecified_temporality_is_rejected() {
+            let mut request = ExportMetricsServiceRequest::from(&resource_metrics());
+            if let Some(TonicMetricData::Sum(sum)) =
+                &mut request.resource_metrics[0].scope_metrics[0].metrics[0].data
+            {
+                sum.aggregation_temporality = TonicTemporality::Unspecified as i32;
+            }
+
+            assert_eq!(
+                Vec::<data::ResourceMetrics>::try_from(request).unwrap_err(),
+                ConversionError::MissingField("aggregation_temporality")
+            );
+        }
+
+        #[test]
+        fn malformed_exemplar_ids_are_rejected() {
+            let mut request = ExportMetricsServiceRequest::from(&resource_metrics());
+            if let Some(TonicMetricData::Sum(sum)) =
+                &mut request.resource_metrics[0].scope_metrics[0].metrics[0].data
+            {
+                sum.data_points[0].exemplars[0].span_id = vec![1; 4];
+            }
+
+            assert_eq!(
+                Vec::<data::ResourceMetrics>::try_from(request).unwrap_err(),
+                ConversionError::InvalidSpanId(vec![1; 4])
+            );
+        }
+
+        #[test]
+        fn double_exemplars_keep_their_fraction() {
+            let mut request = ExportMetricsServiceRequest::from(&resource_metrics());
+            if let Some(TonicMetricData::Sum(sum)) =
+                &mut request.resource_metrics[0].scope_metrics[0].metrics[0].data
+            {
+                sum.data_points[0].exemplars[0].value = Some(TonicExemplarValue::AsDouble(7.5));
+            }
+
+            let resource_metrics = Vec::<data::ResourceMetrics>::try_from(request).unwrap();
+            let sum = resource_metrics[0].scope_metrics[0].metrics[0]
+                .data
+                .as_any()
+                .downcast_ref::<SdkSum<f64>>()
+                .unwrap();
+            assert_eq!(sum.data_points[0].value, 42.0);
+            assert_eq!(sum.data_points[0].exemplars[0].value, 7.5);
+        }
+
+        #[test]
+        fn inexact_values_are_rejected() {
+            assert!(i64::from_f64(1.5).is_err());
+            assert!(i64::from_f64(f64::NAN).is_err());
+            assert!(i64::from_f64(9.3e18).is_err());
+            assert_eq!(i64::from_f64(-3.0), Ok(-3));
+            assert_eq!(f64::from_i64(-3), Ok(-3.0));
+            assert!(f64::from_i64(i64::MAX).is_err());
+
+            let mut request = ExportMetricsServiceRequest::from(&resource_metrics());
+            if let Some(TonicMetricData::Gauge(gauge)) =
+                &mut request.resource_metrics[0].scope_metrics[0].metrics[1].data
+            {
+                gauge.data_points[0].exemplars = sum_exemplars();
+                gauge.data_points[0].exemplars[0].value = Some(TonicExemplarValue::AsInt(i64::MAX));
+            }
+            assert!(matches!(
+                Vec::<data::ResourceMetrics>::try_from(request),
+                Err(ConversionError::UnsupportedValue(_))
+            ));
+        }
+
+        fn sum_exemplars() -> Vec<TonicExemplar> {
+            match ExportMetricsServiceRequest::from(&resource_metrics()).resource_metrics[0]
+                .scope_metrics[0]
+                .metrics[0]
+                .data
+                .take()
+            {
+                Some(TonicMetricData::Sum(mut sum)) => sum.data_points.remove(0).exemplars,
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -1,14 +1,30 @@
 #[cfg(feature = "gen-tonic-messages")]
 pub mod tonic {
+    use crate::proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
     use crate::proto::tonic::resource::v1::Resource;
-    use crate::proto::tonic::trace::v1::{span, status, ResourceSpans, ScopeSpans, Span, Status};
+    use crate::proto::tonic::trace::v1::{
+        span, status, ResourceSpans, ScopeSpans, Span, SpanFlags, Status,
+    };
     use crate::transform::common::{
-        to_nanos,
-        tonic::{resource_attributes, Attributes},
+        from_nanos, to_nanos,
+        tonic::{
+            attributes_from_proto, instrumentation_library_from_proto, resource_attributes,
+            resource_from_proto, span_id_from_bytes, trace_id_from_bytes, Attributes,
+            ConversionError,
+        },
     };
     use opentelemetry::trace;
-    use opentelemetry::trace::{Link, SpanId, SpanKind};
+    use opentelemetry::trace::{
+        Event, Link, SpanContext, SpanId, SpanKind, TraceFlags, TraceId, TraceState,
+    };
     use opentelemetry_sdk::export::trace::SpanData;
+    #[cfg(feature = "persistence")]
+    use opentelemetry_sdk::persistence::{BatchCodec, PersistenceError};
+    use opentelemetry_sdk::trace::{SpanEvents, SpanLinks};
+    #[cfg(feature = "persistence")]
+    use prost::Message;
+    use std::borrow::Cow;
+    use std::str::FromStr;
 
     impl From<SpanKind> for span::SpanKind {
         fn from(span_kind: SpanKind) -> Self {
@@ -109,4 +125,324 @@ pub mod tonic {
             }
         }
     }
+
+    impl From<span::SpanKind> for SpanKind {
+        fn from(span_kind: span::SpanKind) -> Self {
+            match span_kind {
+                // spans of unspecified kind are treated as internal ones
+                span::SpanKind::Unspecified | span::SpanKind::Internal => SpanKind::Internal,
+                span::SpanKind::Server => SpanKind::Server,
+                span::SpanKind::Client => SpanKind::Client,
+                span::SpanKind::Producer => SpanKind::Producer,
+                span::SpanKind::Consumer => SpanKind::Consumer,
+            }
+        }
+    }
+
+    impl TryFrom<Status> for trace::Status {
+        type Error = ConversionError;
+
+        fn try_from(status: Status) -> Result<Self, ConversionError> {
+            let code = status::StatusCode::try_from(status.code).map_err(|_| {
+                ConversionError::UnknownEnumValue {
+                    field: "Status.code",
+                    value: status.code,
+                }
+            })?;
+            Ok(match code {
+                status::StatusCode::Unset => trace::Status::Unset,
+                status::StatusCode::Ok => trace::Status::Ok,
+                status::StatusCode::Error => trace::Status::error(status.message),
+            })
+        }
+    }
+
+    fn span_context(
+        trace_id: &[u8],
+        span_id: &[u8],
+        flags: u32,
+        trace_state: &str,
+    ) -> Result<SpanContext, ConversionError> {
+        let trace_state = if trace_state.is_empty() {
+            TraceState::default()
+        } else {
+            TraceState::from_str(trace_state)
+                .map_err(|_| ConversionError::InvalidTraceState(trace_state.to_string()))?
+        };
+        let is_remote = flags & SpanFlags::ContextHasIsRemoteMask as u32 != 0
+            && flags & SpanFlags::ContextIsRemoteMask as u32 != 0;
+
+        Ok(SpanContext::new(
+            trace_id_from_bytes(trace_id)?,
+            span_id_from_bytes(span_id)?,
+            TraceFlags::new((flags & SpanFlags::TraceFlagsMask as u32) as u8),
+            is_remote,
+            trace_state,
+        ))
+    }
+
+    impl TryFrom<span::Link> for Link {
+        type Error = ConversionError;
+
+        fn try_from(link: span::Link) -> Result<Self, Self::Error> {
+            Ok(Link::new(
+                span_context(&link.trace_id, &link.span_id, link.flags, &link.trace_state)?,
+                attributes_from_proto(link.attributes)?,
+                link.dropped_attributes_count,
+            ))
+        }
+    }
+
+    impl TryFrom<span::Event> for Event {
+        type Error = ConversionError;
+
+        fn try_from(event: span::Event) -> Result<Self, Self::Error> {
+            Ok(Event::new(
+                event.name,
+                from_nanos(event.time_unix_nano),
+                attributes_from_proto(event.attributes)?,
+                event.dropped_attributes_count,
+            ))
+        }
+    }
+
+    impl TryFrom<ResourceSpans> for Vec<SpanData> {
+        type Error = ConversionError;
+
+        fn try_from(resource_spans: ResourceSpans) -> Result<Self, Self::Error> {
+            let resource = resource_from_proto(resource_spans.resource, resource_spans.schema_url)?;
+            let mut spans = Vec::new();
+
+            for scope_spans in resource_spans.scope_spans {
+                let instrumentation_lib =
+                    instrumentation_library_from_proto(scope_spans.scope, scope_spans.schema_url)?;
+
+                for span in scope_spans.spans {
+                    let trace_id = trace_id_from_bytes(&span.trace_id)?;
+                    let span_id = span_id_from_bytes(&span.span_id)?;
+                    if trace_id == TraceId::INVALID {
+                        return Err(ConversionError::InvalidTraceId(span.trace_id));
+                    }
+                    if span_id == SpanId::INVALID {
+                        return Err(ConversionError::InvalidSpanId(span.span_id));
+                    }
+
+                    let span_kind = span::SpanKind::try_from(span.kind).map_err(|_| {
+                        ConversionError::UnknownEnumValue {
+                            field: "Span.kind",
+                            value: span.kind,
+                        }
+                    })?;
+
+                    let mut events = SpanEvents::default();
+                    events.events = span
+                        .events
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?;
+                    events.dropped_count = span.dropped_events_count;
+
+                    let mut links = SpanLinks::default();
+                    links.links = span
+                        .links
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?;
+                    links.dropped_count = span.dropped_links_count;
+
+                    spans.push(SpanData {
+                        span_context: span_context(
+                            &span.trace_id,
+                            &span.span_id,
+                            span.flags,
+                            &span.trace_state,
+                        )?,
+                        parent_span_id: span_id_from_bytes(&span.parent_span_id)?,
+                        span_kind: span_kind.into(),
+                        name: span.name.into(),
+                        start_time: from_nanos(span.start_time_unix_nano),
+                        end_time: from_nanos(span.end_time_unix_nano),
+                        attributes: attributes_from_proto(span.attributes)?,
+                        dropped_attributes_count: span.dropped_attributes_count,
+                        events,
+                        links,
+                        status: span
+                            .status
+                            .map(TryInto::try_into)
+                            .transpose()?
+                            .unwrap_or_default(),
+                        resource: Cow::Owned(resource.clone()),
+                        instrumentation_lib: instrumentation_lib.clone(),
+                    });
+                }
+            }
+
+            Ok(spans)
+        }
+    }
+
+    impl TryFrom<ExportTraceServiceRequest> for Vec<SpanData> {
+        type Error = ConversionError;
+
+        fn try_from(request: ExportTraceServiceRequest) -> Result<Self, Self::Error> {
+            let mut spans = Vec::new();
+            for resource_spans in request.resource_spans {
+                spans.extend(Vec::<SpanData>::try_from(resource_spans)?);
+            }
+
+            Ok(spans)
+        }
+    }
+
+    /// Encodes batches of spans in a [persistent queue] as
+    /// [`ExportTraceServiceRequest`] protobuf messages.
+    ///
+    /// [persistent queue]: opentelemetry_sdk::persistence::PersistentQueue
+    #[cfg(feature = "persistence")]
+    #[derive(Clone, Debug, Default)]
+    pub struct OtlpSpanCodec;
+
+    #[cfg(feature = "persistence")]
+    impl BatchCodec<SpanData> for OtlpSpanCodec {
+        fn encode(&self, batch: &[SpanData]) -> Result<Vec<u8>, PersistenceError> {
+            let request = ExportTraceServiceRequest {
+                resource_spans: batch.iter().cloned().map(Into::into).collect(),
+            };
+            Ok(request.encode_to_vec())
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<Vec<SpanData>, PersistenceError> {
+            let request = ExportTraceServiceRequest::decode(bytes)
+                .map_err(|err| PersistenceError::Codec(err.to_string()))?;
+            Vec::<SpanData>::try_from(request)
+                .map_err(|err| PersistenceError::Codec(err.to_string()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use opentelemetry::KeyValue;
+        use opentelemetry_sdk::{InstrumentationLibrary, Resource};
+        use std::time::{Duration, SystemTime};
+
+        fn span_data() -> SpanData {
+            let start_time =
+                SystemTime::UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789);
+            let mut events = SpanEvents::default();
+            events.events.push(Event::new(
+                "event",
+                start_time + Duration::from_millis(1),
+                vec![KeyValue::new("event.key", 1)],
+                0,
+            ));
+            let mut links = SpanLinks::default();
+            links.links.push(Link::new(
+                SpanContext::new(
+                    TraceId::from_bytes((3u128).to_be_bytes()),
+                    SpanId::from_bytes((4u64).to_be_bytes()),
+                    TraceFlags::SAMPLED,
+                    false,
+                    TraceState::from_key_value([("vendor", "value")]).unwrap(),
+                ),
+                vec![KeyValue::new("link.key", true)],
+                1,
+            ));
+            links.dropped_count = 2;
+
+            SpanData {
+                span_context: SpanContext::new(
+                    TraceId::from_bytes((1u128).to_be_bytes()),
+                    SpanId::from_bytes((2u64).to_be_bytes()),
+                    TraceFlags::SAMPLED,
+                    false,
+                    TraceState::default(),
+                ),
+                parent_span_id: SpanId::from_bytes((5u64).to_be_bytes()),
+                span_kind: SpanKind::Server,
+                name: "span".into(),
+                start_time,
+                end_time: start_time + Duration::from_secs(1),
+                attributes: vec![
+                    KeyValue::new("string", "value"),
+                    KeyValue::new("array", opentelemetry::Value::Array(vec![1.5, 2.5].into())),
+                ],
+                dropped_attributes_count: 3,
+                events,
+                links,
+                status: trace::Status::error("failed"),
+                resource: Cow::Owned(Resource::from_schema_url(
+                    [KeyValue::new("service.name", "test")],
+                    "https://opentelemetry.io/schemas/1.21.0",
+                )),
+                instrumentation_lib: InstrumentationLibrary::builder("library")
+                    .with_version("1.0")
+                    .with_schema_url("https://opentelemetry.io/schemas/1.21.0")
+                    .build(),
+            }
+        }
+
+        #[test]
+        fn resource_spans_round_trip() {
+            let span = span_data();
+            let spans = Vec::<SpanData>::try_from(ResourceSpans::from(span.clone())).unwrap();
+            assert_eq!(spans, vec![span]);
+        }
+
+        #[test]
+        #[cfg(feature = "persistence")]
+        fn span_codec_round_trip() {
+            let batch = vec![span_data(), span_data()];
+            let bytes = OtlpSpanCodec.encode(&batch).unwrap();
+            assert_eq!(OtlpSpanCodec.decode(&bytes).unwrap(), batch);
+            assert!(matches!(
+                OtlpSpanCodec.decode(&bytes[..bytes.len() - 1]),
+                Err(PersistenceError::Codec(_))
+            ));
+        }
+
+        #[test]
+        fn malformed_ids_are_rejected() {
+            let mut resource_spans = ResourceSpans::from(span_data());
+            resource_spans.scope_spans[0].spans[0].span_id = vec![1, 2, 3];
+            assert_eq!(
+                Vec::<SpanData>::try_from(resource_spans),
+                Err(ConversionError::InvalidSpanId(vec![1, 2, 3]))
+            );
+
+            let mut resource_spans = ResourceSpans::from(span_data());
+            resource_spans.scope_spans[0].spans[0].trace_id = vec![];
+            assert_eq!(
+                Vec::<SpanData>::try_from(resource_spans),
+                Err(ConversionError::InvalidTraceId(vec![]))
+            );
+        }
+
+        #[test]
+        fn unknown_enum_values_are_rejected() {
+            let mut resource_spans = ResourceSpans::from(span_data());
+            resource_spans.scope_spans[0].spans[0].kind = 42;
+            assert_eq!(
+                Vec::<SpanData>::try_from(resource_spans),
+                Err(ConversionError::UnknownEnumValue {
+                    field: "Span.kind",
+                    value: 42
+                })
+            );
+
+            let mut resource_spans = ResourceSpans::from(span_data());
+            resource_spans.scope_spans[0].spans[0]
+                .status
+                .as_mut()
+                .unwrap()
+                .code = 7;
+            assert_eq!(
+                Vec::<SpanData>::try_from(resource_spans),
+                Err(ConversionError::UnknownEnumValue {
+                    field: "Status.code",
+                    value: 7
+                })
+            );
+        }
+    }
 }
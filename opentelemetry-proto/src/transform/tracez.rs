@@ -2,13 +2,29 @@
 mod tonic {
     use opentelemetry::trace::{Event, Status};
     use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::trace::zpages::TracezSummary;
 
     use crate::proto::tonic::{
         trace::v1::{span::Event as SpanEvent, Status as SpanStatus},
-        tracez::v1::{ErrorData, LatencyData, RunningData},
+        tracez::v1::{ErrorData, LatencyData, RunningData, TracezCounts},
     };
     use crate::transform::common::{to_nanos, tonic::Attributes};
 
+    impl From<TracezSummary> for TracezCounts {
+        fn from(summary: TracezSummary) -> Self {
+            TracezCounts {
+                spanname: summary.span_name.into_owned(),
+                latency: summary
+                    .latency
+                    .iter()
+                    .map(|count| u32::try_from(*count).unwrap_or(u32::MAX))
+                    .collect(),
+                running: u32::try_from(summary.running).unwrap_or(u32::MAX),
+                error: u32::try_from(summary.errors).unwrap_or(u32::MAX),
+            }
+        }
+    }
+
     impl From<SpanData> for LatencyData {
         fn from(span_data: SpanData) -> Self {
             LatencyData {
@@ -68,6 +68,15 @@ fn build_tonic() {
         "trace.v1.Span.trace_id",
         "trace.v1.Span.span_id",
         "trace.v1.Span.parent_span_id",
+        "tracez.v1.LatencyData.traceid",
+        "tracez.v1.LatencyData.spanid",
+        "tracez.v1.LatencyData.parentid",
+        "tracez.v1.RunningData.traceid",
+        "tracez.v1.RunningData.spanid",
+        "tracez.v1.RunningData.parentid",
+        "tracez.v1.ErrorData.traceid",
+        "tracez.v1.ErrorData.spanid",
+        "tracez.v1.ErrorData.parentid",
     ] {
         builder = builder
             .field_attribute(path, "#[cfg_attr(feature = \"with-serde\", serde(serialize_with = \"crate::proto::serializers::serialize_to_hex_string\", deserialize_with = \"crate::proto::serializers::deserialize_from_hex_string\"))]")
@@ -0,0 +1,586 @@
+//! Metrics the SDK reports about its own pipelines, following the semantic
+//! conventions of the [OpenTelemetry SDK metrics].
+//!
+//! Processors and readers report their metrics once they are given a meter
+//! provider. Without one, or without the `metrics` feature, the handles of
+//! this module do nothing.
+//!
+//! [OpenTelemetry SDK metrics]: https://opentelemetry.io/docs/specs/semconv/otel/sdk-metrics/
+#![cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(all(feature = "metrics", any(feature = "trace", feature = "logs")))]
+use opentelemetry::metrics::UpDownCounter;
+#[cfg(feature = "metrics")]
+use opentelemetry::{
+    metrics::{Counter, Histogram, MeterProvider, Unit},
+    KeyValue,
+};
+#[cfg(all(feature = "metrics", any(feature = "trace", feature = "logs")))]
+use std::time::Instant;
+#[cfg(feature = "metrics")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// The `error.type` of items dropped because the queue of a processor is full.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) const QUEUE_FULL: &str = "queue_full";
+/// The `error.type` of items dropped because their processor is shut down.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) const SHUTDOWN: &str = "shutdown";
+/// The `error.type` of exports that timed out.
+pub(crate) const TIMEOUT: &str = "timeout";
+/// The `error.type` of failures of other kinds.
+pub(crate) const OTHER: &str = "_OTHER";
+
+#[cfg(feature = "metrics")]
+const COMPONENT_TYPE: &str = "otel.component.type";
+#[cfg(feature = "metrics")]
+const COMPONENT_NAME: &str = "otel.component.name";
+#[cfg(feature = "metrics")]
+const ERROR_TYPE: &str = "error.type";
+
+/// The kind of items a pipeline processes.
+#[cfg(all(feature = "metrics", any(feature = "trace", feature = "logs")))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Signal {
+    #[cfg(feature = "trace")]
+    Span,
+    #[cfg(feature = "logs")]
+    Log,
+}
+
+#[cfg(all(feature = "metrics", any(feature = "trace", feature = "logs")))]
+impl Signal {
+    /// The name of the signal in metric names.
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Span => "span",
+            #[cfg(feature = "logs")]
+            Signal::Log => "log",
+        }
+    }
+
+    /// The unit of the number of items.
+    fn unit(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Span => "{span}",
+            #[cfg(feature = "logs")]
+            Signal::Log => "{log_record}",
+        }
+    }
+
+    fn items(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Span => "spans",
+            #[cfg(feature = "logs")]
+            Signal::Log => "log records",
+        }
+    }
+}
+
+/// The `error.type` of a failed operation.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) trait ErrorType {
+    fn error_type(&self) -> &'static str;
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl ErrorType for crate::runtime::TrySendError {
+    fn error_type(&self) -> &'static str {
+        match self {
+            crate::runtime::TrySendError::ChannelFull => QUEUE_FULL,
+            crate::runtime::TrySendError::ChannelClosed => SHUTDOWN,
+            _ => OTHER,
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl ErrorType for opentelemetry::trace::TraceError {
+    fn error_type(&self) -> &'static str {
+        match self {
+            opentelemetry::trace::TraceError::ExportFailed(_) => "export_failed",
+            opentelemetry::trace::TraceError::ExportTimedOut(_) => TIMEOUT,
+            _ => OTHER,
+        }
+    }
+}
+
+#[cfg(feature = "logs")]
+impl ErrorType for opentelemetry::logs::LogError {
+    fn error_type(&self) -> &'static str {
+        match self {
+            opentelemetry::logs::LogError::ExportFailed(_) => "export_failed",
+            opentelemetry::logs::LogError::ExportTimedOut(_) => TIMEOUT,
+            _ => OTHER,
+        }
+    }
+}
+
+/// The attributes identifying a new instance of a component of `component_type`,
+/// named after the number of instances of that type created before.
+#[cfg(feature = "metrics")]
+fn component(component_type: &'static str) -> Vec<KeyValue> {
+    static INSTANCES: once_cell::sync::Lazy<Mutex<HashMap<&'static str, usize>>> =
+        once_cell::sync::Lazy::new(Default::default);
+
+    let id = match INSTANCES.lock() {
+        Ok(mut instances) => {
+            let count = instances.entry(component_type).or_default();
+            *count += 1;
+            *count - 1
+        }
+        Err(_) => 0,
+    };
+    vec![
+        KeyValue::new(COMPONENT_TYPE, component_type),
+        KeyValue::new(COMPONENT_NAME, format!("{}/{}", component_type, id)),
+    ]
+}
+
+#[cfg(feature = "metrics")]
+fn with_error_type(attributes: &[KeyValue], error_type: Option<&'static str>) -> Vec<KeyValue> {
+    let mut attributes = attributes.to_vec();
+    if let Some(error_type) = error_type {
+        attributes.push(KeyValue::new(ERROR_TYPE, error_type));
+    }
+    attributes
+}
+
+#[cfg(feature = "metrics")]
+fn meter(provider: &impl MeterProvider) -> opentelemetry::metrics::Meter {
+    provider.versioned_meter(
+        "opentelemetry_sdk",
+        Some(env!("CARGO_PKG_VERSION")),
+        None::<&'static str>,
+        None,
+    )
+}
+
+/// The metrics of an exporter, reported by the processor or reader calling it.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ExporterMetrics {
+    #[cfg(feature = "metrics")]
+    instruments: Option<Arc<ExporterInstruments>>,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct ExporterInstruments {
+    /// The number of items exported, absent for metric exporters.
+    exported: Option<Counter<u64>>,
+    duration: Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl ExporterMetrics {
+    #[cfg(feature = "metrics")]
+    fn new(
+        meter: &opentelemetry::metrics::Meter,
+        component_type: &'static str,
+        exported: Option<Counter<u64>>,
+    ) -> Self {
+        let duration = meter
+            .f64_histogram("otel.sdk.exporter.operation.duration")
+            .with_unit(Unit::new("s"))
+            .with_description("The duration of exporting a batch of telemetry records.")
+            .init();
+
+        ExporterMetrics {
+            instruments: Some(Arc::new(ExporterInstruments {
+                exported,
+                duration,
+                attributes: component(component_type),
+            })),
+        }
+    }
+
+    /// Record an export of `count` items that took `duration`, failed with
+    /// `error_type` if any.
+    pub(crate) fn record(
+        &self,
+        count: usize,
+        error_type: Option<&'static str>,
+        duration: Duration,
+    ) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            let attributes = with_error_type(&instruments.attributes, error_type);
+            if let Some(exported) = &instruments.exported {
+                exported.add(count as u64, &attributes);
+            }
+            instruments
+                .duration
+                .record(duration.as_secs_f64(), &attributes);
+        }
+    }
+
+    /// Run `export` of `count` items, recording its duration and result.
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    pub(crate) async fn measure<F, E>(&self, count: usize, export: F) -> Result<(), E>
+    where
+        F: Future<Output = Result<(), E>>,
+        E: ErrorType,
+    {
+        #[cfg(feature = "metrics")]
+        if self.instruments.is_some() {
+            let start = Instant::now();
+            let result = export.await;
+            let error_type = result.as_ref().err().map(ErrorType::error_type);
+            self.record(count, error_type, start.elapsed());
+            return result;
+        }
+
+        export.await
+    }
+}
+
+/// The metrics of a batching processor and its exporter.
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ProcessorMetrics {
+    #[cfg(feature = "metrics")]
+    instruments: Option<Arc<ProcessorInstruments>>,
+    exporter: ExporterMetrics,
+}
+
+#[cfg(all(feature = "metrics", any(feature = "trace", feature = "logs")))]
+#[derive(Debug)]
+struct ProcessorInstruments {
+    queue_size: UpDownCounter<i64>,
+    queue_capacity: UpDownCounter<i64>,
+    processed: Counter<u64>,
+    attributes: Vec<KeyValue>,
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl ProcessorMetrics {
+    /// Create the metrics of a batching processor of `signal` with the meters
+    /// of `provider`.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn new(provider: &impl MeterProvider, signal: Signal) -> Self {
+        let meter = meter(provider);
+        let name = signal.name();
+        let unit = signal.unit();
+        let items = signal.items();
+
+        let queue_size = meter
+            .i64_up_down_counter(format!("otel.sdk.processor.{}.queue.size", name))
+            .with_unit(Unit::new(unit))
+            .with_description(format!(
+                "The number of {} in the queue of a given instance of an SDK processor.",
+                items
+            ))
+            .init();
+        let queue_capacity = meter
+            .i64_up_down_counter(format!("otel.sdk.processor.{}.queue.capacity", name))
+            .with_unit(Unit::new(unit))
+            .with_description(format!(
+                "The maximum number of {} the queue of a given instance of an SDK processor can hold.",
+                items
+            ))
+            .init();
+        let processed = meter
+            .u64_counter(format!("otel.sdk.processor.{}.processed", name))
+            .with_unit(Unit::new(unit))
+            .with_description(format!(
+                "The number of {} for which the processing has finished, either successful or failed.",
+                items
+            ))
+            .init();
+
+        let exported = meter
+            .u64_counter(format!("otel.sdk.exporter.{}.exported", name))
+            .with_unit(Unit::new(unit))
+            .with_description(format!(
+                "The number of {} for which the export has finished, either successful or failed.",
+                items
+            ))
+            .init();
+        let (processor_type, exporter_type) = match signal {
+            #[cfg(feature = "trace")]
+            Signal::Span => ("batching_span_processor", "span_exporter"),
+            #[cfg(feature = "logs")]
+            Signal::Log => ("batching_log_processor", "log_exporter"),
+        };
+
+        ProcessorMetrics {
+            instruments: Some(Arc::new(ProcessorInstruments {
+                queue_size,
+                queue_capacity,
+                processed,
+                attributes: component(processor_type),
+            })),
+            exporter: ExporterMetrics::new(&meter, exporter_type, Some(exported)),
+        }
+    }
+
+    /// The metrics of the exporter of the processor.
+    pub(crate) fn exporter(&self) -> &ExporterMetrics {
+        &self.exporter
+    }
+
+    /// Record the capacity of the queue, once the processor is built.
+    pub(crate) fn record_capacity(&self, capacity: usize) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            instruments
+                .queue_capacity
+                .add(capacity as i64, &instruments.attributes);
+        }
+    }
+
+    /// Record an item added to the queue.
+    pub(crate) fn enqueued(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            instruments.queue_size.add(1, &instruments.attributes);
+        }
+    }
+
+    /// Record an item taken from the queue by the processor's worker.
+    pub(crate) fn dequeued(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            instruments.queue_size.add(-1, &instruments.attributes);
+        }
+    }
+
+    /// Record an item dropped for the reason `error_type`.
+    pub(crate) fn dropped(&self, error_type: &'static str) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            let attributes = with_error_type(&instruments.attributes, Some(error_type));
+            instruments.processed.add(1, &attributes);
+        }
+    }
+
+    /// Record `count` items whose processing finished, once they left the
+    /// queue to be exported or persisted, failed with `error_type` if any.
+    pub(crate) fn processed(&self, count: usize, error_type: Option<&'static str>) {
+        #[cfg(feature = "metrics")]
+        if let Some(instruments) = &self.instruments {
+            if count > 0 {
+                let attributes = with_error_type(&instruments.attributes, error_type);
+                instruments.processed.add(count as u64, &attributes);
+            }
+        }
+    }
+}
+
+/// The metrics of a periodic reader and its exporter.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReaderMetrics {
+    instruments: Option<Arc<ReaderInstruments>>,
+    exporter: ExporterMetrics,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct ReaderInstruments {
+    collection_duration: Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+#[cfg(feature = "metrics")]
+impl ReaderMetrics {
+    /// Create the metrics of a periodic reader with the meters of `provider`.
+    pub(crate) fn new(provider: &impl MeterProvider) -> Self {
+        let meter = meter(provider);
+        let collection_duration = meter
+            .f64_histogram("otel.sdk.metric_reader.collection.duration")
+            .with_unit(Unit::new("s"))
+            .with_description("The duration of the collect operation of the metric reader.")
+            .init();
+
+        ReaderMetrics {
+            instruments: Some(Arc::new(ReaderInstruments {
+                collection_duration,
+                attributes: component("periodic_metric_reader"),
+            })),
+            exporter: ExporterMetrics::new(&meter, "metric_exporter", None),
+        }
+    }
+
+    /// The metrics of the exporter of the reader.
+    pub(crate) fn exporter(&self) -> &ExporterMetrics {
+        &self.exporter
+    }
+
+    /// Record a collection that took `duration`, failed with `error_type` if
+    /// any.
+    pub(crate) fn record_collection(&self, error_type: Option<&'static str>, duration: Duration) {
+        if let Some(instruments) = &self.instruments {
+            instruments.collection_duration.record(
+                duration.as_secs_f64(),
+                &with_error_type(&instruments.attributes, error_type),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::metrics::data::{self, ResourceMetrics};
+    use crate::metrics::{SdkMeterProvider, ThreadedPeriodicReader};
+    use crate::testing::metrics::InMemoryMetricsExporter;
+    use crate::testing::trace::{new_test_export_span_data, InMemorySpanExporterBuilder};
+    use crate::trace::{SpanProcessor, ThreadedBatchSpanProcessor};
+    use opentelemetry::Value;
+
+    fn meter_provider() -> (SdkMeterProvider, InMemoryMetricsExporter) {
+        let exporter = InMemoryMetricsExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(ThreadedPeriodicReader::builder(exporter.clone()).build())
+            .build();
+        (meter_provider, exporter)
+    }
+
+    /// The data points of the sum `name` in the last export, as values and
+    /// `error.type` attributes.
+    fn sum<T: Copy + 'static>(metrics: &[ResourceMetrics], name: &str) -> Vec<(T, Option<Value>)> {
+        let metric = metrics
+            .last()
+            .expect("metrics are expected to be exported")
+            .scope_metrics
+            .iter()
+            .flat_map(|scope| &scope.metrics)
+            .find(|metric| metric.name == name)
+            .unwrap_or_else(|| panic!("{} is expected to be exported", name));
+        let sum = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<T>>()
+            .expect("sum aggregation expected");
+        sum.data_points
+            .iter()
+            .map(|dp| {
+                let error_type = dp
+                    .attributes
+                    .iter()
+                    .find(|(key, _)| key.as_str() == ERROR_TYPE)
+                    .map(|(_, value)| value.clone());
+                (dp.value, error_type)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn component_names_are_unique() {
+        let first = component("test_component");
+        let second = component("test_component");
+        assert_eq!(first[0], KeyValue::new(COMPONENT_TYPE, "test_component"));
+        assert_ne!(first[1], second[1]);
+    }
+
+    #[test]
+    fn batch_span_processor_metrics() {
+        let (meter_provider, metrics_exporter) = meter_provider();
+        let mut processor =
+            ThreadedBatchSpanProcessor::builder(InMemorySpanExporterBuilder::new().build())
+                .with_meter_provider(&meter_provider)
+                .build();
+
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        processor.force_flush().unwrap();
+        processor.shutdown().unwrap();
+        processor.on_end(new_test_export_span_data());
+        meter_provider.force_flush().unwrap();
+
+        let metrics = metrics_exporter.get_finished_metrics().unwrap();
+        assert_eq!(
+            sum::<i64>(&metrics, "otel.sdk.processor.span.queue.capacity"),
+            vec![(2048, None)]
+        );
+        assert_eq!(
+            sum::<i64>(&metrics, "otel.sdk.processor.span.queue.size"),
+            vec![(0, None)]
+        );
+        let mut processed = sum::<u64>(&metrics, "otel.sdk.processor.span.processed");
+        processed.sort_by_key(|(_, error_type)| error_type.is_some());
+        assert_eq!(processed, vec![(3, None), (1, Some(Value::from(SHUTDOWN)))]);
+        assert_eq!(
+            sum::<u64>(&metrics, "otel.sdk.exporter.span.exported"),
+            vec![(3, None)]
+        );
+    }
+
+    #[derive(Debug)]
+    struct FailingExporter;
+
+    impl crate::export::trace::SpanExporter for FailingExporter {
+        fn export(
+            &mut self,
+            _batch: Vec<crate::export::trace::SpanData>,
+        ) -> futures_util::future::BoxFuture<'static, crate::export::trace::ExportResult> {
+            Box::pin(std::future::ready(Err("unavailable".into())))
+        }
+    }
+
+    #[test]
+    fn batch_span_processor_records_export_outcome() {
+        let (meter_provider, metrics_exporter) = meter_provider();
+        let processor = ThreadedBatchSpanProcessor::builder(FailingExporter)
+            .with_meter_provider(&meter_provider)
+            .build();
+
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        assert!(processor.force_flush().is_err());
+        meter_provider.force_flush().unwrap();
+
+        let metrics = metrics_exporter.get_finished_metrics().unwrap();
+        assert_eq!(
+            sum::<u64>(&metrics, "otel.sdk.processor.span.processed"),
+            vec![(3, Some(Value::from(OTHER)))]
+        );
+    }
+
+    #[test]
+    fn periodic_reader_metrics() {
+        let (meter_provider, metrics_exporter) = meter_provider();
+        let reader = ThreadedPeriodicReader::builder(InMemoryMetricsExporter::default())
+            .with_meter_provider(&meter_provider)
+            .build();
+        let observed = SdkMeterProvider::builder().with_reader(reader).build();
+
+        observed.force_flush().unwrap();
+        observed.force_flush().unwrap();
+        meter_provider.force_flush().unwrap();
+
+        let metrics = metrics_exporter.get_finished_metrics().unwrap();
+        let histogram_count = |name: &str| {
+            metrics
+                .last()
+                .unwrap()
+                .scope_metrics
+                .iter()
+                .flat_map(|scope| &scope.metrics)
+                .find(|metric| metric.name == name)
+                .and_then(|metric| metric.data.as_any().downcast_ref::<data::Histogram<f64>>())
+                .map(|histogram| histogram.data_points.iter().map(|dp| dp.count).sum::<u64>())
+        };
+        assert_eq!(
+            histogram_count("otel.sdk.metric_reader.collection.duration"),
+            Some(2)
+        );
+        assert_eq!(
+            histogram_count("otel.sdk.exporter.operation.duration"),
+            Some(2)
+        );
+    }
+}
@@ -86,6 +86,12 @@
 //! For `trace` the following feature flags are available:
 //!
 //! * `jaeger_remote_sampler`: Enables the [Jaeger remote sampler](https://www.jaegertracing.io/docs/1.53/sampling/).
+//! * `zpages`: Enables the [zPages](crate::trace::zpages) span processor.
+//!
+//! For `metrics` the following feature flags are available:
+//!
+//! * `metrics_patterns`: Select the instruments of views by regular expression
+//!   and scope version requirement, and redact attribute values.
 //!
 //! For `logs` the following feature flags are available:
 //!
@@ -98,6 +104,11 @@
 //! * `rt-tokio-current-thread`: Spawn telemetry tasks on a separate runtime so that the main runtime won't be blocked.
 //! * `rt-async-std`: Spawn telemetry tasks using [async-std]'s runtime.
 //!
+//! The batches of the batch span and log processors can be kept on disk until
+//! exported with the following flag:
+//!
+//! * `persistence`: Enables the [persistent queue](crate::persistence).
+//!
 //! [tokio]: https://crates.io/crates/tokio
 //! [async-std]: https://crates.io/crates/async-std
 #![warn(
@@ -123,12 +134,17 @@
 pub(crate) mod attributes;
 pub mod export;
 mod instrumentation;
+#[cfg(any(feature = "trace", feature = "logs", feature = "metrics"))]
+pub(crate) mod internal_metrics;
 #[cfg(feature = "logs")]
 #[cfg_attr(docsrs, doc(cfg(feature = "logs")))]
 pub mod logs;
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub mod metrics;
+#[cfg(feature = "persistence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+pub mod persistence;
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod propagation;
@@ -1,7 +1,8 @@
-use super::{BatchLogProcessor, Config, LogProcessor, SimpleLogProcessor};
+use super::{BatchLogProcessor, Config, LogLimits, LogProcessor, SimpleLogProcessor};
 use crate::{
     export::logs::{LogData, LogExporter},
     runtime::RuntimeChannel,
+    util::sdk_disabled,
 };
 use opentelemetry::{
     global::{self},
@@ -110,6 +111,8 @@ impl LoggerProvider {
 struct LoggerProviderInner {
     processors: Vec<Box<dyn LogProcessor>>,
     config: Config,
+    log_limits: LogLimits,
+    is_disabled: bool,
 }
 
 impl Drop for LoggerProviderInner {
@@ -127,6 +130,7 @@ impl Drop for LoggerProviderInner {
 pub struct Builder {
     processors: Vec<Box<dyn LogProcessor>>,
     config: Config,
+    log_limits: LogLimits,
 }
 
 impl Builder {
@@ -161,12 +165,23 @@ impl Builder {
         Builder { config, ..self }
     }
 
+    /// The `LogLimits` applied to the log records emitted by the loggers of
+    /// this provider.
+    pub fn with_log_limits(self, log_limits: LogLimits) -> Self {
+        Builder { log_limits, ..self }
+    }
+
     /// Create a new provider from this configuration.
+    ///
+    /// The loggers of the provider drop all log records if the
+    /// `OTEL_SDK_DISABLED` environment variable is set to `true`.
     pub fn build(self) -> LoggerProvider {
         LoggerProvider {
             inner: Arc::new(LoggerProviderInner {
                 processors: self.processors,
                 config: self.config,
+                log_limits: self.log_limits,
+                is_disabled: sdk_disabled(),
             }),
         }
     }
@@ -205,8 +220,12 @@ impl Logger {
 
 impl opentelemetry::logs::Logger for Logger {
     /// Emit a `LogRecord`.
-    fn emit(&self, record: LogRecord) {
+    fn emit(&self, mut record: LogRecord) {
         let provider = self.provider();
+        if provider.inner.is_disabled {
+            return;
+        }
+        provider.inner.log_limits.apply(&mut record);
         let config = provider.config();
         let processors = provider.log_processors();
         let trace_context = Context::map_current(|cx| {
@@ -230,6 +249,9 @@ impl opentelemetry::logs::Logger for Logger {
     #[cfg(feature = "logs_level_enabled")]
     fn event_enabled(&self, level: Severity, target: &str) -> bool {
         let provider = self.provider();
+        if provider.inner.is_disabled {
+            return false;
+        }
 
         let mut enabled = false;
         for processor in provider.log_processors() {
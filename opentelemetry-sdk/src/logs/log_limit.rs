@@ -0,0 +1,158 @@
+//! # Log limit
+//! Erroneous code can add unintended attributes to a log record, or attribute
+//! values of unbounded size, which can quickly exhaust available memory or
+//! exceed the payload limits of exporters.
+//!
+//! To protect against those errors, users can use log limits to configure
+//!  - Maximum allowed log record attribute count
+//!  - Maximum allowed length of the attribute values
+//!
+//! If the count limit has been breached, the attributes added later are
+//! dropped. String values longer than the length limit are truncated.
+use std::env;
+use std::str::FromStr;
+
+use opentelemetry::logs::{AnyValue, LogRecord};
+
+use crate::util::truncate_string;
+
+pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD: u32 = 128;
+
+/// Log limit configuration to keep the attributes of a log record in a
+/// reasonable size.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LogLimits {
+    /// The max attributes that can be added to a `LogRecord`.
+    pub max_attributes_per_log_record: u32,
+    /// The max length, in characters, of the string attribute values of a
+    /// `LogRecord`, including the strings of array values. `None` means the
+    /// values are not truncated.
+    pub max_attribute_value_length: Option<u32>,
+}
+
+impl Default for LogLimits {
+    /// Create the default log limits, read from the
+    /// `OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT` and
+    /// `OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT` environment variables.
+    fn default() -> Self {
+        let mut limits = LogLimits {
+            max_attributes_per_log_record: DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD,
+            max_attribute_value_length: None,
+        };
+
+        if let Some(max_attributes_per_log_record) =
+            env::var("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT")
+                .ok()
+                .and_then(|count_limit| u32::from_str(&count_limit).ok())
+        {
+            limits.max_attributes_per_log_record = max_attributes_per_log_record;
+        }
+
+        if let Some(max_attribute_value_length) =
+            env::var("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT")
+                .or_else(|_| env::var("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT"))
+                .ok()
+                .and_then(|length_limit| u32::from_str(&length_limit).ok())
+        {
+            limits.max_attribute_value_length = Some(max_attribute_value_length);
+        }
+
+        limits
+    }
+}
+
+impl LogLimits {
+    /// Drop the attributes of `record` past the count limit, counting them in
+    /// its dropped attributes count, and truncate the attribute values longer
+    /// than the length limit.
+    pub(crate) fn apply(&self, record: &mut LogRecord) {
+        if let Some(attributes) = record.attributes.as_mut() {
+            let max_attributes = self.max_attributes_per_log_record as usize;
+            if attributes.len() > max_attributes {
+                let dropped = attributes.len() - max_attributes;
+                record.dropped_attributes_count = record
+                    .dropped_attributes_count
+                    .saturating_add(dropped as u32);
+                attributes.truncate(max_attributes);
+            }
+            if let Some(max_length) = self.max_attribute_value_length {
+                for (_, value) in attributes.iter_mut() {
+                    truncate_any_value(value, max_length as usize);
+                }
+            }
+        }
+    }
+}
+
+/// Truncate the strings of `value` to `max_length` characters.
+pub(crate) fn truncate_any_value(value: &mut AnyValue, max_length: usize) {
+    match value {
+        AnyValue::String(s) => truncate_string(s, max_length),
+        AnyValue::ListAny(values) => values
+            .iter_mut()
+            .for_each(|value| truncate_any_value(value, max_length)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_limits_from_env() {
+        temp_env::with_vars_unset(
+            [
+                "OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT",
+                "OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+                "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            ],
+            || {
+                let limits = LogLimits::default();
+                assert_eq!(
+                    limits.max_attributes_per_log_record,
+                    DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD
+                );
+                assert_eq!(limits.max_attribute_value_length, None);
+            },
+        );
+
+        temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", None),
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("64")),
+            ],
+            || {
+                let limits = LogLimits::default();
+                assert_eq!(limits.max_attribute_value_length, Some(64));
+            },
+        );
+
+        temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("10")),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("256")),
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("64")),
+            ],
+            || {
+                let limits = LogLimits::default();
+                assert_eq!(limits.max_attributes_per_log_record, 10);
+                assert_eq!(limits.max_attribute_value_length, Some(256));
+            },
+        );
+    }
+
+    #[test]
+    fn truncates_strings() {
+        let mut value = AnyValue::from("héllo");
+        truncate_any_value(&mut value, 2);
+        assert_eq!(value, AnyValue::from("hé"));
+
+        let mut value = AnyValue::ListAny(vec!["abc".into(), 42.into(), "a".into()]);
+        truncate_any_value(&mut value, 1);
+        assert_eq!(
+            value,
+            AnyValue::ListAny(vec!["a".into(), 42.into(), "a".into()])
+        );
+    }
+}
@@ -1,6 +1,12 @@
+#[cfg(feature = "metrics")]
+use crate::internal_metrics::Signal;
+#[cfg(feature = "persistence")]
+use crate::persistence::{BatchCodec, PersistentBatches, PersistentQueue};
 use crate::{
     export::logs::{ExportResult, LogData, LogExporter},
+    internal_metrics::{ErrorType, ProcessorMetrics, QUEUE_FULL, SHUTDOWN},
     runtime::{RuntimeChannel, TrySend},
+    util::block_on_timeout,
 };
 use futures_channel::oneshot;
 use futures_util::{
@@ -9,15 +15,22 @@ use futures_util::{
 };
 #[cfg(feature = "logs_level_enabled")]
 use opentelemetry::logs::Severity;
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::MeterProvider;
 use opentelemetry::{
     global,
     logs::{LogError, LogResult},
 };
-use std::{cmp::min, env, sync::Mutex};
+use std::{
+    cmp::min,
+    env,
+    sync::{mpsc, Mutex},
+    thread,
+};
 use std::{
     fmt::{self, Debug, Formatter},
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// Delay interval between two consecutive exports.
@@ -106,6 +119,7 @@ impl LogProcessor for SimpleLogProcessor {
 /// them at a pre-configured interval.
 pub struct BatchLogProcessor<R: RuntimeChannel> {
     message_sender: R::Sender<BatchMessage>,
+    metrics: ProcessorMetrics,
 }
 
 impl<R: RuntimeChannel> Debug for BatchLogProcessor<R> {
@@ -121,7 +135,10 @@ impl<R: RuntimeChannel> LogProcessor for BatchLogProcessor<R> {
         let result = self.message_sender.try_send(BatchMessage::ExportLog(data));
 
         if let Err(err) = result {
+            self.metrics.dropped(err.error_type());
             global::handle_error(LogError::Other(err.into()));
+        } else {
+            self.metrics.enqueued();
         }
     }
 
@@ -154,13 +171,37 @@ impl<R: RuntimeChannel> LogProcessor for BatchLogProcessor<R> {
 }
 
 impl<R: RuntimeChannel> BatchLogProcessor<R> {
-    pub(crate) fn new(mut exporter: Box<dyn LogExporter>, config: BatchConfig, runtime: R) -> Self {
+    fn start(exporter: BatchExporter<R>, config: BatchConfig, runtime: R) -> Self {
         let (message_sender, message_receiver) =
             runtime.batch_message_channel(config.max_queue_size);
         let ticker = runtime
             .interval(config.scheduled_delay)
             .map(|_| BatchMessage::Flush(None));
-        let timeout_runtime = runtime.clone();
+        let metrics = exporter.metrics.clone();
+        let worker_metrics = metrics.clone();
+
+        // Export the persisted batches from a separate task, so that adding
+        // logs to the queue never waits for the exporter.
+        #[cfg(feature = "persistence")]
+        let mut sink = match exporter.persistence.clone() {
+            Some(persistence) => {
+                let (drain, drain_receiver) = futures_channel::mpsc::unbounded();
+                let metrics = exporter.metrics.clone();
+                // Export the batches persisted before the processor started
+                let _ = drain.unbounded_send(DrainMessage::Drain(None));
+                runtime.spawn(Box::pin(
+                    exporter.drain(persistence.clone(), drain_receiver),
+                ));
+                BatchSink::Persist {
+                    persistence,
+                    drain,
+                    metrics,
+                }
+            }
+            None => BatchSink::Export(exporter),
+        };
+        #[cfg(not(feature = "persistence"))]
+        let mut sink = BatchSink::Export(exporter);
 
         // Spawn worker process via user-defined spawn function.
         runtime.spawn(Box::pin(async move {
@@ -171,54 +212,37 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
                 match message {
                     // Log has finished, add to buffer of pending logs.
                     BatchMessage::ExportLog(log) => {
+                        worker_metrics.dequeued();
                         logs.push(log);
 
                         if logs.len() == config.max_export_batch_size {
-                            let result = export_with_timeout(
-                                config.max_export_timeout,
-                                exporter.as_mut(),
-                                &timeout_runtime,
-                                logs.split_off(0),
-                            )
-                            .await;
+                            let result = sink.export(logs.split_off(0)).await;
 
                             if let Err(err) = result {
                                 global::handle_error(err);
                             }
                         }
                     }
-                    // Log batch interval time reached or a force flush has been invoked, export current spans.
-                    BatchMessage::Flush(res_channel) => {
-                        let result = export_with_timeout(
-                            config.max_export_timeout,
-                            exporter.as_mut(),
-                            &timeout_runtime,
-                            logs.split_off(0),
-                        )
-                        .await;
-
-                        if let Some(channel) = res_channel {
-                            if let Err(result) = channel.send(result) {
-                                global::handle_error(LogError::from(format!(
-                                    "failed to send flush result: {:?}",
-                                    result
-                                )));
-                            }
-                        } else if let Err(err) = result {
+                    // Log batch interval time reached, export current spans.
+                    BatchMessage::Flush(None) => {
+                        if let Err(err) = sink.export(logs.split_off(0)).await {
                             global::handle_error(err);
                         }
                     }
+                    // A force flush has been invoked, export current spans.
+                    BatchMessage::Flush(Some(channel)) => {
+                        let result = sink.flush(logs.split_off(0)).await;
+
+                        if let Err(result) = channel.send(result) {
+                            global::handle_error(LogError::from(format!(
+                                "failed to send flush result: {:?}",
+                                result
+                            )));
+                        }
+                    }
                     // Stream has terminated or processor is shutdown, return to finish execution.
                     BatchMessage::Shutdown(ch) => {
-                        let result = export_with_timeout(
-                            config.max_export_timeout,
-                            exporter.as_mut(),
-                            &timeout_runtime,
-                            logs.split_off(0),
-                        )
-                        .await;
-
-                        exporter.shutdown();
+                        let result = sink.shutdown(logs.split_off(0)).await;
 
                         if let Err(result) = ch.send(result) {
                             global::handle_error(LogError::from(format!(
@@ -234,7 +258,10 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
         }));
 
         // Return batch processor with link to worker
-        BatchLogProcessor { message_sender }
+        BatchLogProcessor {
+            message_sender,
+            metrics,
+        }
     }
 
     /// Create a new batch processor builder
@@ -246,8 +273,213 @@ impl<R: RuntimeChannel> BatchLogProcessor<R> {
             exporter,
             config: Default::default(),
             runtime,
+            metrics: ProcessorMetrics::default(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
+        }
+    }
+}
+
+/// Where the worker of a [`BatchLogProcessor`] sends its batches.
+enum BatchSink<R> {
+    /// Export the batches right away.
+    Export(BatchExporter<R>),
+    /// Add the batches to the persistent queue, exported by a separate task.
+    #[cfg(feature = "persistence")]
+    Persist {
+        persistence: PersistentBatches<LogData>,
+        drain: futures_channel::mpsc::UnboundedSender<DrainMessage>,
+        metrics: ProcessorMetrics,
+    },
+}
+
+impl<R: RuntimeChannel> BatchSink<R> {
+    /// Exports `batch`, or adds it to the persistent queue without waiting for
+    /// its export.
+    async fn export(&mut self, batch: Vec<LogData>) -> ExportResult {
+        match self {
+            BatchSink::Export(exporter) => exporter.export_processed(batch).await,
+            #[cfg(feature = "persistence")]
+            BatchSink::Persist {
+                persistence,
+                drain,
+                metrics,
+            } => {
+                persist(persistence, metrics, batch);
+                let _ = drain.unbounded_send(DrainMessage::Drain(None));
+                Ok(())
+            }
+        }
+    }
+
+    /// Exports `batch`, along with the persisted batches.
+    async fn flush(&mut self, batch: Vec<LogData>) -> ExportResult {
+        match self {
+            BatchSink::Export(exporter) => exporter.export_processed(batch).await,
+            #[cfg(feature = "persistence")]
+            BatchSink::Persist {
+                persistence,
+                drain,
+                metrics,
+            } => {
+                persist(persistence, metrics, batch);
+                let message = |sender| DrainMessage::Drain(Some(sender));
+                request_drain(drain, message).await
+            }
+        }
+    }
+
+    /// Exports `batch`, along with the persisted batches, and shuts the
+    /// exporter down.
+    async fn shutdown(&mut self, batch: Vec<LogData>) -> ExportResult {
+        match self {
+            BatchSink::Export(exporter) => {
+                let result = exporter.export_processed(batch).await;
+                exporter.exporter.shutdown();
+                result
+            }
+            #[cfg(feature = "persistence")]
+            BatchSink::Persist {
+                persistence,
+                drain,
+                metrics,
+            } => {
+                persist(persistence, metrics, batch);
+                request_drain(drain, DrainMessage::Shutdown).await
+            }
+        }
+    }
+}
+
+/// Adds `batch` to the persistent queue, where its processing by the
+/// processor ends.
+#[cfg(feature = "persistence")]
+fn persist(
+    persistence: &PersistentBatches<LogData>,
+    metrics: &ProcessorMetrics,
+    batch: Vec<LogData>,
+) {
+    if !batch.is_empty() {
+        metrics.processed(batch.len(), None);
+        persistence.push(batch);
+    }
+}
+
+/// Sends `message` to the task exporting the persisted batches and waits for
+/// its result.
+#[cfg(feature = "persistence")]
+async fn request_drain(
+    drain: &futures_channel::mpsc::UnboundedSender<DrainMessage>,
+    message: impl FnOnce(oneshot::Sender<ExportResult>) -> DrainMessage,
+) -> ExportResult {
+    let (res_sender, res_receiver) = oneshot::channel();
+    drain
+        .unbounded_send(message(res_sender))
+        .map_err(|err| LogError::Other(err.into()))?;
+    res_receiver
+        .await
+        .map_err(|err| LogError::Other(err.into()))
+        .and_then(std::convert::identity)
+}
+
+/// Messages sent to the task exporting the persisted batches of a
+/// [`BatchLogProcessor`].
+#[cfg(feature = "persistence")]
+#[derive(Debug)]
+enum DrainMessage {
+    /// Export the persisted batches, sending the result if requested.
+    Drain(Option<oneshot::Sender<ExportResult>>),
+    /// Export the persisted batches and shut the exporter down.
+    Shutdown(oneshot::Sender<ExportResult>),
+}
+
+/// Exports the batches of a [`BatchLogProcessor`].
+struct BatchExporter<R> {
+    exporter: Box<dyn LogExporter>,
+    time_out: Duration,
+    runtime: R,
+    metrics: ProcessorMetrics,
+    #[cfg(feature = "persistence")]
+    persistence: Option<PersistentBatches<LogData>>,
+}
+
+impl<R: RuntimeChannel> BatchExporter<R> {
+    fn new(exporter: Box<dyn LogExporter>, time_out: Duration, runtime: R) -> Self {
+        BatchExporter {
+            exporter,
+            time_out,
+            runtime,
+            metrics: ProcessorMetrics::default(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
+        }
+    }
+
+    async fn export(&mut self, batch: Vec<LogData>) -> ExportResult {
+        export_with_timeout(
+            self.time_out,
+            self.exporter.as_mut(),
+            &self.runtime,
+            batch,
+            &self.metrics,
+        )
+        .await
+    }
+
+    /// Exports a batch taken from the queue of the processor, recording the
+    /// outcome of its processing.
+    async fn export_processed(&mut self, batch: Vec<LogData>) -> ExportResult {
+        let count = batch.len();
+        let result = self.export(batch).await;
+        self.metrics
+            .processed(count, result.as_ref().err().map(ErrorType::error_type));
+        result
+    }
+
+    /// Exports the batches of `persistence` whenever requested, until the
+    /// processor shuts down.
+    #[cfg(feature = "persistence")]
+    async fn drain(
+        mut self,
+        persistence: PersistentBatches<LogData>,
+        mut messages: futures_channel::mpsc::UnboundedReceiver<DrainMessage>,
+    ) {
+        while let Some(message) = messages.next().await {
+            match message {
+                DrainMessage::Drain(res_channel) => {
+                    let result = self.export_persisted(&persistence).await;
+                    match res_channel {
+                        Some(channel) => {
+                            let _ = channel.send(result);
+                        }
+                        None => {
+                            if let Err(err) = result {
+                                global::handle_error(err);
+                            }
+                        }
+                    }
+                }
+                DrainMessage::Shutdown(channel) => {
+                    let result = self.export_persisted(&persistence).await;
+                    self.exporter.shutdown();
+                    let _ = channel.send(result);
+                    break;
+                }
+            }
         }
     }
+
+    /// Exports the persisted batches in order, stopping at the first failure
+    /// to retry at the next export.
+    #[cfg(feature = "persistence")]
+    async fn export_persisted(&mut self, persistence: &PersistentBatches<LogData>) -> ExportResult {
+        while let Some((id, batch)) = persistence.lease(1).await.pop() {
+            let result = self.export(batch).await;
+            persistence.release(id, result.is_ok());
+            result?;
+        }
+        Ok(())
+    }
 }
 
 async fn export_with_timeout<R, E>(
@@ -255,6 +487,7 @@ async fn export_with_timeout<R, E>(
     exporter: &mut E,
     runtime: &R,
     batch: Vec<LogData>,
+    metrics: &ProcessorMetrics,
 ) -> ExportResult
 where
     R: RuntimeChannel,
@@ -264,14 +497,18 @@ where
         return Ok(());
     }
 
+    let count = batch.len();
     let export = exporter.export(batch);
     let timeout = runtime.delay(time_out);
     pin_mut!(export);
     pin_mut!(timeout);
-    match future::select(export, timeout).await {
-        Either::Left((export_res, _)) => export_res,
-        Either::Right((_, _)) => ExportResult::Err(LogError::ExportTimedOut(time_out)),
-    }
+    let export = async {
+        match future::select(export, timeout).await {
+            Either::Left((export_res, _)) => export_res,
+            Either::Right((_, _)) => ExportResult::Err(LogError::ExportTimedOut(time_out)),
+        }
+    };
+    metrics.exporter().measure(count, export).await
 }
 
 /// Batch log processor configuration.
@@ -421,6 +658,9 @@ pub struct BatchLogProcessorBuilder<E, R> {
     exporter: E,
     config: BatchConfig,
     runtime: R,
+    metrics: ProcessorMetrics,
+    #[cfg(feature = "persistence")]
+    persistence: Option<PersistentBatches<LogData>>,
 }
 
 impl<E, R> BatchLogProcessorBuilder<E, R>
@@ -433,9 +673,59 @@ where
         BatchLogProcessorBuilder { config, ..self }
     }
 
+    /// Report the metrics of the processor and its exporter with a meter of
+    /// `provider`, such as the size of its queue and the number of logs it
+    /// dropped or exported.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.processor.log.*` and `otel.sdk.exporter.*`.
+    #[cfg(feature = "metrics")]
+    pub fn with_meter_provider(self, provider: &impl MeterProvider) -> Self {
+        BatchLogProcessorBuilder {
+            metrics: ProcessorMetrics::new(provider, Signal::Log),
+            ..self
+        }
+    }
+
+    /// Keep the batches in a [`PersistentQueue`] until they are exported,
+    /// encoded with `codec`.
+    ///
+    /// Batches are added to the queue, then exported in order by a separate
+    /// task, so that a slow or failing exporter does not delay the logs being
+    /// emitted. A batch failing to export stays at the front of the queue and
+    /// is exported again at the next scheduled export, along with the batches
+    /// added since. The batches left in the queue by a previous process are
+    /// exported when the processor starts.
+    ///
+    /// The queue files are read and written by a dedicated thread.
+    ///
+    /// The `persistence` feature of `opentelemetry-proto` provides `OtlpLogCodec`,
+    /// storing the batches as OTLP messages.
+    #[cfg(feature = "persistence")]
+    pub fn with_persistent_queue<C>(self, queue: PersistentQueue, codec: C) -> Self
+    where
+        C: BatchCodec<LogData> + 'static,
+    {
+        BatchLogProcessorBuilder {
+            persistence: Some(PersistentBatches::new(queue, Box::new(codec))),
+            ..self
+        }
+    }
+
     /// Build a batch processor
     pub fn build(self) -> BatchLogProcessor<R> {
-        BatchLogProcessor::new(Box::new(self.exporter), self.config, self.runtime)
+        let mut exporter = BatchExporter::new(
+            Box::new(self.exporter),
+            self.config.max_export_timeout,
+            self.runtime.clone(),
+        );
+        self.metrics.record_capacity(self.config.max_queue_size);
+        exporter.metrics = self.metrics;
+        #[cfg(feature = "persistence")]
+        {
+            exporter.persistence = self.persistence;
+        }
+        BatchLogProcessor::start(exporter, self.config, self.runtime)
     }
 }
 
@@ -452,23 +742,316 @@ enum BatchMessage {
     Shutdown(oneshot::Sender<ExportResult>),
 }
 
+/// A [`LogProcessor`] that buffers log records and reports them at a
+/// pre-configured interval from a dedicated thread, without requiring an async
+/// runtime.
+///
+/// Log records are sent to the thread through a channel bounded by the max
+/// queue size of the [`BatchConfig`], and are dropped when it is full. Flush
+/// and shutdown requests go through a separate channel, so they are not
+/// blocked by a full queue. The thread exports the records when a batch is full and every scheduled delay,
+/// blocking on the exporter's futures for at most the max export timeout.
+///
+/// As exports are not run on an async runtime, the exporter must not depend
+/// on one, such as exporters sending requests with a blocking HTTP client.
+#[derive(Debug)]
+pub struct ThreadedBatchLogProcessor {
+    message_sender: mpsc::SyncSender<ThreadedBatchMessage>,
+    control_sender: mpsc::Sender<ThreadedBatchControl>,
+    handle: Option<thread::JoinHandle<()>>,
+    timeout: Duration,
+    metrics: ProcessorMetrics,
+}
+
+impl ThreadedBatchLogProcessor {
+    /// Create a new threaded batch processor builder
+    pub fn builder<E>(exporter: E) -> ThreadedBatchLogProcessorBuilder<E>
+    where
+        E: LogExporter,
+    {
+        ThreadedBatchLogProcessorBuilder {
+            exporter,
+            config: Default::default(),
+            timeout: Duration::from_millis(OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT),
+            metrics: ProcessorMetrics::default(),
+        }
+    }
+
+    /// Send `control` to the worker thread and wait for its result.
+    fn request(
+        &self,
+        control: impl FnOnce(mpsc::SyncSender<ExportResult>) -> ThreadedBatchControl,
+    ) -> LogResult<()> {
+        let (res_sender, res_receiver) = mpsc::sync_channel(1);
+        self.control_sender
+            .send(control(res_sender))
+            .map_err(|_| LogError::Other("batch log processor is shut down".into()))?;
+        // Wake the thread up, unless logs are already waiting for it
+        let _ = self.message_sender.try_send(ThreadedBatchMessage::Wake);
+
+        match res_receiver.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(LogError::ExportTimedOut(self.timeout)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(LogError::Other("batch log processor thread stopped".into()))
+            }
+        }
+    }
+}
+
+impl LogProcessor for ThreadedBatchLogProcessor {
+    fn emit(&self, data: LogData) {
+        let result = self
+            .message_sender
+            .try_send(ThreadedBatchMessage::ExportLog(data));
+
+        if let Err(err) = result {
+            let (error_type, message) = match err {
+                mpsc::TrySendError::Full(_) => (
+                    QUEUE_FULL,
+                    "batch log processor queue is full, dropping log",
+                ),
+                mpsc::TrySendError::Disconnected(_) => {
+                    (SHUTDOWN, "batch log processor is shut down")
+                }
+            };
+            self.metrics.dropped(error_type);
+            global::handle_error(LogError::Other(message.into()));
+        } else {
+            self.metrics.enqueued();
+        }
+    }
+
+    #[cfg(feature = "logs_level_enabled")]
+    fn event_enabled(&self, _level: Severity, _target: &str, _name: &str) -> bool {
+        true
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        self.request(ThreadedBatchControl::Flush)
+    }
+
+    fn shutdown(&mut self) -> LogResult<()> {
+        let result = self.request(ThreadedBatchControl::Shutdown);
+        // The thread stops once it has replied, unless the shutdown timed out
+        if !matches!(result, Err(LogError::ExportTimedOut(_))) {
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+        result
+    }
+}
+
+/// Messages sent to the thread of a [`ThreadedBatchLogProcessor`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum ThreadedBatchMessage {
+    /// Export logs, usually called when the log is emitted.
+    ExportLog(LogData),
+    /// Handle the pending control requests.
+    Wake,
+}
+
+/// Requests sent to the thread of a [`ThreadedBatchLogProcessor`], handled
+/// before the queued logs.
+#[derive(Debug)]
+enum ThreadedBatchControl {
+    /// Flush the current buffer to the backend.
+    Flush(mpsc::SyncSender<ExportResult>),
+    /// Push all logs in buffer to the backend and stop the thread.
+    Shutdown(mpsc::SyncSender<ExportResult>),
+}
+
+struct ThreadedBatchLogWorker {
+    exporter: Box<dyn LogExporter>,
+    logs: Vec<LogData>,
+    config: BatchConfig,
+    metrics: ProcessorMetrics,
+}
+
+impl ThreadedBatchLogWorker {
+    fn run(
+        mut self,
+        messages: mpsc::Receiver<ThreadedBatchMessage>,
+        controls: mpsc::Receiver<ThreadedBatchControl>,
+    ) {
+        let mut next_export = Instant::now() + self.config.scheduled_delay;
+        loop {
+            while let Ok(control) = controls.try_recv() {
+                // Include the logs queued before the request
+                for _ in 0..self.config.max_queue_size {
+                    match messages.try_recv() {
+                        Ok(ThreadedBatchMessage::ExportLog(log)) => self.push(log),
+                        Ok(ThreadedBatchMessage::Wake) => {}
+                        Err(_) => break,
+                    }
+                }
+                match control {
+                    ThreadedBatchControl::Flush(res_sender) => {
+                        let _ = res_sender.send(self.export());
+                    }
+                    ThreadedBatchControl::Shutdown(res_sender) => {
+                        let result = self.export();
+                        self.exporter.shutdown();
+                        let _ = res_sender.send(result);
+                        return;
+                    }
+                }
+            }
+
+            let timeout = next_export.saturating_duration_since(Instant::now());
+            match messages.recv_timeout(timeout) {
+                Ok(ThreadedBatchMessage::ExportLog(log)) => self.push(log),
+                Ok(ThreadedBatchMessage::Wake) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.export() {
+                        global::handle_error(err);
+                    }
+                    next_export = Instant::now() + self.config.scheduled_delay;
+                }
+                // The processor was dropped without being shut down
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    if let Err(err) = self.export() {
+                        global::handle_error(err);
+                    }
+                    self.exporter.shutdown();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Buffer `log`, exporting the buffer once it is a full batch.
+    fn push(&mut self, log: LogData) {
+        self.metrics.dequeued();
+        self.logs.push(log);
+        if self.logs.len() >= self.config.max_export_batch_size {
+            if let Err(err) = self.export() {
+                global::handle_error(err);
+            }
+        }
+    }
+
+    fn export(&mut self) -> ExportResult {
+        if self.logs.is_empty() {
+            return Ok(());
+        }
+
+        let time_out = self.config.max_export_timeout;
+        let batch = self.logs.split_off(0);
+        let count = batch.len();
+        let start = Instant::now();
+        let result = block_on_timeout(self.exporter.export(batch), time_out)
+            .unwrap_or(Err(LogError::ExportTimedOut(time_out)));
+        let error_type = result.as_ref().err().map(ErrorType::error_type);
+        self.metrics
+            .exporter()
+            .record(count, error_type, start.elapsed());
+        self.metrics.processed(count, error_type);
+        result
+    }
+}
+
+/// A builder for creating [`ThreadedBatchLogProcessor`] instances.
+#[derive(Debug)]
+pub struct ThreadedBatchLogProcessorBuilder<E> {
+    exporter: E,
+    config: BatchConfig,
+    timeout: Duration,
+    metrics: ProcessorMetrics,
+}
+
+impl<E> ThreadedBatchLogProcessorBuilder<E>
+where
+    E: LogExporter + 'static,
+{
+    /// Set the BatchConfig for [`ThreadedBatchLogProcessorBuilder`]
+    pub fn with_batch_config(self, config: BatchConfig) -> Self {
+        ThreadedBatchLogProcessorBuilder { config, ..self }
+    }
+
+    /// Set the time `force_flush` and `shutdown` wait for the processor's
+    /// thread to export the buffered logs, 30 seconds by default.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        ThreadedBatchLogProcessorBuilder { timeout, ..self }
+    }
+
+    /// Report the metrics of the processor and its exporter with a meter of
+    /// `provider`, such as the size of its queue and the number of logs it
+    /// dropped or exported.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.processor.log.*` and `otel.sdk.exporter.*`.
+    #[cfg(feature = "metrics")]
+    pub fn with_meter_provider(self, provider: &impl MeterProvider) -> Self {
+        ThreadedBatchLogProcessorBuilder {
+            metrics: ProcessorMetrics::new(provider, Signal::Log),
+            ..self
+        }
+    }
+
+    /// Build a threaded batch processor, starting its thread
+    pub fn build(self) -> ThreadedBatchLogProcessor {
+        let (message_sender, message_receiver) = mpsc::sync_channel(self.config.max_queue_size);
+        let (control_sender, control_receiver) = mpsc::channel();
+        self.metrics.record_capacity(self.config.max_queue_size);
+        let worker = ThreadedBatchLogWorker {
+            exporter: Box::new(self.exporter),
+            logs: Vec::new(),
+            config: self.config,
+            metrics: self.metrics.clone(),
+        };
+
+        let handle = match thread::Builder::new()
+            .name("opentelemetry-batch-log-processor".to_string())
+            .spawn(move || worker.run(message_receiver, control_receiver))
+        {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                global::handle_error(LogError::Other(err.into()));
+                None
+            }
+        };
+
+        ThreadedBatchLogProcessor {
+            message_sender,
+            control_sender,
+            handle,
+            timeout: self.timeout,
+            metrics: self.metrics,
+        }
+    }
+}
+
 #[cfg(all(test, feature = "testing", feature = "logs"))]
 mod tests {
     use super::{
-        BatchLogProcessor, OTEL_BLRP_EXPORT_TIMEOUT, OTEL_BLRP_MAX_EXPORT_BATCH_SIZE,
-        OTEL_BLRP_MAX_QUEUE_SIZE, OTEL_BLRP_SCHEDULE_DELAY,
+        BatchLogProcessor, ThreadedBatchLogProcessor, OTEL_BLRP_EXPORT_TIMEOUT,
+        OTEL_BLRP_MAX_EXPORT_BATCH_SIZE, OTEL_BLRP_MAX_QUEUE_SIZE, OTEL_BLRP_SCHEDULE_DELAY,
     };
     use crate::{
+        export::logs::LogData,
         logs::{
             log_processor::{
                 OTEL_BLRP_EXPORT_TIMEOUT_DEFAULT, OTEL_BLRP_MAX_EXPORT_BATCH_SIZE_DEFAULT,
                 OTEL_BLRP_MAX_QUEUE_SIZE_DEFAULT, OTEL_BLRP_SCHEDULE_DELAY_DEFAULT,
             },
-            BatchConfig, BatchConfigBuilder,
+            BatchConfig, BatchConfigBuilder, LogProcessor,
         },
         runtime,
         testing::logs::InMemoryLogsExporter,
     };
+    #[cfg(feature = "persistence")]
+    use crate::{
+        export::logs::LogExporter,
+        persistence::{PersistenceError, PersistentQueue},
+    };
+    use opentelemetry::logs::LogRecord;
+    #[cfg(feature = "persistence")]
+    use opentelemetry::logs::{AnyValue, LogResult};
+    #[cfg(feature = "persistence")]
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
     #[test]
@@ -620,4 +1203,130 @@ mod tests {
         assert_eq!(actual.max_export_timeout, Duration::from_millis(3));
         assert_eq!(actual.max_queue_size, 4);
     }
+
+    /// Encodes the string bodies of the records of a batch.
+    #[cfg(feature = "persistence")]
+    #[derive(Debug)]
+    struct BodyCodec;
+
+    #[cfg(feature = "persistence")]
+    impl crate::persistence::BatchCodec<LogData> for BodyCodec {
+        fn encode(&self, batch: &[LogData]) -> Result<Vec<u8>, PersistenceError> {
+            let bodies = batch.iter().map(|log| match &log.record.body {
+                Some(AnyValue::String(body)) => body.as_str(),
+                _ => "",
+            });
+            Ok(bodies.collect::<Vec<_>>().join("\n").into_bytes())
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<Vec<LogData>, PersistenceError> {
+            let bodies = String::from_utf8(bytes.to_vec())
+                .map_err(|err| PersistenceError::Codec(err.to_string()))?;
+            Ok(bodies
+                .split('\n')
+                .map(|body| log(body.to_string()))
+                .collect())
+        }
+    }
+
+    fn log(body: String) -> LogData {
+        LogData {
+            record: LogRecord::builder().with_body(body).build(),
+            resource: Default::default(),
+            instrumentation: Default::default(),
+        }
+    }
+
+    /// Records the bodies of the exported records, failing while `down` is set.
+    #[cfg(feature = "persistence")]
+    #[derive(Debug)]
+    struct UnreliableExporter {
+        down: bool,
+        exported: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "persistence")]
+    #[async_trait::async_trait]
+    impl LogExporter for UnreliableExporter {
+        async fn export(&mut self, batch: Vec<LogData>) -> LogResult<()> {
+            if self.down {
+                return Err("exporter is down".into());
+            }
+            let mut exported = self.exported.lock().unwrap();
+            exported.extend(batch.into_iter().filter_map(|log| match log.record.body {
+                Some(AnyValue::String(body)) => Some(body.to_string()),
+                _ => None,
+            }));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "persistence")]
+    async fn test_batch_log_processor_with_persistent_queue() {
+        let dir = std::env::temp_dir().join(format!("opentelemetry-blrp-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = || {
+            BatchConfigBuilder::default()
+                .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+                .with_max_export_batch_size(2)
+                .build()
+        };
+        let exported = Arc::new(Mutex::new(Vec::new()));
+
+        let exporter = UnreliableExporter {
+            down: true,
+            exported: exported.clone(),
+        };
+        let mut processor = BatchLogProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_batch_config(config())
+            .with_persistent_queue(PersistentQueue::builder(&dir).build().unwrap(), BodyCodec)
+            .build();
+        for body in ["a", "b", "c"] {
+            processor.emit(log(body.to_string()));
+        }
+        assert!(processor.force_flush().is_err());
+        assert!(processor.shutdown().is_err());
+        assert!(exported.lock().unwrap().is_empty());
+
+        // The batches persisted while the exporter was down are exported first
+        let exporter = UnreliableExporter {
+            down: false,
+            exported: exported.clone(),
+        };
+        let mut processor = BatchLogProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_batch_config(config())
+            .with_persistent_queue(PersistentQueue::builder(&dir).build().unwrap(), BodyCodec)
+            .build();
+        processor.emit(log("d".to_string()));
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(*exported.lock().unwrap(), vec!["a", "b", "c", "d"]);
+        assert!(processor.shutdown().is_ok());
+
+        let queue = PersistentQueue::builder(&dir).build().unwrap();
+        assert!(queue.is_empty());
+        drop(queue);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_threaded_batch_log_processor() {
+        let exporter = InMemoryLogsExporter::default();
+        let config = BatchConfigBuilder::default()
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_max_export_batch_size(2)
+            .build();
+        let mut processor = ThreadedBatchLogProcessor::builder(exporter.clone())
+            .with_batch_config(config)
+            .build();
+
+        for body in ["a", "b", "c"] {
+            processor.emit(log(body.to_string()));
+        }
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(exporter.get_emitted_logs().unwrap().len(), 3);
+
+        assert!(processor.shutdown().is_ok());
+        assert!(processor.force_flush().is_err());
+    }
 }
@@ -2,13 +2,15 @@
 
 mod config;
 mod log_emitter;
+mod log_limit;
 mod log_processor;
 
 pub use config::{config, Config};
 pub use log_emitter::{Builder, Logger, LoggerProvider};
+pub use log_limit::LogLimits;
 pub use log_processor::{
     BatchConfig, BatchConfigBuilder, BatchLogProcessor, BatchLogProcessorBuilder, LogProcessor,
-    SimpleLogProcessor,
+    SimpleLogProcessor, ThreadedBatchLogProcessor, ThreadedBatchLogProcessorBuilder,
 };
 
 #[cfg(all(test, feature = "testing"))]
@@ -97,4 +99,50 @@ mod tests {
         assert_eq!(attributes[0].key, "test_k".into());
         assert_eq!(attributes[0].value, "test_v".into());
     }
+
+    #[test]
+    fn log_limits_are_applied() {
+        let exporter: InMemoryLogsExporter = InMemoryLogsExporter::default();
+        let logger_provider = LoggerProvider::builder()
+            .with_log_limits(LogLimits {
+                max_attributes_per_log_record: 2,
+                max_attribute_value_length: Some(3),
+            })
+            .with_log_processor(SimpleLogProcessor::new(Box::new(exporter.clone())))
+            .build();
+
+        let mut log_record: LogRecord = LogRecord::default();
+        log_record.attributes = Some(vec![
+            (Key::new("key1"), "value1".into()),
+            (Key::new("key2"), 2.into()),
+            (Key::new("key3"), "value3".into()),
+        ]);
+        logger_provider.logger("test-logger").emit(log_record);
+
+        let exported_logs = exporter.get_emitted_logs().unwrap();
+        assert_eq!(
+            exported_logs[0].record.attributes,
+            Some(vec![
+                (Key::new("key1"), "val".into()),
+                (Key::new("key2"), 2.into()),
+            ])
+        );
+        assert_eq!(exported_logs[0].record.dropped_attributes_count, 1);
+    }
+
+    #[test]
+    fn sdk_disabled_drops_logs() {
+        let exporter: InMemoryLogsExporter = InMemoryLogsExporter::default();
+        let logger_provider = temp_env::with_var("OTEL_SDK_DISABLED", Some("true"), || {
+            LoggerProvider::builder()
+                .with_log_processor(SimpleLogProcessor::new(Box::new(exporter.clone())))
+                .build()
+        });
+
+        logger_provider
+            .logger("test-logger")
+            .emit(LogRecord::default());
+
+        assert!(exporter.get_emitted_logs().unwrap().is_empty());
+    }
 }
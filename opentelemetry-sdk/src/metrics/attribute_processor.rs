@@ -0,0 +1,371 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+#[cfg(feature = "metrics_patterns")]
+use opentelemetry::Array;
+use opentelemetry::{Key, KeyValue, Value};
+#[cfg(feature = "metrics_patterns")]
+use regex::Regex;
+
+use crate::AttributeSet;
+
+/// Reshapes the attributes of the measurements of a [Stream] before they are
+/// aggregated.
+///
+/// The operations are applied in the order they are added, after the
+/// [allowed attribute keys] of the stream, to the attributes of each
+/// measurement:
+///
+/// * [deny_keys] drops the attributes with the given keys,
+/// * [rename_keys] renames the keys of attributes,
+/// * [map_value] replaces the value of the attributes with a given key with the
+///   result of a function,
+/// * `redact` replaces the matches of a regular expression in the string
+///   values of the attributes with a given key, with the `metrics_patterns`
+///   feature.
+///
+/// Each operation applies to the attributes as left by the previous ones, so
+/// a [deny_keys] following a [rename_keys] drops the renamed keys.
+/// Measurements whose attributes end up identical are aggregated together.
+/// The attributes dropped before any other operation is applied are recorded
+/// as the filtered attributes of exemplars.
+///
+/// # Example
+///
+/// ```
+/// use opentelemetry::Value;
+/// use opentelemetry_sdk::metrics::{new_view, AttributeProcessor, Instrument, Stream};
+///
+/// let processor = AttributeProcessor::new()
+///     .deny_keys(["http.user_agent".into()])
+///     .rename_keys([("http.status_code".into(), "http.response.status_code".into())])
+///     .map_value("http.response.status_code", |value| match value {
+///         Value::I64(code) => Value::String(format!("{}xx", code / 100).into()),
+///         other => other.clone(),
+///     });
+///
+/// let view = new_view(
+///     Instrument::new().name("http.server.duration"),
+///     Stream::new().attribute_processor(processor),
+/// );
+/// # drop(view);
+/// ```
+///
+/// [Stream]: crate::metrics::Stream
+/// [allowed attribute keys]: crate::metrics::Stream::allowed_attribute_keys
+/// [deny_keys]: AttributeProcessor::deny_keys
+/// [rename_keys]: AttributeProcessor::rename_keys
+/// [map_value]: AttributeProcessor::map_value
+#[derive(Clone, Default)]
+pub struct AttributeProcessor {
+    operations: Vec<Operation>,
+}
+
+type ValueMapper = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+#[derive(Clone)]
+enum Operation {
+    Deny(Arc<HashSet<Key>>),
+    Rename(Arc<HashMap<Key, Key>>),
+    MapValue(Key, ValueMapper),
+    #[cfg(feature = "metrics_patterns")]
+    Redact(Key, Regex, Arc<str>),
+}
+
+impl AttributeProcessor {
+    /// Create a new attribute processor leaving attributes unchanged.
+    pub fn new() -> Self {
+        AttributeProcessor::default()
+    }
+
+    /// Drop the attributes with any of the given keys.
+    pub fn deny_keys(mut self, keys: impl IntoIterator<Item = Key>) -> Self {
+        self.operations
+            .push(Operation::Deny(Arc::new(keys.into_iter().collect())));
+        self
+    }
+
+    /// Rename the keys of attributes, from the first key of each pair to the
+    /// second one.
+    ///
+    /// If an attribute is renamed to the key of another attribute of the
+    /// measurement, only one of them is kept.
+    pub fn rename_keys(mut self, renames: impl IntoIterator<Item = (Key, Key)>) -> Self {
+        self.operations
+            .push(Operation::Rename(Arc::new(renames.into_iter().collect())));
+        self
+    }
+
+    /// Replace the value of the attributes with the given key with the value
+    /// returned by `f`.
+    pub fn map_value<F>(mut self, key: impl Into<Key>, f: F) -> Self
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.operations
+            .push(Operation::MapValue(key.into(), Arc::new(f)));
+        self
+    }
+
+    /// Replace the matches of `pattern` in the string values, including the
+    /// strings of array values, of the attributes with the given key with
+    /// `replacement`.
+    ///
+    /// `replacement` can refer to the capture groups of `pattern`, as described
+    /// in [Regex::replace].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use opentelemetry_sdk::metrics::AttributeProcessor;
+    /// use regex::Regex;
+    ///
+    /// let processor =
+    ///     AttributeProcessor::new().redact("http.route", Regex::new(r"\d+").unwrap(), "{id}");
+    /// # drop(processor);
+    /// ```
+    #[cfg(feature = "metrics_patterns")]
+    pub fn redact(
+        mut self,
+        key: impl Into<Key>,
+        pattern: Regex,
+        replacement: impl Into<Arc<str>>,
+    ) -> Self {
+        self.operations
+            .push(Operation::Redact(key.into(), pattern, replacement.into()));
+        self
+    }
+
+    /// The number of [Operation::Deny] the operations start with.
+    fn leading_denies(&self) -> usize {
+        self.operations
+            .iter()
+            .take_while(|op| matches!(op, Operation::Deny(_)))
+            .count()
+    }
+
+    /// Whether the attributes with the given key are dropped before any other
+    /// operation is applied.
+    pub(crate) fn is_denied(&self, key: &Key) -> bool {
+        self.operations[..self.leading_denies()]
+            .iter()
+            .any(|op| matches!(op, Operation::Deny(keys) if keys.contains(key)))
+    }
+
+    /// Whether the processor only drops attributes, in which case
+    /// [AttributeProcessor::transform] leaves attributes unchanged.
+    pub(crate) fn only_denies(&self) -> bool {
+        self.leading_denies() == self.operations.len()
+    }
+
+    /// Apply the operations following the leading denies to `attrs`, in order.
+    ///
+    /// The attributes denied before any other operation are expected to have
+    /// already been removed with [AttributeProcessor::is_denied]. `attrs` is
+    /// returned as is if none of the operations applies to its keys.
+    pub(crate) fn transform(&self, attrs: AttributeSet) -> AttributeSet {
+        let operations = &self.operations[self.leading_denies()..];
+        // Operations not applying to any key leave the keys unchanged, so they
+        // do not apply to the keys left by the previous ones either.
+        if !operations
+            .iter()
+            .any(|op| attrs.iter().any(|(key, _)| op.applies_to(key)))
+        {
+            return attrs;
+        }
+
+        let mut attributes: Vec<KeyValue> = attrs
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+            .collect();
+
+        for op in operations {
+            match op {
+                Operation::Deny(keys) => attributes.retain(|kv| !keys.contains(&kv.key)),
+                Operation::Rename(renames) => {
+                    for attribute in attributes.iter_mut() {
+                        if let Some(key) = renames.get(&attribute.key) {
+                            attribute.key = key.clone();
+                        }
+                    }
+                }
+                Operation::MapValue(key, f) => {
+                    for attribute in attributes.iter_mut().filter(|kv| &kv.key == key) {
+                        attribute.value = f(&attribute.value);
+                    }
+                }
+                #[cfg(feature = "metrics_patterns")]
+                Operation::Redact(key, pattern, replacement) => {
+                    for attribute in attributes.iter_mut().filter(|kv| &kv.key == key) {
+                        redact(&mut attribute.value, pattern, replacement);
+                    }
+                }
+            }
+        }
+
+        AttributeSet::from(&attributes[..])
+    }
+}
+
+impl Operation {
+    /// Whether the operation changes the attributes with the given key.
+    fn applies_to(&self, key: &Key) -> bool {
+        match self {
+            Operation::Deny(keys) => keys.contains(key),
+            Operation::Rename(renames) => renames.contains_key(key),
+            Operation::MapValue(k, _) => k == key,
+            #[cfg(feature = "metrics_patterns")]
+            Operation::Redact(k, _, _) => k == key,
+        }
+    }
+}
+
+#[cfg(feature = "metrics_patterns")]
+fn redact(value: &mut Value, pattern: &Regex, replacement: &str) {
+    match value {
+        Value::String(s) => {
+            let redacted = pattern.replace_all(s.as_str(), replacement).into_owned();
+            *s = redacted.into();
+        }
+        Value::Array(Array::String(values)) => {
+            for s in values.iter_mut() {
+                let redacted = pattern.replace_all(s.as_str(), replacement).into_owned();
+                *s = redacted.into();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl fmt::Debug for AttributeProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for op in &self.operations {
+            match op {
+                Operation::Deny(keys) => list.entry(&format_args!("Deny({keys:?})")),
+                Operation::Rename(renames) => list.entry(&format_args!("Rename({renames:?})")),
+                Operation::MapValue(key, _) => list.entry(&format_args!("MapValue({key:?})")),
+                #[cfg(feature = "metrics_patterns")]
+                Operation::Redact(key, pattern, replacement) => list.entry(&format_args!(
+                    "Redact({key:?}, {:?}, {replacement:?})",
+                    pattern.as_str()
+                )),
+            };
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform() {
+        let processor = AttributeProcessor::new()
+            .deny_keys(["secret".into()])
+            .rename_keys([("status".into(), "code".into())])
+            .map_value("code", |value| match value {
+                Value::I64(code) => Value::String(format!("{}xx", code / 100).into()),
+                other => other.clone(),
+            });
+
+        assert!(processor.is_denied(&"secret".into()));
+        assert!(!processor.is_denied(&"status".into()));
+        assert!(!processor.only_denies());
+
+        let attrs = AttributeSet::from(
+            &[
+                KeyValue::new("status", 404),
+                KeyValue::new("path", "/users/42/orders/7"),
+                KeyValue::new("other", "unchanged"),
+            ][..],
+        );
+        assert_eq!(
+            processor.transform(attrs),
+            AttributeSet::from(
+                &[
+                    KeyValue::new("code", "4xx"),
+                    KeyValue::new("path", "/users/42/orders/7"),
+                    KeyValue::new("other", "unchanged"),
+                ][..],
+            )
+        );
+    }
+
+    #[cfg(feature = "metrics_patterns")]
+    #[test]
+    fn redact_values() {
+        let processor = AttributeProcessor::new()
+            .map_value("code", |value| match value {
+                Value::I64(code) => Value::String(format!("{}xx", code / 100).into()),
+                other => other.clone(),
+            })
+            .redact("path", Regex::new(r"\d+").unwrap(), "{id}")
+            .redact("code", Regex::new(r"\d").unwrap(), "N");
+
+        let attrs = AttributeSet::from(
+            &[
+                KeyValue::new("code", 404),
+                KeyValue::new("path", "/users/42/orders/7"),
+                KeyValue::new("other", "unchanged"),
+            ][..],
+        );
+        assert_eq!(
+            processor.transform(attrs),
+            AttributeSet::from(
+                &[
+                    KeyValue::new("code", "Nxx"),
+                    KeyValue::new("path", "/users/{id}/orders/{id}"),
+                    KeyValue::new("other", "unchanged"),
+                ][..],
+            )
+        );
+    }
+
+    #[test]
+    fn applies_operations_in_order() {
+        let attrs = AttributeSet::from(
+            &[
+                KeyValue::new("status", 404),
+                KeyValue::new("other", "unchanged"),
+            ][..],
+        );
+
+        let rename_then_deny = AttributeProcessor::new()
+            .rename_keys([("status".into(), "code".into())])
+            .deny_keys(["code".into(), "status".into()]);
+        assert!(!rename_then_deny.is_denied(&"status".into()));
+        assert!(!rename_then_deny.only_denies());
+        assert_eq!(
+            rename_then_deny.transform(attrs.clone()),
+            AttributeSet::from(&[KeyValue::new("other", "unchanged")][..])
+        );
+
+        let deny_then_rename = AttributeProcessor::new()
+            .deny_keys(["code".into()])
+            .rename_keys([("status".into(), "code".into())]);
+        assert!(deny_then_rename.is_denied(&"code".into()));
+        assert_eq!(
+            deny_then_rename.transform(attrs),
+            AttributeSet::from(
+                &[
+                    KeyValue::new("code", 404),
+                    KeyValue::new("other", "unchanged"),
+                ][..],
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_unaffected_attributes_unchanged() {
+        let processor = AttributeProcessor::new()
+            .rename_keys([("status".into(), "code".into())])
+            .map_value("code", |_| Value::from("mapped"));
+        let attrs = AttributeSet::from(&[KeyValue::new("other", "unchanged")][..]);
+        assert_eq!(processor.transform(attrs.clone()), attrs);
+    }
+}
@@ -0,0 +1,51 @@
+/// Decides which measurements are eligible to be sampled as [Exemplar]s.
+///
+/// Measurements that pass the filter are offered to the [ExemplarReservoir] of
+/// the metric stream they are recorded in.
+///
+/// [Exemplar]: crate::metrics::data::Exemplar
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExemplarFilter {
+    /// Makes all measurements eligible for exemplar sampling.
+    AlwaysOn,
+    /// Makes no measurements eligible for exemplar sampling, disabling exemplars.
+    AlwaysOff,
+    /// Makes measurements recorded in the context of a sampled span eligible
+    /// for exemplar sampling.
+    #[default]
+    TraceBased,
+}
+
+/// The strategy used to sample [Exemplar]s from the measurements of a metric
+/// stream.
+///
+/// A separate reservoir is kept for every attribute set of the stream.
+///
+/// If no reservoir is configured for a [Stream], explicit bucket histogram
+/// aggregations use [ExemplarReservoir::AlignedHistogramBucket] with the
+/// boundaries of the aggregation, exponential histogram aggregations use
+/// [ExemplarReservoir::SimpleFixedSize] with the smaller of the maximum number
+/// of buckets and `20`, and all other aggregations use
+/// [ExemplarReservoir::SimpleFixedSize] with a size of `1`.
+///
+/// [Exemplar]: crate::metrics::data::Exemplar
+/// [Stream]: crate::metrics::Stream
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ExemplarReservoir {
+    /// Keeps a uniformly random sample of at most `size` of the offered
+    /// measurements.
+    SimpleFixedSize {
+        /// The maximum number of exemplars kept.
+        size: usize,
+    },
+    /// Keeps the last measurement offered for each histogram bucket.
+    AlignedHistogramBucket {
+        /// Increasing bucket upper-boundary values.
+        ///
+        /// These should match the boundaries of the histogram the reservoir is
+        /// used with.
+        boundaries: Vec<f64>,
+    },
+}
@@ -11,7 +11,10 @@ use opentelemetry::{
 use crate::{
     attributes::AttributeSet,
     instrumentation::Scope,
-    metrics::{aggregation::Aggregation, internal::Measure},
+    metrics::{
+        aggregation::Aggregation, attribute_processor::AttributeProcessor,
+        exemplar::ExemplarReservoir, internal::Measure,
+    },
 };
 
 pub(crate) const EMPTY_MEASURE_MSG: &str = "no aggregators for observable instrument";
@@ -113,14 +116,6 @@ impl Instrument {
             && self.scope == Scope::default()
     }
 
-    pub(crate) fn matches(&self, other: &Instrument) -> bool {
-        self.matches_name(other)
-            && self.matches_description(other)
-            && self.matches_kind(other)
-            && self.matches_unit(other)
-            && self.matches_scope(other)
-    }
-
     pub(crate) fn matches_name(&self, other: &Instrument) -> bool {
         self.name.is_empty() || self.name.as_ref() == other.name.as_ref()
     }
@@ -137,14 +132,20 @@ impl Instrument {
         self.unit.as_str() == "" || self.unit == other.unit
     }
 
-    pub(crate) fn matches_scope(&self, other: &Instrument) -> bool {
-        (self.scope.name.is_empty() || self.scope.name.as_ref() == other.scope.name.as_ref())
-            && (self.scope.version.is_none()
-                || self.scope.version.as_ref().map(AsRef::as_ref)
-                    == other.scope.version.as_ref().map(AsRef::as_ref))
-            && (self.scope.schema_url.is_none()
-                || self.scope.schema_url.as_ref().map(AsRef::as_ref)
-                    == other.scope.schema_url.as_ref().map(AsRef::as_ref))
+    pub(crate) fn matches_scope_name(&self, other: &Instrument) -> bool {
+        self.scope.name.is_empty() || self.scope.name.as_ref() == other.scope.name.as_ref()
+    }
+
+    pub(crate) fn matches_scope_version(&self, other: &Instrument) -> bool {
+        self.scope.version.is_none()
+            || self.scope.version.as_ref().map(AsRef::as_ref)
+                == other.scope.version.as_ref().map(AsRef::as_ref)
+    }
+
+    pub(crate) fn matches_scope_schema_url(&self, other: &Instrument) -> bool {
+        self.scope.schema_url.is_none()
+            || self.scope.schema_url.as_ref().map(AsRef::as_ref)
+                == other.scope.schema_url.as_ref().map(AsRef::as_ref)
     }
 }
 
@@ -180,6 +181,20 @@ pub struct Stream {
     /// dropped. If the set is empty, all attributes will be dropped, if `None` all
     /// attributes will be kept.
     pub allowed_attribute_keys: Option<Arc<HashSet<Key>>>,
+    /// Drops, renames and transforms the attributes kept by
+    /// `allowed_attribute_keys` before they are aggregated.
+    ///
+    /// If `None`, attributes are aggregated as recorded.
+    pub attribute_processor: Option<AttributeProcessor>,
+    /// The reservoir used to sample exemplars for the stream.
+    ///
+    /// If `None`, the default reservoir of the aggregation is used.
+    pub exemplar_reservoir: Option<ExemplarReservoir>,
+    /// The maximum number of data points, including the overflow data point,
+    /// the stream will produce in a collection cycle.
+    ///
+    /// If `None`, the limit of the reader, or else of the meter provider, is used.
+    pub cardinality_limit: Option<usize>,
 }
 
 impl Stream {
@@ -221,6 +236,27 @@ impl Stream {
 
         self
     }
+
+    /// Set the stream attribute processor.
+    ///
+    /// The processor is applied after the allowed attribute keys, see
+    /// [AttributeProcessor] for details.
+    pub fn attribute_processor(mut self, processor: AttributeProcessor) -> Self {
+        self.attribute_processor = Some(processor);
+        self
+    }
+
+    /// Set the stream exemplar reservoir.
+    pub fn exemplar_reservoir(mut self, reservoir: ExemplarReservoir) -> Self {
+        self.exemplar_reservoir = Some(reservoir);
+        self
+    }
+
+    /// Set the stream cardinality limit.
+    pub fn cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
 }
 
 /// The identifying properties of an instrument.
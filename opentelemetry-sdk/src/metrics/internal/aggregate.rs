@@ -1,14 +1,24 @@
-use std::{marker, sync::Arc};
+use std::{
+    marker,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use once_cell::sync::Lazy;
-use opentelemetry::KeyValue;
+use opentelemetry::{global, metrics::MetricsError, KeyValue};
 
 use crate::{
-    metrics::data::{Aggregation, Gauge, Temporality},
+    metrics::{
+        data::{Aggregation, Exemplar, Gauge, Temporality},
+        AttributeProcessor, ExemplarFilter, ExemplarReservoir,
+    },
     AttributeSet,
 };
 
 use super::{
+    exemplar::{self, Exemplars},
     exponential_histogram::ExpoHistogram,
     histogram::Histogram,
     last_value::LastValue,
@@ -16,15 +26,63 @@ use super::{
     Number,
 };
 
-const STREAM_CARDINALITY_LIMIT: u32 = 2000;
+/// The cardinality limit used for metric streams that don't configure one.
+pub(crate) const DEFAULT_CARDINALITY_LIMIT: usize = 2000;
 pub(crate) static STREAM_OVERFLOW_ATTRIBUTE_SET: Lazy<AttributeSet> = Lazy::new(|| {
     let key_values: [KeyValue; 1] = [KeyValue::new("otel.metric.overflow", "true")];
     AttributeSet::from(&key_values[..])
 });
 
-/// Checks whether aggregator has hit cardinality limit for metric streams
-pub(crate) fn is_under_cardinality_limit(size: usize) -> bool {
-    size < STREAM_CARDINALITY_LIMIT as usize - 1
+/// Enforces the cardinality limit of a metric stream and counts the
+/// measurements recorded in its overflow attribute set.
+#[derive(Debug)]
+pub(crate) struct CardinalityLimiter {
+    limit: usize,
+    overflows: AtomicU64,
+    /// The number of overflows reported by [CardinalityLimiter::delta_overflows].
+    collected_overflows: AtomicU64,
+}
+
+impl CardinalityLimiter {
+    pub(crate) fn new(limit: usize) -> Self {
+        CardinalityLimiter {
+            limit,
+            overflows: AtomicU64::new(0),
+            collected_overflows: AtomicU64::new(0),
+        }
+    }
+
+    /// Checks whether a stream holding `size` attribute sets is below the limit.
+    ///
+    /// One data point of the limit is reserved for the overflow attribute set.
+    pub(crate) fn is_under_limit(&self, size: usize) -> bool {
+        size < self.limit.saturating_sub(1)
+    }
+
+    /// Records a measurement that was added to the overflow attribute set.
+    ///
+    /// Only the first overflow of a stream is reported to the error handler,
+    /// later ones are only counted.
+    pub(crate) fn record_overflow(&self) {
+        if self.overflows.fetch_add(1, Ordering::Relaxed) == 0 {
+            global::handle_error(MetricsError::Other(format!(
+                "Warning: Maximum data points ({}) for metric stream exceeded. Entry added to overflow.",
+                self.limit
+            )));
+        }
+    }
+
+    /// The number of measurements recorded in the overflow attribute set.
+    pub(crate) fn overflows(&self) -> u64 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+
+    /// The number of measurements recorded in the overflow attribute set
+    /// since the previous call.
+    pub(crate) fn delta_overflows(&self) -> u64 {
+        let overflows = self.overflows();
+        overflows - self.collected_overflows.swap(overflows, Ordering::Relaxed)
+    }
 }
 
 /// Receives measurements to be aggregated.
@@ -77,6 +135,18 @@ pub(crate) struct AggregateBuilder<T> {
     /// measurements.
     filter: Option<Filter>,
 
+    /// Renames and transforms the filtered attributes of measurements.
+    attribute_processor: Option<AttributeProcessor>,
+
+    /// The filter deciding which measurements are sampled as exemplars.
+    exemplar_filter: ExemplarFilter,
+
+    /// The reservoir used for exemplars, if not the aggregation's default.
+    exemplar_reservoir: Option<ExemplarReservoir>,
+
+    /// The cardinality limit shared by the aggregate functions built.
+    limiter: Arc<CardinalityLimiter>,
+
     _marker: marker::PhantomData<T>,
 }
 
@@ -87,18 +157,98 @@ impl<T: Number<T>> AggregateBuilder<T> {
         AggregateBuilder {
             temporality,
             filter,
+            attribute_processor: None,
+            exemplar_filter: ExemplarFilter::AlwaysOff,
+            exemplar_reservoir: None,
+            limiter: Arc::new(CardinalityLimiter::new(DEFAULT_CARDINALITY_LIMIT)),
             _marker: marker::PhantomData,
         }
     }
 
+    /// Sets the processor applied to the attributes of measurements after the
+    /// filter.
+    pub(crate) fn with_attribute_processor(
+        mut self,
+        processor: Option<AttributeProcessor>,
+    ) -> Self {
+        self.attribute_processor = processor;
+        self
+    }
+
+    /// Sets the maximum number of data points, including the overflow data
+    /// point, the aggregate functions will produce.
+    pub(crate) fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.limiter = Arc::new(CardinalityLimiter::new(limit));
+        self
+    }
+
+    /// The cardinality limiter of the aggregate functions built.
+    pub(crate) fn cardinality_limiter(&self) -> Arc<CardinalityLimiter> {
+        Arc::clone(&self.limiter)
+    }
+
+    /// Enables exemplar sampling for the aggregate functions that support it.
+    ///
+    /// If `reservoir` is `None`, the default reservoir of each aggregation is
+    /// used.
+    pub(crate) fn with_exemplars(
+        mut self,
+        filter: ExemplarFilter,
+        reservoir: Option<ExemplarReservoir>,
+    ) -> Self {
+        self.exemplar_filter = filter;
+        self.exemplar_reservoir = reservoir;
+        self
+    }
+
+    /// The exemplar reservoirs to use, falling back to `default`.
+    fn exemplars(&self, default: impl FnOnce() -> ExemplarReservoir) -> Exemplars<T> {
+        Exemplars::new(self.exemplar_reservoir.clone().unwrap_or_else(default))
+    }
+
     /// Wraps the passed in measure with an attribute filtering function.
-    fn filter(&self, f: impl Measure<T>) -> impl Measure<T> {
+    fn filter(&self, f: impl Fn(T, AttributeSet) + Send + Sync + 'static) -> impl Measure<T> {
         let filter = self.filter.clone();
+        let processor = self.attribute_processor.clone();
         move |n, mut attrs: AttributeSet| {
             if let Some(filter) = &filter {
                 attrs.retain(filter.as_ref());
             }
-            f.call(n, attrs)
+            if let Some(processor) = &processor {
+                attrs = processor.transform(attrs);
+            }
+            f(n, attrs)
+        }
+    }
+
+    /// Wraps the passed in measure with an attribute filtering function that
+    /// also samples exemplars.
+    ///
+    /// Attributes removed by the filter are recorded as the exemplar's filtered
+    /// attributes.
+    fn filter_with_exemplars(
+        &self,
+        f: impl Fn(T, AttributeSet, Option<Exemplar<T>>) + Send + Sync + 'static,
+    ) -> impl Measure<T> {
+        let filter = self.filter.clone();
+        let processor = self.attribute_processor.clone();
+        let exemplar_filter = self.exemplar_filter;
+        move |n, mut attrs: AttributeSet| {
+            let mut exemplar = exemplar::sample(exemplar_filter, n);
+            if let Some(filter) = &filter {
+                if let Some(exemplar) = exemplar.as_mut() {
+                    exemplar.filtered_attributes = attrs
+                        .iter()
+                        .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+                        .filter(|kv| !filter(kv))
+                        .collect();
+                }
+                attrs.retain(filter.as_ref());
+            }
+            if let Some(processor) = &processor {
+                attrs = processor.transform(attrs);
+            }
+            f(n, attrs, exemplar)
         }
     }
 
@@ -108,7 +258,7 @@ impl<T: Number<T>> AggregateBuilder<T> {
     pub(crate) fn last_value(&self) -> (impl Measure<T>, impl ComputeAggregation) {
         // Delta temporality is the only temporality that makes semantic sense for
         // a last-value aggregate.
-        let lv_filter = Arc::new(LastValue::new());
+        let lv_filter = Arc::new(LastValue::new(self.cardinality_limiter()));
         let lv_agg = Arc::clone(&lv_filter);
 
         (
@@ -136,7 +286,7 @@ impl<T: Number<T>> AggregateBuilder<T> {
         &self,
         monotonic: bool,
     ) -> (impl Measure<T>, impl ComputeAggregation) {
-        let s = Arc::new(PrecomputedSum::new(monotonic));
+        let s = Arc::new(PrecomputedSum::new(monotonic, self.cardinality_limiter()));
         let agg_sum = Arc::clone(&s);
         let t = self.temporality;
 
@@ -151,12 +301,13 @@ impl<T: Number<T>> AggregateBuilder<T> {
 
     /// Builds a sum aggregate function input and output.
     pub(crate) fn sum(&self, monotonic: bool) -> (impl Measure<T>, impl ComputeAggregation) {
-        let s = Arc::new(Sum::new(monotonic));
+        let exemplars = self.exemplars(|| ExemplarReservoir::SimpleFixedSize { size: 1 });
+        let s = Arc::new(Sum::new(monotonic, exemplars, self.cardinality_limiter()));
         let agg_sum = Arc::clone(&s);
         let t = self.temporality;
 
         (
-            self.filter(move |n, a| s.measure(n, a)),
+            self.filter_with_exemplars(move |n, a, e| s.measure(n, a, e)),
             move |dest: Option<&mut dyn Aggregation>| match t {
                 Some(Temporality::Delta) => agg_sum.delta(dest),
                 _ => agg_sum.cumulative(dest),
@@ -171,12 +322,21 @@ impl<T: Number<T>> AggregateBuilder<T> {
         record_min_max: bool,
         record_sum: bool,
     ) -> (impl Measure<T>, impl ComputeAggregation) {
-        let h = Arc::new(Histogram::new(boundaries, record_min_max, record_sum));
+        let exemplars = self.exemplars(|| ExemplarReservoir::AlignedHistogramBucket {
+            boundaries: boundaries.clone(),
+        });
+        let h = Arc::new(Histogram::new(
+            boundaries,
+            record_min_max,
+            record_sum,
+            exemplars,
+            self.cardinality_limiter(),
+        ));
         let agg_h = Arc::clone(&h);
         let t = self.temporality;
 
         (
-            self.filter(move |n, a| h.measure(n, a)),
+            self.filter_with_exemplars(move |n, a, e| h.measure(n, a, e)),
             move |dest: Option<&mut dyn Aggregation>| match t {
                 Some(Temporality::Delta) => agg_h.delta(dest),
                 _ => agg_h.cumulative(dest),
@@ -192,17 +352,22 @@ impl<T: Number<T>> AggregateBuilder<T> {
         record_min_max: bool,
         record_sum: bool,
     ) -> (impl Measure<T>, impl ComputeAggregation) {
+        let exemplars = self.exemplars(|| ExemplarReservoir::SimpleFixedSize {
+            size: max_size.min(20) as usize,
+        });
         let h = Arc::new(ExpoHistogram::new(
             max_size,
             max_scale,
             record_min_max,
             record_sum,
+            exemplars,
+            self.cardinality_limiter(),
         ));
         let agg_h = Arc::clone(&h);
         let t = self.temporality;
 
         (
-            self.filter(move |n, a| h.measure(n, a)),
+            self.filter_with_exemplars(move |n, a, e| h.measure(n, a, e)),
             move |dest: Option<&mut dyn Aggregation>| match t {
                 Some(Temporality::Delta) => agg_h.delta(dest),
                 _ => agg_h.cumulative(dest),
@@ -0,0 +1,249 @@
+use std::{collections::HashMap, sync::Mutex, time::SystemTime};
+
+use rand::Rng;
+
+use crate::{
+    attributes::AttributeSet,
+    metrics::{data::Exemplar, ExemplarFilter, ExemplarReservoir},
+};
+
+use super::Number;
+
+/// Returns an [Exemplar] for the measurement if the filter admits it.
+///
+/// The trace and span ids are taken from the span active in the current
+/// context, and are left empty if there is none.
+pub(crate) fn sample<T: Number<T>>(filter: ExemplarFilter, value: T) -> Option<Exemplar<T>> {
+    if filter == ExemplarFilter::AlwaysOff {
+        return None;
+    }
+
+    let (trace_id, span_id, sampled) = current_span_ids();
+    if filter == ExemplarFilter::TraceBased && !sampled {
+        return None;
+    }
+
+    Some(Exemplar {
+        filtered_attributes: vec![],
+        time: SystemTime::now(),
+        value,
+        span_id,
+        trace_id,
+    })
+}
+
+#[cfg(feature = "trace")]
+fn current_span_ids() -> ([u8; 16], [u8; 8], bool) {
+    use opentelemetry::{trace::TraceContextExt, Context};
+
+    Context::map_current(|cx| {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            (
+                span_context.trace_id().to_bytes(),
+                span_context.span_id().to_bytes(),
+                span_context.is_sampled(),
+            )
+        } else {
+            ([0; 16], [0; 8], false)
+        }
+    })
+}
+
+#[cfg(not(feature = "trace"))]
+fn current_span_ids() -> ([u8; 16], [u8; 8], bool) {
+    ([0; 16], [0; 8], false)
+}
+
+/// Samples the exemplars offered for a single attribute set.
+trait Reservoir<T>: Send + Sync {
+    /// Offers a sampled measurement to the reservoir.
+    fn offer(&mut self, exemplar: Exemplar<T>);
+
+    /// Appends the exemplars held by the reservoir to `dest`.
+    fn collect(&self, dest: &mut Vec<Exemplar<T>>);
+}
+
+/// Keeps a uniformly random sample of the offered exemplars using reservoir
+/// sampling ("Algorithm R").
+struct FixedSizeReservoir<T> {
+    size: usize,
+    seen: usize,
+    store: Vec<Exemplar<T>>,
+}
+
+impl<T: Number<T>> Reservoir<T> for FixedSizeReservoir<T> {
+    fn offer(&mut self, exemplar: Exemplar<T>) {
+        self.seen += 1;
+        if self.store.len() < self.size {
+            self.store.push(exemplar);
+            return;
+        }
+
+        let idx = rand::thread_rng().gen_range(0..self.seen);
+        if idx < self.size {
+            self.store[idx] = exemplar;
+        }
+    }
+
+    fn collect(&self, dest: &mut Vec<Exemplar<T>>) {
+        dest.extend(self.store.iter().cloned());
+    }
+}
+
+/// Keeps the last exemplar offered for each histogram bucket.
+struct HistogramReservoir<T> {
+    bounds: Vec<f64>,
+    store: Vec<Option<Exemplar<T>>>,
+}
+
+impl<T: Number<T>> Reservoir<T> for HistogramReservoir<T> {
+    fn offer(&mut self, exemplar: Exemplar<T>) {
+        let f = exemplar.value.into_float();
+        let idx = self.bounds.partition_point(|&x| x < f);
+        self.store[idx] = Some(exemplar);
+    }
+
+    fn collect(&self, dest: &mut Vec<Exemplar<T>>) {
+        dest.extend(self.store.iter().flatten().cloned());
+    }
+}
+
+/// The exemplar reservoirs of an aggregate function, one per attribute set.
+pub(crate) struct Exemplars<T> {
+    reservoir: ExemplarReservoir,
+    reservoirs: Mutex<HashMap<AttributeSet, Box<dyn Reservoir<T>>>>,
+}
+
+impl<T: Number<T>> Exemplars<T> {
+    pub(crate) fn new(mut reservoir: ExemplarReservoir) -> Self {
+        if let ExemplarReservoir::AlignedHistogramBucket { boundaries } = &mut reservoir {
+            boundaries.retain(|v| !v.is_nan());
+            boundaries.sort_by(|a, b| a.partial_cmp(b).expect("NaNs filtered out"));
+        }
+
+        Exemplars {
+            reservoir,
+            reservoirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn new_reservoir(&self) -> Box<dyn Reservoir<T>> {
+        match &self.reservoir {
+            ExemplarReservoir::SimpleFixedSize { size } => Box::new(FixedSizeReservoir {
+                size: *size,
+                seen: 0,
+                store: Vec::with_capacity(*size),
+            }),
+            ExemplarReservoir::AlignedHistogramBucket { boundaries } => {
+                Box::new(HistogramReservoir {
+                    bounds: boundaries.clone(),
+                    store: vec![None; boundaries.len() + 1],
+                })
+            }
+        }
+    }
+
+    /// Offers the exemplar to the reservoir of the given attribute set.
+    pub(crate) fn offer(&self, attrs: &AttributeSet, exemplar: Exemplar<T>) {
+        if let Ok(mut reservoirs) = self.reservoirs.lock() {
+            if let Some(reservoir) = reservoirs.get_mut(attrs) {
+                reservoir.offer(exemplar);
+            } else {
+                let mut reservoir = self.new_reservoir();
+                reservoir.offer(exemplar);
+                reservoirs.insert(attrs.clone(), reservoir);
+            }
+        }
+    }
+
+    /// Returns the exemplars held for each attribute set.
+    ///
+    /// If `reset` is true all reservoirs are emptied, as is required at the
+    /// end of a delta collection cycle.
+    pub(crate) fn collect(&self, reset: bool) -> HashMap<AttributeSet, Vec<Exemplar<T>>> {
+        let mut reservoirs = match self.reservoirs.lock() {
+            Ok(r) => r,
+            Err(_) => return HashMap::new(),
+        };
+
+        let collect = |reservoir: &dyn Reservoir<T>| {
+            let mut exemplars = vec![];
+            reservoir.collect(&mut exemplars);
+            exemplars
+        };
+        if reset {
+            reservoirs
+                .drain()
+                .map(|(attrs, reservoir)| (attrs, collect(reservoir.as_ref())))
+                .collect()
+        } else {
+            reservoirs
+                .iter()
+                .map(|(attrs, reservoir)| (attrs.clone(), collect(reservoir.as_ref())))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::KeyValue;
+
+    use super::*;
+
+    fn exemplar(value: f64) -> Exemplar<f64> {
+        Exemplar {
+            filtered_attributes: vec![],
+            time: SystemTime::now(),
+            value,
+            span_id: [0; 8],
+            trace_id: [0; 16],
+        }
+    }
+
+    #[test]
+    fn fixed_size_reservoir_keeps_at_most_size_exemplars() {
+        let exemplars = Exemplars::new(ExemplarReservoir::SimpleFixedSize { size: 2 });
+        let attrs = AttributeSet::from(&[KeyValue::new("a", 1)][..]);
+        for v in 0..10 {
+            exemplars.offer(&attrs, exemplar(v as f64));
+        }
+
+        let collected = exemplars.collect(false);
+        assert_eq!(collected[&attrs].len(), 2);
+
+        let collected = exemplars.collect(true);
+        assert_eq!(collected[&attrs].len(), 2);
+        assert!(exemplars.collect(true).is_empty());
+    }
+
+    #[test]
+    fn histogram_reservoir_keeps_last_exemplar_per_bucket() {
+        let exemplars = Exemplars::new(ExemplarReservoir::AlignedHistogramBucket {
+            boundaries: vec![10.0, 5.0],
+        });
+        let attrs = AttributeSet::default();
+        for v in [1.0, 2.0, 7.0, 20.0, 30.0] {
+            exemplars.offer(&attrs, exemplar(v));
+        }
+
+        let values = exemplars.collect(true)[&attrs]
+            .iter()
+            .map(|e| e.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![2.0, 7.0, 30.0]);
+    }
+
+    #[test]
+    fn filter_without_active_span() {
+        assert!(sample(ExemplarFilter::AlwaysOff, 1u64).is_none());
+        assert!(sample(ExemplarFilter::TraceBased, 1u64).is_none());
+
+        let e = sample(ExemplarFilter::AlwaysOn, 1u64).expect("always on samples");
+        assert_eq!(e.value, 1);
+        assert_eq!(e.trace_id, [0; 16]);
+        assert_eq!(e.span_id, [0; 8]);
+    }
+}
@@ -1,14 +1,23 @@
-use std::{collections::HashMap, f64::consts::LOG2_E, sync::Mutex, time::SystemTime};
+use std::{
+    collections::HashMap,
+    f64::consts::LOG2_E,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
 
 use once_cell::sync::Lazy;
 use opentelemetry::metrics::MetricsError;
 
 use crate::{
-    metrics::data::{self, Aggregation, Temporality},
+    metrics::data::{self, Aggregation, Exemplar, Temporality},
     AttributeSet,
 };
 
-use super::Number;
+use super::{
+    aggregate::{CardinalityLimiter, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    exemplar::Exemplars,
+    Number,
+};
 
 pub(crate) const EXPO_MAX_SCALE: i8 = 20;
 pub(crate) const EXPO_MIN_SCALE: i8 = -10;
@@ -318,6 +327,8 @@ pub(crate) struct ExpoHistogram<T> {
     max_scale: i8,
 
     values: Mutex<HashMap<AttributeSet, ExpoHistogramDataPoint<T>>>,
+    exemplars: Exemplars<T>,
+    limiter: Arc<CardinalityLimiter>,
 
     start: Mutex<SystemTime>,
 }
@@ -329,6 +340,8 @@ impl<T: Number<T>> ExpoHistogram<T> {
         max_scale: i8,
         record_min_max: bool,
         record_sum: bool,
+        exemplars: Exemplars<T>,
+        limiter: Arc<CardinalityLimiter>,
     ) -> Self {
         ExpoHistogram {
             record_sum,
@@ -336,11 +349,13 @@ impl<T: Number<T>> ExpoHistogram<T> {
             max_size: max_size as i32,
             max_scale,
             values: Mutex::new(HashMap::default()),
+            exemplars,
+            limiter,
             start: Mutex::new(SystemTime::now()),
         }
     }
 
-    pub(crate) fn measure(&self, value: T, attrs: AttributeSet) {
+    pub(crate) fn measure(&self, value: T, attrs: AttributeSet, exemplar: Option<Exemplar<T>>) {
         let f_value = value.into_float();
         // Ignore NaN and infinity.
         if f_value.is_infinite() || f_value.is_nan() {
@@ -348,6 +363,17 @@ impl<T: Number<T>> ExpoHistogram<T> {
         }
 
         if let Ok(mut values) = self.values.lock() {
+            let attrs = if values.contains_key(&attrs) || self.limiter.is_under_limit(values.len())
+            {
+                attrs
+            } else {
+                self.limiter.record_overflow();
+                STREAM_OVERFLOW_ATTRIBUTE_SET.clone()
+            };
+            if let Some(exemplar) = exemplar {
+                self.exemplars.offer(&attrs, exemplar);
+            }
+
             let v = values.entry(attrs).or_insert_with(|| {
                 ExpoHistogramDataPoint::new(
                     self.max_size,
@@ -394,7 +420,9 @@ impl<T: Number<T>> ExpoHistogram<T> {
             h.data_points.reserve_exact(n - h.data_points.capacity());
         }
 
+        let mut exemplars = self.exemplars.collect(true);
         for (a, b) in values.drain() {
+            let exemplars = exemplars.remove(&a).unwrap_or_default();
             h.data_points.push(data::ExponentialHistogramDataPoint {
                 attributes: a,
                 start_time: start,
@@ -422,7 +450,7 @@ impl<T: Number<T>> ExpoHistogram<T> {
                     counts: b.neg_buckets.counts.clone(),
                 },
                 zero_threshold: 0.0,
-                exemplars: vec![],
+                exemplars,
             });
         }
 
@@ -472,6 +500,7 @@ impl<T: Number<T>> ExpoHistogram<T> {
         // are unbounded number of attribute sets being aggregated. Attribute
         // sets that become "stale" need to be forgotten so this will not
         // overload the system.
+        let mut exemplars = self.exemplars.collect(false);
         for (a, b) in values.iter() {
             h.data_points.push(data::ExponentialHistogramDataPoint {
                 attributes: a.clone(),
@@ -500,7 +529,7 @@ impl<T: Number<T>> ExpoHistogram<T> {
                     counts: b.neg_buckets.counts.clone(),
                 },
                 zero_threshold: 0.0,
-                exemplars: vec![],
+                exemplars: exemplars.remove(a).unwrap_or_default(),
             });
         }
 
@@ -514,10 +543,21 @@ mod tests {
 
     use opentelemetry::KeyValue;
 
-    use crate::metrics::internal::{self, AggregateBuilder};
+    use crate::metrics::{
+        internal::{self, aggregate::DEFAULT_CARDINALITY_LIMIT, AggregateBuilder},
+        ExemplarReservoir,
+    };
 
     use super::*;
 
+    fn no_exemplars<T: Number<T>>() -> Exemplars<T> {
+        Exemplars::new(ExemplarReservoir::SimpleFixedSize { size: 0 })
+    }
+
+    fn default_limiter() -> Arc<CardinalityLimiter> {
+        Arc::new(CardinalityLimiter::new(DEFAULT_CARDINALITY_LIMIT))
+    }
+
     #[test]
     fn test_expo_histogram_data_point_record() {
         run_data_point_record::<f64>();
@@ -673,9 +713,9 @@ mod tests {
         ];
 
         for test in test_cases {
-            let h = ExpoHistogram::new(4, 20, true, true);
+            let h = ExpoHistogram::new(4, 20, true, true, no_exemplars(), default_limiter());
             for v in test.values {
-                h.measure(v, alice.clone());
+                h.measure(v, alice.clone(), None);
             }
             let values = h.values.lock().unwrap();
             let dp = values.get(&alice).unwrap();
@@ -724,9 +764,9 @@ mod tests {
         ];
 
         for test in test_cases {
-            let h = ExpoHistogram::new(4, 20, true, true);
+            let h = ExpoHistogram::new(4, 20, true, true, no_exemplars(), default_limiter());
             for v in test.values {
-                h.measure(v, alice.clone());
+                h.measure(v, alice.clone(), None);
             }
             let values = h.values.lock().unwrap();
             let dp = values.get(&alice).unwrap();
@@ -1,13 +1,16 @@
-use std::{collections::HashMap, sync::Mutex, time::SystemTime};
-
-use crate::metrics::data::{self, Aggregation, Temporality};
-use crate::{attributes::AttributeSet, metrics::data::HistogramDataPoint};
-use opentelemetry::{global, metrics::MetricsError};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
 
 use super::{
-    aggregate::{is_under_cardinality_limit, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    aggregate::{CardinalityLimiter, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    exemplar::Exemplars,
     Number,
 };
+use crate::metrics::data::{self, Aggregation, Exemplar, Temporality};
+use crate::{attributes::AttributeSet, metrics::data::HistogramDataPoint};
 
 #[derive(Default)]
 struct Buckets<T> {
@@ -47,10 +50,11 @@ struct HistValues<T> {
     record_sum: bool,
     bounds: Vec<f64>,
     values: Mutex<HashMap<AttributeSet, Buckets<T>>>,
+    limiter: Arc<CardinalityLimiter>,
 }
 
 impl<T: Number<T>> HistValues<T> {
-    fn new(mut bounds: Vec<f64>, record_sum: bool) -> Self {
+    fn new(mut bounds: Vec<f64>, record_sum: bool, limiter: Arc<CardinalityLimiter>) -> Self {
         bounds.retain(|v| !v.is_nan());
         bounds.sort_by(|a, b| a.partial_cmp(b).expect("NaNs filtered out"));
 
@@ -58,12 +62,22 @@ impl<T: Number<T>> HistValues<T> {
             record_sum,
             bounds,
             values: Mutex::new(Default::default()),
+            limiter,
         }
     }
 }
 
 impl<T: Number<T>> HistValues<T> {
-    fn measure(&self, measurement: T, attrs: AttributeSet) {
+    /// Records the measurement in the buckets of `attrs`.
+    ///
+    /// The exemplar of the measurement is offered while the values are locked,
+    /// so that a concurrent collection takes both or neither.
+    fn measure(
+        &self,
+        measurement: T,
+        attrs: AttributeSet,
+        exemplar: Option<(&Exemplars<T>, Exemplar<T>)>,
+    ) {
         let f = measurement.into_float();
 
         // This search will return an index in the range `[0, bounds.len()]`, where
@@ -77,7 +91,9 @@ impl<T: Number<T>> HistValues<T> {
             Ok(guard) => guard,
             Err(_) => return,
         };
+        let exemplar_attrs = exemplar.as_ref().map(|_| attrs.clone());
         let size = values.len();
+        let mut overflowed = false;
 
         let b = if let Some(b) = values.get_mut(&attrs) {
             b
@@ -93,10 +109,11 @@ impl<T: Number<T>> HistValues<T> {
             // Ensure min and max are recorded values (not zero), for new buckets.
             (b.min, b.max) = (measurement, measurement);
 
-            if is_under_cardinality_limit(size) {
+            if self.limiter.is_under_limit(size) {
                 values.entry(attrs).or_insert(b)
             } else {
-                global::handle_error(MetricsError::Other("Warning: Maximum data points for metric stream exceeded. Entry added to overflow.".into()));
+                self.limiter.record_overflow();
+                overflowed = true;
                 values
                     .entry(STREAM_OVERFLOW_ATTRIBUTE_SET.clone())
                     .or_insert(b)
@@ -107,6 +124,14 @@ impl<T: Number<T>> HistValues<T> {
         if self.record_sum {
             b.sum(measurement)
         }
+
+        if let (Some((exemplars, exemplar)), Some(attrs)) = (exemplar, exemplar_attrs) {
+            if overflowed {
+                exemplars.offer(&STREAM_OVERFLOW_ATTRIBUTE_SET, exemplar);
+            } else {
+                exemplars.offer(&attrs, exemplar);
+            }
+        }
     }
 }
 
@@ -114,21 +139,35 @@ impl<T: Number<T>> HistValues<T> {
 /// buckets.
 pub(crate) struct Histogram<T> {
     hist_values: HistValues<T>,
+    exemplars: Exemplars<T>,
     record_min_max: bool,
     start: Mutex<SystemTime>,
 }
 
 impl<T: Number<T>> Histogram<T> {
-    pub(crate) fn new(boundaries: Vec<f64>, record_min_max: bool, record_sum: bool) -> Self {
+    pub(crate) fn new(
+        boundaries: Vec<f64>,
+        record_min_max: bool,
+        record_sum: bool,
+        exemplars: Exemplars<T>,
+        limiter: Arc<CardinalityLimiter>,
+    ) -> Self {
         Histogram {
-            hist_values: HistValues::new(boundaries, record_sum),
+            hist_values: HistValues::new(boundaries, record_sum, limiter),
+            exemplars,
             record_min_max,
             start: Mutex::new(SystemTime::now()),
         }
     }
 
-    pub(crate) fn measure(&self, measurement: T, attrs: AttributeSet) {
-        self.hist_values.measure(measurement, attrs)
+    pub(crate) fn measure(
+        &self,
+        measurement: T,
+        attrs: AttributeSet,
+        exemplar: Option<Exemplar<T>>,
+    ) {
+        let exemplar = exemplar.map(|exemplar| (&self.exemplars, exemplar));
+        self.hist_values.measure(measurement, attrs, exemplar);
     }
 
     pub(crate) fn delta(
@@ -163,7 +202,9 @@ impl<T: Number<T>> Histogram<T> {
             h.data_points.reserve_exact(n - h.data_points.capacity());
         }
 
+        let mut exemplars = self.exemplars.collect(true);
         for (a, b) in values.drain() {
+            let exemplars = exemplars.remove(&a).unwrap_or_default();
             h.data_points.push(HistogramDataPoint {
                 attributes: a,
                 start_time: start,
@@ -186,7 +227,7 @@ impl<T: Number<T>> Histogram<T> {
                 } else {
                     None
                 },
-                exemplars: vec![],
+                exemplars,
             });
         }
 
@@ -234,6 +275,7 @@ impl<T: Number<T>> Histogram<T> {
         // are unbounded number of attribute sets being aggregated. Attribute
         // sets that become "stale" need to be forgotten so this will not
         // overload the system.
+        let mut exemplars = self.exemplars.collect(false);
         for (a, b) in values.iter() {
             h.data_points.push(HistogramDataPoint {
                 attributes: a.clone(),
@@ -257,7 +299,7 @@ impl<T: Number<T>> Histogram<T> {
                 } else {
                     None
                 },
-                exemplars: vec![],
+                exemplars: exemplars.remove(a).unwrap_or_default(),
             });
         }
 
@@ -1,16 +1,14 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
-    sync::Mutex,
+    sync::{Arc, Mutex},
     time::SystemTime,
 };
 
-use crate::{attributes::AttributeSet, metrics::data::DataPoint};
-use opentelemetry::{global, metrics::MetricsError};
-
 use super::{
-    aggregate::{is_under_cardinality_limit, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    aggregate::{CardinalityLimiter, STREAM_OVERFLOW_ATTRIBUTE_SET},
     Number,
 };
+use crate::{attributes::AttributeSet, metrics::data::DataPoint};
 
 /// Timestamped measurement data.
 struct DataPointValue<T> {
@@ -19,14 +17,17 @@ struct DataPointValue<T> {
 }
 
 /// Summarizes a set of measurements as the last one made.
-#[derive(Default)]
 pub(crate) struct LastValue<T> {
     values: Mutex<HashMap<AttributeSet, DataPointValue<T>>>,
+    limiter: Arc<CardinalityLimiter>,
 }
 
 impl<T: Number<T>> LastValue<T> {
-    pub(crate) fn new() -> Self {
-        Self::default()
+    pub(crate) fn new(limiter: Arc<CardinalityLimiter>) -> Self {
+        LastValue {
+            values: Mutex::new(HashMap::new()),
+            limiter,
+        }
     }
 
     pub(crate) fn measure(&self, measurement: T, attrs: AttributeSet) {
@@ -41,11 +42,11 @@ impl<T: Number<T>> LastValue<T> {
                     occupied_entry.insert(d);
                 }
                 Entry::Vacant(vacant_entry) => {
-                    if is_under_cardinality_limit(size) {
+                    if self.limiter.is_under_limit(size) {
                         vacant_entry.insert(d);
                     } else {
                         values.insert(STREAM_OVERFLOW_ATTRIBUTE_SET.clone(), d);
-                        global::handle_error(MetricsError::Other("Warning: Maximum data points for metric stream exceeded. Entry added to overflow.".into()));
+                        self.limiter.record_overflow();
                     }
                 }
             }
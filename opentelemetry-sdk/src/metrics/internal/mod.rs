@@ -1,4 +1,5 @@
 mod aggregate;
+mod exemplar;
 mod exponential_histogram;
 mod histogram;
 mod last_value;
@@ -9,7 +10,9 @@ use std::ops::{Add, AddAssign, Sub};
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Mutex;
 
-pub(crate) use aggregate::{AggregateBuilder, ComputeAggregation, Measure};
+pub(crate) use aggregate::{
+    AggregateBuilder, CardinalityLimiter, ComputeAggregation, Measure, DEFAULT_CARDINALITY_LIMIT,
+};
 pub(crate) use exponential_histogram::{EXPO_MAX_SCALE, EXPO_MIN_SCALE};
 
 /// Marks a type that can have a value added and retrieved atomically. Required since
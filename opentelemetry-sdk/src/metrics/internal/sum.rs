@@ -1,67 +1,88 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
     collections::{hash_map::Entry, HashMap},
-    sync::Mutex,
+    sync::{Arc, Mutex},
     time::SystemTime,
 };
 
-use crate::attributes::AttributeSet;
-use crate::metrics::data::{self, Aggregation, DataPoint, Temporality};
-use opentelemetry::{global, metrics::MetricsError};
-
 use super::{
-    aggregate::{is_under_cardinality_limit, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    aggregate::{CardinalityLimiter, STREAM_OVERFLOW_ATTRIBUTE_SET},
+    exemplar::Exemplars,
     AtomicTracker, Number,
 };
+use crate::attributes::AttributeSet;
+use crate::metrics::data::{self, Aggregation, DataPoint, Exemplar, Temporality};
 
 /// The storage for sums.
 struct ValueMap<T: Number<T>> {
     values: Mutex<HashMap<AttributeSet, T>>,
     has_no_value_attribute_value: AtomicBool,
     no_attribute_value: T::AtomicTracker,
-}
-
-impl<T: Number<T>> Default for ValueMap<T> {
-    fn default() -> Self {
-        ValueMap::new()
-    }
+    limiter: Arc<CardinalityLimiter>,
 }
 
 impl<T: Number<T>> ValueMap<T> {
-    fn new() -> Self {
+    fn new(limiter: Arc<CardinalityLimiter>) -> Self {
         ValueMap {
             values: Mutex::new(HashMap::new()),
             has_no_value_attribute_value: AtomicBool::new(false),
             no_attribute_value: T::new_atomic_tracker(),
+            limiter,
         }
     }
 }
 
 impl<T: Number<T>> ValueMap<T> {
-    fn measure(&self, measurement: T, attrs: AttributeSet) {
+    /// Adds the measurement to the value of `attrs`, or of the overflow
+    /// attribute set if the cardinality limit is reached.
+    ///
+    /// The exemplar of the measurement is offered while the values are locked,
+    /// so that a concurrent collection takes both or neither.
+    fn measure(
+        &self,
+        measurement: T,
+        attrs: AttributeSet,
+        exemplar: Option<(&Exemplars<T>, Exemplar<T>)>,
+    ) {
         if attrs.is_empty() {
+            // Collections read this value while holding the lock of the values.
+            let _values = exemplar.as_ref().map(|_| self.values.lock());
             self.no_attribute_value.add(measurement);
             self.has_no_value_attribute_value
                 .store(true, Ordering::Release);
+            if let Some((exemplars, exemplar)) = exemplar {
+                exemplars.offer(&attrs, exemplar);
+            }
         } else if let Ok(mut values) = self.values.lock() {
+            let exemplar_attrs = exemplar.as_ref().map(|_| attrs.clone());
             let size = values.len();
+            let mut overflowed = false;
             match values.entry(attrs) {
                 Entry::Occupied(mut occupied_entry) => {
                     let sum = occupied_entry.get_mut();
                     *sum += measurement;
                 }
                 Entry::Vacant(vacant_entry) => {
-                    if is_under_cardinality_limit(size) {
+                    if self.limiter.is_under_limit(size) {
                         vacant_entry.insert(measurement);
                     } else {
                         values
                             .entry(STREAM_OVERFLOW_ATTRIBUTE_SET.clone())
                             .and_modify(|val| *val += measurement)
                             .or_insert(measurement);
-                        global::handle_error(MetricsError::Other("Warning: Maximum data points for metric stream exceeded. Entry added to overflow.".into()));
+                        self.limiter.record_overflow();
+                        overflowed = true;
                     }
                 }
             }
+
+            if let (Some((exemplars, exemplar)), Some(attrs)) = (exemplar, exemplar_attrs) {
+                if overflowed {
+                    exemplars.offer(&STREAM_OVERFLOW_ATTRIBUTE_SET, exemplar);
+                } else {
+                    exemplars.offer(&attrs, exemplar);
+                }
+            }
         }
     }
 }
@@ -69,6 +90,7 @@ impl<T: Number<T>> ValueMap<T> {
 /// Summarizes a set of measurements made as their arithmetic sum.
 pub(crate) struct Sum<T: Number<T>> {
     value_map: ValueMap<T>,
+    exemplars: Exemplars<T>,
     monotonic: bool,
     start: Mutex<SystemTime>,
 }
@@ -79,16 +101,27 @@ impl<T: Number<T>> Sum<T> {
     ///
     /// Each sum is scoped by attributes and the aggregation cycle the measurements
     /// were made in.
-    pub(crate) fn new(monotonic: bool) -> Self {
+    pub(crate) fn new(
+        monotonic: bool,
+        exemplars: Exemplars<T>,
+        limiter: Arc<CardinalityLimiter>,
+    ) -> Self {
         Sum {
-            value_map: ValueMap::new(),
+            value_map: ValueMap::new(limiter),
+            exemplars,
             monotonic,
             start: Mutex::new(SystemTime::now()),
         }
     }
 
-    pub(crate) fn measure(&self, measurement: T, attrs: AttributeSet) {
-        self.value_map.measure(measurement, attrs)
+    pub(crate) fn measure(
+        &self,
+        measurement: T,
+        attrs: AttributeSet,
+        exemplar: Option<Exemplar<T>>,
+    ) {
+        let exemplar = exemplar.map(|exemplar| (&self.exemplars, exemplar));
+        self.value_map.measure(measurement, attrs, exemplar);
     }
 
     pub(crate) fn delta(
@@ -125,6 +158,7 @@ impl<T: Number<T>> Sum<T> {
         }
 
         let prev_start = self.start.lock().map(|start| *start).unwrap_or(t);
+        let mut exemplars = self.exemplars.collect(true);
         if self
             .value_map
             .has_no_value_attribute_value
@@ -135,17 +169,20 @@ impl<T: Number<T>> Sum<T> {
                 start_time: Some(prev_start),
                 time: Some(t),
                 value: self.value_map.no_attribute_value.get_and_reset_value(),
-                exemplars: vec![],
+                exemplars: exemplars
+                    .remove(&AttributeSet::default())
+                    .unwrap_or_default(),
             });
         }
 
         for (attrs, value) in values.drain() {
+            let exemplars = exemplars.remove(&attrs).unwrap_or_default();
             s_data.data_points.push(DataPoint {
                 attributes: attrs,
                 start_time: Some(prev_start),
                 time: Some(t),
                 value,
-                exemplars: vec![],
+                exemplars,
             });
         }
 
@@ -194,6 +231,7 @@ impl<T: Number<T>> Sum<T> {
         }
 
         let prev_start = self.start.lock().map(|start| *start).unwrap_or(t);
+        let mut exemplars = self.exemplars.collect(false);
 
         if self
             .value_map
@@ -205,7 +243,9 @@ impl<T: Number<T>> Sum<T> {
                 start_time: Some(prev_start),
                 time: Some(t),
                 value: self.value_map.no_attribute_value.get_value(),
-                exemplars: vec![],
+                exemplars: exemplars
+                    .remove(&AttributeSet::default())
+                    .unwrap_or_default(),
             });
         }
 
@@ -219,7 +259,7 @@ impl<T: Number<T>> Sum<T> {
                 start_time: Some(prev_start),
                 time: Some(t),
                 value: *value,
-                exemplars: vec![],
+                exemplars: exemplars.remove(attrs).unwrap_or_default(),
             });
         }
 
@@ -239,9 +279,9 @@ pub(crate) struct PrecomputedSum<T: Number<T>> {
 }
 
 impl<T: Number<T>> PrecomputedSum<T> {
-    pub(crate) fn new(monotonic: bool) -> Self {
+    pub(crate) fn new(monotonic: bool, limiter: Arc<CardinalityLimiter>) -> Self {
         PrecomputedSum {
-            value_map: ValueMap::new(),
+            value_map: ValueMap::new(limiter),
             monotonic,
             start: Mutex::new(SystemTime::now()),
             reported: Mutex::new(Default::default()),
@@ -249,7 +289,7 @@ impl<T: Number<T>> PrecomputedSum<T> {
     }
 
     pub(crate) fn measure(&self, measurement: T, attrs: AttributeSet) {
-        self.value_map.measure(measurement, attrs)
+        self.value_map.measure(measurement, attrs, None);
     }
 
     pub(crate) fn delta(
@@ -35,6 +35,7 @@ pub struct ManualReader {
     inner: Box<Mutex<ManualReaderInner>>,
     temporality_selector: Box<dyn TemporalitySelector>,
     aggregation_selector: Box<dyn AggregationSelector>,
+    cardinality_limit: Option<usize>,
 }
 
 impl Default for ManualReader {
@@ -67,6 +68,7 @@ impl ManualReader {
         temporality_selector: Box<dyn TemporalitySelector>,
         aggregation_selector: Box<dyn AggregationSelector>,
         producers: Vec<Box<dyn MetricProducer>>,
+        cardinality_limit: Option<usize>,
     ) -> Self {
         ManualReader {
             inner: Box::new(Mutex::new(ManualReaderInner {
@@ -76,6 +78,7 @@ impl ManualReader {
             })),
             temporality_selector,
             aggregation_selector,
+            cardinality_limit,
         }
     }
 }
@@ -154,6 +157,10 @@ impl MetricReader for ManualReader {
 
         Ok(())
     }
+
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        self.cardinality_limit
+    }
 }
 
 /// Configuration for a [ManualReader]
@@ -161,6 +168,7 @@ pub struct ManualReaderBuilder {
     temporality_selector: Box<dyn TemporalitySelector>,
     aggregation_selector: Box<dyn AggregationSelector>,
     producers: Vec<Box<dyn MetricProducer>>,
+    cardinality_limit: Option<usize>,
 }
 
 impl fmt::Debug for ManualReaderBuilder {
@@ -175,6 +183,7 @@ impl Default for ManualReaderBuilder {
             temporality_selector: Box::new(DefaultTemporalitySelector { _private: () }),
             aggregation_selector: Box::new(DefaultAggregationSelector { _private: () }),
             producers: vec![],
+            cardinality_limit: None,
         }
     }
 }
@@ -219,12 +228,24 @@ impl ManualReaderBuilder {
         self
     }
 
+    /// Sets the cardinality limit of the metric streams read by this reader.
+    ///
+    /// This option overrides the default limit of the meter provider, but not
+    /// the limit set on a [Stream].
+    ///
+    /// [Stream]: crate::metrics::Stream
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
     /// Create a new [ManualReader] from this configuration.
     pub fn build(self) -> ManualReader {
         ManualReader::new(
             self.temporality_selector,
             self.aggregation_selector,
             self.producers,
+            self.cardinality_limit,
         )
     }
 }
@@ -14,9 +14,12 @@ use opentelemetry::{
     KeyValue,
 };
 
-use crate::{instrumentation::Scope, Resource};
+use crate::{instrumentation::Scope, util::sdk_disabled, Resource};
 
-use super::{meter::SdkMeter, pipeline::Pipelines, reader::MetricReader, view::View};
+use super::{
+    exemplar::ExemplarFilter, internal, meter::SdkMeter, pipeline::Pipelines, reader::MetricReader,
+    view::View,
+};
 
 /// Handles the creation and coordination of [Meter]s.
 ///
@@ -35,6 +38,7 @@ struct SdkMeterProviderInner {
     pipes: Arc<Pipelines>,
     meters: Arc<Mutex<HashMap<Scope, Arc<SdkMeter>>>>,
     is_shutdown: Arc<AtomicBool>,
+    is_disabled: bool,
 }
 
 impl Default for SdkMeterProvider {
@@ -144,7 +148,7 @@ impl MeterProvider for SdkMeterProvider {
         schema_url: Option<impl Into<Cow<'static, str>>>,
         attributes: Option<Vec<KeyValue>>,
     ) -> Meter {
-        if self.inner.is_shutdown.load(Ordering::Relaxed) {
+        if self.inner.is_disabled || self.inner.is_shutdown.load(Ordering::Relaxed) {
             return Meter::new(Arc::new(NoopMeterCore::new()));
         }
 
@@ -182,6 +186,8 @@ pub struct MeterProviderBuilder {
     resource: Option<Resource>,
     readers: Vec<Box<dyn MetricReader>>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: Option<ExemplarFilter>,
+    cardinality_limit: Option<usize>,
 }
 
 impl MeterProviderBuilder {
@@ -219,8 +225,46 @@ impl MeterProviderBuilder {
         self
     }
 
-    /// Construct a new [MeterProvider] with this configuration.
+    /// Associates an [ExemplarFilter] with a [MeterProvider].
+    ///
+    /// The filter decides which measurements are offered to the exemplar
+    /// reservoirs of every metric stream.
+    ///
+    /// By default, if this option is not used, the filter set by the
+    /// `OTEL_METRICS_EXEMPLAR_FILTER` environment variable (`always_on`,
+    /// `always_off` or `trace_based`) is used, falling back to
+    /// [ExemplarFilter::TraceBased]: measurements recorded in the context of a
+    /// sampled span are eligible as exemplars.
+    pub fn with_exemplar_filter(mut self, filter: ExemplarFilter) -> Self {
+        self.exemplar_filter = Some(filter);
+        self
+    }
+
+    /// Sets the default cardinality limit of the metric streams of a
+    /// [MeterProvider].
+    ///
+    /// The limit is the maximum number of data points, including the overflow
+    /// data point, a metric stream produces in a collection cycle. Measurements
+    /// made with new attributes once the limit is reached are aggregated into a
+    /// single data point with the `otel.metric.overflow=true` attribute. The
+    /// number of these measurements is reported by the
+    /// `otel.sdk.metric.cardinality_overflows` metric.
+    ///
+    /// The limit set on a [Stream] or its [MetricReader] takes precedence over
+    /// this one.
+    ///
+    /// By default, if this option is not used, a limit of `2000` is used.
+    ///
+    /// [Stream]: crate::metrics::Stream
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
 
+    /// Construct a new [MeterProvider] with this configuration.
+    ///
+    /// The meters of the provider are no-ops if the `OTEL_SDK_DISABLED`
+    /// environment variable is set to `true`.
     pub fn build(self) -> SdkMeterProvider {
         SdkMeterProvider {
             inner: Arc::new(SdkMeterProviderInner {
@@ -228,20 +272,45 @@ impl MeterProviderBuilder {
                     self.resource.unwrap_or_default(),
                     self.readers,
                     self.views,
+                    self.exemplar_filter
+                        .unwrap_or_else(exemplar_filter_from_env),
+                    self.cardinality_limit
+                        .unwrap_or(internal::DEFAULT_CARDINALITY_LIMIT),
                 )),
                 meters: Default::default(),
                 is_shutdown: Arc::new(AtomicBool::new(false)),
+                is_disabled: sdk_disabled(),
             }),
         }
     }
 }
 
+fn exemplar_filter_from_env() -> ExemplarFilter {
+    match std::env::var("OTEL_METRICS_EXEMPLAR_FILTER") {
+        Ok(filter) => match filter.trim() {
+            "always_on" => ExemplarFilter::AlwaysOn,
+            "always_off" => ExemplarFilter::AlwaysOff,
+            "trace_based" => ExemplarFilter::TraceBased,
+            other => {
+                global::handle_error(MetricsError::Config(format!(
+                    "Unrecognised OTEL_METRICS_EXEMPLAR_FILTER value: {}. Falling back to default: trace_based",
+                    other
+                )));
+                ExemplarFilter::TraceBased
+            }
+        },
+        Err(_) => ExemplarFilter::TraceBased,
+    }
+}
+
 impl fmt::Debug for MeterProviderBuilder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("MeterProviderBuilder")
             .field("resource", &self.resource)
             .field("readers", &self.readers)
             .field("views", &self.views.len())
+            .field("exemplar_filter", &self.exemplar_filter)
+            .field("cardinality_limit", &self.cardinality_limit)
             .finish()
     }
 }
@@ -433,4 +502,19 @@ mod tests {
         // Now the shutdown should be invoked
         assert!(reader.is_shutdown());
     }
+
+    #[test]
+    fn test_exemplar_filter_from_env() {
+        use super::{exemplar_filter_from_env, ExemplarFilter};
+
+        temp_env::with_var_unset("OTEL_METRICS_EXEMPLAR_FILTER", || {
+            assert_eq!(exemplar_filter_from_env(), ExemplarFilter::TraceBased)
+        });
+        temp_env::with_var("OTEL_METRICS_EXEMPLAR_FILTER", Some("always_off"), || {
+            assert_eq!(exemplar_filter_from_env(), ExemplarFilter::AlwaysOff)
+        });
+        temp_env::with_var("OTEL_METRICS_EXEMPLAR_FILTER", Some("unknown"), || {
+            assert_eq!(exemplar_filter_from_env(), ExemplarFilter::TraceBased)
+        });
+    }
 }
@@ -37,7 +37,9 @@
 //! [Resource]: crate::Resource
 
 pub(crate) mod aggregation;
+pub(crate) mod attribute_processor;
 pub mod data;
+pub(crate) mod exemplar;
 pub mod exporter;
 pub(crate) mod instrument;
 pub(crate) mod internal;
@@ -50,6 +52,8 @@ pub mod reader;
 pub(crate) mod view;
 
 pub use aggregation::*;
+pub use attribute_processor::*;
+pub use exemplar::*;
 pub use instrument::*;
 pub use manual_reader::*;
 pub use meter::*;
@@ -65,16 +69,44 @@ mod tests {
     use crate::metrics::data::{ResourceMetrics, Temporality};
     use crate::metrics::reader::TemporalitySelector;
     use crate::testing::metrics::InMemoryMetricsExporterBuilder;
+    use crate::AttributeSet;
     use crate::{runtime, testing::metrics::InMemoryMetricsExporter};
     use opentelemetry::metrics::{Counter, UpDownCounter};
     use opentelemetry::{
         metrics::{MeterProvider as _, Unit},
-        KeyValue,
+        KeyValue, Value,
     };
     use std::borrow::Cow;
 
     // "multi_thread" tokio flavor must be used else flush won't
     // be able to make progress!
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn sdk_disabled() {
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let meter_provider = temp_env::with_var("OTEL_SDK_DISABLED", Some("true"), || {
+            SdkMeterProvider::builder().with_reader(reader).build()
+        });
+
+        // Act
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .init();
+        counter.add(1, &[KeyValue::new("key1", "value1")]);
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        assert!(resource_metrics
+            .iter()
+            .all(|metrics| metrics.scope_metrics.is_empty()));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_aggregation() {
         // Run this test with stdout enabled to see output.
@@ -607,6 +639,79 @@ mod tests {
         assert_eq!(data_point.value, 30);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn view_attribute_processor_merges_transformed_attributes() {
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let processor = AttributeProcessor::new()
+            .deny_keys(["user_id".into()])
+            .rename_keys([("statusCode".into(), "http.response.status_code".into())])
+            .map_value("http.response.status_code", |value| match value {
+                Value::I64(code) => Value::String(format!("{}xx", code / 100).into()),
+                other => other.clone(),
+            });
+        let view = new_view(
+            Instrument::new().name("my_counter"),
+            Stream::new().attribute_processor(processor),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_view(view)
+            .build();
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .init();
+
+        // Act
+        counter.add(
+            1,
+            &[
+                KeyValue::new("statusCode", 200),
+                KeyValue::new("user_id", "a"),
+            ],
+        );
+        counter.add(
+            2,
+            &[
+                KeyValue::new("statusCode", 204),
+                KeyValue::new("user_id", "b"),
+            ],
+        );
+        counter.add(4, &[KeyValue::new("statusCode", 503)]);
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metric = &resource_metrics[0].scope_metrics[0].metrics[0];
+        let sum = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("Sum aggregation expected for Counter instruments by default");
+        assert_eq!(sum.data_points.len(), 2);
+
+        let value_of = |class: &str| {
+            let attrs = AttributeSet::from(
+                &[KeyValue::new(
+                    "http.response.status_code",
+                    class.to_string(),
+                )][..],
+            );
+            sum.data_points
+                .iter()
+                .find(|dp| dp.attributes == attrs)
+                .map(|dp| dp.value)
+        };
+        assert_eq!(value_of("2xx"), Some(3));
+        assert_eq!(value_of("5xx"), Some(4));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn no_attr_cumulative_counter() {
         let mut test_context = TestContext::new(Some(Temporality::Cumulative));
@@ -866,6 +971,307 @@ mod tests {
         assert!(resource_metrics.is_empty(), "No metrics should be exported as no new measurements were recorded since last collect.");
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_from_sampled_span() {
+        use opentelemetry::testing::trace::TestSpan;
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use opentelemetry::Context;
+
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let view = new_view(
+            Instrument::new().name("my_counter"),
+            Stream::new().allowed_attribute_keys(vec!["key1".into()]),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_view(view)
+            .build();
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .init();
+
+        // Act
+        counter.add(1, &[KeyValue::new("key1", "value1")]);
+        let span_context = SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        );
+        let cx = Context::current().with_span(TestSpan(span_context));
+        {
+            let _guard = cx.attach();
+            counter.add(
+                5,
+                &[
+                    KeyValue::new("key1", "value1"),
+                    KeyValue::new("key2", "value2"),
+                ],
+            );
+        }
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metric = &resource_metrics[0].scope_metrics[0].metrics[0];
+        let sum = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("Sum aggregation expected for Counter instruments by default");
+        assert_eq!(sum.data_points.len(), 1);
+
+        let data_point = &sum.data_points[0];
+        assert_eq!(data_point.value, 6);
+        assert_eq!(
+            data_point.exemplars.len(),
+            1,
+            "Only the measurement in a sampled span should be an exemplar"
+        );
+        let exemplar = &data_point.exemplars[0];
+        assert_eq!(exemplar.value, 5);
+        assert_eq!(exemplar.trace_id, TraceId::from_u128(1).to_bytes());
+        assert_eq!(exemplar.span_id, SpanId::from_u64(2).to_bytes());
+        assert_eq!(
+            exemplar.filtered_attributes,
+            vec![KeyValue::new("key2", "value2")]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn histogram_exemplars_always_on() {
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let view = new_view(
+            Instrument::new().name("my_histogram"),
+            Stream::new().aggregation(Aggregation::ExplicitBucketHistogram {
+                boundaries: vec![10.0, 100.0],
+                record_min_max: true,
+            }),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_view(view)
+            .with_exemplar_filter(ExemplarFilter::AlwaysOn)
+            .build();
+        let histogram = meter_provider
+            .meter("test")
+            .u64_histogram("my_histogram")
+            .init();
+
+        // Act
+        for v in [1, 2, 50, 500] {
+            histogram.record(v, &[]);
+        }
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metric = &resource_metrics[0].scope_metrics[0].metrics[0];
+        let histogram = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<u64>>()
+            .expect("Histogram aggregation expected for Histogram instruments");
+
+        let data_point = &histogram.data_points[0];
+        let values = data_point
+            .exemplars
+            .iter()
+            .map(|e| e.value)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![2, 50, 500],
+            "Expected the last exemplar of each bucket"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_cardinality_limit_from_view_reports_overflows() {
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        let view = new_view(
+            Instrument::new().name("my_counter"),
+            Stream::new().cardinality_limit(3),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_view(view)
+            .build();
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .init();
+
+        // Act
+        for i in 0..5 {
+            counter.add(1, &[KeyValue::new("key", i)]);
+        }
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let scope_metrics = &resource_metrics[0].scope_metrics;
+
+        let test_scope = find_scope_metric(scope_metrics, "test").expect("test scope expected");
+        let sum = test_scope.metrics[0]
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("Sum aggregation expected for Counter instruments by default");
+        assert_eq!(sum.data_points.len(), 3);
+        let overflow = sum
+            .data_points
+            .iter()
+            .find(|dp| {
+                dp.attributes
+                    .iter()
+                    .any(|(k, _)| k.as_str() == "otel.metric.overflow")
+            })
+            .expect("overflow data point expected");
+        assert_eq!(overflow.value, 3);
+
+        let sdk_scope =
+            find_scope_metric(scope_metrics, "opentelemetry_sdk").expect("sdk scope expected");
+        let metric = &sdk_scope.metrics[0];
+        assert_eq!(metric.name, "otel.sdk.metric.cardinality_overflows");
+        let overflows = metric
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("Sum aggregation expected for overflow count");
+        assert!(overflows.is_monotonic);
+        assert_eq!(overflows.temporality, Temporality::Cumulative);
+        assert_eq!(overflows.data_points.len(), 1);
+        assert_eq!(overflows.data_points[0].value, 3);
+        assert_eq!(
+            overflows.data_points[0].attributes,
+            AttributeSet::from(
+                &[
+                    KeyValue::new("otel.metric.name", "my_counter"),
+                    KeyValue::new("otel.scope.name", "test"),
+                ][..]
+            )
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn cardinality_overflows_use_reader_temporality() {
+        // Arrange
+        let exporter = InMemoryMetricsExporterBuilder::new()
+            .with_temporality_selector(DeltaTemporalitySelector())
+            .build();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio)
+            .with_cardinality_limit(2)
+            .build();
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+        let counter = meter_provider
+            .versioned_meter("test", Some("1.0"), None::<&str>, None)
+            .u64_counter("my_counter")
+            .init();
+
+        // Act
+        for i in 0..4 {
+            counter.add(1, &[KeyValue::new("key", i)]);
+        }
+        meter_provider.force_flush().unwrap();
+        counter.add(1, &[KeyValue::new("key", 5)]);
+        counter.add(1, &[KeyValue::new("key", 6)]);
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let overflows = resource_metrics
+            .iter()
+            .map(|rm| {
+                let sdk_scope = find_scope_metric(&rm.scope_metrics, "opentelemetry_sdk")
+                    .expect("sdk scope expected");
+                let sum = sdk_scope.metrics[0]
+                    .data
+                    .as_any()
+                    .downcast_ref::<data::Sum<u64>>()
+                    .expect("Sum aggregation expected for overflow count");
+                assert_eq!(sum.temporality, Temporality::Delta);
+                assert_eq!(
+                    sum.data_points[0].attributes,
+                    AttributeSet::from(
+                        &[
+                            KeyValue::new("otel.metric.name", "my_counter"),
+                            KeyValue::new("otel.scope.name", "test"),
+                            KeyValue::new("otel.scope.version", "1.0"),
+                        ][..]
+                    )
+                );
+                sum.data_points[0].value
+            })
+            .collect::<Vec<_>>();
+        // Delta streams start over from an empty stream after each collection.
+        assert_eq!(overflows, vec![3, 1]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn reader_cardinality_limit_overrides_provider_default() {
+        // Arrange
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio)
+            .with_cardinality_limit(4)
+            .build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_cardinality_limit(2)
+            .build();
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .init();
+
+        // Act
+        for i in 0..3 {
+            counter.add(1, &[KeyValue::new("key", i)]);
+        }
+
+        meter_provider.force_flush().unwrap();
+
+        // Assert
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let scope_metrics = &resource_metrics[0].scope_metrics;
+        let sum = scope_metrics[0].metrics[0]
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .expect("Sum aggregation expected for Counter instruments by default");
+        assert_eq!(sum.data_points.len(), 3);
+        assert!(
+            find_scope_metric(scope_metrics, "opentelemetry_sdk").is_none(),
+            "No overflow metrics expected below the limit"
+        );
+    }
+
     fn find_scope_metric<'a>(
         metrics: &'a [ScopeMetrics],
         name: &'a str,
@@ -1,7 +1,8 @@
 use std::{
     env, fmt, mem,
     sync::{Arc, Mutex, Weak},
-    time::Duration,
+    thread,
+    time::{Duration, Instant},
 };
 
 use futures_channel::{mpsc, oneshot};
@@ -13,10 +14,12 @@ use futures_util::{
 };
 use opentelemetry::{
     global,
-    metrics::{MetricsError, Result},
+    metrics::{MeterProvider, MetricsError, Result},
 };
 
+use crate::internal_metrics::{ReaderMetrics, OTHER, TIMEOUT};
 use crate::runtime::Runtime;
+use crate::util::block_on_timeout;
 use crate::{
     metrics::{
         exporter::PushMetricsExporter,
@@ -59,6 +62,8 @@ pub struct PeriodicReaderBuilder<E, RT> {
     timeout: Duration,
     exporter: E,
     producers: Vec<Box<dyn MetricProducer>>,
+    cardinality_limit: Option<usize>,
+    metrics: ReaderMetrics,
     runtime: RT,
 }
 
@@ -68,19 +73,12 @@ where
     RT: Runtime,
 {
     fn new(exporter: E, runtime: RT) -> Self {
-        let interval = env::var(METRIC_EXPORT_INTERVAL_NAME)
-            .ok()
-            .and_then(|v| v.parse().map(Duration::from_millis).ok())
-            .unwrap_or(DEFAULT_INTERVAL);
-        let timeout = env::var(METRIC_EXPORT_TIMEOUT_NAME)
-            .ok()
-            .and_then(|v| v.parse().map(Duration::from_millis).ok())
-            .unwrap_or(DEFAULT_TIMEOUT);
-
         PeriodicReaderBuilder {
-            interval,
-            timeout,
+            interval: interval_from_env(),
+            timeout: timeout_from_env(),
             producers: vec![],
+            cardinality_limit: None,
+            metrics: ReaderMetrics::default(),
             exporter,
             runtime,
         }
@@ -124,6 +122,29 @@ where
         self
     }
 
+    /// Sets the cardinality limit of the metric streams read by this reader.
+    ///
+    /// This option overrides the default limit of the meter provider, but not
+    /// the limit set on a [Stream].
+    ///
+    /// [Stream]: crate::metrics::Stream
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
+    /// Report the metrics of the reader and its exporter with a meter of
+    /// `provider`, such as the duration of its collections and exports.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.metric_reader.*` and `otel.sdk.exporter.*`.
+    /// The meter provider the reader is registered with can not be used, as it
+    /// is built after the reader.
+    pub fn with_meter_provider(mut self, provider: &impl MeterProvider) -> Self {
+        self.metrics = ReaderMetrics::new(provider);
+        self
+    }
+
     /// Create a [PeriodicReader] with the given config.
     pub fn build(self) -> PeriodicReader {
         let (message_sender, message_receiver) = mpsc::channel(256);
@@ -142,6 +163,7 @@ where
                     reader: reader.clone(),
                     timeout: self.timeout,
                     runtime,
+                    metrics: self.metrics,
                     rm: ResourceMetrics {
                         resource: Resource::empty(),
                         scope_metrics: Vec::new(),
@@ -153,6 +175,7 @@ where
 
         PeriodicReader {
             exporter: Arc::new(self.exporter),
+            cardinality_limit: self.cardinality_limit,
             inner: Arc::new(Mutex::new(PeriodicReaderInner {
                 message_sender,
                 is_shutdown: false,
@@ -163,6 +186,136 @@ where
     }
 }
 
+fn interval_from_env() -> Duration {
+    env::var(METRIC_EXPORT_INTERVAL_NAME)
+        .ok()
+        .and_then(|v| v.parse().map(Duration::from_millis).ok())
+        .unwrap_or(DEFAULT_INTERVAL)
+}
+
+fn timeout_from_env() -> Duration {
+    env::var(METRIC_EXPORT_TIMEOUT_NAME)
+        .ok()
+        .and_then(|v| v.parse().map(Duration::from_millis).ok())
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+/// Configuration options for a [ThreadedPeriodicReader].
+///
+/// The options and their defaults are the ones of [PeriodicReaderBuilder].
+#[derive(Debug)]
+pub struct ThreadedPeriodicReaderBuilder<E> {
+    interval: Duration,
+    timeout: Duration,
+    exporter: E,
+    producers: Vec<Box<dyn MetricProducer>>,
+    cardinality_limit: Option<usize>,
+    metrics: ReaderMetrics,
+}
+
+impl<E> ThreadedPeriodicReaderBuilder<E>
+where
+    E: PushMetricsExporter,
+{
+    /// Configures the intervening time between exports for a [ThreadedPeriodicReader].
+    ///
+    /// This option overrides any value set for the `OTEL_METRIC_EXPORT_INTERVAL`
+    /// environment variable.
+    ///
+    /// If this option is not used or `interval` is equal to zero, 60 seconds is
+    /// used as the default.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        if !interval.is_zero() {
+            self.interval = interval;
+        }
+        self
+    }
+
+    /// Configures the time a [ThreadedPeriodicReader] waits for an export to complete
+    /// before canceling it.
+    ///
+    /// This option overrides any value set for the `OTEL_METRIC_EXPORT_TIMEOUT`
+    /// environment variable.
+    ///
+    /// If this option is not used or `timeout` is equal to zero, 30 seconds is used
+    /// as the default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        if !timeout.is_zero() {
+            self.timeout = timeout;
+        }
+        self
+    }
+
+    /// Registers a an external [MetricProducer] with this reader.
+    ///
+    /// The producer is used as a source of aggregated metric data which is
+    /// incorporated into metrics collected from the SDK.
+    pub fn with_producer(mut self, producer: impl MetricProducer + 'static) -> Self {
+        self.producers.push(Box::new(producer));
+        self
+    }
+
+    /// Sets the cardinality limit of the metric streams read by this reader.
+    ///
+    /// This option overrides the default limit of the meter provider, but not
+    /// the limit set on a [Stream].
+    ///
+    /// [Stream]: crate::metrics::Stream
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
+    /// Report the metrics of the reader and its exporter with a meter of
+    /// `provider`, such as the duration of its collections and exports.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.metric_reader.*` and `otel.sdk.exporter.*`.
+    /// The meter provider the reader is registered with can not be used, as it
+    /// is built after the reader.
+    pub fn with_meter_provider(mut self, provider: &impl MeterProvider) -> Self {
+        self.metrics = ReaderMetrics::new(provider);
+        self
+    }
+
+    /// Create a [ThreadedPeriodicReader] with the given config, starting its
+    /// thread once it is registered with a meter provider.
+    pub fn build(self) -> ThreadedPeriodicReader {
+        let (message_sender, message_receiver) = mpsc::channel(256);
+
+        let worker = move |reader: &PeriodicReader| {
+            let worker = ThreadedPeriodicReaderWorker {
+                reader: reader.clone(),
+                timeout: self.timeout,
+                metrics: self.metrics,
+                rm: ResourceMetrics {
+                    resource: Resource::empty(),
+                    scope_metrics: Vec::new(),
+                },
+            };
+            let interval = self.interval;
+
+            if let Err(err) = thread::Builder::new()
+                .name("opentelemetry-periodic-reader".to_string())
+                .spawn(move || worker.run(message_receiver, interval))
+            {
+                global::handle_error(MetricsError::Other(err.to_string()));
+            }
+        };
+
+        ThreadedPeriodicReader(PeriodicReader {
+            exporter: Arc::new(self.exporter),
+            cardinality_limit: self.cardinality_limit,
+            inner: Arc::new(Mutex::new(PeriodicReaderInner {
+                message_sender,
+                is_shutdown: false,
+                external_producers: self.producers,
+                sdk_producer_or_worker: ProducerOrWorker::Worker(Box::new(worker)),
+            })),
+        })
+    }
+}
+
 /// A [MetricReader] that continuously collects and exports metric data at a set
 /// interval.
 ///
@@ -203,6 +356,7 @@ where
 #[derive(Clone)]
 pub struct PeriodicReader {
     exporter: Arc<dyn PushMetricsExporter>,
+    cardinality_limit: Option<usize>,
     inner: Arc<Mutex<PeriodicReaderInner>>,
 }
 
@@ -223,6 +377,56 @@ impl fmt::Debug for PeriodicReader {
     }
 }
 
+/// A [MetricReader] that continuously collects and exports metric data at a set
+/// interval from a dedicated thread, without requiring an async runtime.
+///
+/// It behaves like a [PeriodicReader], with the same defaults, but its thread
+/// blocks on the exporter's futures for at most the export timeout, so the
+/// exporter must not depend on an async runtime. As flushing and shutting down
+/// the reader wait for the thread to collect and export, they complete within
+/// the export timeout once the data is collected.
+///
+/// # Example
+///
+/// ```no_run
+/// use opentelemetry_sdk::metrics::ThreadedPeriodicReader;
+/// # fn example<E>(get_exporter: impl Fn() -> E)
+/// # where
+/// #     E: opentelemetry_sdk::metrics::exporter::PushMetricsExporter,
+/// # {
+///
+/// let exporter = get_exporter(); // set up a push exporter like OTLP
+///
+/// let reader = ThreadedPeriodicReader::builder(exporter).build();
+/// # drop(reader);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ThreadedPeriodicReader(PeriodicReader);
+
+impl ThreadedPeriodicReader {
+    /// Configuration options for a threaded periodic reader
+    pub fn builder<E>(exporter: E) -> ThreadedPeriodicReaderBuilder<E>
+    where
+        E: PushMetricsExporter,
+    {
+        ThreadedPeriodicReaderBuilder {
+            interval: interval_from_env(),
+            timeout: timeout_from_env(),
+            producers: vec![],
+            cardinality_limit: None,
+            metrics: ReaderMetrics::default(),
+            exporter,
+        }
+    }
+}
+
+impl fmt::Debug for ThreadedPeriodicReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadedPeriodicReader").finish()
+    }
+}
+
 struct PeriodicReaderInner {
     message_sender: mpsc::Sender<Message>,
     is_shutdown: bool,
@@ -246,21 +450,34 @@ struct PeriodicReaderWorker<RT: Runtime> {
     reader: PeriodicReader,
     timeout: Duration,
     runtime: RT,
+    metrics: ReaderMetrics,
     rm: ResourceMetrics,
 }
 
 impl<RT: Runtime> PeriodicReaderWorker<RT> {
     async fn collect_and_export(&mut self) -> Result<()> {
-        self.reader.collect(&mut self.rm)?;
+        collect(&self.reader, &self.metrics, &mut self.rm)?;
 
+        let start = Instant::now();
         let export = self.reader.exporter.export(&mut self.rm);
         let timeout = self.runtime.delay(self.timeout);
         pin_mut!(export);
         pin_mut!(timeout);
 
         match future::select(export, timeout).await {
-            Either::Left(_) => Ok(()),
-            Either::Right(_) => Err(MetricsError::Other("export timed out".into())),
+            Either::Left((res, _)) => {
+                let error_type = res.as_ref().err().map(|_| OTHER);
+                self.metrics
+                    .exporter()
+                    .record(0, error_type, start.elapsed());
+                res
+            }
+            Either::Right(_) => {
+                self.metrics
+                    .exporter()
+                    .record(0, Some(TIMEOUT), start.elapsed());
+                Err(MetricsError::Other("export timed out".into()))
+            }
         }
     }
 
@@ -299,6 +516,91 @@ impl<RT: Runtime> PeriodicReaderWorker<RT> {
     }
 }
 
+/// Collect the metrics of `reader` in `rm`, recording the collection in
+/// `metrics`.
+fn collect(
+    reader: &PeriodicReader,
+    metrics: &ReaderMetrics,
+    rm: &mut ResourceMetrics,
+) -> Result<()> {
+    let start = Instant::now();
+    let res = reader.collect(rm);
+    metrics.record_collection(res.as_ref().err().map(|_| OTHER), start.elapsed());
+    res
+}
+
+struct ThreadedPeriodicReaderWorker {
+    reader: PeriodicReader,
+    timeout: Duration,
+    metrics: ReaderMetrics,
+    rm: ResourceMetrics,
+}
+
+impl ThreadedPeriodicReaderWorker {
+    fn collect_and_export(&mut self) -> Result<()> {
+        collect(&self.reader, &self.metrics, &mut self.rm)?;
+
+        let start = Instant::now();
+        let (error_type, res) =
+            match block_on_timeout(self.reader.exporter.export(&mut self.rm), self.timeout) {
+                Some(Ok(())) => (None, Ok(())),
+                Some(Err(err)) => (Some(OTHER), Err(err)),
+                None => (
+                    Some(TIMEOUT),
+                    Err(MetricsError::Other("export timed out".into())),
+                ),
+            };
+        self.metrics
+            .exporter()
+            .record(0, error_type, start.elapsed());
+        res
+    }
+
+    fn process_message(&mut self, message: Message) -> bool {
+        match message {
+            Message::Export => {
+                if let Err(err) = self.collect_and_export() {
+                    global::handle_error(err)
+                }
+            }
+            Message::Flush(ch) => {
+                let res = self.collect_and_export();
+                if ch.send(res).is_err() {
+                    global::handle_error(MetricsError::Other("flush channel closed".into()))
+                }
+            }
+            Message::Shutdown(ch) => {
+                let res = self.collect_and_export();
+                let _ = self.reader.exporter.shutdown();
+                if ch.send(res).is_err() {
+                    global::handle_error(MetricsError::Other("shutdown channel closed".into()))
+                }
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn run(mut self, mut messages: mpsc::Receiver<Message>, interval: Duration) {
+        let mut next_export = Instant::now() + interval;
+        loop {
+            let timeout = next_export.saturating_duration_since(Instant::now());
+            let message = match block_on_timeout(messages.next(), timeout) {
+                Some(Some(message)) => message,
+                Some(None) => break,
+                None => {
+                    next_export = Instant::now() + interval;
+                    Message::Export
+                }
+            };
+            if !self.process_message(message) {
+                break;
+            }
+        }
+    }
+}
+
 impl AggregationSelector for PeriodicReader {
     fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
         self.exporter.aggregation(kind)
@@ -403,16 +705,64 @@ impl MetricReader for PeriodicReader {
 
         shutdown_result
     }
+
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        self.cardinality_limit
+    }
+}
+
+impl AggregationSelector for ThreadedPeriodicReader {
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.0.aggregation(kind)
+    }
+}
+
+impl TemporalitySelector for ThreadedPeriodicReader {
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.0.temporality(kind)
+    }
+}
+
+impl MetricReader for ThreadedPeriodicReader {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.0.register_pipeline(pipeline)
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> Result<()> {
+        self.0.collect(rm)
+    }
+
+    fn force_flush(&self) -> Result<()> {
+        self.0.force_flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.0.shutdown()
+    }
+
+    fn cardinality_limit(&self, kind: InstrumentKind) -> Option<usize> {
+        self.0.cardinality_limit(kind)
+    }
 }
 
 #[cfg(all(test, feature = "testing"))]
 mod tests {
-    use super::PeriodicReader;
+    use super::{PeriodicReader, ThreadedPeriodicReader};
+    use crate::metrics::{
+        data::Temporality,
+        exporter::PushMetricsExporter,
+        reader::{
+            AggregationSelector, DefaultAggregationSelector, DefaultTemporalitySelector,
+            TemporalitySelector,
+        },
+        Aggregation, InstrumentKind,
+    };
     use crate::{
         metrics::data::ResourceMetrics, metrics::reader::MetricReader, metrics::SdkMeterProvider,
         runtime, testing::metrics::InMemoryMetricsExporter, Resource,
     };
-    use opentelemetry::metrics::MeterProvider;
+    use async_trait::async_trait;
+    use opentelemetry::metrics::{MeterProvider, MetricsError, Result};
     use std::sync::mpsc;
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -457,4 +807,78 @@ mod tests {
         // Assert
         result.expect_err("error expected when reader is not registered");
     }
+
+    #[test]
+    fn threaded_reader_collects_and_exports() {
+        // Arrange
+        let interval = std::time::Duration::from_millis(1);
+        let exporter = InMemoryMetricsExporter::default();
+        let reader = ThreadedPeriodicReader::builder(exporter.clone())
+            .with_interval(interval)
+            .build();
+        let (sender, receiver) = mpsc::channel();
+
+        // Act
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+        let meter = meter_provider.meter("test");
+        let counter = meter.u64_observable_counter("testcounter").init();
+        meter
+            .register_callback(&[counter.as_any()], move |_| {
+                let _ = sender.send(());
+            })
+            .expect("callback registration should succeed");
+
+        // Assert
+        receiver
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("message should be available in channel, indicating a collection occurred");
+        meter_provider.force_flush().expect("flush should succeed");
+        assert!(!exporter.get_finished_metrics().unwrap().is_empty());
+        meter_provider.shutdown().expect("shutdown should succeed");
+    }
+
+    #[derive(Debug)]
+    struct FailingExporter;
+
+    impl AggregationSelector for FailingExporter {
+        fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+            DefaultAggregationSelector::new().aggregation(kind)
+        }
+    }
+
+    impl TemporalitySelector for FailingExporter {
+        fn temporality(&self, kind: InstrumentKind) -> Temporality {
+            DefaultTemporalitySelector::new().temporality(kind)
+        }
+    }
+
+    #[async_trait]
+    impl PushMetricsExporter for FailingExporter {
+        async fn export(&self, _metrics: &mut ResourceMetrics) -> Result<()> {
+            Err(MetricsError::Other("export failed".into()))
+        }
+
+        async fn force_flush(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn flush_reports_failed_exports() {
+        let reader = PeriodicReader::builder(FailingExporter, runtime::Tokio).build();
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+        meter_provider
+            .force_flush()
+            .expect_err("flush should report the failed export");
+
+        let reader = ThreadedPeriodicReader::builder(FailingExporter).build();
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+        meter_provider
+            .force_flush()
+            .expect_err("flush should report the failed export");
+    }
 }
@@ -3,6 +3,7 @@ use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
+    time::SystemTime,
 };
 
 use opentelemetry::{
@@ -12,13 +13,16 @@ use opentelemetry::{
 };
 
 use crate::{
+    attributes::AttributeSet,
     instrumentation::Scope,
     metrics::{
         aggregation,
-        data::{Metric, ResourceMetrics, ScopeMetrics},
+        data::{self, DataPoint, Metric, ResourceMetrics, ScopeMetrics, Temporality},
+        exemplar::ExemplarFilter,
         instrument::{Instrument, InstrumentId, InstrumentKind, Stream},
         internal,
         internal::AggregateBuilder,
+        internal::CardinalityLimiter,
         internal::Number,
         reader::{AggregationSelector, DefaultAggregationSelector, MetricReader, SdkProducer},
         view::View,
@@ -26,6 +30,10 @@ use crate::{
     Resource,
 };
 
+/// The name of the metric reporting measurements aggregated into the overflow
+/// data point of a metric stream.
+const CARDINALITY_OVERFLOWS_METRIC: &str = "otel.sdk.metric.cardinality_overflows";
+
 /// Connects all of the instruments created by a meter provider to a [MetricReader].
 ///
 /// This is the object that will be registered when a meter provider is
@@ -39,6 +47,9 @@ pub struct Pipeline {
     pub(crate) resource: Resource,
     reader: Box<dyn MetricReader>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: ExemplarFilter,
+    cardinality_limit: usize,
+    start: SystemTime,
     inner: Box<Mutex<PipelineInner>>,
 }
 
@@ -56,6 +67,8 @@ struct PipelineInner {
     aggregations: HashMap<Scope, Vec<InstrumentSync>>,
     callbacks: Vec<GenericCallback>,
     multi_callbacks: Vec<Option<GenericCallback>>,
+    /// The time the cardinality overflows were last collected.
+    overflows_collected: Option<SystemTime>,
 }
 
 impl fmt::Debug for PipelineInner {
@@ -118,7 +131,7 @@ impl Pipeline {
 impl SdkProducer for Pipeline {
     /// Returns aggregated metrics from a single collection.
     fn produce(&self, rm: &mut ResourceMetrics) -> Result<()> {
-        let inner = self.inner.lock()?;
+        let mut inner = self.inner.lock()?;
         for cb in &inner.callbacks {
             // TODO consider parallel callbacks.
             cb();
@@ -182,18 +195,93 @@ impl SdkProducer for Pipeline {
             }
         }
 
+        if let Some(overflows) = self.cardinality_overflows(&mut inner) {
+            match rm.scope_metrics.get_mut(i) {
+                Some(sm) => *sm = overflows,
+                None => rm.scope_metrics.push(overflows),
+            }
+            i += 1;
+        }
+
         rm.scope_metrics.truncate(i);
 
         Ok(())
     }
 }
 
+impl Pipeline {
+    /// Returns the SDK metrics reporting the number of measurements aggregated
+    /// into overflow data points, if any stream has overflowed.
+    ///
+    /// The counts are reported with the reader's temporality for counters, and
+    /// attributed to the name and scope of each stream.
+    fn cardinality_overflows(&self, inner: &mut PipelineInner) -> Option<ScopeMetrics> {
+        let now = SystemTime::now();
+        let temporality = self.reader.temporality(InstrumentKind::Counter);
+        let last_collected = inner.overflows_collected.replace(now);
+        let start_time = match temporality {
+            Temporality::Delta => last_collected.unwrap_or(self.start),
+            _ => self.start,
+        };
+
+        let data_points = inner
+            .aggregations
+            .iter()
+            .flat_map(|(scope, instruments)| instruments.iter().map(move |inst| (scope, inst)))
+            .filter_map(|(scope, inst)| {
+                let overflows = match temporality {
+                    Temporality::Delta => inst.limiter.delta_overflows(),
+                    _ => inst.limiter.overflows(),
+                };
+                if overflows == 0 {
+                    return None;
+                }
+
+                let mut attributes = vec![
+                    KeyValue::new("otel.metric.name", inst.name.clone()),
+                    KeyValue::new("otel.scope.name", scope.name.clone()),
+                ];
+                if let Some(version) = &scope.version {
+                    attributes.push(KeyValue::new("otel.scope.version", version.clone()));
+                }
+                Some(DataPoint {
+                    attributes: AttributeSet::from(&attributes[..]),
+                    start_time: Some(start_time),
+                    time: Some(now),
+                    value: overflows,
+                    exemplars: vec![],
+                })
+            })
+            .collect::<Vec<_>>();
+        if data_points.is_empty() {
+            return None;
+        }
+
+        Some(ScopeMetrics {
+            scope: Scope::builder("opentelemetry_sdk")
+                .with_version(env!("CARGO_PKG_VERSION"))
+                .build(),
+            metrics: vec![Metric {
+                name: CARDINALITY_OVERFLOWS_METRIC.into(),
+                description: "The number of measurements aggregated into the overflow data point of a metric stream.".into(),
+                unit: Unit::new("{measurement}"),
+                data: Box::new(data::Sum {
+                    data_points,
+                    temporality,
+                    is_monotonic: true,
+                }),
+            }],
+        })
+    }
+}
+
 /// A synchronization point between a [Pipeline] and an instrument's aggregate function.
 struct InstrumentSync {
     name: Cow<'static, str>,
     description: Cow<'static, str>,
     unit: Unit,
     comp_agg: Box<dyn internal::ComputeAggregation>,
+    limiter: Arc<CardinalityLimiter>,
 }
 
 impl fmt::Debug for InstrumentSync {
@@ -315,6 +403,9 @@ where
             unit: inst.unit,
             aggregation: None,
             allowed_attribute_keys: None,
+            attribute_processor: None,
+            exemplar_reservoir: None,
+            cardinality_limit: None,
         };
 
         match self.cached_aggregator(&inst.scope, kind, stream) {
@@ -384,12 +475,29 @@ where
         let mut cache = self.aggregators.lock()?;
 
         let cached = cache.entry(id).or_insert_with(|| {
-            let filter = stream
-                .allowed_attribute_keys
-                .clone()
-                .map(|allowed| Arc::new(move |kv: &KeyValue| allowed.contains(&kv.key)) as Arc<_>);
+            let allowed = stream.allowed_attribute_keys.clone();
+            let processor = stream.attribute_processor.take();
+            let filter = match (allowed, processor.clone()) {
+                (None, None) => None,
+                (allowed, processor) => Some(Arc::new(move |kv: &KeyValue| {
+                    allowed.as_ref().map_or(true, |keys| keys.contains(&kv.key))
+                        && processor.as_ref().map_or(true, |p| !p.is_denied(&kv.key))
+                }) as Arc<_>),
+            };
 
-            let b = AggregateBuilder::new(Some(self.pipeline.reader.temporality(kind)), filter);
+            let b = AggregateBuilder::new(Some(self.pipeline.reader.temporality(kind)), filter)
+                .with_attribute_processor(processor.filter(|p| !p.only_denies()))
+                .with_exemplars(
+                    self.pipeline.exemplar_filter,
+                    stream.exemplar_reservoir.take(),
+                )
+                .with_cardinality_limit(
+                    stream
+                        .cardinality_limit
+                        .or_else(|| self.pipeline.reader.cardinality_limit(kind))
+                        .unwrap_or(self.pipeline.cardinality_limit),
+                );
+            let limiter = b.cardinality_limiter();
             let (m, ca) = match aggregate_fn(b, &agg, kind) {
                 Ok(Some((m, ca))) => (m, ca),
                 other => return other.map(|fs| fs.map(|(m, _)| m)), // Drop aggregator or error
@@ -402,6 +510,7 @@ where
                     description: stream.description,
                     unit: stream.unit,
                     comp_agg: ca,
+                    limiter,
                 },
             );
 
@@ -594,13 +703,19 @@ impl Pipelines {
         res: Resource,
         readers: Vec<Box<dyn MetricReader>>,
         views: Vec<Arc<dyn View>>,
+        exemplar_filter: ExemplarFilter,
+        cardinality_limit: usize,
     ) -> Self {
+        let start = SystemTime::now();
         let mut pipes = Vec::with_capacity(readers.len());
         for r in readers {
             let p = Arc::new(Pipeline {
                 resource: res.clone(),
                 reader: r,
                 views: views.clone(),
+                exemplar_filter,
+                cardinality_limit,
+                start,
                 inner: Default::default(),
             });
             p.reader.register_pipeline(Arc::downgrade(&p));
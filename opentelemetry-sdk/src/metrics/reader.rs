@@ -54,6 +54,17 @@ pub trait MetricReader:
     /// After `shutdown` is called, calls to `collect` will perform no operation and
     /// instead will return an error indicating the shutdown state.
     fn shutdown(&self) -> Result<()>;
+
+    /// The cardinality limit of the metric streams of instruments of the given
+    /// kind read by this reader.
+    ///
+    /// Returns `None` to use the default limit of the meter provider. The limit
+    /// set on a [Stream] takes precedence over this one.
+    ///
+    /// [Stream]: crate::metrics::Stream
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        None
+    }
 }
 
 /// Produces metrics for a [MetricReader].
@@ -1,9 +1,17 @@
-use super::instrument::{Instrument, Stream};
+#[cfg(feature = "metrics_patterns")]
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use super::instrument::{Instrument, InstrumentKind, Stream};
 use glob::Pattern;
 use opentelemetry::{
     global,
     metrics::{MetricsError, Result},
 };
+#[cfg(feature = "metrics_patterns")]
+use regex::Regex;
+#[cfg(feature = "metrics_patterns")]
+use semver::{Version, VersionReq};
 
 fn empty_view(_inst: &Instrument) -> Option<Stream> {
     None
@@ -68,6 +76,188 @@ impl View for Box<dyn View> {
     }
 }
 
+/// The placeholder replaced by the instrument name in a [Stream] mask name.
+const NAME_TEMPLATE: &str = "{name}";
+
+/// Selects the instruments a view created with [new_view] applies to.
+///
+/// The instruments must match all the non-empty fields of the criteria
+/// [instrument](ViewCriteria::instrument), along with the criteria that do not
+/// describe a single instrument. The `name_regex` and `scope_version_req`
+/// criteria require the `metrics_patterns` feature.
+///
+/// # Example
+///
+/// ```
+/// use opentelemetry_sdk::metrics::{new_view, Instrument, InstrumentKind, Stream, ViewCriteria};
+///
+/// let criteria = ViewCriteria::new()
+///     .instrument(Instrument::new().name("http.*"))
+///     .kinds([InstrumentKind::Histogram]);
+/// let mask = Stream::new().name("legacy.{name}");
+///
+/// let view = new_view(criteria, mask);
+/// # drop(view);
+/// ```
+#[derive(Clone, Default, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ViewCriteria {
+    /// The instrument whose non-empty fields the instruments must match.
+    pub instrument: Instrument,
+    /// The functional groups one of which the instrument must belong to.
+    pub kinds: Option<HashSet<InstrumentKind>>,
+    /// A regular expression the whole instrument name must match.
+    #[cfg(feature = "metrics_patterns")]
+    pub name_regex: Option<Cow<'static, str>>,
+    /// A semantic version requirement, such as `>=0.20, <0.23`, the version of
+    /// the instrumentation scope must satisfy.
+    #[cfg(feature = "metrics_patterns")]
+    pub scope_version_req: Option<Cow<'static, str>>,
+}
+
+impl ViewCriteria {
+    /// Create new criteria matching no instrument.
+    pub fn new() -> Self {
+        ViewCriteria::default()
+    }
+
+    /// Set the instrument whose non-empty fields the instruments must match.
+    pub fn instrument(mut self, instrument: Instrument) -> Self {
+        self.instrument = instrument;
+        self
+    }
+
+    /// Set the instrument kinds to match.
+    pub fn kinds(mut self, kinds: impl IntoIterator<Item = InstrumentKind>) -> Self {
+        self.kinds = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// Set the regular expression the whole instrument name must match.
+    #[cfg(feature = "metrics_patterns")]
+    pub fn name_regex(mut self, pattern: impl Into<Cow<'static, str>>) -> Self {
+        self.name_regex = Some(pattern.into());
+        self
+    }
+
+    /// Set the semantic version requirement the scope version must satisfy.
+    ///
+    /// Scope versions with a missing minor or patch number are compared as if
+    /// it was `0`, and scopes without a valid version never match.
+    ///
+    /// # Example
+    ///
+    /// Re-namespacing the instruments of a range of library versions:
+    ///
+    /// ```
+    /// use opentelemetry_sdk::{
+    ///     metrics::{new_view, Instrument, Stream, ViewCriteria},
+    ///     Scope,
+    /// };
+    ///
+    /// let criteria = ViewCriteria::new()
+    ///     .instrument(Instrument::new().scope(Scope::builder("my_library*").build()))
+    ///     .scope_version_req("<2.0");
+    /// let mask = Stream::new().name("legacy.{name}");
+    ///
+    /// let view = new_view(criteria, mask);
+    /// # drop(view);
+    /// ```
+    #[cfg(feature = "metrics_patterns")]
+    pub fn scope_version_req(mut self, req: impl Into<Cow<'static, str>>) -> Self {
+        self.scope_version_req = Some(req.into());
+        self
+    }
+
+    /// Whether all the criteria are their default value.
+    fn is_empty(&self) -> bool {
+        #[cfg(feature = "metrics_patterns")]
+        if self.name_regex.is_some() || self.scope_version_req.is_some() {
+            return false;
+        }
+        self.instrument.is_empty() && self.kinds.is_none()
+    }
+
+    /// Whether the criteria can match instruments of different names.
+    fn matches_many_names(&self) -> bool {
+        #[cfg(feature = "metrics_patterns")]
+        if self.name_regex.is_some() {
+            return true;
+        }
+        contains_wildcard(&self.instrument.name)
+    }
+
+    fn matches_kinds(&self, other: &Instrument) -> bool {
+        self.kinds.as_ref().map_or(true, |kinds| {
+            other.kind.map_or(false, |kind| kinds.contains(&kind))
+        })
+    }
+}
+
+impl From<Instrument> for ViewCriteria {
+    fn from(instrument: Instrument) -> Self {
+        ViewCriteria::new().instrument(instrument)
+    }
+}
+
+/// The compiled regular expression and version requirement of [ViewCriteria].
+#[cfg(feature = "metrics_patterns")]
+struct Patterns {
+    name_regex: Option<Regex>,
+    scope_version_req: Option<VersionReq>,
+}
+
+#[cfg(feature = "metrics_patterns")]
+impl Patterns {
+    fn new(criteria: &ViewCriteria) -> Result<Self> {
+        let name_regex = match &criteria.name_regex {
+            Some(pattern) => Some(
+                Regex::new(&format!("^(?:{pattern})$"))
+                    .map_err(|e| MetricsError::Config(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let scope_version_req = match &criteria.scope_version_req {
+            Some(req) => {
+                Some(VersionReq::parse(req).map_err(|e| MetricsError::Config(e.to_string()))?)
+            }
+            None => None,
+        };
+        Ok(Patterns {
+            name_regex,
+            scope_version_req,
+        })
+    }
+
+    fn matches(&self, i: &Instrument) -> bool {
+        self.name_regex
+            .as_ref()
+            .map_or(true, |r| r.is_match(&i.name))
+            && self.scope_version_req.as_ref().map_or(true, |req| {
+                i.scope
+                    .version
+                    .as_deref()
+                    .and_then(parse_version)
+                    .map_or(false, |version| req.matches(&version))
+            })
+    }
+}
+
+/// Without the `metrics_patterns` feature, criteria have no patterns.
+#[cfg(not(feature = "metrics_patterns"))]
+struct Patterns;
+
+#[cfg(not(feature = "metrics_patterns"))]
+impl Patterns {
+    fn new(_criteria: &ViewCriteria) -> Result<Self> {
+        Ok(Patterns)
+    }
+
+    fn matches(&self, _i: &Instrument) -> bool {
+        true
+    }
+}
+
 /// Creates a [View] that applies the [Stream] mask for all instruments that
 /// match criteria.
 ///
@@ -77,10 +267,13 @@ impl View for Box<dyn View> {
 /// instruments is returned. If you need to match an empty-value field, create a
 /// [View] directly.
 ///
-/// The [Instrument::name] field of criteria supports wildcard pattern matching.
-/// The wildcard `*` is recognized as matching zero or more characters, and `?`
-/// is recognized as matching exactly one character. For example, a pattern of
-/// `*` will match all instrument names.
+/// The [Instrument::name] and the scope name of criteria support wildcard
+/// pattern matching. The wildcard `*` is recognized as matching zero or more
+/// characters, and `?` is recognized as matching exactly one character. For
+/// example, a pattern of `*` will match all instrument names.
+///
+/// Criteria can either be an [Instrument], or [ViewCriteria] to also select
+/// instruments by kinds, regular expression or scope version requirement.
 ///
 /// The [Stream] mask only applies updates for non-empty fields. By default, the
 /// [Instrument] the [View] matches against will be use for the name,
@@ -89,6 +282,11 @@ impl View for Box<dyn View> {
 /// instead of the default. If you need to set a an empty value in the returned
 /// stream, create a custom [View] directly.
 ///
+/// The `{name}` placeholder in the mask name is replaced by the name of the
+/// matched instrument. When criteria match instruments by wildcard or regular
+/// expression, the mask name must contain this placeholder, as renaming them
+/// all to the same name would produce conflicting streams.
+///
 /// # Example
 ///
 /// ```
@@ -100,37 +298,43 @@ impl View for Box<dyn View> {
 /// let view = new_view(criteria, mask);
 /// # drop(view);
 /// ```
-pub fn new_view(criteria: Instrument, mask: Stream) -> Result<Box<dyn View>> {
+pub fn new_view(criteria: impl Into<ViewCriteria>, mask: Stream) -> Result<Box<dyn View>> {
+    let criteria = criteria.into();
     if criteria.is_empty() {
         global::handle_error(MetricsError::Config(format!(
             "no criteria provided, dropping view. mask: {mask:?}"
         )));
         return Ok(Box::new(empty_view));
     }
-    let contains_wildcard = criteria.name.contains(|c| c == '*' || c == '?');
     let err_msg_criteria = criteria.clone();
 
-    let match_fn: Box<dyn Fn(&Instrument) -> bool + Send + Sync> = if contains_wildcard {
-        if mask.name != "" {
-            global::handle_error(MetricsError::Config(format!(
-				"name replacement for multiple instruments, dropping view, criteria: {criteria:?}, mask: {mask:?}"
-			)));
-            return Ok(Box::new(empty_view));
-        }
+    if criteria.matches_many_names() && mask.name != "" && !mask.name.contains(NAME_TEMPLATE) {
+        global::handle_error(MetricsError::Config(format!(
+            "name replacement for multiple instruments, dropping view, criteria: {criteria:?}, mask: {mask:?}"
+        )));
+        return Ok(Box::new(empty_view));
+    }
 
-        let pattern = criteria.name.clone();
-        let glob_pattern =
-            Pattern::new(&pattern).map_err(|e| MetricsError::Config(e.to_string()))?;
+    let name_glob = glob_pattern(&criteria.instrument.name)?;
+    let scope_name_glob = glob_pattern(&criteria.instrument.scope.name)?;
+    let patterns = Patterns::new(&criteria)?;
 
-        Box::new(move |i| {
-            glob_pattern.matches(&i.name)
-                && criteria.matches_description(i)
-                && criteria.matches_kind(i)
-                && criteria.matches_unit(i)
-                && criteria.matches_scope(i)
-        })
-    } else {
-        Box::new(move |i| criteria.matches(i))
+    let match_fn = move |i: &Instrument| -> bool {
+        let instrument = &criteria.instrument;
+        name_glob
+            .as_ref()
+            .map_or_else(|| instrument.matches_name(i), |p| p.matches(&i.name))
+            && instrument.matches_description(i)
+            && instrument.matches_kind(i)
+            && criteria.matches_kinds(i)
+            && instrument.matches_unit(i)
+            && scope_name_glob.as_ref().map_or_else(
+                || instrument.matches_scope_name(i),
+                |p| p.matches(&i.scope.name),
+            )
+            && instrument.matches_scope_version(i)
+            && instrument.matches_scope_schema_url(i)
+            && patterns.matches(i)
     };
 
     let mut agg = None;
@@ -150,7 +354,9 @@ pub fn new_view(criteria: Instrument, mask: Stream) -> Result<Box<dyn View>> {
     Ok(Box::new(move |i: &Instrument| -> Option<Stream> {
         if match_fn(i) {
             Some(Stream {
-                name: if !mask.name.is_empty() {
+                name: if mask.name.contains(NAME_TEMPLATE) {
+                    mask.name.replace(NAME_TEMPLATE, &i.name).into()
+                } else if !mask.name.is_empty() {
                     mask.name.clone()
                 } else {
                     i.name.clone()
@@ -167,6 +373,9 @@ pub fn new_view(criteria: Instrument, mask: Stream) -> Result<Box<dyn View>> {
                 },
                 aggregation: agg.clone(),
                 allowed_attribute_keys: mask.allowed_attribute_keys.clone(),
+                attribute_processor: mask.attribute_processor.clone(),
+                exemplar_reservoir: mask.exemplar_reservoir.clone(),
+                cardinality_limit: mask.cardinality_limit,
             })
         } else {
             None
@@ -174,9 +383,42 @@ pub fn new_view(criteria: Instrument, mask: Stream) -> Result<Box<dyn View>> {
     }))
 }
 
+fn contains_wildcard(pattern: &str) -> bool {
+    pattern.contains(|c| c == '*' || c == '?')
+}
+
+/// The glob pattern of a criteria field, if it contains wildcards.
+fn glob_pattern(pattern: &str) -> Result<Option<Pattern>> {
+    if !contains_wildcard(pattern) {
+        return Ok(None);
+    }
+    Pattern::new(pattern)
+        .map(Some)
+        .map_err(|e| MetricsError::Config(e.to_string()))
+}
+
+/// Parses a scope version, completing versions such as `1` or `1.2` with zeros.
+#[cfg(feature = "metrics_patterns")]
+fn parse_version(version: &str) -> Option<Version> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let core_len = version.find(['-', '+']).unwrap_or(version.len());
+    let padding = match version[..core_len].split('.').count() {
+        1 => ".0.0",
+        2 => ".0",
+        _ => "",
+    };
+    let mut padded = String::with_capacity(version.len() + padding.len());
+    padded.push_str(&version[..core_len]);
+    padded.push_str(padding);
+    padded.push_str(&version[core_len..]);
+    Version::parse(&padded).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "metrics_patterns")]
+    use crate::Scope;
     #[test]
     fn test_new_view_matching_all() {
         let criteria = Instrument::new().name("*");
@@ -252,4 +494,107 @@ mod tests {
             "Expected not to match instrument with test_? pattern"
         );
     }
+
+    #[cfg(feature = "metrics_patterns")]
+    #[test]
+    fn test_new_view_name_regex() {
+        let criteria = ViewCriteria::new().name_regex(r"http\.(client|server)\.duration");
+        let mask = Stream::new();
+
+        let view = new_view(criteria, mask).expect("Expected to create a new view");
+
+        assert!(view
+            .match_inst(&Instrument::new().name("http.server.duration"))
+            .is_some());
+        assert!(
+            view.match_inst(&Instrument::new().name("http.server.duration.total"))
+                .is_none(),
+            "Expected the regex to match the whole name"
+        );
+    }
+
+    #[cfg(feature = "metrics_patterns")]
+    #[test]
+    fn test_new_view_invalid_name_regex() {
+        let criteria = ViewCriteria::new().name_regex("(unclosed");
+
+        assert!(new_view(criteria, Stream::new()).is_err());
+    }
+
+    #[test]
+    fn test_new_view_kinds() {
+        let criteria =
+            ViewCriteria::new().kinds([InstrumentKind::Counter, InstrumentKind::Histogram]);
+        let view = new_view(criteria, Stream::new()).expect("Expected to create a new view");
+
+        let mut counter = Instrument::new().name("counter");
+        counter.kind = Some(InstrumentKind::Counter);
+        assert!(view.match_inst(&counter).is_some());
+
+        let mut gauge = Instrument::new().name("gauge");
+        gauge.kind = Some(InstrumentKind::Gauge);
+        assert!(view.match_inst(&gauge).is_none());
+    }
+
+    #[cfg(feature = "metrics_patterns")]
+    #[test]
+    fn test_new_view_scope_name_glob_and_version_req() {
+        let criteria = ViewCriteria::new()
+            .instrument(Instrument::new().scope(Scope::builder("my_library*").build()))
+            .scope_version_req(">=0.20, <1");
+        let view = new_view(criteria, Stream::new()).expect("Expected to create a new view");
+
+        let instrument = |scope: &'static str, version: &'static str| {
+            Instrument::new()
+                .name("counter")
+                .scope(Scope::builder(scope).with_version(version).build())
+        };
+        assert!(view
+            .match_inst(&instrument("my_library", "0.22.1"))
+            .is_some());
+        assert!(view
+            .match_inst(&instrument("my_library_http", "v0.21"))
+            .is_some());
+        assert!(view
+            .match_inst(&instrument("my_library", "1.0.0"))
+            .is_none());
+        assert!(view
+            .match_inst(&instrument("other_library", "0.22.1"))
+            .is_none());
+        assert!(
+            view.match_inst(
+                &Instrument::new()
+                    .name("counter")
+                    .scope(Scope::builder("my_library").build())
+            )
+            .is_none(),
+            "Expected scopes without a version not to match"
+        );
+    }
+
+    #[test]
+    fn test_new_view_templated_rename() {
+        let criteria = Instrument::new().name("http.*");
+        let mask = Stream::new().name("legacy.{name}");
+
+        let view = new_view(criteria, mask).expect("Expected to create a new view");
+
+        let stream = view
+            .match_inst(&Instrument::new().name("http.server.duration"))
+            .expect("Expected to match instrument with matching prefix");
+        assert_eq!(stream.name, "legacy.http.server.duration");
+    }
+
+    #[cfg(feature = "metrics_patterns")]
+    #[test]
+    fn test_new_view_rename_multiple_instruments_requires_template() {
+        let criteria = ViewCriteria::new().name_regex("http.*");
+        let mask = Stream::new().name("renamed");
+
+        let view = new_view(criteria, mask).expect("Expected to create a new view");
+
+        assert!(view
+            .match_inst(&Instrument::new().name("http.server.duration"))
+            .is_none());
+    }
 }
@@ -0,0 +1,1040 @@
+//! # Persistent Queue
+//!
+//! A disk-backed queue of export batches, keeping the batches of the
+//! [`BatchSpanProcessor`] and [`BatchLogProcessor`] across exporter outages and
+//! process restarts.
+//!
+//! Batches are encoded with a [`BatchCodec`], for example the OTLP codecs of
+//! the `opentelemetry-proto` crate, and appended to segment files in the queue
+//! directory. A batch is removed from the queue once exported. Batches still in
+//! the queue when the process stops are exported after it restarts.
+//!
+//! Each record of a segment file is made of the length of the batch and its
+//! CRC32 checksum, as little endian 32 bits integers, followed by the encoded
+//! batch. When a segment is corrupted, for example after a crash in the middle
+//! of a write, its records starting from the first invalid one are dropped.
+//! The position of the next batch to export is kept in a `cursor` file, so
+//! batches may be exported twice if the process stops before it is updated.
+//!
+//! The batch processors hand their queue to a dedicated thread, so that
+//! reading, writing and synchronizing its files never blocks their runtime.
+//!
+//! [`BatchSpanProcessor`]: crate::trace::BatchSpanProcessor
+//! [`BatchLogProcessor`]: crate::logs::BatchLogProcessor
+#[cfg(any(feature = "trace", feature = "logs"))]
+use std::{collections::BTreeSet, future::Future, sync::mpsc, thread};
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+use futures_channel::oneshot;
+#[cfg(any(feature = "trace", feature = "logs"))]
+use futures_util::FutureExt as _;
+use opentelemetry::global;
+use thiserror::Error;
+
+/// The extension of segment files.
+const SEGMENT_EXTENSION: &str = "seg";
+/// The name of the file keeping the position of the next batch to export.
+const CURSOR_FILE: &str = "cursor";
+/// The length and checksum preceding each batch.
+const RECORD_HEADER_SIZE: u64 = 8;
+
+/// Default maximum size of a segment file, 8 MiB.
+const DEFAULT_MAX_SEGMENT_SIZE: u64 = 8 * 1024 * 1024;
+/// Default maximum size of the queue, 256 MiB.
+const DEFAULT_MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Errors raised by the persistent queue.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum PersistenceError {
+    /// Reading or writing the queue files failed.
+    #[error("persistent queue I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// A batch could not be encoded or decoded.
+    #[error("failed to encode or decode a batch: {0}")]
+    Codec(String),
+}
+
+/// Encodes batches of spans or log records to the bytes stored in a
+/// [`PersistentQueue`], and decodes them back.
+pub trait BatchCodec<T>: fmt::Debug + Send + Sync {
+    /// Encodes a batch.
+    fn encode(&self, batch: &[T]) -> Result<Vec<u8>, PersistenceError>;
+
+    /// Decodes a batch previously encoded with [`BatchCodec::encode`].
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<T>, PersistenceError>;
+}
+
+/// When the queue files are synchronized to the disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// After every batch added or removed, guaranteeing no batch is lost on
+    /// power loss, at the cost of throughput.
+    Always,
+    /// At most once per interval when batches are added, batches added since
+    /// the last synchronization may be lost on power loss.
+    Interval(Duration),
+    /// Never, leaving it to the operating system.
+    #[default]
+    Never,
+}
+
+/// Configures and opens a [`PersistentQueue`].
+#[derive(Debug)]
+pub struct PersistentQueueBuilder {
+    dir: PathBuf,
+    max_segment_size: u64,
+    max_size: u64,
+    sync_policy: SyncPolicy,
+}
+
+impl PersistentQueueBuilder {
+    /// Set the maximum size of a segment file in bytes, 8 MiB by default.
+    ///
+    /// A new segment file is started when adding a batch to the current one
+    /// would make it bigger, and segment files are deleted once all their
+    /// batches are exported.
+    pub fn with_max_segment_size(mut self, max_segment_size: u64) -> Self {
+        self.max_segment_size = max_segment_size;
+        self
+    }
+
+    /// Set the maximum size of the queue in bytes, 256 MiB by default.
+    ///
+    /// When the queue grows bigger, its oldest segment files are dropped.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set when the queue files are synchronized to the disk,
+    /// [`SyncPolicy::Never`] by default.
+    pub fn with_sync_policy(mut self, sync_policy: SyncPolicy) -> Self {
+        self.sync_policy = sync_policy;
+        self
+    }
+
+    /// Opens the queue, creating its directory if needed and recovering the
+    /// batches left by a previous process.
+    pub fn build(self) -> Result<PersistentQueue, PersistenceError> {
+        PersistentQueue::open(self)
+    }
+}
+
+/// A segment file.
+#[derive(Debug)]
+struct Segment {
+    id: u64,
+    size: u64,
+}
+
+/// A batch waiting to be exported.
+#[derive(Debug, Clone, Copy)]
+struct Record {
+    segment: u64,
+    offset: u64,
+    len: u32,
+    checksum: u32,
+}
+
+impl Record {
+    fn end(&self) -> u64 {
+        self.offset + RECORD_HEADER_SIZE + u64::from(self.len)
+    }
+}
+
+/// A disk-backed queue of encoded batches, stored in segment files.
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry_sdk::persistence::{PersistentQueue, SyncPolicy};
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), opentelemetry_sdk::persistence::PersistenceError> {
+/// let queue = PersistentQueue::builder("/var/lib/my-service/spans")
+///     .with_max_size(64 * 1024 * 1024)
+///     .with_sync_policy(SyncPolicy::Interval(Duration::from_secs(1)))
+///     .build()?;
+/// # drop(queue);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PersistentQueue {
+    dir: PathBuf,
+    max_segment_size: u64,
+    max_size: u64,
+    sync_policy: SyncPolicy,
+    segments: VecDeque<Segment>,
+    pending: VecDeque<Record>,
+    /// The number of batches removed from the front of the queue since it was
+    /// opened.
+    removed: u64,
+    writer: File,
+    last_sync: Instant,
+}
+
+impl fmt::Debug for PersistentQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentQueue")
+            .field("dir", &self.dir)
+            .field("max_segment_size", &self.max_segment_size)
+            .field("max_size", &self.max_size)
+            .field("sync_policy", &self.sync_policy)
+            .field("len", &self.pending.len())
+            .finish()
+    }
+}
+
+impl PersistentQueue {
+    /// Create a builder of a queue stored in `dir`.
+    ///
+    /// The directory must not be shared with another queue.
+    pub fn builder(dir: impl Into<PathBuf>) -> PersistentQueueBuilder {
+        PersistentQueueBuilder {
+            dir: dir.into(),
+            max_segment_size: DEFAULT_MAX_SEGMENT_SIZE,
+            max_size: DEFAULT_MAX_SIZE,
+            sync_policy: SyncPolicy::default(),
+        }
+    }
+
+    fn open(builder: PersistentQueueBuilder) -> Result<Self, PersistenceError> {
+        let dir = builder.dir;
+        fs::create_dir_all(&dir)?;
+        let cursor = read_cursor(&dir.join(CURSOR_FILE));
+
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some(SEGMENT_EXTENSION) {
+                if let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                {
+                    ids.push(id);
+                }
+            }
+        }
+        ids.sort_unstable();
+
+        let mut segments = VecDeque::new();
+        let mut pending = VecDeque::new();
+        for &id in &ids {
+            let path = segment_path(&dir, id);
+            let (records, size) = recover_segment(&path, id)?;
+            let records = records
+                .into_iter()
+                .filter(|record| cursor.map_or(true, |cursor| (id, record.offset) >= cursor))
+                .collect::<Vec<_>>();
+            if records.is_empty() {
+                fs::remove_file(&path)?;
+            } else {
+                pending.extend(records);
+                segments.push_back(Segment { id, size });
+            }
+        }
+
+        // Batches are always added to a new segment, so that they come after
+        // the cursor.
+        let next_id = ids
+            .last()
+            .map(|id| id + 1)
+            .into_iter()
+            .chain(cursor.map(|(id, _)| id + 1))
+            .max()
+            .unwrap_or_default();
+        let writer = create_segment(&dir, next_id)?;
+        segments.push_back(Segment {
+            id: next_id,
+            size: 0,
+        });
+
+        Ok(PersistentQueue {
+            dir,
+            max_segment_size: builder.max_segment_size,
+            max_size: builder.max_size,
+            sync_policy: builder.sync_policy,
+            segments,
+            pending,
+            removed: 0,
+            writer,
+            last_sync: Instant::now(),
+        })
+    }
+
+    /// The number of batches in the queue.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether the queue has no batch.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The size of the segment files of the queue in bytes.
+    pub fn size(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.size).sum()
+    }
+
+    /// Adds an encoded batch at the end of the queue, dropping the oldest
+    /// segment files if the queue grows bigger than its maximum size.
+    pub fn push(&mut self, batch: &[u8]) -> Result<(), PersistenceError> {
+        let len = u32::try_from(batch.len())
+            .ok()
+            .filter(|len| RECORD_HEADER_SIZE + u64::from(*len) <= self.max_size)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "batch of {} bytes exceeds the queue max size of {} bytes",
+                        batch.len(),
+                        self.max_size
+                    ),
+                )
+            })?;
+        let record_size = RECORD_HEADER_SIZE + u64::from(len);
+
+        let active = self.active_segment();
+        if active.size > 0 && active.size + record_size > self.max_segment_size {
+            self.roll()?;
+        }
+
+        let checksum = crc32fast::hash(batch);
+        let mut record = Vec::with_capacity(record_size as usize);
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&checksum.to_le_bytes());
+        record.extend_from_slice(batch);
+        let size = self.active_segment().size;
+        if let Err(err) = append_record(&self.writer, &self.writer, size, &record) {
+            // Later records must not start after a partially written one
+            if self
+                .writer
+                .metadata()
+                .map_or(true, |meta| meta.len() != size)
+            {
+                let _ = self.roll();
+            }
+            return Err(err.into());
+        }
+
+        let active = self.active_segment_mut();
+        let record = Record {
+            segment: active.id,
+            offset: active.size,
+            len,
+            checksum,
+        };
+        active.size += record_size;
+        self.pending.push_back(record);
+
+        self.sync()?;
+        self.evict()
+    }
+
+    /// The batch at the front of the queue, if any.
+    ///
+    /// Batches that can no longer be read from their segment file are
+    /// dropped.
+    pub fn front(&mut self) -> Result<Option<Vec<u8>>, PersistenceError> {
+        while let Some(record) = self.pending.front().copied() {
+            match self.read(&record) {
+                Ok(batch) => return Ok(Some(batch)),
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                    global::handle_error(global::Error::Other(format!(
+                        "dropping corrupted batch of persistent queue {}: {}",
+                        self.dir.display(),
+                        err
+                    )));
+                    self.pop()?;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Removes the batch at the front of the queue, once exported.
+    pub fn pop(&mut self) -> Result<(), PersistenceError> {
+        let record = match self.pending.pop_front() {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+        self.removed += 1;
+        self.write_cursor(record.segment, record.end())?;
+
+        // Delete the segments whose batches are all exported
+        while self.segments.len() > 1 {
+            let oldest = self.segments[0].id;
+            if self
+                .pending
+                .front()
+                .map_or(false, |record| record.segment == oldest)
+            {
+                break;
+            }
+            self.segments.pop_front();
+            fs::remove_file(segment_path(&self.dir, oldest))?;
+        }
+        Ok(())
+    }
+
+    fn active_segment(&self) -> &Segment {
+        self.segments.back().expect("queue has an active segment")
+    }
+
+    fn active_segment_mut(&mut self) -> &mut Segment {
+        self.segments
+            .back_mut()
+            .expect("queue has an active segment")
+    }
+
+    /// Starts a new segment file.
+    fn roll(&mut self) -> io::Result<()> {
+        if self.sync_policy != SyncPolicy::Never {
+            self.writer.sync_data()?;
+        }
+        let id = self.active_segment().id + 1;
+        self.writer = create_segment(&self.dir, id)?;
+        self.segments.push_back(Segment { id, size: 0 });
+        Ok(())
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        let due = match self.sync_policy {
+            SyncPolicy::Always => true,
+            SyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+            SyncPolicy::Never => false,
+        };
+        if due {
+            self.writer.sync_data()?;
+            self.last_sync = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Drops the oldest segments while the queue is bigger than its maximum size.
+    fn evict(&mut self) -> Result<(), PersistenceError> {
+        let mut dropped = 0;
+        while self.size() > self.max_size && self.segments.len() > 1 {
+            if let Some(segment) = self.segments.pop_front() {
+                let before = self.pending.len();
+                self.pending.retain(|record| record.segment != segment.id);
+                dropped += before - self.pending.len();
+                self.removed += (before - self.pending.len()) as u64;
+                fs::remove_file(segment_path(&self.dir, segment.id))?;
+            }
+        }
+        if dropped > 0 {
+            global::handle_error(global::Error::Other(format!(
+                "persistent queue {} is full, dropped {} batches",
+                self.dir.display(),
+                dropped
+            )));
+        }
+        Ok(())
+    }
+
+    fn read(&self, record: &Record) -> io::Result<Vec<u8>> {
+        let mut file = File::open(segment_path(&self.dir, record.segment))?;
+        file.seek(SeekFrom::Start(record.offset + RECORD_HEADER_SIZE))?;
+        let mut batch = vec![0; record.len as usize];
+        file.read_exact(&mut batch).map_err(|err| {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                io::Error::new(io::ErrorKind::InvalidData, "truncated batch")
+            } else {
+                err
+            }
+        })?;
+        if crc32fast::hash(&batch) != record.checksum {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "batch checksum mismatch",
+            ));
+        }
+        Ok(batch)
+    }
+
+    /// Persists the position of the next batch to export.
+    fn write_cursor(&self, segment: u64, offset: u64) -> io::Result<()> {
+        let mut cursor = Vec::with_capacity(20);
+        cursor.extend_from_slice(&segment.to_le_bytes());
+        cursor.extend_from_slice(&offset.to_le_bytes());
+        cursor.extend_from_slice(&crc32fast::hash(&cursor).to_le_bytes());
+
+        let tmp = self.dir.join(format!("{}.tmp", CURSOR_FILE));
+        let mut file = File::create(&tmp)?;
+        file.write_all(&cursor)?;
+        if self.sync_policy == SyncPolicy::Always {
+            file.sync_data()?;
+        }
+        fs::rename(tmp, self.dir.join(CURSOR_FILE))
+    }
+}
+
+impl Drop for PersistentQueue {
+    fn drop(&mut self) {
+        if self.sync_policy != SyncPolicy::Never {
+            let _ = self.writer.sync_data();
+        }
+    }
+}
+
+/// Identifies a batch leased from [`PersistentBatches`].
+#[cfg(any(feature = "trace", feature = "logs"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LeaseId(
+    /// The position of the batch in the queue, or `None` for a batch that
+    /// could not be persisted.
+    Option<u64>,
+);
+
+/// A [`PersistentQueue`] owned by a dedicated thread, with the [`BatchCodec`]
+/// of its batches.
+///
+/// Batches are leased to be exported, possibly concurrently, and removed from
+/// the queue once they and all the batches before them are exported. Batches
+/// failing to export are released to be leased again.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) struct PersistentBatches<T> {
+    commands: mpsc::Sender<Command<T>>,
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl<T> Clone for PersistentBatches<T> {
+    fn clone(&self) -> Self {
+        PersistentBatches {
+            commands: self.commands.clone(),
+        }
+    }
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl<T> fmt::Debug for PersistentBatches<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentBatches").finish()
+    }
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+enum Command<T> {
+    Push(Vec<T>),
+    Lease(usize, oneshot::Sender<Vec<(LeaseId, Vec<T>)>>),
+    Release(LeaseId, bool),
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl<T: Send + 'static> PersistentBatches<T> {
+    /// Hand `queue` to a new thread, which stops once all the handles are
+    /// dropped.
+    pub(crate) fn new(queue: PersistentQueue, codec: Box<dyn BatchCodec<T>>) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let worker = PersistenceWorker {
+            queue,
+            codec,
+            leased: BTreeSet::new(),
+            exported: BTreeSet::new(),
+            unpersisted: VecDeque::new(),
+        };
+        if let Err(err) = thread::Builder::new()
+            .name("opentelemetry-persistent-queue".to_string())
+            .spawn(move || worker.run(receiver))
+        {
+            global::handle_error(global::Error::Other(format!(
+                "failed to start the persistent queue thread: {}",
+                err
+            )));
+        }
+        PersistentBatches { commands }
+    }
+
+    /// Adds `batch` at the end of the queue, without waiting for it to be
+    /// written.
+    pub(crate) fn push(&self, batch: Vec<T>) {
+        let _ = self.commands.send(Command::Push(batch));
+    }
+
+    /// Leases up to `max` batches to export, in order, skipping the batches
+    /// already leased.
+    pub(crate) fn lease(&self, max: usize) -> impl Future<Output = Vec<(LeaseId, Vec<T>)>> {
+        let (sender, receiver) = oneshot::channel();
+        let _ = self.commands.send(Command::Lease(max, sender));
+        receiver.map(|leases| leases.unwrap_or_default())
+    }
+
+    /// Releases a leased batch, removing it from the queue if it was
+    /// `exported`.
+    pub(crate) fn release(&self, id: LeaseId, exported: bool) {
+        let _ = self.commands.send(Command::Release(id, exported));
+    }
+}
+
+/// The thread owning the queue of [`PersistentBatches`].
+#[cfg(any(feature = "trace", feature = "logs"))]
+struct PersistenceWorker<T> {
+    queue: PersistentQueue,
+    codec: Box<dyn BatchCodec<T>>,
+    /// The positions of the leased batches.
+    leased: BTreeSet<u64>,
+    /// The positions of the exported batches still waiting for a batch before
+    /// them.
+    exported: BTreeSet<u64>,
+    /// The batches that could not be persisted, exported from memory.
+    unpersisted: VecDeque<Vec<T>>,
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+impl<T> PersistenceWorker<T> {
+    fn run(mut self, commands: mpsc::Receiver<Command<T>>) {
+        for command in commands {
+            match command {
+                Command::Push(batch) => self.push(batch),
+                Command::Lease(max, sender) => {
+                    let _ = sender.send(self.lease(max));
+                }
+                Command::Release(id, exported) => self.release(id, exported),
+            }
+        }
+    }
+
+    /// The position of the batch at the front of the queue.
+    fn front(&self) -> u64 {
+        self.queue.removed
+    }
+
+    fn push(&mut self, batch: Vec<T>) {
+        let end = self.front() + self.queue.len() as u64;
+        let result = self
+            .codec
+            .encode(&batch)
+            .and_then(|encoded| self.queue.push(&encoded));
+        if let Err(err) = result {
+            global::handle_error(global::Error::Other(format!(
+                "failed to add a batch to persistent queue {}: {}",
+                self.queue.dir.display(),
+                err
+            )));
+            if self.front() + self.queue.len() as u64 == end {
+                // Export the batch from memory rather than dropping it
+                self.unpersisted.push_back(batch);
+            }
+        }
+        self.forget_removed();
+    }
+
+    fn lease(&mut self, max: usize) -> Vec<(LeaseId, Vec<T>)> {
+        let mut leases = Vec::new();
+        while leases.len() < max {
+            match self.unpersisted.pop_front() {
+                Some(batch) => leases.push((LeaseId(None), batch)),
+                None => break,
+            }
+        }
+
+        let mut index = 0;
+        while leases.len() < max && index < self.queue.len() {
+            let position = self.front() + index as u64;
+            let record = self.queue.pending[index];
+            index += 1;
+            if self.leased.contains(&position) || self.exported.contains(&position) {
+                continue;
+            }
+
+            let batch = self
+                .queue
+                .read(&record)
+                .map_err(PersistenceError::from)
+                .and_then(|encoded| self.codec.decode(&encoded));
+            match batch {
+                Ok(batch) => {
+                    self.leased.insert(position);
+                    leases.push((LeaseId(Some(position)), batch));
+                }
+                Err(PersistenceError::Io(err)) if err.kind() != io::ErrorKind::InvalidData => {
+                    global::handle_error(global::Error::Other(format!(
+                        "failed to read a batch of persistent queue {}: {}",
+                        self.queue.dir.display(),
+                        err
+                    )));
+                    break;
+                }
+                Err(err) => {
+                    global::handle_error(global::Error::Other(format!(
+                        "dropping unreadable batch of persistent queue {}: {}",
+                        self.queue.dir.display(),
+                        err
+                    )));
+                    self.exported.insert(position);
+                }
+            }
+        }
+
+        self.remove_exported();
+        leases
+    }
+
+    fn release(&mut self, id: LeaseId, exported: bool) {
+        if let LeaseId(Some(position)) = id {
+            self.leased.remove(&position);
+            if exported && position >= self.front() {
+                self.exported.insert(position);
+                self.remove_exported();
+            }
+        }
+    }
+
+    /// Removes the exported batches at the front of the queue.
+    fn remove_exported(&mut self) {
+        while self.exported.remove(&self.front()) {
+            if let Err(err) = self.queue.pop() {
+                global::handle_error(global::Error::Other(format!(
+                    "failed to remove a batch from persistent queue {}: {}",
+                    self.queue.dir.display(),
+                    err
+                )));
+            }
+        }
+        self.forget_removed();
+    }
+
+    /// Forgets the batches removed from the queue, exported or evicted.
+    fn forget_removed(&mut self) {
+        let front = self.front();
+        self.leased = self.leased.split_off(&front);
+        self.exported = self.exported.split_off(&front);
+    }
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", id, SEGMENT_EXTENSION))
+}
+
+/// Appends `record` to `segment`, of `size` bytes, with `writer`.
+///
+/// If the write fails part-way, for example when the disk is full, the
+/// segment is truncated back to `size` so that the next record starts there.
+fn append_record(
+    mut writer: impl Write,
+    segment: &File,
+    size: u64,
+    record: &[u8],
+) -> io::Result<()> {
+    writer.write_all(record).map_err(|err| {
+        let _ = segment.set_len(size);
+        err
+    })
+}
+
+fn create_segment(dir: &Path, id: u64) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(segment_path(dir, id))
+}
+
+/// The position of the next batch to export, if known and valid.
+fn read_cursor(path: &Path) -> Option<(u64, u64)> {
+    let cursor = fs::read(path).ok()?;
+    if cursor.len() != 20 {
+        return None;
+    }
+    let (position, checksum) = cursor.split_at(16);
+    if crc32fast::hash(position).to_le_bytes() != checksum {
+        return None;
+    }
+    let segment = u64::from_le_bytes(position[..8].try_into().ok()?);
+    let offset = u64::from_le_bytes(position[8..].try_into().ok()?);
+    Some((segment, offset))
+}
+
+/// Reads the records of a segment file, truncating it before its first
+/// invalid record.
+fn recover_segment(path: &Path, id: u64) -> io::Result<(Vec<Record>, u64)> {
+    let data = fs::read(path)?;
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while let Some(header) = data.get(offset..offset + RECORD_HEADER_SIZE as usize) {
+        let len = u32::from_le_bytes(header[..4].try_into().unwrap_or_default());
+        let checksum = u32::from_le_bytes(header[4..].try_into().unwrap_or_default());
+        let start = offset + RECORD_HEADER_SIZE as usize;
+        match data.get(start..start + len as usize) {
+            Some(batch) if crc32fast::hash(batch) == checksum => {
+                records.push(Record {
+                    segment: id,
+                    offset: offset as u64,
+                    len,
+                    checksum,
+                });
+                offset = start + len as usize;
+            }
+            _ => break,
+        }
+    }
+
+    if offset < data.len() {
+        global::handle_error(global::Error::Other(format!(
+            "persistent queue segment {} is corrupted, dropping its last {} bytes",
+            path.display(),
+            data.len() - offset
+        )));
+        OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_len(offset as u64)?;
+    }
+    Ok((records, offset as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A directory removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "opentelemetry-persistence-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn segment_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("seg"))
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let dir = TempDir::new();
+        let mut queue = PersistentQueue::builder(&dir.0)
+            .with_sync_policy(SyncPolicy::Always)
+            .build()
+            .unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(queue.front().unwrap(), None);
+
+        queue.push(b"first").unwrap();
+        queue.push(b"second").unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.size(), 2 * RECORD_HEADER_SIZE + 11);
+        assert_eq!(queue.front().unwrap(), Some(b"first".to_vec()));
+        assert_eq!(queue.front().unwrap(), Some(b"first".to_vec()));
+
+        queue.pop().unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"second".to_vec()));
+        queue.pop().unwrap();
+        assert!(queue.is_empty());
+        queue.pop().unwrap();
+    }
+
+    #[test]
+    fn replay_after_restart() {
+        let dir = TempDir::new();
+        {
+            let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+            for batch in ["a", "b", "c"] {
+                queue.push(batch.as_bytes()).unwrap();
+            }
+            queue.pop().unwrap();
+        }
+
+        let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.front().unwrap(), Some(b"b".to_vec()));
+        queue.pop().unwrap();
+        queue.push(b"d").unwrap();
+        drop(queue);
+
+        let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"c".to_vec()));
+        queue.pop().unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"d".to_vec()));
+        queue.pop().unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(segment_files(&dir.0).len(), 1);
+    }
+
+    #[test]
+    fn roll_and_evict_segments() {
+        let dir = TempDir::new();
+        let mut queue = PersistentQueue::builder(&dir.0)
+            .with_max_segment_size(2 * (RECORD_HEADER_SIZE + 4))
+            .with_max_size(4 * (RECORD_HEADER_SIZE + 4))
+            .build()
+            .unwrap();
+
+        for i in 0..5u32 {
+            queue.push(&i.to_le_bytes()).unwrap();
+        }
+        // The first segment is dropped when the fifth batch is added.
+        assert_eq!(segment_files(&dir.0).len(), 2);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.front().unwrap(), Some(2u32.to_le_bytes().to_vec()));
+
+        queue.pop().unwrap();
+        queue.pop().unwrap();
+        assert_eq!(segment_files(&dir.0).len(), 1);
+
+        assert!(matches!(
+            queue.push(&[0; 64]),
+            Err(PersistenceError::Io(err)) if err.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+
+    #[test]
+    fn recover_corrupted_segment() {
+        let dir = TempDir::new();
+        {
+            let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+            queue.push(b"valid").unwrap();
+            queue.push(b"corrupted").unwrap();
+            queue.push(b"lost").unwrap();
+        }
+        let segment = segment_files(&dir.0).pop().unwrap();
+        let mut data = fs::read(&segment).unwrap();
+        let corrupted = (RECORD_HEADER_SIZE * 2) as usize + 5;
+        data[corrupted] ^= 0xff;
+        fs::write(&segment, &data).unwrap();
+
+        let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.front().unwrap(), Some(b"valid".to_vec()));
+        assert_eq!(
+            fs::metadata(&segment).unwrap().len(),
+            RECORD_HEADER_SIZE + 5
+        );
+
+        // Batches corrupted after the queue is opened are dropped when read
+        queue.push(b"next").unwrap();
+        let active = segment_files(&dir.0).pop().unwrap();
+        fs::write(&active, b"garbage").unwrap();
+        queue.pop().unwrap();
+        assert_eq!(queue.front().unwrap(), None);
+        assert!(queue.is_empty());
+    }
+
+    /// Writes at most `space` bytes, as on a full disk.
+    struct ShortWriter<'a> {
+        file: &'a File,
+        space: usize,
+    }
+
+    impl Write for ShortWriter<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.space == 0 {
+                return Err(io::Error::new(io::ErrorKind::Other, "no space left"));
+            }
+            let written = self.file.write(&buf[..buf.len().min(self.space)])?;
+            self.space -= written;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.file.flush()
+        }
+    }
+
+    #[test]
+    fn truncate_short_writes() {
+        let dir = TempDir::new();
+        let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        queue.push(b"first").unwrap();
+
+        let size = queue.active_segment().size;
+        let writer = ShortWriter {
+            file: &queue.writer,
+            space: 10,
+        };
+        assert!(append_record(writer, &queue.writer, size, b"partial record").is_err());
+        assert_eq!(queue.writer.metadata().unwrap().len(), size);
+
+        queue.push(b"second").unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"first".to_vec()));
+        queue.pop().unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"second".to_vec()));
+        drop(queue);
+
+        let mut queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        assert_eq!(queue.front().unwrap(), Some(b"second".to_vec()));
+    }
+
+    /// Encodes batches of strings as lines.
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    #[derive(Debug)]
+    struct LinesCodec;
+
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    impl BatchCodec<String> for LinesCodec {
+        fn encode(&self, batch: &[String]) -> Result<Vec<u8>, PersistenceError> {
+            Ok(batch.join("\n").into_bytes())
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<Vec<String>, PersistenceError> {
+            let lines = String::from_utf8_lossy(bytes);
+            Ok(lines.split('\n').map(str::to_string).collect())
+        }
+    }
+
+    #[test]
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    fn lease_and_release_batches() {
+        use futures_executor::block_on;
+
+        let dir = TempDir::new();
+        let queue = PersistentQueue::builder(&dir.0).build().unwrap();
+        let batches = PersistentBatches::new(queue, Box::new(LinesCodec));
+        for batch in ["a", "b", "c"] {
+            batches.push(vec![batch.to_string()]);
+        }
+
+        let leases = block_on(batches.lease(2));
+        let names = leases
+            .iter()
+            .map(|(_, batch)| batch[0].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b"]);
+        let (c, _) = block_on(batches.lease(2)).pop().unwrap();
+
+        // Batches are only removed once the batches before them are exported
+        batches.release(leases[1].0, true);
+        batches.release(c, true);
+        batches.release(leases[0].0, false);
+        let leases = block_on(batches.lease(2));
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].1, vec!["a".to_string()]);
+
+        batches.release(leases[0].0, true);
+        assert!(block_on(batches.lease(2)).is_empty());
+        assert!(PersistentQueue::builder(&dir.0).build().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! # Datadog Propagator
+//!
+
+use once_cell::sync::Lazy;
+use opentelemetry::{
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+
+const DATADOG_TRACE_ID_HEADER: &str = "x-datadog-trace-id";
+const DATADOG_PARENT_ID_HEADER: &str = "x-datadog-parent-id";
+const DATADOG_SAMPLING_PRIORITY_HEADER: &str = "x-datadog-sampling-priority";
+const DATADOG_TAGS_HEADER: &str = "x-datadog-tags";
+
+/// The propagation tag holding the upper 64 bits of 128-bit trace ids.
+const TRACE_ID_HIGH_TAG: &str = "_dd.p.tid";
+
+const AUTO_REJECT: &str = "0";
+const AUTO_KEEP: &str = "1";
+
+static DATADOG_HEADER_FIELDS: Lazy<[String; 4]> = Lazy::new(|| {
+    [
+        DATADOG_TRACE_ID_HEADER.to_owned(),
+        DATADOG_PARENT_ID_HEADER.to_owned(),
+        DATADOG_SAMPLING_PRIORITY_HEADER.to_owned(),
+        DATADOG_TAGS_HEADER.to_owned(),
+    ]
+});
+
+/// Propagates `SpanContext`s in the format of the [Datadog] tracers.
+///
+/// The span context is propagated under the following headers:
+///
+///    - `x-datadog-trace-id`, the lower 64 bits of the trace id, in decimal.
+///    - `x-datadog-parent-id`, the span id, in decimal.
+///    - `x-datadog-sampling-priority`, the sampling decision. Positive
+///      priorities (`1` for automatic and `2` for user decisions) mark sampled
+///      traces, and are extracted as sampled span contexts. Sampled span
+///      contexts are injected with the priority `1`, others with `0`.
+///    - `x-datadog-tags`, the propagation tags, from which only `_dd.p.tid`,
+///      the upper 64 bits of 128-bit trace ids in 16 hexadecimal digits, is
+///      used. Trace ids without upper bits map to the 64-bit Datadog trace ids.
+///
+/// Here's an example of the headers of a sampled trace with a 128-bit trace id.
+///
+/// ```text
+/// x-datadog-trace-id: 11803532876627986230
+/// x-datadog-parent-id: 67667974448284343
+/// x-datadog-sampling-priority: 1
+/// x-datadog-tags: _dd.p.tid=4bf92f3577b34da6
+/// ```
+///
+/// [Datadog]: https://docs.datadoghq.com/tracing/trace_collection/trace_context_propagation/
+#[derive(Clone, Debug, Default)]
+pub struct DatadogPropagator {
+    _private: (),
+}
+
+impl DatadogPropagator {
+    /// Create a new `DatadogPropagator`.
+    pub fn new() -> Self {
+        DatadogPropagator { _private: () }
+    }
+
+    /// Extract span context from the `x-datadog-*` headers.
+    fn extract_span_context(&self, extractor: &dyn Extractor) -> Result<SpanContext, ()> {
+        let trace_id_low = parse_id(extractor.get(DATADOG_TRACE_ID_HEADER))?;
+        let span_id = parse_id(extractor.get(DATADOG_PARENT_ID_HEADER))?;
+
+        let trace_id_high = extractor
+            .get(DATADOG_TAGS_HEADER)
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|tag| tag.split_once('='))
+            .find(|(key, _)| key.trim() == TRACE_ID_HIGH_TAG)
+            .and_then(|(_, value)| {
+                let value = value.trim();
+                (value.len() == 16)
+                    .then(|| u64::from_str_radix(value, 16).ok())
+                    .flatten()
+            })
+            .unwrap_or(0);
+
+        let sampling_priority = extractor
+            .get(DATADOG_SAMPLING_PRIORITY_HEADER)
+            .and_then(|priority| priority.trim().parse::<i32>().ok());
+        let trace_flags = match sampling_priority {
+            Some(priority) if priority > 0 => TraceFlags::SAMPLED,
+            _ => TraceFlags::default(),
+        };
+
+        let trace_id = TraceId::from_bytes(
+            (u128::from(trace_id_high) << 64 | u128::from(trace_id_low)).to_be_bytes(),
+        );
+        let span_context = SpanContext::new(
+            trace_id,
+            SpanId::from_bytes(span_id.to_be_bytes()),
+            trace_flags,
+            true,
+            TraceState::default(),
+        );
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+}
+
+impl TextMapPropagator for DatadogPropagator {
+    /// Properly encodes the values of the `SpanContext` and injects them
+    /// into the `Injector`.
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let trace_id = u128::from_be_bytes(span_context.trace_id().to_bytes());
+            let span_id = u64::from_be_bytes(span_context.span_id().to_bytes());
+            let sampling_priority = if span_context.is_sampled() {
+                AUTO_KEEP
+            } else {
+                AUTO_REJECT
+            };
+
+            injector.set(DATADOG_TRACE_ID_HEADER, (trace_id as u64).to_string());
+            injector.set(DATADOG_PARENT_ID_HEADER, span_id.to_string());
+            injector.set(
+                DATADOG_SAMPLING_PRIORITY_HEADER,
+                sampling_priority.to_string(),
+            );
+            let trace_id_high = (trace_id >> 64) as u64;
+            if trace_id_high != 0 {
+                injector.set(
+                    DATADOG_TAGS_HEADER,
+                    format!("{TRACE_ID_HIGH_TAG}={trace_id_high:016x}"),
+                );
+            }
+        }
+    }
+
+    /// Retrieves encoded `SpanContext`s using the `Extractor`. It decodes
+    /// the `SpanContext` and returns it. If no `SpanContext` was retrieved
+    /// OR if the retrieved SpanContext is invalid then an empty `SpanContext`
+    /// is returned.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        self.extract_span_context(extractor)
+            .map(|sc| cx.with_remote_span_context(sc))
+            .unwrap_or_else(|_| cx.clone())
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(DATADOG_HEADER_FIELDS.as_ref())
+    }
+}
+
+/// Parses a non-zero decimal 64-bit id.
+fn parse_id(value: Option<&str>) -> Result<u64, ()> {
+    match value.unwrap_or("").trim().parse::<u64>() {
+        Ok(id) if id != 0 => Ok(id),
+        _ => Err(()),
+    }
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use std::collections::HashMap;
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, &'static str, &'static str, &'static str, SpanContext)> {
+        vec![
+            ("11803532876627986230", "67667974448284343", "1", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "2", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "0", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "-1", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "1", "_dd.p.dm=-1,_dd.p.tid=4bf92f3577b34da6", SpanContext::new(TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "1", "_dd.p.tid=4bf92f35", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_data_invalid() -> Vec<(&'static str, &'static str, &'static str)> {
+        vec![
+            ("", "67667974448284343", "missing trace id"),
+            ("11803532876627986230", "", "missing parent id"),
+            ("a3ce929d0e0e4736", "67667974448284343", "hexadecimal trace id"),
+            ("11803532876627986230", "-1", "negative parent id"),
+            ("18446744073709551616", "67667974448284343", "trace id overflow"),
+            ("0", "0", "zero trace id and parent id"),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(&'static str, &'static str, &'static str, Option<&'static str>, SpanContext)> {
+        vec![
+            ("11803532876627986230", "67667974448284343", "1", None, SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "0", None, SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+            ("11803532876627986230", "67667974448284343", "1", Some("_dd.p.tid=4bf92f3577b34da6"), SpanContext::new(TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+        ]
+    }
+
+    #[test]
+    fn extract_datadog() {
+        let propagator = DatadogPropagator::new();
+
+        for (trace_id, parent_id, priority, tags, expected_context) in extract_data() {
+            let mut extractor = HashMap::new();
+            extractor.insert(DATADOG_TRACE_ID_HEADER.to_string(), trace_id.to_string());
+            extractor.insert(DATADOG_PARENT_ID_HEADER.to_string(), parent_id.to_string());
+            extractor.insert(
+                DATADOG_SAMPLING_PRIORITY_HEADER.to_string(),
+                priority.to_string(),
+            );
+            extractor.insert(DATADOG_TAGS_HEADER.to_string(), tags.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &expected_context
+            )
+        }
+    }
+
+    #[test]
+    fn extract_datadog_reject_invalid() {
+        let propagator = DatadogPropagator::new();
+
+        for (trace_id, parent_id, reason) in extract_data_invalid() {
+            let mut extractor = HashMap::new();
+            extractor.insert(DATADOG_TRACE_ID_HEADER.to_string(), trace_id.to_string());
+            extractor.insert(DATADOG_PARENT_ID_HEADER.to_string(), parent_id.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+                "{}",
+                reason
+            )
+        }
+    }
+
+    #[test]
+    fn inject_datadog() {
+        let propagator = DatadogPropagator::new();
+
+        for (trace_id, parent_id, priority, tags, context) in inject_data() {
+            let mut injector = HashMap::new();
+            propagator.inject_context(
+                &Context::current_with_span(TestSpan(context)),
+                &mut injector,
+            );
+
+            assert_eq!(
+                Extractor::get(&injector, DATADOG_TRACE_ID_HEADER),
+                Some(trace_id)
+            );
+            assert_eq!(
+                Extractor::get(&injector, DATADOG_PARENT_ID_HEADER),
+                Some(parent_id)
+            );
+            assert_eq!(
+                Extractor::get(&injector, DATADOG_SAMPLING_PRIORITY_HEADER),
+                Some(priority)
+            );
+            assert_eq!(Extractor::get(&injector, DATADOG_TAGS_HEADER), tags);
+        }
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(
+            &Context::current_with_span(TestSpan(SpanContext::empty_context())),
+            &mut injector,
+        );
+        assert!(injector.is_empty());
+    }
+}
@@ -1,6 +1,14 @@
 //! OpenTelemetry Propagators
 mod baggage;
+mod datadog;
+mod ot_trace;
+mod registry;
 mod trace_context;
+mod xray;
 
 pub use baggage::BaggagePropagator;
+pub use datadog::DatadogPropagator;
+pub use ot_trace::OtTracePropagator;
+pub use registry::PropagatorRegistry;
 pub use trace_context::TraceContextPropagator;
+pub use xray::XrayPropagator;
@@ -0,0 +1,307 @@
+//! # OpenTracing Propagator
+//!
+
+use once_cell::sync::Lazy;
+use opentelemetry::{
+    baggage::{BaggageExt, KeyValueMetadata},
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+
+const OT_TRACE_ID_HEADER: &str = "ot-tracer-traceid";
+const OT_SPAN_ID_HEADER: &str = "ot-tracer-spanid";
+const OT_SAMPLED_HEADER: &str = "ot-tracer-sampled";
+const OT_BAGGAGE_PREFIX: &str = "ot-baggage-";
+
+static OT_TRACE_HEADER_FIELDS: Lazy<[String; 3]> = Lazy::new(|| {
+    [
+        OT_TRACE_ID_HEADER.to_owned(),
+        OT_SPAN_ID_HEADER.to_owned(),
+        OT_SAMPLED_HEADER.to_owned(),
+    ]
+});
+
+/// Propagates `SpanContext`s and baggage in the format of the [OpenTracing]
+/// basic tracers, such as the Lightstep tracers.
+///
+/// The span context is propagated under three headers:
+///
+///    - `ot-tracer-traceid`, the trace id in 16 or 32 hexadecimal digits.
+///      Only the lower 64 bits of the trace id are injected, as OpenTracing
+///      tracers use 64-bit trace ids, and extracted 64-bit trace ids are left
+///      padded with zeros.
+///    - `ot-tracer-spanid`, the span id in 16 hexadecimal digits.
+///    - `ot-tracer-sampled`, `true` if the trace is sampled, `false` otherwise.
+///
+/// Each baggage entry is propagated under its own `ot-baggage-{name}` header.
+/// Entries whose name or value are not valid in a header are not injected.
+///
+/// Here's an example of the headers of a sampled trace with baggage.
+///
+/// ```text
+/// ot-tracer-traceid: a3ce929d0e0e4736
+/// ot-tracer-spanid: 00f067aa0ba902b7
+/// ot-tracer-sampled: true
+/// ot-baggage-user_id: 42
+/// ```
+///
+/// [OpenTracing]: https://github.com/opentracing/basictracer-go
+#[derive(Clone, Debug, Default)]
+pub struct OtTracePropagator {
+    _private: (),
+}
+
+impl OtTracePropagator {
+    /// Create a new `OtTracePropagator`.
+    pub fn new() -> Self {
+        OtTracePropagator { _private: () }
+    }
+
+    /// Extract span context from the `ot-tracer-*` headers.
+    fn extract_span_context(&self, extractor: &dyn Extractor) -> Result<SpanContext, ()> {
+        let trace_id = extractor.get(OT_TRACE_ID_HEADER).unwrap_or("").trim();
+        let trace_id = match trace_id.len() {
+            16 => format!("{trace_id:0>32}"),
+            32 => trace_id.to_string(),
+            _ => return Err(()),
+        };
+        let trace_id = TraceId::from_hex(&trace_id).map_err(|_| ())?;
+
+        let span_id = extractor.get(OT_SPAN_ID_HEADER).unwrap_or("").trim();
+        if span_id.len() != 16 {
+            return Err(());
+        }
+        let span_id = SpanId::from_hex(span_id).map_err(|_| ())?;
+
+        let sampled = extractor.get(OT_SAMPLED_HEADER).unwrap_or("").trim();
+        let trace_flags = if sampled.eq_ignore_ascii_case("true") || sampled == "1" {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::default()
+        };
+
+        let span_context =
+            SpanContext::new(trace_id, span_id, trace_flags, true, TraceState::default());
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+}
+
+impl TextMapPropagator for OtTracePropagator {
+    /// Properly encodes the values of the `SpanContext` and the baggage of the
+    /// `Context` and injects them into the `Injector`.
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let trace_id = span_context.trace_id().to_string();
+            injector.set(OT_TRACE_ID_HEADER, trace_id[16..].to_string());
+            injector.set(OT_SPAN_ID_HEADER, span_context.span_id().to_string());
+            injector.set(OT_SAMPLED_HEADER, span_context.is_sampled().to_string());
+        }
+
+        for (name, (value, _)) in cx.baggage() {
+            let value = value.as_str();
+            if is_header_token(name.as_str()) && is_header_value(&value) {
+                injector.set(&format!("{OT_BAGGAGE_PREFIX}{name}"), value.into_owned());
+            }
+        }
+    }
+
+    /// Retrieves encoded `SpanContext`s and baggage using the `Extractor`. If
+    /// no `SpanContext` was retrieved OR if the retrieved SpanContext is
+    /// invalid then an empty `SpanContext` is returned.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        let baggage = extractor
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let lowercase_key = key.to_ascii_lowercase();
+                let name = lowercase_key.strip_prefix(OT_BAGGAGE_PREFIX)?;
+                let value = extractor.get(key)?;
+                (!name.is_empty())
+                    .then(|| KeyValueMetadata::new(name.to_string(), value.trim().to_string(), ""))
+            })
+            .collect::<Vec<_>>();
+        let cx = if baggage.is_empty() {
+            cx.clone()
+        } else {
+            // Keep the baggage extracted by other propagators
+            let existing = cx.baggage().iter().map(|(key, (value, metadata))| {
+                KeyValueMetadata::new(key.clone(), value.clone(), metadata.clone())
+            });
+            cx.with_baggage(existing.chain(baggage).collect::<Vec<_>>())
+        };
+
+        self.extract_span_context(extractor)
+            .map(|sc| cx.with_remote_span_context(sc))
+            .unwrap_or(cx)
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(OT_TRACE_HEADER_FIELDS.as_ref())
+    }
+}
+
+/// Whether `name` is a valid header name, as defined by RFC 7230.
+fn is_header_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Whether `value` is a valid header value, without control characters.
+fn is_header_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use opentelemetry::{baggage::BaggageExt, KeyValue, StringValue};
+    use std::collections::HashMap;
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, &'static str, &'static str, SpanContext)> {
+        vec![
+            ("4bf92f3577b34da6a3ce929d0e0e4736", "00f067aa0ba902b7", "true", SpanContext::new(TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("a3ce929d0e0e4736", "00f067aa0ba902b7", "true", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("a3ce929d0e0e4736", "00f067aa0ba902b7", "1", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("a3ce929d0e0e4736", "00f067aa0ba902b7", "false", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+            ("a3ce929d0e0e4736", "00f067aa0ba902b7", "", SpanContext::new(TraceId::from_u128(0xa3ce_929d_0e0e_4736), SpanId::from_u64(0x00f0_67aa_0ba9_02b7), TraceFlags::default(), true, TraceState::default())),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_data_invalid() -> Vec<(&'static str, &'static str, &'static str)> {
+        vec![
+            ("", "00f067aa0ba902b7", "missing trace id"),
+            ("a3ce929d0e0e473", "00f067aa0ba902b7", "wrong trace id length"),
+            ("a3ce929d0e0e47qw", "00f067aa0ba902b7", "bogus trace id"),
+            ("a3ce929d0e0e4736", "", "missing span id"),
+            ("a3ce929d0e0e4736", "00f067aa0ba902b", "wrong span id length"),
+            ("0000000000000000", "0000000000000000", "zero trace id and span id"),
+        ]
+    }
+
+    #[test]
+    fn extract_ot_trace() {
+        let propagator = OtTracePropagator::new();
+
+        for (trace_id, span_id, sampled, expected_context) in extract_data() {
+            let mut extractor = HashMap::new();
+            extractor.insert(OT_TRACE_ID_HEADER.to_string(), trace_id.to_string());
+            extractor.insert(OT_SPAN_ID_HEADER.to_string(), span_id.to_string());
+            extractor.insert(OT_SAMPLED_HEADER.to_string(), sampled.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &expected_context
+            )
+        }
+    }
+
+    #[test]
+    fn extract_ot_trace_reject_invalid() {
+        let propagator = OtTracePropagator::new();
+
+        for (trace_id, span_id, reason) in extract_data_invalid() {
+            let mut extractor = HashMap::new();
+            extractor.insert(OT_TRACE_ID_HEADER.to_string(), trace_id.to_string());
+            extractor.insert(OT_SPAN_ID_HEADER.to_string(), span_id.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+                "{}",
+                reason
+            )
+        }
+    }
+
+    #[test]
+    fn extract_ot_baggage() {
+        let propagator = OtTracePropagator::new();
+        let mut extractor = HashMap::new();
+        extractor.insert("ot-baggage-user_id".to_string(), "42".to_string());
+        extractor.insert("ot-baggage-tenant".to_string(), " acme ".to_string());
+        extractor.insert("other-header".to_string(), "value".to_string());
+
+        let cx = propagator.extract(&extractor);
+        let baggage = cx.baggage();
+        assert_eq!(baggage.len(), 2);
+        assert_eq!(baggage.get("user_id"), Some(&"42".into()));
+        assert_eq!(baggage.get("tenant"), Some(&"acme".into()));
+        assert!(
+            !cx.has_active_span(),
+            "baggage is extracted without span context"
+        );
+    }
+
+    #[test]
+    fn extract_ot_baggage_keeps_existing_baggage() {
+        let propagator = OtTracePropagator::new();
+        let mut extractor = HashMap::new();
+        extractor.insert("ot-baggage-user_id".to_string(), "42".to_string());
+
+        let cx = Context::new().with_baggage(vec![
+            KeyValue::new("tenant", "acme"),
+            KeyValue::new("user_id", "7"),
+        ]);
+        let cx = propagator.extract_with_context(&cx, &extractor);
+        let baggage = cx.baggage();
+        assert_eq!(baggage.len(), 2);
+        assert_eq!(baggage.get("tenant"), Some(&"acme".into()));
+        assert_eq!(baggage.get("user_id"), Some(&"42".into()));
+    }
+
+    #[test]
+    fn inject_ot_trace() {
+        let propagator = OtTracePropagator::new();
+        let span_context = SpanContext::new(
+            TraceId::from_u128(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736),
+            SpanId::from_u64(0x00f0_67aa_0ba9_02b7),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let cx = Context::current_with_span(TestSpan(span_context)).with_baggage(vec![
+            KeyValue::new("user_id", 42),
+            KeyValue::new("invalid name", "dropped"),
+            KeyValue::new("invalid_value", StringValue::from("line\nbreak")),
+        ]);
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(&cx, &mut injector);
+
+        let mut expected = HashMap::new();
+        expected.insert(
+            OT_TRACE_ID_HEADER.to_string(),
+            "a3ce929d0e0e4736".to_string(),
+        );
+        expected.insert(
+            OT_SPAN_ID_HEADER.to_string(),
+            "00f067aa0ba902b7".to_string(),
+        );
+        expected.insert(OT_SAMPLED_HEADER.to_string(), "true".to_string());
+        expected.insert("ot-baggage-user_id".to_string(), "42".to_string());
+        assert_eq!(injector, expected);
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(
+            &Context::current_with_span(TestSpan(SpanContext::empty_context())),
+            &mut injector,
+        );
+        assert!(injector.is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! # Propagator Registry
+//!
+//! Maps propagator names, as used by the `OTEL_PROPAGATORS` environment
+//! variable, to the constructors of the propagators they designate.
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    env, fmt,
+    sync::Arc,
+};
+
+use opentelemetry::{
+    global,
+    propagation::{TextMapCompositePropagator, TextMapPropagator},
+};
+
+use super::{
+    BaggagePropagator, DatadogPropagator, OtTracePropagator, TraceContextPropagator, XrayPropagator,
+};
+
+/// The environment variable listing the propagators to use.
+const OTEL_PROPAGATORS: &str = "OTEL_PROPAGATORS";
+/// The propagators used when `OTEL_PROPAGATORS` is not set.
+const OTEL_PROPAGATORS_DEFAULT: &str = "tracecontext,baggage";
+/// The name disabling the automatically configured propagators.
+const NONE: &str = "none";
+
+type Constructor = Arc<dyn Fn() -> Box<dyn TextMapPropagator + Send + Sync> + Send + Sync>;
+
+/// A registry of propagators by name, building composite propagators from a
+/// list of names such as the one of the `OTEL_PROPAGATORS` environment variable.
+///
+/// The registry knows the propagators of this crate under the following names:
+///
+/// * `tracecontext`: [TraceContextPropagator],
+/// * `baggage`: [BaggagePropagator],
+/// * `xray`: [XrayPropagator],
+/// * `ottrace`: [OtTracePropagator],
+/// * `datadog`: [DatadogPropagator].
+///
+/// Other crates can register their own propagators, for example `b3` and
+/// `b3multi` are provided by `opentelemetry-zipkin` and `jaeger` by
+/// `opentelemetry-jaeger-propagator`.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::global;
+/// use opentelemetry_sdk::propagation::{PropagatorRegistry, TraceContextPropagator};
+///
+/// let mut registry = PropagatorRegistry::new();
+/// registry.register("w3c", || Box::new(TraceContextPropagator::new()));
+///
+/// // Uses the propagators listed in `OTEL_PROPAGATORS`, `tracecontext,baggage` if not set.
+/// global::set_text_map_propagator(registry.build_from_env());
+/// ```
+#[derive(Clone)]
+pub struct PropagatorRegistry {
+    constructors: HashMap<Cow<'static, str>, Constructor>,
+}
+
+impl PropagatorRegistry {
+    /// Create a registry of the propagators of this crate.
+    pub fn new() -> Self {
+        let mut registry = PropagatorRegistry {
+            constructors: HashMap::new(),
+        };
+        registry
+            .register("tracecontext", || Box::new(TraceContextPropagator::new()))
+            .register("baggage", || Box::new(BaggagePropagator::new()))
+            .register("xray", || Box::new(XrayPropagator::new()))
+            .register("ottrace", || Box::new(OtTracePropagator::new()))
+            .register("datadog", || Box::new(DatadogPropagator::new()));
+        registry
+    }
+
+    /// Register a propagator constructor under `name`, replacing the one
+    /// previously registered under the same name, if any.
+    pub fn register<F>(&mut self, name: impl Into<Cow<'static, str>>, constructor: F) -> &mut Self
+    where
+        F: Fn() -> Box<dyn TextMapPropagator + Send + Sync> + Send + Sync + 'static,
+    {
+        self.constructors.insert(name.into(), Arc::new(constructor));
+        self
+    }
+
+    /// Create the propagator registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<Box<dyn TextMapPropagator + Send + Sync>> {
+        self.constructors.get(name).map(|constructor| constructor())
+    }
+
+    /// Build a composite of the propagators registered under `names`, in order.
+    ///
+    /// Names are trimmed and deduplicated, and `none` is ignored. Unknown
+    /// names are reported to the global error handler and ignored.
+    pub fn build<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> TextMapCompositePropagator {
+        let mut seen = HashSet::new();
+        let propagators = names
+            .into_iter()
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && *name != NONE && seen.insert(*name))
+            .filter_map(|name| {
+                let propagator = self.get(name);
+                if propagator.is_none() {
+                    global::handle_error(global::Error::Other(format!(
+                        "Unrecognised propagator: {}. Ignoring it",
+                        name
+                    )));
+                }
+                propagator
+            })
+            .collect();
+
+        TextMapCompositePropagator::new(propagators)
+    }
+
+    /// Build a composite of the propagators listed in the comma separated
+    /// `OTEL_PROPAGATORS` environment variable, `tracecontext,baggage` if it
+    /// is unset or empty.
+    ///
+    /// Setting `OTEL_PROPAGATORS` to `none` builds a composite without any
+    /// propagator.
+    pub fn build_from_env(&self) -> TextMapCompositePropagator {
+        let names = env::var(OTEL_PROPAGATORS)
+            .ok()
+            .filter(|names| !names.trim().is_empty())
+            .unwrap_or_else(|| OTEL_PROPAGATORS_DEFAULT.to_string());
+
+        self.build(names.split(','))
+    }
+}
+
+impl Default for PropagatorRegistry {
+    fn default() -> Self {
+        PropagatorRegistry::new()
+    }
+}
+
+impl fmt::Debug for PropagatorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.constructors.keys().collect::<Vec<_>>();
+        names.sort();
+        f.debug_struct("PropagatorRegistry")
+            .field("propagators", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(propagator: &TextMapCompositePropagator) -> Vec<String> {
+        let mut fields = propagator.fields().map(str::to_string).collect::<Vec<_>>();
+        fields.sort();
+        fields
+    }
+
+    #[test]
+    fn build_by_name() {
+        let registry = PropagatorRegistry::new();
+
+        assert_eq!(
+            fields(&registry.build(["xray", " baggage ", "xray", "unknown"])),
+            vec!["baggage", "x-amzn-trace-id"]
+        );
+        assert!(fields(&registry.build(["none"])).is_empty());
+        assert!(registry.get("b3").is_none());
+    }
+
+    #[test]
+    fn register_propagator() {
+        let mut registry = PropagatorRegistry::new();
+        registry
+            .register("w3c", || Box::new(TraceContextPropagator::new()))
+            .register("baggage", || Box::new(XrayPropagator::new()));
+
+        assert_eq!(
+            fields(&registry.build(["w3c", "baggage"])),
+            vec!["traceparent", "tracestate", "x-amzn-trace-id"]
+        );
+    }
+
+    #[test]
+    fn build_from_env() {
+        let registry = PropagatorRegistry::new();
+
+        temp_env::with_var_unset(OTEL_PROPAGATORS, || {
+            assert_eq!(
+                fields(&registry.build_from_env()),
+                vec!["baggage", "traceparent", "tracestate"]
+            );
+        });
+        temp_env::with_var(OTEL_PROPAGATORS, Some("ottrace,datadog"), || {
+            assert_eq!(
+                fields(&registry.build_from_env()),
+                vec![
+                    "ot-tracer-sampled",
+                    "ot-tracer-spanid",
+                    "ot-tracer-traceid",
+                    "x-datadog-parent-id",
+                    "x-datadog-sampling-priority",
+                    "x-datadog-tags",
+                    "x-datadog-trace-id",
+                ]
+            );
+        });
+        temp_env::with_var(OTEL_PROPAGATORS, Some("none"), || {
+            assert!(fields(&registry.build_from_env()).is_empty());
+        });
+    }
+}
@@ -0,0 +1,316 @@
+//! # AWS X-Ray Propagator
+//!
+
+use once_cell::sync::Lazy;
+use opentelemetry::{
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+
+const AWS_XRAY_TRACE_HEADER: &str = "x-amzn-trace-id";
+const AWS_XRAY_VERSION: &str = "1";
+
+const HEADER_ROOT_KEY: &str = "Root";
+const HEADER_PARENT_KEY: &str = "Parent";
+const HEADER_SAMPLED_KEY: &str = "Sampled";
+const HEADER_LINEAGE_KEY: &str = "Lineage";
+
+const SAMPLED: &str = "1";
+const NOT_SAMPLED: &str = "0";
+const REQUESTED_SAMPLING_DECISION: &str = "?";
+
+/// The trace state key the lineage of a trace is kept under between its
+/// extraction and injection.
+const LINEAGE_TRACE_STATE_KEY: &str = "xray_lineage";
+
+static AWS_XRAY_HEADER_FIELDS: Lazy<[String; 1]> = Lazy::new(|| [AWS_XRAY_TRACE_HEADER.to_owned()]);
+
+/// Propagates `SpanContext`s in [AWS X-Ray] format under the `X-Amzn-Trace-Id`
+/// header.
+///
+/// Here's an example of an `X-Amzn-Trace-Id` header.
+///
+/// `X-Amzn-Trace-Id: Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`
+///
+/// The header has the following fields, separated by `;`:
+///
+///    - `Root`, the trace id, made of the version `1`, the time of the original
+///      request in Unix epoch seconds in 8 hexadecimal digits, and a 96-bit
+///      identifier in 24 hexadecimal digits. It maps to the OpenTelemetry trace
+///      id made of the last two parts, as generated by the [XrayIdGenerator].
+///    - `Parent`, the span id of the parent span.
+///    - `Sampled`, `1` if the trace is sampled, `0` if it is not, and `?` if
+///      the sampling decision is left to the receiver.
+///    - `Lineage`, the counters AWS services such as Lambda use to detect
+///      request loops. It is kept in the [TraceState] of the extracted span
+///      context, under the `xray_lineage` key, and injected back with it.
+///
+/// Other fields, such as the `Self` field added by load balancers, are dropped.
+///
+/// See the [AWS X-Ray documentation] for more details.
+///
+/// [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/xray-concepts.html#xray-concepts-tracingheader
+/// [AWS X-Ray documentation]: https://docs.aws.amazon.com/xray/latest/devguide/xray-concepts.html#xray-concepts-tracingheader
+/// [XrayIdGenerator]: crate::trace::XrayIdGenerator
+#[derive(Clone, Debug, Default)]
+pub struct XrayPropagator {
+    _private: (),
+}
+
+impl XrayPropagator {
+    /// Create a new `XrayPropagator`.
+    pub fn new() -> Self {
+        XrayPropagator { _private: () }
+    }
+
+    /// Extract span context from the X-Ray trace header.
+    fn extract_span_context(&self, extractor: &dyn Extractor) -> Result<SpanContext, ()> {
+        let header_value = extractor.get(AWS_XRAY_TRACE_HEADER).unwrap_or("").trim();
+
+        let mut trace_id = None;
+        let mut parent_id = None;
+        let mut trace_flags = TraceFlags::default();
+        let mut trace_state = TraceState::default();
+        for field in header_value.split(';') {
+            let (key, value) = match field.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            if key.eq_ignore_ascii_case(HEADER_ROOT_KEY) {
+                trace_id = Some(trace_id_from_xray(value)?);
+            } else if key.eq_ignore_ascii_case(HEADER_PARENT_KEY) {
+                if value.len() != 16 {
+                    return Err(());
+                }
+                parent_id = Some(SpanId::from_hex(value).map_err(|_| ())?);
+            } else if key.eq_ignore_ascii_case(HEADER_SAMPLED_KEY) {
+                trace_flags = match value {
+                    SAMPLED => TraceFlags::SAMPLED,
+                    NOT_SAMPLED | REQUESTED_SAMPLING_DECISION => TraceFlags::default(),
+                    _ => return Err(()),
+                };
+            } else if key.eq_ignore_ascii_case(HEADER_LINEAGE_KEY) && is_valid_lineage(value) {
+                trace_state = TraceState::from_key_value([(LINEAGE_TRACE_STATE_KEY, value)])
+                    .map_err(|_| ())?;
+            }
+        }
+
+        let span_context = SpanContext::new(
+            trace_id.ok_or(())?,
+            parent_id.ok_or(())?,
+            trace_flags,
+            true,
+            trace_state,
+        );
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+}
+
+impl TextMapPropagator for XrayPropagator {
+    /// Properly encodes the values of the `SpanContext` and injects them
+    /// into the `Injector`.
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let sampled = if span_context.is_sampled() {
+                SAMPLED
+            } else {
+                NOT_SAMPLED
+            };
+            let mut header_value = format!(
+                "{}={};{}={};{}={}",
+                HEADER_ROOT_KEY,
+                trace_id_to_xray(span_context.trace_id()),
+                HEADER_PARENT_KEY,
+                span_context.span_id(),
+                HEADER_SAMPLED_KEY,
+                sampled
+            );
+            if let Some(lineage) = span_context.trace_state().get(LINEAGE_TRACE_STATE_KEY) {
+                header_value.push_str(&format!(";{HEADER_LINEAGE_KEY}={lineage}"));
+            }
+            injector.set(AWS_XRAY_TRACE_HEADER, header_value);
+        }
+    }
+
+    /// Retrieves encoded `SpanContext`s using the `Extractor`. It decodes
+    /// the `SpanContext` and returns it. If no `SpanContext` was retrieved
+    /// OR if the retrieved SpanContext is invalid then an empty `SpanContext`
+    /// is returned.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        self.extract_span_context(extractor)
+            .map(|sc| cx.with_remote_span_context(sc))
+            .unwrap_or_else(|_| cx.clone())
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(AWS_XRAY_HEADER_FIELDS.as_ref())
+    }
+}
+
+/// Parses an X-Ray trace id, `1-{8 hex digits epoch}-{24 hex digits}`.
+fn trace_id_from_xray(value: &str) -> Result<TraceId, ()> {
+    let mut parts = value.split('-');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(AWS_XRAY_VERSION), Some(epoch), Some(id), None)
+            if epoch.len() == 8
+                && id.len() == 24
+                && epoch
+                    .chars()
+                    .chain(id.chars())
+                    .all(|c| c.is_ascii_hexdigit()) =>
+        {
+            TraceId::from_hex(&format!("{epoch}{id}")).map_err(|_| ())
+        }
+        _ => Err(()),
+    }
+}
+
+fn trace_id_to_xray(trace_id: TraceId) -> String {
+    let hex = trace_id.to_string();
+    format!("{}-{}-{}", AWS_XRAY_VERSION, &hex[..8], &hex[8..])
+}
+
+/// Whether a lineage is made of a request counter, an 8 hex digits hash and a
+/// loop counter, e.g. `10:a87bd80c:1`.
+fn is_valid_lineage(value: &str) -> bool {
+    let mut parts = value.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(request_counter), Some(hash), Some(loop_counter), None) => {
+            request_counter.parse::<u16>().map_or(false, |c| c <= 32767)
+                && hash.len() == 8
+                && hash.chars().all(|c| c.is_ascii_hexdigit())
+                && loop_counter.parse::<u8>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(all(test, feature = "testing", feature = "trace"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use std::collections::HashMap;
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Sampled=1;Self=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Root=1-5759e988-bd862e3fe1be46a994272793", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("root=1-5759e988-bd862e3fe1be46a994272793; parent=53995c3f42cd8ad8; sampled=1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=10:a87bd80c:1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::from_key_value([("xray_lineage", "10:a87bd80c:1")]).unwrap())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=a87bd80c:1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_data_invalid() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("", "empty header"),
+            ("Parent=53995c3f42cd8ad8;Sampled=1", "missing root"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1", "missing parent"),
+            ("Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", "wrong version"),
+            ("Root=1-5759e98-bd862e3fe1be46a9942727930;Parent=53995c3f42cd8ad8", "wrong epoch length"),
+            ("Root=1-5759e988bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", "missing separator"),
+            ("Root=1-5759e988-bd862e3fe1be46a99427279z;Parent=53995c3f42cd8ad8", "bogus trace id"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad", "wrong parent length"),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=2", "bogus sampled"),
+            ("Root=1-00000000-000000000000000000000000;Parent=0000000000000000", "zero trace id and span id"),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(&'static str, SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::default(), true, TraceState::default())),
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=10:a87bd80c:1", SpanContext::new(TraceId::from_u128(0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793), SpanId::from_u64(0x5399_5c3f_42cd_8ad8), TraceFlags::SAMPLED, true, TraceState::from_key_value([("xray_lineage", "10:a87bd80c:1")]).unwrap())),
+            ("", SpanContext::empty_context()),
+        ]
+    }
+
+    #[test]
+    fn extract_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (header, expected_context) in extract_data() {
+            let mut extractor = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &expected_context,
+                "{}",
+                header
+            )
+        }
+    }
+
+    #[test]
+    fn extract_xray_reject_invalid() {
+        let propagator = XrayPropagator::new();
+
+        for (invalid_header, reason) in extract_data_invalid() {
+            let mut extractor = HashMap::new();
+            extractor.insert(
+                AWS_XRAY_TRACE_HEADER.to_string(),
+                invalid_header.to_string(),
+            );
+
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+                "{}",
+                reason
+            )
+        }
+    }
+
+    #[test]
+    fn inject_xray() {
+        let propagator = XrayPropagator::new();
+
+        for (expected_header, context) in inject_data() {
+            let mut injector = HashMap::new();
+            propagator.inject_context(
+                &Context::current_with_span(TestSpan(context)),
+                &mut injector,
+            );
+
+            assert_eq!(
+                Extractor::get(&injector, AWS_XRAY_TRACE_HEADER).unwrap_or(""),
+                expected_header
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_xray() {
+        let propagator = XrayPropagator::new();
+        let header =
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=10:a87bd80c:1";
+
+        let mut extractor = HashMap::new();
+        extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_string());
+        let cx = propagator.extract(&extractor);
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(&cx, &mut injector);
+        assert_eq!(
+            Extractor::get(&injector, AWS_XRAY_TRACE_HEADER),
+            Some(header)
+        );
+    }
+}
@@ -30,3 +30,104 @@ pub(crate) const TELEMETRY_SDK_NAME: &str = "telemetry.sdk.name";
 ///
 /// - `1.2.3`
 pub(crate) const TELEMETRY_SDK_VERSION: &str = "telemetry.sdk.version";
+
+/// Name of the host. On Unix systems, it may contain what the hostname command returns, or the fully qualified hostname, or another name specified by the user.
+///
+/// # Examples
+///
+/// - `opentelemetry-test`
+pub(crate) const HOST_NAME: &str = "host.name";
+
+/// The CPU architecture the host system is running on.
+///
+/// # Examples
+///
+/// - `amd64`
+/// - `arm64`
+pub(crate) const HOST_ARCH: &str = "host.arch";
+
+/// The operating system type.
+///
+/// # Examples
+///
+/// - `linux`
+/// - `darwin`
+pub(crate) const OS_TYPE: &str = "os.type";
+
+/// Human readable (not intended to be parsed) OS version information, like e.g. reported by `ver` or `lsb_release -a` commands.
+///
+/// # Examples
+///
+/// - `Ubuntu 22.04.3 LTS`
+pub(crate) const OS_DESCRIPTION: &str = "os.description";
+
+/// Human readable operating system name.
+///
+/// # Examples
+///
+/// - `Ubuntu`
+pub(crate) const OS_NAME: &str = "os.name";
+
+/// The version string of the operating system.
+///
+/// # Examples
+///
+/// - `22.04`
+pub(crate) const OS_VERSION: &str = "os.version";
+
+/// Process identifier (PID).
+///
+/// # Examples
+///
+/// - `1234`
+pub(crate) const PROCESS_PID: &str = "process.pid";
+
+/// The name of the process executable. On Linux based systems, can be set to the `Name` in `proc/[pid]/status`. On Windows, can be set to the base name of `GetProcessImageFileNameW`.
+///
+/// # Examples
+///
+/// - `otelcol`
+pub(crate) const PROCESS_EXECUTABLE_NAME: &str = "process.executable.name";
+
+/// The full path to the process executable. On Linux based systems, can be set to the target of `proc/[pid]/exe`. On Windows, can be set to the result of `GetProcessImageFileNameW`.
+///
+/// # Examples
+///
+/// - `/usr/bin/cmd/otelcol`
+pub(crate) const PROCESS_EXECUTABLE_PATH: &str = "process.executable.path";
+
+/// The command used to launch the process (i.e. the command name). On Linux based systems, can be set to the zeroth string in `proc/[pid]/cmdline`. On Windows, can be set to the first parameter extracted from `GetCommandLineW`.
+///
+/// # Examples
+///
+/// - `cmd/otelcol`
+pub(crate) const PROCESS_COMMAND: &str = "process.command";
+
+/// All the command arguments (including the command/executable itself) as received by the process. On Linux-based systems (and some other Unixoid systems supporting procfs), can be set according to the list of null-delimited strings extracted from `proc/[pid]/cmdline`.
+///
+/// # Examples
+///
+/// - `cmd/otecol`
+/// - `--config=config.yaml`
+pub(crate) const PROCESS_COMMAND_ARGS: &str = "process.command_args";
+
+/// The name of the runtime of this process.
+///
+/// # Examples
+///
+/// - `rustc`
+pub(crate) const PROCESS_RUNTIME_NAME: &str = "process.runtime.name";
+
+/// An additional description about the runtime of the process, for example a specific vendor customization of the runtime environment.
+///
+/// # Examples
+///
+/// - `Rust compiled for x86_64-linux`
+pub(crate) const PROCESS_RUNTIME_DESCRIPTION: &str = "process.runtime.description";
+
+/// Container ID. Usually a UUID, as for example used to [identify Docker containers](https://docs.docker.com/engine/reference/run/#container-identification). The UUID might be abbreviated.
+///
+/// # Examples
+///
+/// - `a3bf90e006b2`
+pub(crate) const CONTAINER_ID: &str = "container.id";
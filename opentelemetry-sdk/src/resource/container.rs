@@ -0,0 +1,125 @@
+//! Container resource detector
+//!
+//! Implementation of `ResourceDetector` to detect the container the process
+//! runs in.
+use crate::resource::{Resource, ResourceDetector};
+use opentelemetry::KeyValue;
+use std::fs;
+use std::time::Duration;
+
+const CGROUP_PATH: &str = "/proc/self/cgroup";
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+const CONTAINER_ID_LENGTH: usize = 64;
+
+/// Detect the container the process runs in.
+///
+/// It provides the container id (`container.id`), parsed from
+/// `/proc/self/cgroup` for cgroup v1 hosts and from `/proc/self/mountinfo` for
+/// cgroup v2 hosts. Outside of a container, or on platforms without procfs, the
+/// detected resource is empty.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/container.md) for details.
+#[derive(Debug)]
+pub struct ContainerResourceDetector;
+
+impl ResourceDetector for ContainerResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let container_id = fs::read_to_string(CGROUP_PATH)
+            .ok()
+            .and_then(|cgroup| container_id_from_cgroup(&cgroup))
+            .or_else(|| {
+                fs::read_to_string(MOUNTINFO_PATH)
+                    .ok()
+                    .and_then(|mountinfo| container_id_from_mountinfo(&mountinfo))
+            });
+
+        match container_id {
+            Some(id) => Resource::new(vec![KeyValue::new(super::CONTAINER_ID, id)]),
+            None => Resource::empty(),
+        }
+    }
+}
+
+/// Find the container id in the content of `/proc/self/cgroup`, whose lines
+/// look like `1:name=systemd:/docker/<id>` or
+/// `0::/system.slice/docker-<id>.scope`.
+fn container_id_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let path = line.splitn(3, ':').nth(2)?;
+        let segment = path.rsplit('/').next()?;
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let id = segment.rsplit('-').next()?;
+        is_container_id(id).then(|| id.to_string())
+    })
+}
+
+/// Find the container id in the content of `/proc/self/mountinfo`, where the
+/// files the runtime mounts into the container, e.g. `/etc/hostname`, have a
+/// root like `/var/lib/docker/containers/<id>/hostname`.
+fn container_id_from_mountinfo(mountinfo: &str) -> Option<String> {
+    mountinfo.lines().find_map(|line| {
+        let root = line.split_whitespace().nth(3)?;
+        let mut segments = root.split('/');
+        while let Some(segment) = segments.next() {
+            if segment == "containers" {
+                let id = segments.next()?;
+                if is_container_id(id) {
+                    return Some(id.to_string());
+                }
+            }
+        }
+        None
+    })
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == CONTAINER_ID_LENGTH && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "ac679f8a8319c8cf7d38e1adf263bc08d231f2ff81abda3915f6e8ba4d64156a";
+
+    #[test]
+    fn test_container_id_from_cgroup() {
+        let cases = [
+            format!("12:pids:/docker/{}\n1:name=systemd:/docker/{}", ID, ID),
+            format!("0::/system.slice/docker-{}.scope", ID),
+            format!(
+                "1:cpu:/kubepods/besteffort/pod2c48913c/cri-containerd-{}.scope",
+                ID
+            ),
+            format!("11:devices:/kubepods.slice/crio-{}.scope", ID),
+        ];
+        for cgroup in cases {
+            assert_eq!(container_id_from_cgroup(&cgroup), Some(ID.to_string()));
+        }
+
+        assert_eq!(container_id_from_cgroup("0::/"), None);
+        assert_eq!(
+            container_id_from_cgroup("0::/user.slice/user-1000.slice/session-3.scope"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_container_id_from_mountinfo() {
+        let mountinfo = format!(
+            "608 607 0:164 / / rw,relatime master:1 - overlay overlay rw,lowerdir=/var/lib/docker/overlay2/l/ABC\n\
+             614 608 254:1 /docker/containers/{}/resolv.conf /etc/resolv.conf rw,relatime - ext4 /dev/vda1 rw\n\
+             615 608 254:1 /docker/containers/{}/hostname /etc/hostname rw,relatime - ext4 /dev/vda1 rw",
+            ID, ID
+        );
+        assert_eq!(
+            container_id_from_mountinfo(&mountinfo),
+            Some(ID.to_string())
+        );
+
+        assert_eq!(
+            container_id_from_mountinfo("22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw"),
+            None
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Host resource detector
+//!
+//! Implementation of `ResourceDetector` to detect the host the process runs on.
+use crate::resource::{Resource, ResourceDetector};
+use opentelemetry::KeyValue;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+/// Detect the host the process runs on.
+///
+/// It provides:
+/// - The hostname (`host.name`), read from `/proc/sys/kernel/hostname` or
+///   `/etc/hostname` on Unix systems, and from the `HOSTNAME` or `COMPUTERNAME`
+///   environment variables otherwise.
+/// - The CPU architecture (`host.arch`), e.g. `amd64` or `arm64`.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/host.md) for details.
+#[derive(Debug)]
+pub struct HostResourceDetector;
+
+impl ResourceDetector for HostResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![KeyValue::new(
+            super::HOST_ARCH,
+            host_arch(env::consts::ARCH),
+        )];
+        if let Some(hostname) = hostname() {
+            attributes.push(KeyValue::new(super::HOST_NAME, hostname));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+fn hostname() -> Option<String> {
+    ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .chain(
+            ["HOSTNAME", "COMPUTERNAME"]
+                .iter()
+                .filter_map(|var| env::var(var).ok()),
+        )
+        .map(|hostname| hostname.trim().to_string())
+        .find(|hostname| !hostname.is_empty())
+}
+
+/// Map a `std::env::consts::ARCH` value to its `host.arch` value.
+fn host_arch(arch: &'static str) -> &'static str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc" => "ppc32",
+        "powerpc64" => "ppc64",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::{Key, Value};
+
+    #[test]
+    fn test_host_arch() {
+        assert_eq!(host_arch("x86_64"), "amd64");
+        assert_eq!(host_arch("aarch64"), "arm64");
+        assert_eq!(host_arch("s390x"), "s390x");
+    }
+
+    #[test]
+    fn test_host_resource_detector() {
+        let resource = HostResourceDetector.detect(Duration::from_secs(0));
+        assert_eq!(
+            resource.get(Key::from_static_str(crate::resource::HOST_ARCH)),
+            Some(Value::from(host_arch(env::consts::ARCH)))
+        );
+    }
+}
@@ -18,15 +18,28 @@
 //! - [`EnvResourceDetector`] - detect resource from environmental variables.
 //! - [`TelemetryResourceDetector`] - detect telemetry SDK's information.
 //!
-//! The OS and Process resource detectors are now packaged separately in the `opentelemetry-resource-detector` [crate](https://github.com/open-telemetry/opentelemetry-rust-contrib/tree/main/opentelemetry-resource-detectors).
+//! The following are also provided, but are not used by default.
+//!
+//! - [`HostResourceDetector`] - detect the hostname and CPU architecture.
+//! - [`OsResourceDetector`] - detect the operating system type and version.
+//! - [`ProcessResourceDetector`] - detect the process id, executable and command line.
+//! - [`ContainerResourceDetector`] - detect the id of the container the process runs in.
+mod container;
 mod env;
+mod host;
+mod os;
+mod process;
 mod telemetry;
 
 mod attributes;
 pub(crate) use attributes::*;
 
+pub use container::ContainerResourceDetector;
 pub use env::EnvResourceDetector;
 pub use env::SdkProvidedResourceDetector;
+pub use host::HostResourceDetector;
+pub use os::OsResourceDetector;
+pub use process::ProcessResourceDetector;
 pub use telemetry::TelemetryResourceDetector;
 
 use opentelemetry::{Key, KeyValue, Value};
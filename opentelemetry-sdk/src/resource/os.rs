@@ -0,0 +1,125 @@
+//! OS resource detector
+//!
+//! Implementation of `ResourceDetector` to detect the operating system the
+//! process runs on.
+use crate::resource::{Resource, ResourceDetector};
+use opentelemetry::KeyValue;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+const OS_RELEASE_PATHS: [&str; 2] = ["/etc/os-release", "/usr/lib/os-release"];
+
+/// Detect the operating system the process runs on.
+///
+/// It provides:
+/// - The operating system type (`os.type`), e.g. `linux` or `darwin`.
+/// - The name (`os.name`), version (`os.version`) and description
+///   (`os.description`) of the distribution, read from the `NAME`,
+///   `VERSION_ID` and `PRETTY_NAME` fields of [`os-release`] when available.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/os.md) for details.
+///
+/// [`os-release`]: https://www.freedesktop.org/software/systemd/man/os-release.html
+#[derive(Debug)]
+pub struct OsResourceDetector;
+
+impl ResourceDetector for OsResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![KeyValue::new(super::OS_TYPE, os_type(env::consts::OS))];
+        if let Some(os_release) = OS_RELEASE_PATHS
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+        {
+            attributes.extend(parse_os_release(&os_release));
+        }
+
+        Resource::new(attributes)
+    }
+}
+
+/// Map a `std::env::consts::OS` value to its `os.type` value.
+fn os_type(os: &'static str) -> &'static str {
+    match os {
+        "macos" => "darwin",
+        "dragonfly" => "dragonflybsd",
+        "illumos" | "solaris" => "solaris",
+        other => other,
+    }
+}
+
+/// Extract the `os.*` attributes from the content of an `os-release` file.
+fn parse_os_release(content: &str) -> Vec<KeyValue> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.trim().split_once('=')?;
+            let key = match name {
+                "NAME" => super::OS_NAME,
+                "VERSION_ID" => super::OS_VERSION,
+                "PRETTY_NAME" => super::OS_DESCRIPTION,
+                _ => return None,
+            };
+            let value = unquote(value.trim());
+            if value.is_empty() {
+                None
+            } else {
+                Some(KeyValue::new(key, value))
+            }
+        })
+        .collect()
+}
+
+/// Remove the quotes around an `os-release` value, along with the escaping
+/// backslashes of double quoted values.
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].to_string();
+    }
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut unquoted = String::with_capacity(value.len());
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => unquoted.extend(chars.next()),
+                c => unquoted.push(c),
+            }
+        }
+        return unquoted;
+    }
+
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_os_release() {
+        let content = r#"
+PRETTY_NAME="Ubuntu 22.04.3 LTS"
+NAME='Ubuntu'
+VERSION_ID="22.04"
+VERSION="22.04.3 LTS (Jammy \"Jellyfish\")"
+ID=ubuntu
+BUILD_ID=
+"#;
+        assert_eq!(
+            parse_os_release(content),
+            vec![
+                KeyValue::new(crate::resource::OS_DESCRIPTION, "Ubuntu 22.04.3 LTS"),
+                KeyValue::new(crate::resource::OS_NAME, "Ubuntu"),
+                KeyValue::new(crate::resource::OS_VERSION, "22.04"),
+            ]
+        );
+        assert_eq!(unquote(r#""Jammy \"Jellyfish\"""#), r#"Jammy "Jellyfish""#);
+    }
+
+    #[test]
+    fn test_os_type() {
+        assert_eq!(os_type("linux"), "linux");
+        assert_eq!(os_type("macos"), "darwin");
+        assert_eq!(os_type("windows"), "windows");
+    }
+}
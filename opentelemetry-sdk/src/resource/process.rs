@@ -0,0 +1,93 @@
+//! Process resource detector
+//!
+//! Implementation of `ResourceDetector` to detect the process producing
+//! telemetry.
+use crate::resource::{Resource, ResourceDetector};
+use opentelemetry::{KeyValue, StringValue, Value};
+use std::env;
+use std::process;
+use std::time::Duration;
+
+/// Detect the process producing telemetry.
+///
+/// It provides:
+/// - The process identifier (`process.pid`).
+/// - The name (`process.executable.name`) and full path
+///   (`process.executable.path`) of the executable.
+/// - The command (`process.command`) and all the arguments
+///   (`process.command_args`) the process was launched with.
+/// - The runtime name (`process.runtime.name`), `rustc`, and a description of
+///   the compilation target (`process.runtime.description`).
+///
+/// Note that the command arguments may contain sensitive data.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/process.md) for details.
+#[derive(Debug)]
+pub struct ProcessResourceDetector;
+
+impl ResourceDetector for ProcessResourceDetector {
+    fn detect(&self, _timeout: Duration) -> Resource {
+        let mut attributes = vec![
+            KeyValue::new(super::PROCESS_PID, process::id() as i64),
+            KeyValue::new(super::PROCESS_RUNTIME_NAME, "rustc"),
+            KeyValue::new(
+                super::PROCESS_RUNTIME_DESCRIPTION,
+                format!(
+                    "Rust compiled for {}-{}",
+                    env::consts::ARCH,
+                    env::consts::OS
+                ),
+            ),
+        ];
+
+        if let Ok(path) = env::current_exe() {
+            if let Some(name) = path.file_name() {
+                attributes.push(KeyValue::new(
+                    super::PROCESS_EXECUTABLE_NAME,
+                    name.to_string_lossy().into_owned(),
+                ));
+            }
+            attributes.push(KeyValue::new(
+                super::PROCESS_EXECUTABLE_PATH,
+                path.to_string_lossy().into_owned(),
+            ));
+        }
+
+        let args: Vec<StringValue> = env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned().into())
+            .collect();
+        if let Some(command) = args.first() {
+            attributes.push(KeyValue::new(super::PROCESS_COMMAND, command.clone()));
+        }
+        attributes.push(KeyValue::new(
+            super::PROCESS_COMMAND_ARGS,
+            Value::Array(args.into()),
+        ));
+
+        Resource::new(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::Key;
+
+    #[test]
+    fn test_process_resource_detector() {
+        let resource = ProcessResourceDetector.detect(Duration::from_secs(0));
+        assert_eq!(
+            resource.get(Key::from_static_str(crate::resource::PROCESS_PID)),
+            Some(Value::from(process::id() as i64))
+        );
+        assert!(resource
+            .get(Key::from_static_str(
+                crate::resource::PROCESS_EXECUTABLE_PATH
+            ))
+            .is_some());
+        assert!(matches!(
+            resource.get(Key::from_static_str(crate::resource::PROCESS_COMMAND_ARGS)),
+            Some(Value::Array(_))
+        ));
+    }
+}
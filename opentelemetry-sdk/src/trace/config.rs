@@ -73,6 +73,13 @@ impl Config {
         self
     }
 
+    /// Specify the maximum length of the string attribute values of spans,
+    /// events and links.
+    pub fn with_max_attribute_value_length(mut self, max_length: u32) -> Self {
+        self.span_limits = self.span_limits.with_max_attribute_value_length(max_length);
+        self
+    }
+
     /// Specify all limit via the span_limits
     pub fn with_span_limits(mut self, span_limits: SpanLimits) -> Self {
         self.span_limits = span_limits;
@@ -117,6 +124,16 @@ impl Default for Config {
             config.span_limits.max_links_per_span = max_links_per_span;
         }
 
+        if let Some(max_attribute_value_length) = env::var("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT")
+            .or_else(|_| env::var("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT"))
+            .ok()
+            .and_then(|length_limit| u32::from_str(&length_limit).ok())
+        {
+            config.span_limits = config
+                .span_limits
+                .with_max_attribute_value_length(max_attribute_value_length);
+        }
+
         let sampler_arg = env::var("OTEL_TRACES_SAMPLER_ARG").ok();
         if let Ok(sampler) = env::var("OTEL_TRACES_SAMPLER") {
             config.sampler = match sampler.as_str() {
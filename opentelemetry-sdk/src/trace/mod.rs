@@ -14,8 +14,14 @@ mod provider;
 mod sampler;
 mod span;
 mod span_limit;
+#[cfg(feature = "metrics")]
+mod span_metrics;
 mod span_processor;
+mod tail_sampling;
 mod tracer;
+#[cfg(feature = "zpages")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zpages")))]
+pub mod zpages;
 
 pub use config::{config, Config};
 pub use events::SpanEvents;
@@ -31,15 +37,23 @@ pub use provider::{Builder, TracerProvider};
 pub use sampler::{Sampler, ShouldSample};
 pub use span::Span;
 pub use span_limit::SpanLimits;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use span_metrics::{SpanMetricsProcessor, SpanMetricsProcessorBuilder};
 pub use span_processor::{
     BatchConfig, BatchConfigBuilder, BatchSpanProcessor, BatchSpanProcessorBuilder,
-    SimpleSpanProcessor, SpanProcessor,
+    SimpleSpanProcessor, SpanProcessor, ThreadedBatchSpanProcessor,
+    ThreadedBatchSpanProcessorBuilder,
 };
+pub use tail_sampling::{TailSamplingPolicy, TailSamplingProcessor, TailSamplingProcessorBuilder};
 pub use tracer::Tracer;
 
 #[cfg(feature = "jaeger_remote_sampler")]
 pub use sampler::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
 
+#[cfg(feature = "zpages")]
+pub use zpages::ZPagesSpanProcessor;
+
 #[cfg(test)]
 mod runtime_tests;
 
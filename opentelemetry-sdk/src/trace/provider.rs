@@ -10,6 +10,7 @@
 //! of the [`TracerProvider`] have different versions of these data.
 use crate::runtime::RuntimeChannel;
 use crate::trace::{BatchSpanProcessor, SimpleSpanProcessor, Tracer};
+use crate::util::sdk_disabled;
 use crate::{export::trace::SpanExporter, trace::SpanProcessor};
 use crate::{InstrumentationLibrary, Resource};
 use once_cell::sync::OnceCell;
@@ -26,6 +27,7 @@ static PROVIDER_RESOURCE: OnceCell<Resource> = OnceCell::new();
 pub(crate) struct TracerProviderInner {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: crate::trace::Config,
+    is_disabled: bool,
 }
 
 impl Drop for TracerProviderInner {
@@ -71,6 +73,12 @@ impl TracerProvider {
         &self.inner.config
     }
 
+    /// Whether the SDK was disabled by the `OTEL_SDK_DISABLED` environment
+    /// variable when this provider was built.
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.inner.is_disabled
+    }
+
     /// Force flush all remaining spans in span processors and return results.
     ///
     /// # Examples
@@ -196,6 +204,9 @@ impl Builder {
     }
 
     /// Create a new provider from this configuration.
+    ///
+    /// The tracers of the provider only create non-recording spans if the
+    /// `OTEL_SDK_DISABLED` environment variable is set to `true`.
     pub fn build(self) -> TracerProvider {
         let mut config = self.config;
 
@@ -222,6 +233,7 @@ impl Builder {
             inner: Arc::new(TracerProviderInner {
                 processors: self.processors,
                 config,
+                is_disabled: sdk_disabled(),
             }),
         }
     }
@@ -277,6 +289,7 @@ mod tests {
                 Box::from(TestSpanProcessor { success: false }),
             ],
             config: Default::default(),
+            is_disabled: false,
         }));
 
         let results = tracer_provider.force_flush();
@@ -412,4 +425,37 @@ mod tests {
 
         assert_eq!(no_service_name.config().resource.len(), 0)
     }
+
+    #[test]
+    fn test_sdk_disabled() {
+        use opentelemetry::trace::{
+            Span as _, SpanContext, SpanId, TraceContextExt as _, TraceFlags, TraceId, Tracer as _,
+            TracerProvider as _,
+        };
+
+        let provider = temp_env::with_var("OTEL_SDK_DISABLED", Some("true"), || {
+            super::TracerProvider::builder().build()
+        });
+        let span = provider.tracer("test").start("span");
+        assert!(!span.is_recording());
+        assert!(!span.span_context().is_valid());
+
+        // like the no-op tracer, the parent span context is propagated
+        let cx = Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            TraceFlags::SAMPLED,
+            true,
+            Default::default(),
+        ));
+        let span = provider.tracer("test").start_with_context("span", &cx);
+        assert!(!span.is_recording());
+        assert_eq!(span.span_context().trace_id(), TraceId::from_u128(1));
+        assert_eq!(span.span_context().span_id(), SpanId::from_u64(2));
+
+        let provider = temp_env::with_var("OTEL_SDK_DISABLED", Some("false"), || {
+            super::TracerProvider::builder().build()
+        });
+        assert!(provider.tracer("test").start("span").is_recording());
+    }
 }
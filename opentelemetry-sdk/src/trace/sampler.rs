@@ -5,9 +5,13 @@ use opentelemetry::{
     Context, KeyValue,
 };
 
+mod consistent;
 #[cfg(feature = "jaeger_remote_sampler")]
 mod jaeger_remote;
 
+#[cfg(feature = "metrics")]
+pub(crate) use consistent::ADJUSTED_COUNT;
+
 #[cfg(feature = "jaeger_remote_sampler")]
 pub use jaeger_remote::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
 #[cfg(feature = "jaeger_remote_sampler")]
@@ -123,6 +127,23 @@ pub enum Sampler {
     /// *Note:* If this is used then all Spans in a trace will become sampled assuming that the
     /// first span is sampled as it is based on the `trace_id` not the `span_id`
     TraceIdRatioBased(f64),
+    /// Sample a given fraction of traces consistently across services, regardless of the parent
+    /// span's decision. Fractions >= 1 will always sample, fractions <= 0 will never sample.
+    ///
+    /// The decision compares the explicit randomness in the `rv` of the `ot` trace state entry,
+    /// or the lower 56 bits of the trace id, to the rejection threshold of the fraction, which is
+    /// written to the `th` of the `ot` entry of sampled spans. Sampled spans also record their
+    /// adjusted count, the number of spans they represent, in the `sampling.adjusted_count`
+    /// attribute.
+    ConsistentProbabilityBased(f64),
+    /// Respects the parent span's sampling decision and the threshold of its `ot` trace state
+    /// entry, or delegates to a delegate sampler for root spans, typically a
+    /// [`Sampler::ConsistentProbabilityBased`].
+    ///
+    /// The threshold of the parent is removed when the parent is not sampled or when it is
+    /// inconsistent with the randomness of the trace. Otherwise the span records the adjusted
+    /// count derived from it in the `sampling.adjusted_count` attribute.
+    ConsistentParentBased(Box<dyn ShouldSample>),
     /// Jaeger remote sampler supports any remote service that implemented the jaeger remote sampler protocol.
     /// The proto definition can be found [here](https://github.com/jaegertracing/jaeger-idl/blob/main/proto/api_v2/sampling.proto)
     ///
@@ -205,6 +226,22 @@ impl ShouldSample for Sampler {
                 ),
             // Probabilistically sample the trace.
             Sampler::TraceIdRatioBased(prob) => sample_based_on_probability(prob, trace_id),
+            // Probabilistically sample the trace, recording the threshold in the trace state.
+            Sampler::ConsistentProbabilityBased(prob) => {
+                return consistent::sample_by_probability(*prob, parent_context, trace_id)
+            }
+            // The parent decision and threshold; otherwise the result of delegate_sampler
+            Sampler::ConsistentParentBased(delegate_sampler) => {
+                return consistent::sample_parent_based(
+                    delegate_sampler.as_ref(),
+                    parent_context,
+                    trace_id,
+                    name,
+                    span_kind,
+                    attributes,
+                    links,
+                )
+            }
             #[cfg(feature = "jaeger_remote_sampler")]
             Sampler::JaegerRemote(remote_sampler) => {
                 remote_sampler
@@ -295,6 +332,16 @@ mod tests {
 
             // Spans with a sampled parent, but when using the NeverSample Sampler, aren't sampled
             ("sampled_parent_span_with_never_sample", Sampler::AlwaysOff, 0.0, true, true),
+
+            // Consistent samplers sample the same fraction of traces
+            ("consistent_-1", Sampler::ConsistentProbabilityBased(-1.0), 0.0, false, false),
+            ("consistent_.25", Sampler::ConsistentProbabilityBased(0.25), 0.25, false, false),
+            ("consistent_.75", Sampler::ConsistentProbabilityBased(0.75), 0.75, false, false),
+            ("consistent_2.0", Sampler::ConsistentProbabilityBased(2.0), 1.0, false, false),
+            ("consistent_delegate_to_.25", Sampler::ConsistentParentBased(Box::new(Sampler::ConsistentProbabilityBased(0.25))), 0.25, false, false),
+            ("unsampled_parent_with_consistent_.25", Sampler::ConsistentProbabilityBased(0.25), 0.25, true, false),
+            ("unsampled_parent_consistent_delegate_to_always_on", Sampler::ConsistentParentBased(Box::new(Sampler::AlwaysOn)), 0.0, true, false),
+            ("sampled_parent_consistent_delegate_to_always_off", Sampler::ConsistentParentBased(Box::new(Sampler::AlwaysOff)), 1.0, true, true),
         ]
     }
 
@@ -441,4 +488,120 @@ mod tests {
             assert_eq!(result.decision, expected);
         }
     }
+
+    #[test]
+    fn consistent_sampling() {
+        let mut rng = rand::thread_rng();
+        let sample = |sampler: &Sampler, parent_cx: Option<&Context>, trace_id| {
+            sampler.should_sample(parent_cx, trace_id, "span", &SpanKind::Internal, &[], &[])
+        };
+        let root =
+            Sampler::ConsistentParentBased(Box::new(Sampler::ConsistentProbabilityBased(0.25)));
+
+        for _ in 0..1_000 {
+            let trace_id = TraceId::from(rng.gen::<u128>());
+            let result = sample(&root, None, trace_id);
+            // Traces sampled with a lower probability are sampled with a higher one
+            let consistent = sample(&Sampler::ConsistentProbabilityBased(0.5), None, trace_id);
+            if result.decision == SamplingDecision::Drop {
+                assert_eq!(result.trace_state.get("ot"), None);
+                assert!(result.attributes.is_empty());
+                continue;
+            }
+            assert_eq!(consistent.decision, SamplingDecision::RecordAndSample);
+            assert_eq!(consistent.trace_state.get("ot"), Some("th:8"));
+            assert_eq!(result.trace_state.get("ot"), Some("th:c"));
+            assert_eq!(
+                result.attributes,
+                vec![KeyValue::new("sampling.adjusted_count", 4.0)]
+            );
+
+            // Children follow the parent, recording its adjusted count
+            let parent_cx = Context::current_with_span(TestSpan(SpanContext::new(
+                trace_id,
+                SpanId::from_u64(1),
+                TraceFlags::SAMPLED,
+                true,
+                result.trace_state,
+            )));
+            let child = sample(&root, Some(&parent_cx), trace_id);
+            assert_eq!(child.decision, SamplingDecision::RecordAndSample);
+            assert_eq!(child.trace_state.get("ot"), Some("th:c"));
+            assert_eq!(
+                child.attributes,
+                vec![KeyValue::new("sampling.adjusted_count", 4.0)]
+            );
+        }
+    }
+
+    #[test]
+    fn consistent_sampling_with_explicit_randomness() {
+        let sample = |sampler: Sampler, sampled: bool, ot: &str| {
+            let trace_flags = if sampled {
+                TraceFlags::SAMPLED
+            } else {
+                TraceFlags::default()
+            };
+            let parent_cx = Context::current_with_span(TestSpan(SpanContext::new(
+                // Randomness of the trace id is ignored, as it is below every threshold
+                TraceId::from_u128(1),
+                SpanId::from_u64(1),
+                trace_flags,
+                true,
+                TraceState::from_key_value([("ot", ot), ("vendor", "value")]).unwrap(),
+            )));
+            let result = sampler.should_sample(
+                Some(&parent_cx),
+                TraceId::from_u128(1),
+                "span",
+                &SpanKind::Internal,
+                &[],
+                &[],
+            );
+            (result.decision, result.trace_state.header())
+        };
+        let parent_based = || Sampler::ConsistentParentBased(Box::new(Sampler::AlwaysOff));
+
+        assert_eq!(
+            sample(
+                Sampler::ConsistentProbabilityBased(0.5),
+                false,
+                "rv:c0000000000000"
+            ),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=th:8;rv:c0000000000000,vendor=value".to_string()
+            )
+        );
+        assert_eq!(
+            sample(
+                Sampler::ConsistentProbabilityBased(0.25),
+                true,
+                "th:8;rv:80000000000000"
+            ),
+            (
+                SamplingDecision::Drop,
+                "ot=rv:80000000000000,vendor=value".to_string()
+            )
+        );
+        assert_eq!(
+            sample(parent_based(), true, "th:8;rv:80000000000000"),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=th:8;rv:80000000000000,vendor=value".to_string()
+            )
+        );
+        // Thresholds inconsistent with the randomness are removed
+        assert_eq!(
+            sample(parent_based(), true, "th:c;rv:80000000000000"),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=rv:80000000000000,vendor=value".to_string()
+            )
+        );
+        assert_eq!(
+            sample(parent_based(), false, "th:8"),
+            (SamplingDecision::Drop, "vendor=value".to_string())
+        );
+    }
 }
@@ -0,0 +1,286 @@
+//! Consistent probability sampling, as described by the OpenTelemetry
+//! [tracestate handling] specification.
+//!
+//! Sampling decisions compare a 56-bit randomness value, either the explicit
+//! `rv` of the `ot` tracestate entry or the lower 56 bits of the trace id, to
+//! a rejection threshold: a span is sampled when its randomness is greater
+//! than or equal to the threshold. The threshold of sampled spans is written
+//! to the `th` of the `ot` entry, so services sampling with different
+//! probabilities reach consistent decisions, and the adjusted count of a span,
+//! the number of spans it represents, can be derived from it.
+//!
+//! [tracestate handling]: https://opentelemetry.io/docs/specs/otel/trace/tracestate-handling/
+use opentelemetry::{
+    global,
+    trace::{
+        Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId, TraceState,
+    },
+    Context, Key, KeyValue,
+};
+
+use super::ShouldSample;
+
+/// The span attribute the adjusted count of consistently sampled spans is
+/// recorded in.
+pub(crate) const ADJUSTED_COUNT: Key = Key::from_static_str("sampling.adjusted_count");
+
+/// The tracestate key of the OpenTelemetry entry.
+const OT_KEY: &str = "ot";
+const THRESHOLD_KEY: &str = "th";
+const RANDOMNESS_KEY: &str = "rv";
+
+/// The number of hexadecimal digits of thresholds and randomness values.
+const HEX_DIGITS: usize = 14;
+/// The number of distinct randomness values, 2^56.
+const MAX_THRESHOLD: u64 = 1 << 56;
+
+/// The `ot` entry of a tracestate, as `key:value` pairs separated by `;`.
+#[derive(Debug, Default, PartialEq)]
+struct OtTraceState {
+    threshold: Option<u64>,
+    randomness: Option<u64>,
+    /// Other entries, kept as is.
+    rest: Vec<(String, String)>,
+}
+
+impl OtTraceState {
+    /// Parses the `ot` entry of `trace_state`, ignoring invalid thresholds and
+    /// randomness values.
+    fn from_trace_state(trace_state: &TraceState) -> Self {
+        let mut ot = OtTraceState::default();
+        for (key, value) in trace_state
+            .get(OT_KEY)
+            .unwrap_or_default()
+            .split(';')
+            .filter_map(|entry| entry.split_once(':'))
+        {
+            match key {
+                THRESHOLD_KEY => ot.threshold = parse_threshold(value),
+                RANDOMNESS_KEY => ot.randomness = parse_randomness(value),
+                _ => ot.rest.push((key.to_string(), value.to_string())),
+            }
+        }
+        ot
+    }
+
+    /// The randomness of the trace, the explicit one if any.
+    fn randomness(&self, trace_id: TraceId) -> u64 {
+        self.randomness.unwrap_or_else(|| {
+            u128::from_be_bytes(trace_id.to_bytes()) as u64 & (MAX_THRESHOLD - 1)
+        })
+    }
+
+    /// Writes this entry in `trace_state`, removing it if empty.
+    fn apply(&self, trace_state: &TraceState) -> TraceState {
+        let mut entries = Vec::with_capacity(self.rest.len() + 2);
+        if let Some(threshold) = self.threshold {
+            entries.push(format!("{}:{}", THRESHOLD_KEY, format_threshold(threshold)));
+        }
+        if let Some(randomness) = self.randomness {
+            entries.push(format!("{}:{:014x}", RANDOMNESS_KEY, randomness));
+        }
+        entries.extend(
+            self.rest
+                .iter()
+                .map(|(key, value)| format!("{key}:{value}")),
+        );
+
+        let updated = if entries.is_empty() {
+            if trace_state.get(OT_KEY).is_none() {
+                return trace_state.clone();
+            }
+            trace_state.delete(OT_KEY)
+        } else {
+            trace_state.insert(OT_KEY, entries.join(";"))
+        };
+        updated.unwrap_or_else(|err| {
+            global::handle_error(err);
+            trace_state.clone()
+        })
+    }
+}
+
+/// Parses a threshold of 1 to 14 lowercase hexadecimal digits, right padded
+/// with zeros.
+fn parse_threshold(value: &str) -> Option<u64> {
+    if value.is_empty() || value.len() > HEX_DIGITS || !is_lower_hex(value) {
+        return None;
+    }
+    let threshold = u64::from_str_radix(value, 16).ok()?;
+    Some(threshold << (4 * (HEX_DIGITS - value.len())))
+}
+
+/// Parses a randomness value of exactly 14 lowercase hexadecimal digits.
+fn parse_randomness(value: &str) -> Option<u64> {
+    if value.len() != HEX_DIGITS || !is_lower_hex(value) {
+        return None;
+    }
+    u64::from_str_radix(value, 16).ok()
+}
+
+fn is_lower_hex(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Formats a threshold without its trailing zeros, `0` for the threshold
+/// sampling every span.
+fn format_threshold(threshold: u64) -> String {
+    let formatted = format!("{:014x}", threshold);
+    match formatted.trim_end_matches('0') {
+        "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// The rejection threshold sampling spans with probability `prob`, `None` if
+/// no span is sampled.
+fn threshold(prob: f64) -> Option<u64> {
+    if prob >= 1.0 {
+        return Some(0);
+    }
+    let accepted = (prob.max(0.0) * MAX_THRESHOLD as f64).round() as u64;
+    (accepted > 0).then(|| MAX_THRESHOLD - accepted)
+}
+
+/// The number of spans represented by a span sampled with `threshold`.
+fn adjusted_count(threshold: u64) -> f64 {
+    MAX_THRESHOLD as f64 / (MAX_THRESHOLD - threshold) as f64
+}
+
+fn parent_trace_state(parent_context: Option<&Context>) -> TraceState {
+    match parent_context {
+        Some(ctx) => ctx.span().span_context().trace_state().clone(),
+        None => TraceState::default(),
+    }
+}
+
+fn sampling_result(sampled: bool, ot: &OtTraceState, trace_state: &TraceState) -> SamplingResult {
+    SamplingResult {
+        decision: if sampled {
+            SamplingDecision::RecordAndSample
+        } else {
+            SamplingDecision::Drop
+        },
+        attributes: ot
+            .threshold
+            .map(|threshold| vec![KeyValue::new(ADJUSTED_COUNT, adjusted_count(threshold))])
+            .unwrap_or_default(),
+        trace_state: ot.apply(trace_state),
+    }
+}
+
+/// Samples the trace with probability `prob`, regardless of the parent's
+/// decision.
+pub(crate) fn sample_by_probability(
+    prob: f64,
+    parent_context: Option<&Context>,
+    trace_id: TraceId,
+) -> SamplingResult {
+    let trace_state = parent_trace_state(parent_context);
+    let mut ot = OtTraceState::from_trace_state(&trace_state);
+    let threshold = threshold(prob);
+    let sampled = threshold.map_or(false, |threshold| ot.randomness(trace_id) >= threshold);
+    ot.threshold = threshold.filter(|_| sampled);
+
+    sampling_result(sampled, &ot, &trace_state)
+}
+
+/// Follows the parent's decision, keeping its threshold if consistent with
+/// the randomness of the trace, or the one of `delegate` for root spans.
+pub(crate) fn sample_parent_based(
+    delegate: &dyn ShouldSample,
+    parent_context: Option<&Context>,
+    trace_id: TraceId,
+    name: &str,
+    span_kind: &SpanKind,
+    attributes: &[KeyValue],
+    links: &[Link],
+) -> SamplingResult {
+    match parent_context.filter(|cx| cx.has_active_span()) {
+        Some(cx) => {
+            let span = cx.span();
+            let parent_span_context = span.span_context();
+            let trace_state = parent_span_context.trace_state();
+            let mut ot = OtTraceState::from_trace_state(trace_state);
+            let sampled = parent_span_context.is_sampled();
+            let randomness = ot.randomness(trace_id);
+            ot.threshold = ot
+                .threshold
+                .filter(|threshold| sampled && randomness >= *threshold);
+
+            sampling_result(sampled, &ot, trace_state)
+        }
+        None => {
+            delegate.should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ot_trace_state() {
+        let trace_state =
+            TraceState::from_key_value([("ot", "th:c;rv:0123456789abcd;p:8"), ("vendor", "v")])
+                .unwrap();
+        let ot = OtTraceState::from_trace_state(&trace_state);
+        assert_eq!(
+            ot,
+            OtTraceState {
+                threshold: Some(0xc0_0000_0000_0000),
+                randomness: Some(0x01_2345_6789_abcd),
+                rest: vec![("p".to_string(), "8".to_string())],
+            }
+        );
+        assert_eq!(
+            ot.apply(&trace_state).header(),
+            "ot=th:c;rv:0123456789abcd;p:8,vendor=v"
+        );
+
+        for invalid in ["th:;rv:123", "th:C;rv:0123456789ABCD", "th:123456789abcdef"] {
+            let trace_state = TraceState::from_key_value([("ot", invalid)]).unwrap();
+            let ot = OtTraceState::from_trace_state(&trace_state);
+            assert_eq!(ot, OtTraceState::default(), "{}", invalid);
+            assert_eq!(ot.apply(&trace_state).header(), "");
+        }
+    }
+
+    #[test]
+    fn thresholds() {
+        assert_eq!(threshold(1.0), Some(0));
+        assert_eq!(threshold(2.0), Some(0));
+        assert_eq!(threshold(0.0), None);
+        assert_eq!(threshold(-1.0), None);
+        assert_eq!(threshold(1e-20), None);
+
+        for (prob, formatted, count) in [
+            (1.0, "0", 1.0),
+            (0.5, "8", 2.0),
+            (0.25, "c", 4.0),
+            (0.1, "e6666666666666", 10.0),
+        ] {
+            let threshold = threshold(prob).unwrap();
+            assert_eq!(format_threshold(threshold), formatted);
+            assert_eq!(parse_threshold(formatted), Some(threshold));
+            assert!((adjusted_count(threshold) - count).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn randomness() {
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        assert_eq!(
+            OtTraceState::default().randomness(trace_id),
+            0xce_929d_0e0e_4736
+        );
+        let explicit = OtTraceState {
+            randomness: Some(42),
+            ..Default::default()
+        };
+        assert_eq!(explicit.randomness(trace_id), 42);
+    }
+}
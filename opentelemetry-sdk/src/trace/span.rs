@@ -72,6 +72,12 @@ impl Span {
         self.data.as_mut().map(f)
     }
 
+    /// The name of the span, if it is recording.
+    #[cfg(feature = "zpages")]
+    pub(crate) fn name(&self) -> Option<&Cow<'static, str>> {
+        self.data.as_ref().map(|data| &data.name)
+    }
+
     /// Convert information in this span into `exporter::trace::SpanData`.
     /// This function copies all data from the current span, which will create a
     /// overhead.
@@ -101,11 +107,13 @@ impl opentelemetry::trace::Span for Span {
     {
         let span_events_limit = self.span_limits.max_events_per_span as usize;
         let event_attributes_limit = self.span_limits.max_attributes_per_event as usize;
+        let span_limits = self.span_limits;
         self.with_data(|data| {
             if data.events.len() < span_events_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(event_attributes_limit);
                 attributes.truncate(event_attributes_limit);
+                span_limits.truncate_values(&mut attributes);
 
                 data.events.add_event(Event::new(
                     name,
@@ -136,10 +144,12 @@ impl opentelemetry::trace::Span for Span {
     /// Note that the OpenTelemetry project documents certain ["standard
     /// attributes"](https://github.com/open-telemetry/opentelemetry-specification/tree/v0.5.0/specification/trace/semantic_conventions/README.md)
     /// that have prescribed semantic meanings.
-    fn set_attribute(&mut self, attribute: KeyValue) {
+    fn set_attribute(&mut self, mut attribute: KeyValue) {
         let span_attribute_limit = self.span_limits.max_attributes_per_span as usize;
+        let span_limits = self.span_limits;
         self.with_data(|data| {
             if data.attributes.len() < span_attribute_limit {
+                span_limits.truncate_values(std::slice::from_mut(&mut attribute));
                 data.attributes.push(attribute);
             } else {
                 data.dropped_attributes_count += 1;
@@ -175,12 +185,14 @@ impl opentelemetry::trace::Span for Span {
     fn add_link(&mut self, span_context: SpanContext, attributes: Vec<KeyValue>) {
         let span_links_limit = self.span_limits.max_links_per_span as usize;
         let link_attributes_limit = self.span_limits.max_attributes_per_link as usize;
+        let span_limits = self.span_limits;
         self.with_data(|data| {
             if data.links.links.len() < span_links_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(link_attributes_limit);
                 let mut attributes = attributes;
                 attributes.truncate(link_attributes_limit);
+                span_limits.truncate_values(&mut attributes);
                 data.links.add_link(Link::new(
                     span_context,
                     attributes,
@@ -573,6 +585,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn truncate_attribute_values() {
+        let provider = crate::trace::TracerProvider::builder()
+            .with_config(crate::trace::config().with_max_attribute_value_length(3))
+            .build();
+        let tracer = provider.tracer("opentelemetry-test");
+
+        let link = Link::new(
+            SpanContext::new(
+                TraceId::from_u128(12),
+                SpanId::from_u64(12),
+                TraceFlags::default(),
+                false,
+                Default::default(),
+            ),
+            vec![KeyValue::new("link", "truncated")],
+            0,
+        );
+        let span_builder = tracer
+            .span_builder("test")
+            .with_attributes(vec![KeyValue::new("builder", "truncated")])
+            .with_links(vec![link]);
+        let mut span = tracer.build(span_builder);
+        span.set_attribute(KeyValue::new("set", "truncated"));
+        span.set_attribute(KeyValue::new("short", "ok"));
+        span.add_event("event", vec![KeyValue::new("event", "truncated")]);
+
+        let data = span.data.clone().expect("span data should not be empty");
+        assert_eq!(
+            data.attributes,
+            vec![
+                KeyValue::new("builder", "tru"),
+                KeyValue::new("set", "tru"),
+                KeyValue::new("short", "ok"),
+            ]
+        );
+        assert_eq!(data.dropped_attributes_count, 0);
+        assert_eq!(
+            data.events.events[0].attributes,
+            vec![KeyValue::new("event", "tru")]
+        );
+        assert_eq!(
+            data.links.links[0].attributes,
+            vec![KeyValue::new("link", "tru")]
+        );
+    }
+
     #[test]
     fn exceed_event_attributes_limit() {
         let exporter = NoopSpanExporter::new();
@@ -1,3 +1,7 @@
+use opentelemetry::{Array, KeyValue, Value};
+
+use crate::util::truncate_string;
+
 /// # Span limit
 /// Erroneous code can add unintended attributes, events, and links to a span. If these collections
 /// are unbounded, they can quickly exhaust available memory, resulting in crashes that are
@@ -9,9 +13,11 @@
 ///  - Maximum allowed span link count
 ///  - Maximum allowed attribute per span event count
 ///  - Maximum allowed attribute per span link count
+///  - Maximum allowed length of the attribute values
 ///
 /// If the limit has been breached. The attributes, events or links will be dropped based on their
-/// index in the collection. The one added to collections later will be dropped first.
+/// index in the collection. The one added to collections later will be dropped first. String
+/// values longer than the length limit are truncated, without being counted as dropped.
 
 pub(crate) const DEFAULT_MAX_EVENT_PER_SPAN: u32 = 128;
 pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_SPAN: u32 = 128;
@@ -32,6 +38,9 @@ pub struct SpanLimits {
     pub max_attributes_per_event: u32,
     /// The max attributes that can be added into a `Link`
     pub max_attributes_per_link: u32,
+    /// The max length of the string attribute values, see
+    /// [`SpanLimits::with_max_attribute_value_length`].
+    max_attribute_value_length: Option<u32>,
 }
 
 impl Default for SpanLimits {
@@ -42,6 +51,76 @@ impl Default for SpanLimits {
             max_links_per_span: DEFAULT_MAX_LINKS_PER_SPAN,
             max_attributes_per_link: DEFAULT_MAX_ATTRIBUTES_PER_LINK,
             max_attributes_per_event: DEFAULT_MAX_ATTRIBUTES_PER_EVENT,
+            max_attribute_value_length: None,
+        }
+    }
+}
+
+impl SpanLimits {
+    /// Set the max length, in characters, of the string attribute values of a `Span`, its
+    /// `Event`s and `Link`s, including the strings of array values. Values are not truncated by
+    /// default.
+    pub fn with_max_attribute_value_length(mut self, max_length: u32) -> Self {
+        self.max_attribute_value_length = Some(max_length);
+        self
+    }
+
+    /// The max length, in characters, of the string attribute values, `None` if the values are
+    /// not truncated.
+    pub fn max_attribute_value_length(&self) -> Option<u32> {
+        self.max_attribute_value_length
+    }
+
+    /// Truncate the values of `attributes` longer than the length limit.
+    pub(crate) fn truncate_values(&self, attributes: &mut [KeyValue]) {
+        if let Some(max_length) = self.max_attribute_value_length {
+            for attribute in attributes {
+                truncate_value(&mut attribute.value, max_length as usize);
+            }
         }
     }
 }
+
+/// Truncate the strings of `value` to `max_length` characters.
+fn truncate_value(value: &mut Value, max_length: usize) {
+    match value {
+        Value::String(s) => truncate_string(s, max_length),
+        Value::Array(Array::String(values)) => values
+            .iter_mut()
+            .for_each(|s| truncate_string(s, max_length)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_values() {
+        let limits = SpanLimits::default().with_max_attribute_value_length(2);
+        let mut attributes = vec![
+            KeyValue::new("string", "héllo"),
+            KeyValue::new("short", "a"),
+            KeyValue::new("int", 12345),
+            KeyValue::new(
+                "array",
+                Value::Array(Array::String(vec!["abc".into(), "d".into()])),
+            ),
+        ];
+        limits.truncate_values(&mut attributes);
+
+        assert_eq!(
+            attributes,
+            vec![
+                KeyValue::new("string", "hé"),
+                KeyValue::new("short", "a"),
+                KeyValue::new("int", 12345),
+                KeyValue::new(
+                    "array",
+                    Value::Array(Array::String(vec!["ab".into(), "d".into()]))
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! # Span Metrics
+//!
+//! Derives request, error and duration metrics from ended spans, so services
+//! get them without instrumenting their operations twice.
+use std::fmt;
+
+use opentelemetry::{
+    metrics::{Counter, Histogram, Meter, Unit},
+    trace::{SpanKind, Status, TraceContextExt, TraceResult},
+    Context, Key, KeyValue, Value,
+};
+
+use crate::export::trace::SpanData;
+
+use super::{sampler::ADJUSTED_COUNT, Span, SpanProcessor};
+
+const SPAN_NAME: Key = Key::from_static_str("span.name");
+const SPAN_KIND: Key = Key::from_static_str("span.kind");
+const STATUS_CODE: Key = Key::from_static_str("status.code");
+
+/// A [`SpanProcessor`] recording the number of calls and the durations of
+/// ended spans with a [`Meter`].
+///
+/// Spans are counted in the `traces.span.metrics.calls` counter and their
+/// durations, in seconds, are recorded in the `traces.span.metrics.duration`
+/// histogram. Both are keyed by the `span.name`, `span.kind` and
+/// `status.code` of the spans, along with the span attributes of the
+/// configured dimensions, and measured in the context of their span so their
+/// exemplars link to it.
+///
+/// Sampled spans count for their adjusted count, the number of spans they
+/// represent: the one recorded in their `sampling.adjusted_count` attribute by
+/// the consistent probability samplers, or otherwise the one configured with
+/// [`SpanMetricsProcessorBuilder::with_adjusted_count`], such as `10` for a
+/// [`Sampler::TraceIdRatioBased`] sampler of ratio `0.1`. The calls are
+/// weighted by it, fractional adjusted counts being rounded randomly so they
+/// are accurate on average, while the duration of a counted span is recorded
+/// once, so the duration histogram describes the sampled spans.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::{metrics::MeterProvider as _, trace::TracerProvider as _};
+/// use opentelemetry_sdk::metrics::SdkMeterProvider;
+/// use opentelemetry_sdk::trace::{Sampler, SpanMetricsProcessor, TracerProvider};
+///
+/// let meter_provider = SdkMeterProvider::builder().build();
+/// let processor = SpanMetricsProcessor::builder(meter_provider.meter("span-metrics"))
+///     .with_dimension("http.route")
+///     .with_adjusted_count(10.0)
+///     .build();
+///
+/// let provider = TracerProvider::builder()
+///     .with_config(
+///         opentelemetry_sdk::trace::config().with_sampler(Sampler::TraceIdRatioBased(0.1)),
+///     )
+///     .with_span_processor(processor)
+///     .build();
+/// # drop(provider);
+/// ```
+///
+/// [`Sampler::TraceIdRatioBased`]: crate::trace::Sampler::TraceIdRatioBased
+pub struct SpanMetricsProcessor {
+    calls: Counter<u64>,
+    duration: Histogram<f64>,
+    dimensions: Vec<Key>,
+    adjusted_count: f64,
+}
+
+impl fmt::Debug for SpanMetricsProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpanMetricsProcessor")
+            .field("dimensions", &self.dimensions)
+            .field("adjusted_count", &self.adjusted_count)
+            .finish()
+    }
+}
+
+impl SpanMetricsProcessor {
+    /// Create a new [`SpanMetricsProcessor`] builder recording metrics with
+    /// `meter`.
+    pub fn builder(meter: Meter) -> SpanMetricsProcessorBuilder {
+        SpanMetricsProcessorBuilder {
+            meter,
+            dimensions: Vec::new(),
+            adjusted_count: 1.0,
+        }
+    }
+
+    /// The attributes of the metrics of `span`.
+    fn attributes(&self, span: &SpanData) -> Vec<KeyValue> {
+        let mut attributes = Vec::with_capacity(self.dimensions.len() + 3);
+        attributes.push(KeyValue::new(SPAN_NAME, span.name.clone()));
+        attributes.push(KeyValue::new(SPAN_KIND, span_kind(&span.span_kind)));
+        attributes.push(KeyValue::new(STATUS_CODE, status_code(&span.status)));
+        attributes.extend(self.dimensions.iter().filter_map(|key| {
+            span.attributes
+                .iter()
+                .find(|attribute| &attribute.key == key)
+                .cloned()
+        }));
+        attributes
+    }
+
+    /// The number of spans `span` represents.
+    fn adjusted_count(&self, span: &SpanData) -> f64 {
+        if !span.span_context.is_sampled() {
+            return 1.0;
+        }
+        span.attributes
+            .iter()
+            .find(|attribute| attribute.key == ADJUSTED_COUNT)
+            .and_then(|attribute| match attribute.value {
+                Value::F64(count) => Some(count),
+                Value::I64(count) => Some(count as f64),
+                _ => None,
+            })
+            .unwrap_or(self.adjusted_count)
+    }
+}
+
+/// The whole number of spans a span of `adjusted_count` is recorded as,
+/// rounded up with the probability of its fractional part.
+fn weight(adjusted_count: f64) -> u64 {
+    let adjusted_count = adjusted_count.max(0.0);
+    let whole = adjusted_count.floor();
+    let round_up = rand::random::<f64>() < adjusted_count - whole;
+    whole as u64 + u64::from(round_up)
+}
+
+fn span_kind(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Client => "SPAN_KIND_CLIENT",
+        SpanKind::Server => "SPAN_KIND_SERVER",
+        SpanKind::Producer => "SPAN_KIND_PRODUCER",
+        SpanKind::Consumer => "SPAN_KIND_CONSUMER",
+        SpanKind::Internal => "SPAN_KIND_INTERNAL",
+    }
+}
+
+fn status_code(status: &Status) -> &'static str {
+    match status {
+        Status::Unset => "STATUS_CODE_UNSET",
+        Status::Error { .. } => "STATUS_CODE_ERROR",
+        Status::Ok => "STATUS_CODE_OK",
+    }
+}
+
+impl SpanProcessor for SpanMetricsProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let attributes = self.attributes(&span);
+        let duration = span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default();
+        let weight = weight(self.adjusted_count(&span));
+        if weight == 0 {
+            return;
+        }
+
+        let _guard = Context::current()
+            .with_remote_span_context(span.span_context)
+            .attach();
+        self.calls.add(weight, &attributes);
+        self.duration.record(duration.as_secs_f64(), &attributes);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        // Measurements are exported by the readers of the meter provider.
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+/// A builder for creating [`SpanMetricsProcessor`] instances.
+#[derive(Debug)]
+pub struct SpanMetricsProcessorBuilder {
+    meter: Meter,
+    dimensions: Vec<Key>,
+    adjusted_count: f64,
+}
+
+impl SpanMetricsProcessorBuilder {
+    /// Add a span attribute to key the metrics by, along with the name, kind
+    /// and status code of the spans. Spans without the attribute are recorded
+    /// without it.
+    pub fn with_dimension(mut self, key: impl Into<Key>) -> Self {
+        self.dimensions.push(key.into());
+        self
+    }
+
+    /// Set the number of spans each sampled span represents when it does not
+    /// record its own adjusted count, the inverse of the sampling ratio. The
+    /// default is `1`.
+    pub fn with_adjusted_count(self, adjusted_count: f64) -> Self {
+        SpanMetricsProcessorBuilder {
+            adjusted_count,
+            ..self
+        }
+    }
+
+    /// Build a span metrics processor, creating its instruments.
+    pub fn build(self) -> SpanMetricsProcessor {
+        let calls = self
+            .meter
+            .u64_counter("traces.span.metrics.calls")
+            .with_unit(Unit::new("{call}"))
+            .with_description("The number of calls, derived from spans.")
+            .init();
+        let duration = self
+            .meter
+            .f64_histogram("traces.span.metrics.duration")
+            .with_unit(Unit::new("s"))
+            .with_description("The duration of calls, derived from spans.")
+            .init();
+
+        SpanMetricsProcessor {
+            calls,
+            duration,
+            dimensions: self.dimensions,
+            adjusted_count: self.adjusted_count,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::metrics::{data, SdkMeterProvider, ThreadedPeriodicReader};
+    use crate::testing::metrics::InMemoryMetricsExporter;
+    use crate::testing::trace::new_test_export_span_data;
+    use opentelemetry::metrics::MeterProvider;
+    use std::time::Duration;
+
+    #[test]
+    fn records_calls_and_durations() {
+        let exporter = InMemoryMetricsExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(ThreadedPeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let processor = SpanMetricsProcessor::builder(meter_provider.meter("test"))
+            .with_dimension("http.route")
+            .with_adjusted_count(4.0)
+            .build();
+
+        let start = opentelemetry::time::now();
+        let span = SpanData {
+            start_time: start,
+            end_time: start + Duration::from_millis(250),
+            attributes: vec![
+                KeyValue::new("http.route", "/checkout"),
+                KeyValue::new("http.method", "GET"),
+            ],
+            ..new_test_export_span_data()
+        };
+        processor.on_end(span.clone());
+        processor.on_end(SpanData {
+            status: Status::error("failed"),
+            attributes: vec![KeyValue::new(ADJUSTED_COUNT, 2.0)],
+            ..span
+        });
+
+        meter_provider.force_flush().unwrap();
+        let rm = exporter.get_finished_metrics().unwrap();
+        let metrics = &rm[0].scope_metrics[0].metrics;
+        let calls = metrics[0]
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap();
+        assert_eq!(metrics[0].unit.as_str(), "{call}");
+        let mut values = calls
+            .data_points
+            .iter()
+            .map(|dp| (dp.value, dp.attributes.len()))
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![(2, 3), (4, 4)]);
+
+        let duration = metrics[1]
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<f64>>()
+            .unwrap();
+        let point = duration
+            .data_points
+            .iter()
+            .find(|dp| dp.attributes.len() == 4)
+            .unwrap();
+        assert_eq!(point.count, 1);
+        assert_eq!(point.sum, 0.25);
+        assert_eq!(
+            point.exemplars[0].trace_id,
+            new_test_export_span_data()
+                .span_context
+                .trace_id()
+                .to_bytes()
+        );
+    }
+
+    #[test]
+    fn weights() {
+        assert_eq!(weight(1.0), 1);
+        assert_eq!(weight(10.0), 10);
+        assert_eq!(weight(-1.0), 0);
+        assert_eq!(weight(f64::NAN), 0);
+        assert!((2..=3).contains(&weight(2.5)));
+
+        let total = (0..10_000).map(|_| weight(0.5)).sum::<u64>();
+        assert!((4_000..6_000).contains(&total), "{}", total);
+    }
+}
@@ -35,8 +35,14 @@
 //! [`TracerProvider`]: opentelemetry::trace::TracerProvider
 
 use crate::export::trace::{ExportResult, SpanData, SpanExporter};
+#[cfg(feature = "metrics")]
+use crate::internal_metrics::Signal;
+use crate::internal_metrics::{ErrorType, ProcessorMetrics, QUEUE_FULL, SHUTDOWN};
+#[cfg(feature = "persistence")]
+use crate::persistence::{BatchCodec, LeaseId, PersistentBatches, PersistentQueue};
 use crate::runtime::{RuntimeChannel, TrySend};
 use crate::trace::Span;
+use crate::util::block_on_timeout;
 use futures_channel::oneshot;
 use futures_util::{
     future::{self, BoxFuture, Either},
@@ -45,13 +51,16 @@ use futures_util::{
     Stream, StreamExt as _,
 };
 use opentelemetry::global;
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::MeterProvider;
 use opentelemetry::{
     trace::{TraceError, TraceResult},
     Context,
 };
 use std::cmp::min;
-use std::sync::Mutex;
-use std::{env, fmt, str::FromStr, time::Duration};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use std::{env, fmt, str::FromStr, thread};
 
 /// Delay interval between two consecutive exports.
 const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
@@ -211,6 +220,7 @@ impl SpanProcessor for SimpleSpanProcessor {
 /// [`async-std`]: https://async.rs
 pub struct BatchSpanProcessor<R: RuntimeChannel> {
     message_sender: R::Sender<BatchMessage>,
+    metrics: ProcessorMetrics,
 }
 
 impl<R: RuntimeChannel> fmt::Debug for BatchSpanProcessor<R> {
@@ -234,7 +244,10 @@ impl<R: RuntimeChannel> SpanProcessor for BatchSpanProcessor<R> {
         let result = self.message_sender.try_send(BatchMessage::ExportSpan(span));
 
         if let Err(err) = result {
+            self.metrics.dropped(err.error_type());
             global::handle_error(TraceError::Other(err.into()));
+        } else {
+            self.metrics.enqueued();
         }
     }
 
@@ -283,24 +296,43 @@ struct BatchSpanProcessorInternal<R> {
     runtime: R,
     exporter: Box<dyn SpanExporter>,
     config: BatchConfig,
+    metrics: ProcessorMetrics,
+    #[cfg(feature = "persistence")]
+    persistence: Option<PersistentBatches<SpanData>>,
 }
 
 impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
+    fn new(exporter: Box<dyn SpanExporter>, config: BatchConfig, runtime: R) -> Self {
+        BatchSpanProcessorInternal {
+            spans: Vec::new(),
+            export_tasks: FuturesUnordered::new(),
+            runtime,
+            exporter,
+            config,
+            metrics: ProcessorMetrics::default(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
+        }
+    }
+
     async fn flush(&mut self, res_channel: Option<oneshot::Sender<ExportResult>>) {
-        let export_task = self.export();
-        let task = Box::pin(async move {
-            let result = export_task.await;
-
-            if let Some(channel) = res_channel {
-                if let Err(result) = channel.send(result) {
-                    global::handle_error(TraceError::from(format!(
-                        "failed to send flush result: {:?}",
-                        result
-                    )));
+        #[cfg(feature = "persistence")]
+        if self.persistence.is_some() {
+            self.persist();
+            // Scheduled flushes only start exporting the persisted batches
+            match res_channel {
+                Some(res_channel) => {
+                    let result = self.export_persisted().await;
+                    send_flush_result(result, Some(res_channel));
                 }
-            } else if let Err(err) = result {
-                global::handle_error(err);
+                None => self.drain_persisted().await,
             }
+            return;
+        }
+
+        let export_task = self.export();
+        let task = Box::pin(async move {
+            send_flush_result(export_task.await, res_channel);
 
             Ok(())
         });
@@ -320,9 +352,17 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
         match message {
             // Span has finished, add to buffer of pending spans.
             BatchMessage::ExportSpan(span) => {
+                self.metrics.dequeued();
                 self.spans.push(span);
 
                 if self.spans.len() == self.config.max_export_batch_size {
+                    #[cfg(feature = "persistence")]
+                    if self.persistence.is_some() {
+                        self.persist();
+                        self.drain_persisted().await;
+                        return true;
+                    }
+
                     // If concurrent exports are saturated, wait for one to complete.
                     if !self.export_tasks.is_empty()
                         && self.export_tasks.len() == self.config.max_concurrent_exports
@@ -387,25 +427,126 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
             return Box::pin(future::ready(Ok(())));
         }
 
-        let export = self.exporter.export(self.spans.split_off(0));
-        let timeout = self.runtime.delay(self.config.max_export_timeout);
-        let time_out = self.config.max_export_timeout;
+        let count = self.spans.len();
+        let export = export_with_timeout(
+            self.exporter.as_mut(),
+            self.spans.split_off(0),
+            &self.runtime,
+            self.config.max_export_timeout,
+            &self.metrics,
+        );
+        let metrics = self.metrics.clone();
+        Box::pin(async move {
+            let result = export.await;
+            metrics.processed(count, result.as_ref().err().map(ErrorType::error_type));
+            result
+        })
+    }
+
+    /// Adds the buffered spans to the persistent queue, where their processing
+    /// by the processor ends.
+    #[cfg(feature = "persistence")]
+    fn persist(&mut self) {
+        if let Some(persistence) = &self.persistence {
+            if !self.spans.is_empty() {
+                self.metrics.processed(self.spans.len(), None);
+                persistence.push(self.spans.split_off(0));
+            }
+        }
+    }
+
+    /// Starts exporting persisted batches, as many as the concurrent exports
+    /// allow, without waiting for them.
+    #[cfg(feature = "persistence")]
+    async fn drain_persisted(&mut self) {
+        let available = self
+            .config
+            .max_concurrent_exports
+            .max(1)
+            .saturating_sub(self.export_tasks.len());
+        let leases = match &self.persistence {
+            Some(persistence) if available > 0 => persistence.lease(available),
+            _ => return,
+        };
+        for (id, batch) in leases.await {
+            let task = self.export_leased(id, batch);
+            self.export_tasks.push(task);
+        }
+    }
+
+    /// Exports the persisted batches in order, stopping at the first failure
+    /// to retry at the next export.
+    #[cfg(feature = "persistence")]
+    async fn export_persisted(&mut self) -> ExportResult {
+        while let Some(result) = self.export_tasks.next().await {
+            if let Err(err) = result {
+                global::handle_error(err);
+            }
+        }
+
+        loop {
+            let leases = match &self.persistence {
+                Some(persistence) => persistence.lease(self.config.max_concurrent_exports.max(1)),
+                None => return Ok(()),
+            }
+            .await;
+            if leases.is_empty() {
+                return Ok(());
+            }
+
+            let exports = leases
+                .into_iter()
+                .map(|(id, batch)| self.export_leased(id, batch))
+                .collect::<Vec<_>>();
+            for result in future::join_all(exports).await {
+                result?;
+            }
+        }
+    }
 
+    /// Exports a batch leased from the persistent queue, releasing it once
+    /// exported or failed.
+    #[cfg(feature = "persistence")]
+    fn export_leased(
+        &mut self,
+        id: LeaseId,
+        batch: Vec<SpanData>,
+    ) -> BoxFuture<'static, ExportResult> {
+        let export = export_with_timeout(
+            self.exporter.as_mut(),
+            batch,
+            &self.runtime,
+            self.config.max_export_timeout,
+            &self.metrics,
+        );
+        let persistence = self.persistence.clone();
         Box::pin(async move {
-            match future::select(export, timeout).await {
-                Either::Left((export_res, _)) => export_res,
-                Either::Right((_, _)) => ExportResult::Err(TraceError::ExportTimedOut(time_out)),
+            let result = export.await;
+            if let Some(persistence) = persistence {
+                persistence.release(id, result.is_ok());
             }
+            result
         })
     }
 
     async fn run(mut self, mut messages: impl Stream<Item = BatchMessage> + Unpin + FusedStream) {
+        // Export the batches persisted before the processor started
+        #[cfg(feature = "persistence")]
+        self.drain_persisted().await;
+
         loop {
             select! {
                 // FuturesUnordered implements Fuse intelligently such that it
                 // will become eligible again once new tasks are added to it.
-                _ = self.export_tasks.next() => {
-                    // An export task completed; do we need to do anything with it?
+                result = self.export_tasks.next() => {
+                    if let Some(Err(err)) = result {
+                        // Failed persisted batches are retried at the next export
+                        global::handle_error(err);
+                    } else if result.is_some() {
+                        // Keep exporting the persisted batches while the exporter is up
+                        #[cfg(feature = "persistence")]
+                        self.drain_persisted().await;
+                    }
                 },
                 message = messages.next() => {
                     match message {
@@ -422,29 +563,69 @@ impl<R: RuntimeChannel> BatchSpanProcessorInternal<R> {
     }
 }
 
+/// Export `batch` with `exporter`, giving up after `time_out`, and record the
+/// export in the exporter metrics of `metrics`.
+fn export_with_timeout<R: RuntimeChannel>(
+    exporter: &mut dyn SpanExporter,
+    batch: Vec<SpanData>,
+    runtime: &R,
+    time_out: Duration,
+    metrics: &ProcessorMetrics,
+) -> BoxFuture<'static, ExportResult> {
+    let count = batch.len();
+    let export = exporter.export(batch);
+    let timeout = runtime.delay(time_out);
+    let metrics = metrics.exporter().clone();
+
+    Box::pin(async move {
+        let export = async {
+            match future::select(export, timeout).await {
+                Either::Left((export_res, _)) => export_res,
+                Either::Right((_, _)) => ExportResult::Err(TraceError::ExportTimedOut(time_out)),
+            }
+        };
+        metrics.measure(count, export).await
+    })
+}
+
+fn send_flush_result(result: ExportResult, res_channel: Option<oneshot::Sender<ExportResult>>) {
+    if let Some(channel) = res_channel {
+        if let Err(result) = channel.send(result) {
+            global::handle_error(TraceError::from(format!(
+                "failed to send flush result: {:?}",
+                result
+            )));
+        }
+    } else if let Err(err) = result {
+        global::handle_error(err);
+    }
+}
+
 impl<R: RuntimeChannel> BatchSpanProcessor<R> {
+    #[cfg(test)]
     pub(crate) fn new(exporter: Box<dyn SpanExporter>, config: BatchConfig, runtime: R) -> Self {
+        let processor = BatchSpanProcessorInternal::new(exporter, config, runtime.clone());
+        BatchSpanProcessor::start(processor, runtime)
+    }
+
+    fn start(processor: BatchSpanProcessorInternal<R>, runtime: R) -> Self {
         let (message_sender, message_receiver) =
-            runtime.batch_message_channel(config.max_queue_size);
+            runtime.batch_message_channel(processor.config.max_queue_size);
         let ticker = runtime
-            .interval(config.scheduled_delay)
+            .interval(processor.config.scheduled_delay)
             .map(|_| BatchMessage::Flush(None));
-        let timeout_runtime = runtime.clone();
 
         let messages = Box::pin(stream::select(message_receiver, ticker));
-        let processor = BatchSpanProcessorInternal {
-            spans: Vec::new(),
-            export_tasks: FuturesUnordered::new(),
-            runtime: timeout_runtime,
-            config,
-            exporter,
-        };
 
+        let metrics = processor.metrics.clone();
         // Spawn worker process via user-defined spawn function.
         runtime.spawn(Box::pin(processor.run(messages)));
 
         // Return batch processor with link to worker
-        BatchSpanProcessor { message_sender }
+        BatchSpanProcessor {
+            message_sender,
+            metrics,
+        }
     }
 
     /// Create a new batch processor builder
@@ -456,6 +637,9 @@ impl<R: RuntimeChannel> BatchSpanProcessor<R> {
             exporter,
             config: Default::default(),
             runtime,
+            metrics: ProcessorMetrics::default(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
         }
     }
 }
@@ -642,6 +826,9 @@ pub struct BatchSpanProcessorBuilder<E, R> {
     exporter: E,
     config: BatchConfig,
     runtime: R,
+    metrics: ProcessorMetrics,
+    #[cfg(feature = "persistence")]
+    persistence: Option<PersistentBatches<SpanData>>,
 }
 
 impl<E, R> BatchSpanProcessorBuilder<E, R>
@@ -654,9 +841,363 @@ where
         BatchSpanProcessorBuilder { config, ..self }
     }
 
+    /// Report the metrics of the processor and its exporter with a meter of
+    /// `provider`, such as the size of its queue and the number of spans it
+    /// dropped or exported.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.processor.span.*` and `otel.sdk.exporter.*`.
+    #[cfg(feature = "metrics")]
+    pub fn with_meter_provider(self, provider: &impl MeterProvider) -> Self {
+        BatchSpanProcessorBuilder {
+            metrics: ProcessorMetrics::new(provider, Signal::Span),
+            ..self
+        }
+    }
+
+    /// Keep the batches in a [`PersistentQueue`] until they are exported,
+    /// encoded with `codec`.
+    ///
+    /// Batches are added to the queue before being exported in order, up to
+    /// the configured max concurrent exports at a time, and removed once they
+    /// and the batches before them are exported. A batch failing to export
+    /// stays in the queue and is exported again at the next scheduled export.
+    /// The batches left in the queue by a previous process are exported when
+    /// the processor starts, without delaying the spans ending meanwhile.
+    ///
+    /// The queue files are read and written by a dedicated thread.
+    ///
+    /// The `persistence` feature of `opentelemetry-proto` provides `OtlpSpanCodec`,
+    /// storing the batches as OTLP messages.
+    #[cfg(feature = "persistence")]
+    pub fn with_persistent_queue<C>(self, queue: PersistentQueue, codec: C) -> Self
+    where
+        C: BatchCodec<SpanData> + 'static,
+    {
+        BatchSpanProcessorBuilder {
+            persistence: Some(PersistentBatches::new(queue, Box::new(codec))),
+            ..self
+        }
+    }
+
     /// Build a batch processor
     pub fn build(self) -> BatchSpanProcessor<R> {
-        BatchSpanProcessor::new(Box::new(self.exporter), self.config, self.runtime)
+        let mut processor = BatchSpanProcessorInternal::new(
+            Box::new(self.exporter),
+            self.config,
+            self.runtime.clone(),
+        );
+        self.metrics
+            .record_capacity(processor.config.max_queue_size);
+        processor.metrics = self.metrics;
+        #[cfg(feature = "persistence")]
+        {
+            processor.persistence = self.persistence;
+        }
+        BatchSpanProcessor::start(processor, self.runtime)
+    }
+}
+
+/// A [`SpanProcessor`] that buffers finished spans and reports them at a
+/// preconfigured interval from a dedicated thread, without requiring an async
+/// runtime.
+///
+/// Spans are sent to the thread through a channel bounded by the max queue
+/// size of the [`BatchConfig`], and are dropped when it is full. Flush and
+/// shutdown requests go through a separate channel, so they are not blocked
+/// by a full queue. The thread
+/// exports the spans when a batch is full and every scheduled delay, blocking
+/// on the exporter's futures for at most the max export timeout. The max
+/// concurrent exports are ignored: batches are exported one after the other.
+///
+/// As exports are not run on an async runtime, the exporter must not depend
+/// on one, such as exporters sending requests with a blocking HTTP client.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::{testing::trace::NoopSpanExporter, trace};
+/// use opentelemetry_sdk::trace::BatchConfigBuilder;
+///
+/// let exporter = NoopSpanExporter::new();
+/// let batch = trace::ThreadedBatchSpanProcessor::builder(exporter)
+///     .with_batch_config(BatchConfigBuilder::default().with_max_queue_size(4096).build())
+///     .build();
+///
+/// let provider = trace::TracerProvider::builder()
+///     .with_span_processor(batch)
+///     .build();
+/// # drop(provider);
+/// ```
+#[derive(Debug)]
+pub struct ThreadedBatchSpanProcessor {
+    message_sender: mpsc::SyncSender<ThreadedBatchMessage>,
+    control_sender: mpsc::Sender<ThreadedBatchControl>,
+    handle: Option<thread::JoinHandle<()>>,
+    timeout: Duration,
+    metrics: ProcessorMetrics,
+}
+
+impl ThreadedBatchSpanProcessor {
+    /// Create a new threaded batch processor builder
+    pub fn builder<E>(exporter: E) -> ThreadedBatchSpanProcessorBuilder<E>
+    where
+        E: SpanExporter,
+    {
+        ThreadedBatchSpanProcessorBuilder {
+            exporter,
+            config: Default::default(),
+            timeout: Duration::from_millis(OTEL_BSP_EXPORT_TIMEOUT_DEFAULT),
+            metrics: ProcessorMetrics::default(),
+        }
+    }
+
+    /// Send `control` to the worker thread and wait for its result.
+    fn request(
+        &self,
+        control: impl FnOnce(mpsc::SyncSender<ExportResult>) -> ThreadedBatchControl,
+    ) -> TraceResult<()> {
+        let (res_sender, res_receiver) = mpsc::sync_channel(1);
+        self.control_sender
+            .send(control(res_sender))
+            .map_err(|_| TraceError::Other("batch span processor is shut down".into()))?;
+        // Wake the thread up, unless spans are already waiting for it
+        let _ = self.message_sender.try_send(ThreadedBatchMessage::Wake);
+
+        match res_receiver.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(TraceError::ExportTimedOut(self.timeout)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(TraceError::Other(
+                "batch span processor thread stopped".into(),
+            )),
+        }
+    }
+}
+
+impl SpanProcessor for ThreadedBatchSpanProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if !span.span_context.is_sampled() {
+            return;
+        }
+
+        let result = self
+            .message_sender
+            .try_send(ThreadedBatchMessage::ExportSpan(span));
+
+        if let Err(err) = result {
+            let (error_type, message) = match err {
+                mpsc::TrySendError::Full(_) => (
+                    QUEUE_FULL,
+                    "batch span processor queue is full, dropping span",
+                ),
+                mpsc::TrySendError::Disconnected(_) => {
+                    (SHUTDOWN, "batch span processor is shut down")
+                }
+            };
+            self.metrics.dropped(error_type);
+            global::handle_error(TraceError::Other(message.into()));
+        } else {
+            self.metrics.enqueued();
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.request(ThreadedBatchControl::Flush)
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        let result = self.request(ThreadedBatchControl::Shutdown);
+        // The thread stops once it has replied, unless the shutdown timed out
+        if !matches!(result, Err(TraceError::ExportTimedOut(_))) {
+            if let Some(handle) = self.handle.take() {
+                let _ = handle.join();
+            }
+        }
+        result
+    }
+}
+
+/// Messages sent to the thread of a [`ThreadedBatchSpanProcessor`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum ThreadedBatchMessage {
+    /// Export spans, usually called when span ends
+    ExportSpan(SpanData),
+    /// Handle the pending control requests.
+    Wake,
+}
+
+/// Requests sent to the thread of a [`ThreadedBatchSpanProcessor`], handled
+/// before the queued spans.
+#[derive(Debug)]
+enum ThreadedBatchControl {
+    /// Flush the current buffer to the backend
+    Flush(mpsc::SyncSender<ExportResult>),
+    /// Push all spans in buffer to the backend and stop the thread.
+    Shutdown(mpsc::SyncSender<ExportResult>),
+}
+
+struct ThreadedBatchSpanWorker {
+    exporter: Box<dyn SpanExporter>,
+    spans: Vec<SpanData>,
+    config: BatchConfig,
+    metrics: ProcessorMetrics,
+}
+
+impl ThreadedBatchSpanWorker {
+    fn run(
+        mut self,
+        messages: mpsc::Receiver<ThreadedBatchMessage>,
+        controls: mpsc::Receiver<ThreadedBatchControl>,
+    ) {
+        let mut next_export = Instant::now() + self.config.scheduled_delay;
+        loop {
+            while let Ok(control) = controls.try_recv() {
+                // Include the spans queued before the request
+                for _ in 0..self.config.max_queue_size {
+                    match messages.try_recv() {
+                        Ok(ThreadedBatchMessage::ExportSpan(span)) => self.push(span),
+                        Ok(ThreadedBatchMessage::Wake) => {}
+                        Err(_) => break,
+                    }
+                }
+                match control {
+                    ThreadedBatchControl::Flush(res_sender) => {
+                        let _ = res_sender.send(self.export());
+                    }
+                    ThreadedBatchControl::Shutdown(res_sender) => {
+                        let result = self.export();
+                        self.exporter.shutdown();
+                        let _ = res_sender.send(result);
+                        return;
+                    }
+                }
+            }
+
+            let timeout = next_export.saturating_duration_since(Instant::now());
+            match messages.recv_timeout(timeout) {
+                Ok(ThreadedBatchMessage::ExportSpan(span)) => self.push(span),
+                Ok(ThreadedBatchMessage::Wake) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.export() {
+                        global::handle_error(err);
+                    }
+                    next_export = Instant::now() + self.config.scheduled_delay;
+                }
+                // The processor was dropped without being shut down
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    if let Err(err) = self.export() {
+                        global::handle_error(err);
+                    }
+                    self.exporter.shutdown();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Buffer `span`, exporting the buffer once it is a full batch.
+    fn push(&mut self, span: SpanData) {
+        self.metrics.dequeued();
+        self.spans.push(span);
+        if self.spans.len() >= self.config.max_export_batch_size {
+            if let Err(err) = self.export() {
+                global::handle_error(err);
+            }
+        }
+    }
+
+    fn export(&mut self) -> ExportResult {
+        if self.spans.is_empty() {
+            return Ok(());
+        }
+
+        let time_out = self.config.max_export_timeout;
+        let count = self.spans.len();
+        let start = Instant::now();
+        let result = block_on_timeout(self.exporter.export(self.spans.split_off(0)), time_out)
+            .unwrap_or(Err(TraceError::ExportTimedOut(time_out)));
+        let error_type = result.as_ref().err().map(ErrorType::error_type);
+        self.metrics
+            .exporter()
+            .record(count, error_type, start.elapsed());
+        self.metrics.processed(count, error_type);
+        result
+    }
+}
+
+/// A builder for creating [`ThreadedBatchSpanProcessor`] instances.
+#[derive(Debug)]
+pub struct ThreadedBatchSpanProcessorBuilder<E> {
+    exporter: E,
+    config: BatchConfig,
+    timeout: Duration,
+    metrics: ProcessorMetrics,
+}
+
+impl<E> ThreadedBatchSpanProcessorBuilder<E>
+where
+    E: SpanExporter + 'static,
+{
+    /// Set the BatchConfig for [ThreadedBatchSpanProcessorBuilder]
+    pub fn with_batch_config(self, config: BatchConfig) -> Self {
+        ThreadedBatchSpanProcessorBuilder { config, ..self }
+    }
+
+    /// Set the time `force_flush` and `shutdown` wait for the processor's
+    /// thread to export the buffered spans, 30 seconds by default.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        ThreadedBatchSpanProcessorBuilder { timeout, ..self }
+    }
+
+    /// Report the metrics of the processor and its exporter with a meter of
+    /// `provider`, such as the size of its queue and the number of spans it
+    /// dropped or exported.
+    ///
+    /// The metrics follow the semantic conventions of the OpenTelemetry SDK
+    /// metrics, named `otel.sdk.processor.span.*` and `otel.sdk.exporter.*`.
+    #[cfg(feature = "metrics")]
+    pub fn with_meter_provider(self, provider: &impl MeterProvider) -> Self {
+        ThreadedBatchSpanProcessorBuilder {
+            metrics: ProcessorMetrics::new(provider, Signal::Span),
+            ..self
+        }
+    }
+
+    /// Build a threaded batch processor, starting its thread
+    pub fn build(self) -> ThreadedBatchSpanProcessor {
+        let (message_sender, message_receiver) = mpsc::sync_channel(self.config.max_queue_size);
+        let (control_sender, control_receiver) = mpsc::channel();
+        self.metrics.record_capacity(self.config.max_queue_size);
+        let worker = ThreadedBatchSpanWorker {
+            exporter: Box::new(self.exporter),
+            spans: Vec::new(),
+            config: self.config,
+            metrics: self.metrics.clone(),
+        };
+
+        let handle = match thread::Builder::new()
+            .name("opentelemetry-batch-span-processor".to_string())
+            .spawn(move || worker.run(message_receiver, control_receiver))
+        {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                global::handle_error(TraceError::Other(err.into()));
+                None
+            }
+        };
+
+        ThreadedBatchSpanProcessor {
+            message_sender,
+            control_sender,
+            handle,
+            timeout: self.timeout,
+            metrics: self.metrics,
+        }
     }
 }
 
@@ -664,9 +1205,9 @@ where
 mod tests {
     // cargo test trace::span_processor::tests:: --features=trace,testing
     use super::{
-        BatchSpanProcessor, SimpleSpanProcessor, SpanProcessor, OTEL_BSP_EXPORT_TIMEOUT,
-        OTEL_BSP_MAX_EXPORT_BATCH_SIZE, OTEL_BSP_MAX_QUEUE_SIZE, OTEL_BSP_MAX_QUEUE_SIZE_DEFAULT,
-        OTEL_BSP_SCHEDULE_DELAY, OTEL_BSP_SCHEDULE_DELAY_DEFAULT,
+        BatchSpanProcessor, SimpleSpanProcessor, SpanProcessor, ThreadedBatchSpanProcessor,
+        OTEL_BSP_EXPORT_TIMEOUT, OTEL_BSP_MAX_EXPORT_BATCH_SIZE, OTEL_BSP_MAX_QUEUE_SIZE,
+        OTEL_BSP_MAX_QUEUE_SIZE_DEFAULT, OTEL_BSP_SCHEDULE_DELAY, OTEL_BSP_SCHEDULE_DELAY_DEFAULT,
     };
     use crate::export::trace::{ExportResult, SpanData, SpanExporter};
     use crate::runtime;
@@ -679,7 +1220,7 @@ mod tests {
     };
     use crate::trace::{BatchConfig, BatchConfigBuilder, SpanEvents, SpanLinks};
     use async_trait::async_trait;
-    use opentelemetry::trace::{SpanContext, SpanId, SpanKind, Status};
+    use opentelemetry::trace::{SpanContext, SpanId, SpanKind, Status, TraceError};
     use std::fmt::Debug;
     use std::future::Future;
     use std::time::Duration;
@@ -1013,4 +1554,287 @@ mod tests {
         let shutdown_res = processor.shutdown();
         assert!(shutdown_res.is_ok());
     }
+
+    /// Encodes the names of the spans of a batch.
+    #[cfg(feature = "persistence")]
+    #[derive(Debug)]
+    struct NameCodec;
+
+    #[cfg(feature = "persistence")]
+    impl crate::persistence::BatchCodec<SpanData> for NameCodec {
+        fn encode(
+            &self,
+            batch: &[SpanData],
+        ) -> Result<Vec<u8>, crate::persistence::PersistenceError> {
+            let names = batch.iter().map(|span| span.name.as_ref());
+            Ok(names.collect::<Vec<_>>().join("\n").into_bytes())
+        }
+
+        fn decode(
+            &self,
+            bytes: &[u8],
+        ) -> Result<Vec<SpanData>, crate::persistence::PersistenceError> {
+            let names = String::from_utf8_lossy(bytes);
+            Ok(names
+                .split('\n')
+                .map(|name| SpanData {
+                    name: name.to_string().into(),
+                    ..new_test_export_span_data()
+                })
+                .collect())
+        }
+    }
+
+    /// Records the names of the exported spans, failing while `down` is set.
+    #[cfg(feature = "persistence")]
+    #[derive(Debug)]
+    struct UnreliableExporter {
+        down: bool,
+        exported: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "persistence")]
+    impl SpanExporter for UnreliableExporter {
+        fn export(
+            &mut self,
+            batch: Vec<SpanData>,
+        ) -> futures_util::future::BoxFuture<'static, ExportResult> {
+            if self.down {
+                return Box::pin(std::future::ready(Err("exporter is down".into())));
+            }
+            let mut exported = self.exported.lock().unwrap();
+            exported.extend(batch.into_iter().map(|span| span.name.into_owned()));
+            Box::pin(std::future::ready(Ok(())))
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "persistence")]
+    async fn test_batch_span_processor_with_persistent_queue() {
+        use crate::persistence::PersistentQueue;
+
+        let dir = std::env::temp_dir().join(format!("opentelemetry-bsp-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = || {
+            BatchConfigBuilder::default()
+                .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+                .with_max_export_batch_size(2)
+                .build()
+        };
+        let span = |name: &'static str| SpanData {
+            name: name.into(),
+            ..new_test_export_span_data()
+        };
+        let exported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let exporter = UnreliableExporter {
+            down: true,
+            exported: exported.clone(),
+        };
+        let mut processor = BatchSpanProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_batch_config(config())
+            .with_persistent_queue(PersistentQueue::builder(&dir).build().unwrap(), NameCodec)
+            .build();
+        for name in ["a", "b", "c"] {
+            processor.on_end(span(name));
+        }
+        assert!(processor.force_flush().is_err());
+        assert!(processor.shutdown().is_err());
+        assert!(exported.lock().unwrap().is_empty());
+
+        // The batches persisted while the exporter was down are exported first
+        let exporter = UnreliableExporter {
+            down: false,
+            exported: exported.clone(),
+        };
+        let mut processor = BatchSpanProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_batch_config(config())
+            .with_persistent_queue(PersistentQueue::builder(&dir).build().unwrap(), NameCodec)
+            .build();
+        processor.on_end(span("d"));
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(*exported.lock().unwrap(), vec!["a", "b", "c", "d"]);
+        assert!(processor.shutdown().is_ok());
+
+        let queue = PersistentQueue::builder(&dir).build().unwrap();
+        assert!(queue.is_empty());
+        drop(queue);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "persistence", feature = "metrics"))]
+    async fn test_persisted_spans_are_processed_once() {
+        use crate::metrics::{data, SdkMeterProvider, ThreadedPeriodicReader};
+        use crate::persistence::PersistentQueue;
+        use crate::testing::metrics::InMemoryMetricsExporter;
+
+        let dir =
+            std::env::temp_dir().join(format!("opentelemetry-bsp-metrics-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let metrics_exporter = InMemoryMetricsExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(ThreadedPeriodicReader::builder(metrics_exporter.clone()).build())
+            .build();
+
+        let exporter = UnreliableExporter {
+            down: true,
+            exported: Default::default(),
+        };
+        let mut processor = BatchSpanProcessor::builder(exporter, runtime::TokioCurrentThread)
+            .with_batch_config(
+                BatchConfigBuilder::default()
+                    .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+                    .with_max_export_batch_size(2)
+                    .build(),
+            )
+            .with_persistent_queue(PersistentQueue::builder(&dir).build().unwrap(), NameCodec)
+            .with_meter_provider(&meter_provider)
+            .build();
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        // Every flush retries the persisted batches
+        assert!(processor.force_flush().is_err());
+        assert!(processor.force_flush().is_err());
+        assert!(processor.shutdown().is_err());
+        meter_provider.force_flush().unwrap();
+
+        let metrics = metrics_exporter.get_finished_metrics().unwrap();
+        let sum = |name: &str| {
+            let metric = metrics
+                .last()
+                .unwrap()
+                .scope_metrics
+                .iter()
+                .flat_map(|scope| &scope.metrics)
+                .find(|metric| metric.name == name)
+                .unwrap();
+            let sum = metric
+                .data
+                .as_any()
+                .downcast_ref::<data::Sum<u64>>()
+                .unwrap();
+            sum.data_points
+                .iter()
+                .map(|dp| {
+                    (
+                        dp.value,
+                        dp.attributes
+                            .iter()
+                            .any(|(k, _)| k.as_str() == "error.type"),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sum("otel.sdk.processor.span.processed"), vec![(3, false)]);
+        let failed_exports = sum("otel.sdk.exporter.span.exported");
+        assert!(matches!(failed_exports[..], [(count, true)] if count > 3));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn threaded_batch_span_processor_exports_batches() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let config = BatchConfigBuilder::default()
+            .with_scheduled_delay(Duration::from_secs(60 * 60 * 24))
+            .with_max_export_batch_size(2)
+            .build();
+        let mut processor = ThreadedBatchSpanProcessor::builder(exporter.clone())
+            .with_batch_config(config)
+            .build();
+
+        for _ in 0..3 {
+            processor.on_end(new_test_export_span_data());
+        }
+        assert!(processor.force_flush().is_ok());
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 3);
+
+        assert!(processor.shutdown().is_ok());
+        assert!(processor.force_flush().is_err());
+    }
+
+    #[test]
+    fn threaded_batch_span_processor_scheduled_export() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let config = BatchConfigBuilder::default()
+            .with_scheduled_delay(Duration::from_millis(10))
+            .build();
+        let mut processor = ThreadedBatchSpanProcessor::builder(exporter.clone())
+            .with_batch_config(config)
+            .build();
+
+        processor.on_end(new_test_export_span_data());
+        let mut attempts = 0;
+        while exporter.get_finished_spans().unwrap().is_empty() && attempts < 100 {
+            std::thread::sleep(Duration::from_millis(10));
+            attempts += 1;
+        }
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn threaded_batch_span_processor_timeouts() {
+        let exporter = BlockingExporter {
+            delay_for: Duration::ZERO,
+            delay_fn: |_| futures_util::future::pending(),
+        };
+        let config = BatchConfigBuilder::default()
+            .with_max_export_timeout(Duration::from_millis(10))
+            .build();
+        let mut processor = ThreadedBatchSpanProcessor::builder(exporter)
+            .with_batch_config(config)
+            .build();
+        processor.on_end(new_test_export_span_data());
+        assert!(matches!(
+            processor.force_flush(),
+            Err(TraceError::ExportTimedOut(timeout)) if timeout == Duration::from_millis(10)
+        ));
+        assert!(processor.shutdown().is_ok());
+
+        // Waiting for the thread times out before the export does
+        let exporter = BlockingExporter {
+            delay_for: Duration::ZERO,
+            delay_fn: |_| futures_util::future::pending(),
+        };
+        let processor = ThreadedBatchSpanProcessor::builder(exporter)
+            .with_timeout(Duration::from_millis(20))
+            .build();
+        processor.on_end(new_test_export_span_data());
+        assert!(matches!(
+            processor.force_flush(),
+            Err(TraceError::ExportTimedOut(timeout)) if timeout == Duration::from_millis(20)
+        ));
+    }
+
+    #[test]
+    fn threaded_batch_span_processor_flush_with_full_queue() {
+        let exporter = BlockingExporter {
+            delay_for: Duration::ZERO,
+            delay_fn: |_| futures_util::future::pending(),
+        };
+        let config = BatchConfigBuilder::default()
+            .with_max_queue_size(1)
+            .with_max_export_batch_size(1)
+            .with_max_export_timeout(Duration::from_secs(10))
+            .build();
+        let processor = ThreadedBatchSpanProcessor::builder(exporter)
+            .with_batch_config(config)
+            .with_timeout(Duration::from_millis(50))
+            .build();
+
+        // The thread is stuck exporting the first span, the second fills the queue
+        processor.on_end(new_test_export_span_data());
+        std::thread::sleep(Duration::from_millis(20));
+        processor.on_end(new_test_export_span_data());
+
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            processor.force_flush(),
+            Err(TraceError::ExportTimedOut(_))
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
 }
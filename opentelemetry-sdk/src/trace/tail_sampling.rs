@@ -0,0 +1,681 @@
+//! # Tail Sampling
+//!
+//! Samplers decide whether to record a span when it starts, before knowing
+//! whether its operation fails or how long it lasts. The
+//! [`TailSamplingProcessor`] instead buffers the ended spans of each trace for
+//! a decision wait window, then evaluates [`TailSamplingPolicy`]s against the
+//! whole trace and forwards the spans of the traces it keeps to another
+//! [`SpanProcessor`].
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use opentelemetry::{
+    global,
+    trace::{SamplingDecision, Status, TraceError, TraceId, TraceResult},
+    Context, KeyValue,
+};
+
+use crate::export::trace::{SpanData, SpanExporter};
+
+use super::{
+    sampler::sample_based_on_probability, Span, SpanProcessor, ThreadedBatchSpanProcessor,
+};
+
+/// Default time spans of a trace are buffered before deciding to keep it.
+const DEFAULT_DECISION_WAIT: Duration = Duration::from_secs(30);
+/// Default maximum number of spans buffered while waiting for decisions.
+const DEFAULT_MAX_BUFFERED_SPANS: usize = 100_000;
+/// The number of decided traces remembered to route their late spans.
+const MAX_DECISIONS: usize = 10_000;
+/// Bounds of the interval the ticker thread decides expired traces at.
+const MIN_TICK_INTERVAL: Duration = Duration::from_millis(10);
+const MAX_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A policy deciding whether to keep a trace from all its buffered spans.
+///
+/// Composite policies evaluate their policies in order and stop as soon as
+/// the outcome is known, so a [`TailSamplingPolicy::RateLimited`] policy only
+/// consumes its budget for the traces it is evaluated for.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TailSamplingPolicy {
+    /// Keep every trace.
+    AlwaysSample,
+    /// Keep traces with at least one span whose status is an error.
+    StatusError,
+    /// Keep traces lasting at least the given duration, from the earliest
+    /// start to the latest end of their spans.
+    Latency(Duration),
+    /// Keep traces with at least one span with the given attribute.
+    Attribute(KeyValue),
+    /// Keep a given fraction of traces, decided from their trace id as
+    /// [`Sampler::TraceIdRatioBased`] does.
+    ///
+    /// [`Sampler::TraceIdRatioBased`]: crate::trace::Sampler::TraceIdRatioBased
+    Probabilistic(f64),
+    /// Keep traces as long as the spans kept by this policy stay within the
+    /// given number of spans per second.
+    RateLimited(u64),
+    /// Keep traces kept by all the given policies.
+    And(Vec<TailSamplingPolicy>),
+    /// Keep traces kept by any of the given policies.
+    Or(Vec<TailSamplingPolicy>),
+}
+
+/// A [`TailSamplingPolicy`] along with the state of its rate limits.
+#[derive(Debug)]
+enum Policy {
+    AlwaysSample,
+    StatusError,
+    Latency(Duration),
+    Attribute(KeyValue),
+    Probabilistic(f64),
+    RateLimited(RateLimiter),
+    And(Vec<Policy>),
+    Or(Vec<Policy>),
+}
+
+impl From<TailSamplingPolicy> for Policy {
+    fn from(policy: TailSamplingPolicy) -> Self {
+        match policy {
+            TailSamplingPolicy::AlwaysSample => Policy::AlwaysSample,
+            TailSamplingPolicy::StatusError => Policy::StatusError,
+            TailSamplingPolicy::Latency(threshold) => Policy::Latency(threshold),
+            TailSamplingPolicy::Attribute(attribute) => Policy::Attribute(attribute),
+            TailSamplingPolicy::Probabilistic(prob) => Policy::Probabilistic(prob),
+            TailSamplingPolicy::RateLimited(spans_per_second) => {
+                Policy::RateLimited(RateLimiter::new(spans_per_second))
+            }
+            TailSamplingPolicy::And(policies) => {
+                Policy::And(policies.into_iter().map(Policy::from).collect())
+            }
+            TailSamplingPolicy::Or(policies) => {
+                Policy::Or(policies.into_iter().map(Policy::from).collect())
+            }
+        }
+    }
+}
+
+impl Policy {
+    /// Whether to keep the trace `trace_id` made of `spans`.
+    fn should_keep(&self, trace_id: TraceId, spans: &[SpanData]) -> bool {
+        match self {
+            Policy::AlwaysSample => true,
+            Policy::StatusError => spans
+                .iter()
+                .any(|span| matches!(span.status, Status::Error { .. })),
+            Policy::Latency(threshold) => trace_duration(spans) >= *threshold,
+            Policy::Attribute(attribute) => {
+                spans.iter().any(|span| span.attributes.contains(attribute))
+            }
+            Policy::Probabilistic(prob) => {
+                sample_based_on_probability(prob, trace_id) == SamplingDecision::RecordAndSample
+            }
+            Policy::RateLimited(limiter) => limiter.try_acquire(spans.len() as u64),
+            Policy::And(policies) => policies
+                .iter()
+                .all(|policy| policy.should_keep(trace_id, spans)),
+            Policy::Or(policies) => policies
+                .iter()
+                .any(|policy| policy.should_keep(trace_id, spans)),
+        }
+    }
+}
+
+/// The time between the earliest start and the latest end of `spans`.
+fn trace_duration(spans: &[SpanData]) -> Duration {
+    let start = spans.iter().map(|span| span.start_time).min();
+    let end = spans.iter().map(|span| span.end_time).max();
+    match (start, end) {
+        (Some(start), Some(end)) => end.duration_since(start).unwrap_or_default(),
+        _ => Duration::ZERO,
+    }
+}
+
+/// Limits the number of spans kept in each one second window.
+#[derive(Debug)]
+struct RateLimiter {
+    spans_per_second: u64,
+    window: Mutex<(SystemTime, u64)>,
+}
+
+impl RateLimiter {
+    fn new(spans_per_second: u64) -> Self {
+        RateLimiter {
+            spans_per_second,
+            window: Mutex::new((opentelemetry::time::now(), 0)),
+        }
+    }
+
+    /// Whether `spans` more spans fit in the current window, counting them
+    /// if so.
+    fn try_acquire(&self, spans: u64) -> bool {
+        let mut window = match self.window.lock() {
+            Ok(window) => window,
+            Err(_) => return false,
+        };
+        let now = opentelemetry::time::now();
+        let (start, count) = &mut *window;
+        if now.duration_since(*start).unwrap_or_default() >= Duration::from_secs(1) {
+            *start = now;
+            *count = 0;
+        }
+        if *count + spans > self.spans_per_second {
+            return false;
+        }
+        *count += spans;
+        true
+    }
+}
+
+/// The spans waiting for decisions and the decisions made for recent traces.
+#[derive(Debug, Default)]
+struct TailSamplingState {
+    pending: HashMap<TraceId, Vec<SpanData>>,
+    /// Pending traces by the time their first span was buffered.
+    arrivals: VecDeque<(Instant, TraceId)>,
+    buffered_spans: usize,
+    decisions: HashMap<TraceId, bool>,
+    decided: VecDeque<TraceId>,
+}
+
+impl TailSamplingState {
+    /// Decide the oldest pending trace, returning its spans if kept.
+    fn decide_oldest(&mut self, policy: &Policy) -> Option<Vec<SpanData>> {
+        let (_, trace_id) = self.arrivals.pop_front()?;
+        let spans = self.pending.remove(&trace_id).unwrap_or_default();
+        self.buffered_spans -= spans.len();
+
+        let keep = policy.should_keep(trace_id, &spans);
+        if self.decided.len() >= MAX_DECISIONS {
+            if let Some(oldest) = self.decided.pop_front() {
+                self.decisions.remove(&oldest);
+            }
+        }
+        self.decisions.insert(trace_id, keep);
+        self.decided.push_back(trace_id);
+
+        keep.then_some(spans)
+    }
+}
+
+/// A [`SpanProcessor`] keeping or dropping whole traces once they ended,
+/// according to [`TailSamplingPolicy`]s, and forwarding the spans of the
+/// traces it keeps to another span processor.
+///
+/// The spans of a trace are buffered from the end of its first span for the
+/// decision wait window, 30 seconds by default, then the trace is kept if any
+/// of the policies keeps it. Spans ending after the decision follow the
+/// decision of their trace. When more spans than the configured maximum are
+/// buffered, the oldest traces are decided early. A dedicated thread decides
+/// the traces whose window elapsed, and flushing or shutting down the
+/// processor decides all the pending traces.
+///
+/// Spans are buffered only if sampled, so tail sampling is typically combined
+/// with the [`Sampler::AlwaysOn`] sampler.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry::trace::TracerProvider as _;
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{TailSamplingPolicy, TailSamplingProcessor, TracerProvider};
+/// use std::time::Duration;
+///
+/// // Keep failing or slow traces, and a tenth of the other ones.
+/// let processor = TailSamplingProcessor::exporter_builder(NoopSpanExporter::new())
+///     .with_policy(TailSamplingPolicy::StatusError)
+///     .with_policy(TailSamplingPolicy::Latency(Duration::from_secs(1)))
+///     .with_policy(TailSamplingPolicy::Probabilistic(0.1))
+///     .with_decision_wait(Duration::from_secs(10))
+///     .build();
+///
+/// let provider = TracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # drop(provider);
+/// ```
+///
+/// [`Sampler::AlwaysOn`]: crate::trace::Sampler::AlwaysOn
+pub struct TailSamplingProcessor {
+    sampler: Arc<TailSampler>,
+    ticker: Option<Ticker>,
+}
+
+impl fmt::Debug for TailSamplingProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TailSamplingProcessor")
+            .field("processor", &self.sampler.processor)
+            .field("policy", &self.sampler.policy)
+            .field("decision_wait", &self.sampler.decision_wait)
+            .field("max_buffered_spans", &self.sampler.max_buffered_spans)
+            .finish()
+    }
+}
+
+impl TailSamplingProcessor {
+    /// Create a new [`TailSamplingProcessor`] builder forwarding the spans of
+    /// the traces it keeps to `processor`.
+    pub fn builder<P>(processor: P) -> TailSamplingProcessorBuilder<P>
+    where
+        P: SpanProcessor + 'static,
+    {
+        TailSamplingProcessorBuilder {
+            processor,
+            policies: Vec::new(),
+            decision_wait: DEFAULT_DECISION_WAIT,
+            max_buffered_spans: DEFAULT_MAX_BUFFERED_SPANS,
+        }
+    }
+
+    /// Create a new [`TailSamplingProcessor`] builder exporting the spans of
+    /// the traces it keeps to `exporter`, with a [`ThreadedBatchSpanProcessor`]
+    /// of the default configuration.
+    pub fn exporter_builder<E>(
+        exporter: E,
+    ) -> TailSamplingProcessorBuilder<ThreadedBatchSpanProcessor>
+    where
+        E: SpanExporter + 'static,
+    {
+        TailSamplingProcessor::builder(ThreadedBatchSpanProcessor::builder(exporter).build())
+    }
+}
+
+/// The buffered traces and their policy, shared with the ticker thread.
+struct TailSampler {
+    processor: Box<dyn SpanProcessor>,
+    policy: Policy,
+    decision_wait: Duration,
+    max_buffered_spans: usize,
+    state: Mutex<TailSamplingState>,
+}
+
+impl TailSampler {
+    /// Decide the pending traces, all of them if `all`, otherwise the ones
+    /// whose window elapsed and the oldest ones above the buffer bound, and
+    /// return the spans of the ones kept.
+    fn decide(&self, state: &mut TailSamplingState, all: bool) -> Vec<SpanData> {
+        let now = Instant::now();
+        let mut kept = Vec::new();
+        while let Some((arrival, _)) = state.arrivals.front() {
+            let expired = now.saturating_duration_since(*arrival) >= self.decision_wait;
+            if !all && !expired && state.buffered_spans <= self.max_buffered_spans {
+                break;
+            }
+            if let Some(spans) = state.decide_oldest(&self.policy) {
+                kept.extend(spans);
+            }
+        }
+        kept
+    }
+
+    /// Decide the pending traces, all of them if `all`, and forward the spans
+    /// of the ones kept.
+    fn decide_and_forward(&self, all: bool) -> TraceResult<()> {
+        let kept = self
+            .state
+            .lock()
+            .map(|mut state| self.decide(&mut state, all))
+            .map_err(|_| TraceError::Other("TailSamplingProcessor mutex poison".into()))?;
+
+        for span in kept {
+            self.processor.on_end(span);
+        }
+        Ok(())
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => {
+                global::handle_error(TraceError::Other(
+                    "TailSamplingProcessor mutex poison".into(),
+                ));
+                return;
+            }
+        };
+
+        let trace_id = span.span_context.trace_id();
+        let mut kept = Vec::new();
+        match state.decisions.get(&trace_id) {
+            Some(true) => kept.push(span),
+            Some(false) => {}
+            None => {
+                let spans = state.pending.entry(trace_id).or_default();
+                let first = spans.is_empty();
+                spans.push(span);
+                if first {
+                    state.arrivals.push_back((Instant::now(), trace_id));
+                }
+                state.buffered_spans += 1;
+            }
+        }
+        kept.extend(self.decide(&mut state, false));
+        drop(state);
+
+        for span in kept {
+            self.processor.on_end(span);
+        }
+    }
+}
+
+/// A thread deciding the traces whose window elapsed, until its sender is
+/// dropped.
+struct Ticker {
+    stop: mpsc::Sender<()>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl Ticker {
+    fn spawn(sampler: Arc<TailSampler>) -> Option<Self> {
+        let interval = sampler
+            .decision_wait
+            .clamp(MIN_TICK_INTERVAL, MAX_TICK_INTERVAL);
+        let (stop, stopped) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("opentelemetry-tail-sampling".to_string())
+            .spawn(move || {
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    if let Err(err) = sampler.decide_and_forward(false) {
+                        global::handle_error(err);
+                    }
+                }
+            });
+
+        match spawned {
+            Ok(handle) => Some(Ticker { stop, handle }),
+            Err(err) => {
+                global::handle_error(TraceError::Other(err.into()));
+                None
+            }
+        }
+    }
+
+    fn stop(self) {
+        drop(self.stop);
+        let _ = self.handle.join();
+    }
+}
+
+impl SpanProcessor for TailSamplingProcessor {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.sampler.processor.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if !span.span_context.is_sampled() {
+            return;
+        }
+        self.sampler.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.sampler.decide_and_forward(true)?;
+        self.sampler.processor.force_flush()
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        if let Some(ticker) = self.ticker.take() {
+            ticker.stop();
+        }
+        let decided = self.sampler.decide_and_forward(true);
+        match Arc::get_mut(&mut self.sampler) {
+            Some(sampler) => sampler.processor.shutdown()?,
+            None => {
+                return Err(TraceError::Other(
+                    "TailSamplingProcessor is still in use by its ticker".into(),
+                ))
+            }
+        }
+        decided
+    }
+}
+
+/// A builder for creating [`TailSamplingProcessor`] instances.
+#[derive(Debug)]
+pub struct TailSamplingProcessorBuilder<P> {
+    processor: P,
+    policies: Vec<TailSamplingPolicy>,
+    decision_wait: Duration,
+    max_buffered_spans: usize,
+}
+
+impl<P> TailSamplingProcessorBuilder<P>
+where
+    P: SpanProcessor + 'static,
+{
+    /// Add a policy to keep traces with. Traces are kept if any of the
+    /// policies keeps them, so without policies every trace is dropped.
+    pub fn with_policy(mut self, policy: TailSamplingPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
+
+    /// Set the time the spans of a trace are buffered from the end of its
+    /// first span before deciding whether to keep it.
+    pub fn with_decision_wait(self, decision_wait: Duration) -> Self {
+        TailSamplingProcessorBuilder {
+            decision_wait,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of spans buffered while waiting for decisions.
+    /// The oldest traces are decided early when more spans are buffered.
+    pub fn with_max_buffered_spans(self, max_buffered_spans: usize) -> Self {
+        TailSamplingProcessorBuilder {
+            max_buffered_spans,
+            ..self
+        }
+    }
+
+    /// Build a tail sampling processor.
+    pub fn build(self) -> TailSamplingProcessor {
+        let sampler = Arc::new(TailSampler {
+            processor: Box::new(self.processor),
+            policy: Policy::from(TailSamplingPolicy::Or(self.policies)),
+            decision_wait: self.decision_wait,
+            max_buffered_spans: self.max_buffered_spans,
+            state: Mutex::new(TailSamplingState::default()),
+        });
+        let ticker = Ticker::spawn(sampler.clone());
+
+        TailSamplingProcessor { sampler, ticker }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::{
+        new_test_export_span_data, InMemorySpanExporter, InMemorySpanExporterBuilder,
+    };
+    use crate::trace::SimpleSpanProcessor;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceState};
+
+    fn span(trace_id: u128, span_id: u64) -> SpanData {
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from_u128(trace_id),
+                SpanId::from_u64(span_id),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            ..new_test_export_span_data()
+        }
+    }
+
+    fn error_span(trace_id: u128, span_id: u64) -> SpanData {
+        SpanData {
+            status: Status::error("failed"),
+            ..span(trace_id, span_id)
+        }
+    }
+
+    fn processor(
+        builder: impl FnOnce(
+            TailSamplingProcessorBuilder<SimpleSpanProcessor>,
+        ) -> TailSamplingProcessorBuilder<SimpleSpanProcessor>,
+    ) -> (TailSamplingProcessor, InMemorySpanExporter) {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor =
+            TailSamplingProcessor::builder(SimpleSpanProcessor::new(Box::new(exporter.clone())));
+        (builder(processor).build(), exporter)
+    }
+
+    fn exported_span_ids(exporter: &InMemorySpanExporter) -> Vec<u64> {
+        let mut span_ids = exporter
+            .get_finished_spans()
+            .unwrap()
+            .iter()
+            .map(|span| u64::from_be_bytes(span.span_context.span_id().to_bytes()))
+            .collect::<Vec<_>>();
+        span_ids.sort_unstable();
+        span_ids
+    }
+
+    fn should_keep(policy: TailSamplingPolicy, spans: &[SpanData]) -> bool {
+        Policy::from(policy).should_keep(spans[0].span_context.trace_id(), spans)
+    }
+
+    #[test]
+    fn policies() {
+        let ok = [span(1, 1), span(1, 2)];
+        let failed = [span(1, 1), error_span(1, 2)];
+        assert!(!should_keep(TailSamplingPolicy::StatusError, &ok));
+        assert!(should_keep(TailSamplingPolicy::StatusError, &failed));
+
+        let start = opentelemetry::time::now();
+        let slow = [
+            SpanData {
+                start_time: start,
+                end_time: start + Duration::from_millis(10),
+                ..span(1, 1)
+            },
+            SpanData {
+                start_time: start + Duration::from_millis(500),
+                end_time: start + Duration::from_secs(2),
+                ..span(1, 2)
+            },
+        ];
+        assert!(should_keep(
+            TailSamplingPolicy::Latency(Duration::from_secs(2)),
+            &slow
+        ));
+        assert!(!should_keep(
+            TailSamplingPolicy::Latency(Duration::from_secs(3)),
+            &slow
+        ));
+
+        let attribute = KeyValue::new("http.route", "/checkout");
+        let tagged = [SpanData {
+            attributes: vec![attribute.clone()],
+            ..span(1, 1)
+        }];
+        assert!(should_keep(
+            TailSamplingPolicy::Attribute(attribute.clone()),
+            &tagged
+        ));
+        assert!(!should_keep(TailSamplingPolicy::Attribute(attribute), &ok));
+
+        assert!(should_keep(TailSamplingPolicy::Probabilistic(1.0), &ok));
+        assert!(!should_keep(TailSamplingPolicy::Probabilistic(0.0), &ok));
+
+        assert!(should_keep(
+            TailSamplingPolicy::And(vec![
+                TailSamplingPolicy::AlwaysSample,
+                TailSamplingPolicy::StatusError
+            ]),
+            &failed
+        ));
+        assert!(!should_keep(
+            TailSamplingPolicy::And(vec![
+                TailSamplingPolicy::AlwaysSample,
+                TailSamplingPolicy::StatusError
+            ]),
+            &ok
+        ));
+        assert!(should_keep(
+            TailSamplingPolicy::Or(vec![
+                TailSamplingPolicy::StatusError,
+                TailSamplingPolicy::AlwaysSample
+            ]),
+            &ok
+        ));
+        assert!(!should_keep(TailSamplingPolicy::Or(vec![]), &ok));
+    }
+
+    #[test]
+    fn rate_limited_policy() {
+        let policy = Policy::from(TailSamplingPolicy::RateLimited(3));
+        let trace = [span(1, 1), span(1, 2)];
+        assert!(policy.should_keep(trace[0].span_context.trace_id(), &trace));
+        assert!(!policy.should_keep(trace[0].span_context.trace_id(), &trace));
+        assert!(policy.should_keep(trace[0].span_context.trace_id(), &trace[..1]));
+    }
+
+    #[test]
+    fn keeps_failing_traces() {
+        let (processor, exporter) =
+            processor(|builder| builder.with_policy(TailSamplingPolicy::StatusError));
+
+        processor.on_end(span(1, 1));
+        processor.on_end(error_span(1, 2));
+        processor.on_end(span(2, 3));
+        processor.on_end(span(2, 4));
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        processor.force_flush().unwrap();
+        assert_eq!(exported_span_ids(&exporter), vec![1, 2]);
+
+        // Late spans follow the decision of their trace
+        processor.on_end(span(1, 5));
+        processor.on_end(span(2, 6));
+        assert_eq!(exported_span_ids(&exporter), vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn decides_after_decision_wait() {
+        let (mut processor, exporter) = processor(|builder| {
+            builder
+                .with_policy(TailSamplingPolicy::StatusError)
+                .with_decision_wait(Duration::from_millis(50))
+        });
+
+        processor.on_end(error_span(1, 1));
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        // The ticker decides the trace without further spans ending
+        let mut attempts = 0;
+        while exporter.get_finished_spans().unwrap().is_empty() && attempts < 100 {
+            std::thread::sleep(Duration::from_millis(20));
+            attempts += 1;
+        }
+        assert_eq!(exported_span_ids(&exporter), vec![1]);
+        assert!(processor.shutdown().is_ok());
+    }
+
+    #[test]
+    fn decides_oldest_traces_when_full() {
+        let (mut processor, exporter) = processor(|builder| {
+            builder
+                .with_policy(TailSamplingPolicy::AlwaysSample)
+                .with_max_buffered_spans(2)
+        });
+
+        processor.on_end(span(1, 1));
+        processor.on_end(span(1, 2));
+        processor.on_end(span(2, 3));
+        assert_eq!(exported_span_ids(&exporter), vec![1, 2]);
+
+        processor.force_flush().unwrap();
+        assert_eq!(exported_span_ids(&exporter), vec![1, 2, 3]);
+        assert!(processor.shutdown().is_ok());
+    }
+}
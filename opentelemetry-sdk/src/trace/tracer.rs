@@ -81,6 +81,7 @@ impl Tracer {
             .len()
             .saturating_sub(span_attributes_limit);
         attribute_options.truncate(span_attributes_limit);
+        span_limits.truncate_values(&mut attribute_options);
         let dropped_attributes_count = dropped_attributes_count as u32;
 
         // Links are available as Option<Vec<Link>> in the builder
@@ -101,6 +102,7 @@ impl Tracer {
                 let dropped_attributes_count =
                     link.attributes.len().saturating_sub(link_attributes_limit);
                 link.attributes.truncate(link_attributes_limit);
+                span_limits.truncate_values(&mut link.attributes);
                 link.dropped_attributes_count = dropped_attributes_count as u32;
             }
             SpanLinks {
@@ -133,6 +135,7 @@ impl Tracer {
                     .len()
                     .saturating_sub(event_attributes_limit);
                 event.attributes.truncate(event_attributes_limit);
+                span_limits.truncate_values(&mut event.attributes);
                 event.dropped_attributes_count = dropped_attributes_count as u32;
             }
             SpanEvents {
@@ -185,6 +188,16 @@ impl opentelemetry::trace::Tracer for Tracer {
         }
 
         let provider = provider.unwrap();
+        if provider.is_disabled() {
+            // like the no-op tracer, propagate the context of the parent span
+            let span_context = if parent_cx.has_active_span() {
+                parent_cx.span().span_context().clone()
+            } else {
+                SpanContext::empty_context()
+            };
+            return Span::new(span_context, None, self.clone(), SpanLimits::default());
+        }
+
         let config = provider.config();
         let span_id = builder
             .span_id
@@ -0,0 +1,432 @@
+//! # zPages
+//!
+//! In-process inspection of spans, following the [tracez] page of zPages.
+//!
+//! The [`ZPagesSpanProcessor`] counts the spans of every span name and keeps
+//! samples of them: of the running spans, of the spans ended with an error
+//! status, and of the other ended spans bucketed by latency.
+//! The [`Tracez`] handle returned by [`ZPagesSpanProcessor::tracez`] queries
+//! them. The `opentelemetry-zpages` crate serves them over HTTP.
+//!
+//! ```
+//! use opentelemetry::trace::{Tracer, TracerProvider as _};
+//! use opentelemetry_sdk::trace::{TracerProvider, ZPagesSpanProcessor};
+//!
+//! let processor = ZPagesSpanProcessor::new();
+//! let tracez = processor.tracez();
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(processor)
+//!     .build();
+//!
+//! provider.tracer("example").in_span("operation", |_cx| {});
+//! assert_eq!(tracez.summaries()[0].span_name, "operation");
+//! ```
+//!
+//! [tracez]: https://opencensus.io/zpages/#tracez
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use opentelemetry::trace::{SpanId, Status, TraceResult};
+use opentelemetry::Context;
+
+use crate::export::trace::SpanData;
+use crate::trace::{Span, SpanProcessor};
+
+/// The upper bounds of the latency buckets, the last bucket being unbounded.
+pub const LATENCY_BUCKET_BOUNDARIES: [Duration; 8] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(100),
+];
+
+/// The number of latency buckets.
+pub const LATENCY_BUCKET_COUNT: usize = LATENCY_BUCKET_BOUNDARIES.len() + 1;
+
+const DEFAULT_SAMPLES_PER_BUCKET: usize = 10;
+
+/// A [`SpanProcessor`] recording spans for in-process inspection.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug)]
+pub struct ZPagesSpanProcessor {
+    tracez: Tracez,
+}
+
+impl Default for ZPagesSpanProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZPagesSpanProcessor {
+    /// Create a processor keeping 10 samples per latency bucket and span name,
+    /// and of running spans and spans ended with an error.
+    pub fn new() -> Self {
+        ZPagesSpanProcessor {
+            tracez: Tracez {
+                inner: Arc::new(Mutex::new(TracezInner {
+                    samples_per_bucket: DEFAULT_SAMPLES_PER_BUCKET,
+                    spans: HashMap::new(),
+                })),
+            },
+        }
+    }
+
+    /// Set the number of samples kept per latency bucket and span name, and
+    /// of running spans and spans ended with an error.
+    pub fn with_samples_per_bucket(self, samples_per_bucket: usize) -> Self {
+        if let Ok(mut inner) = self.tracez.inner.lock() {
+            inner.samples_per_bucket = samples_per_bucket;
+        }
+        self
+    }
+
+    /// The handle querying the spans recorded by this processor.
+    pub fn tracez(&self) -> Tracez {
+        self.tracez.clone()
+    }
+}
+
+impl SpanProcessor for ZPagesSpanProcessor {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        let name = match span.name() {
+            Some(name) => name.clone(),
+            None => return,
+        };
+        if let Ok(mut inner) = self.tracez.inner.lock() {
+            let samples_per_bucket = inner.samples_per_bucket;
+            let spans = inner.spans.entry(name).or_default();
+            spans.running_count += 1;
+            // only the sampled spans are copied
+            if spans.running_samples.len() < samples_per_bucket {
+                if let Some(data) = span.exported_data() {
+                    spans.running_samples.push(data);
+                }
+            }
+        }
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if let Ok(mut inner) = self.tracez.inner.lock() {
+            inner.end_running(&span);
+            let samples_per_bucket = inner.samples_per_bucket;
+            inner
+                .spans
+                .entry(span.name.clone())
+                .or_default()
+                .end(span, samples_per_bucket);
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        Ok(())
+    }
+}
+
+/// A handle querying the spans recorded by a [`ZPagesSpanProcessor`].
+#[derive(Clone)]
+pub struct Tracez {
+    inner: Arc<Mutex<TracezInner>>,
+}
+
+impl fmt::Debug for Tracez {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracez").finish()
+    }
+}
+
+struct TracezInner {
+    samples_per_bucket: usize,
+    spans: HashMap<Cow<'static, str>, SpanNameData>,
+}
+
+impl TracezInner {
+    /// Stop counting `span` as running.
+    ///
+    /// Spans are looked up by their id among the running samples, so spans
+    /// renamed while running are counted under the name they started with.
+    /// Spans that are not sampled are looked up by the name they end with.
+    fn end_running(&mut self, span: &SpanData) {
+        let span_id = span.span_context.span_id();
+        let sampled = self
+            .spans
+            .iter()
+            .find(|(_, spans)| spans.running_sample(span_id).is_some())
+            .map(|(name, _)| name.clone());
+        let spans = match sampled {
+            Some(name) => self.spans.get_mut(&name),
+            None => self
+                .spans
+                .get_mut(&span.name)
+                .filter(|spans| spans.running_count > 0),
+        };
+
+        if let Some(spans) = spans {
+            spans.running_count = spans.running_count.saturating_sub(1);
+            if let Some(index) = spans.running_sample(span_id) {
+                spans.running_samples.swap_remove(index);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct SpanNameData {
+    running_count: usize,
+    running_samples: Vec<SpanData>,
+    latency_counts: [u64; LATENCY_BUCKET_COUNT],
+    latency_samples: [VecDeque<SpanData>; LATENCY_BUCKET_COUNT],
+    error_count: u64,
+    error_samples: VecDeque<SpanData>,
+}
+
+impl SpanNameData {
+    fn running_sample(&self, span_id: SpanId) -> Option<usize> {
+        self.running_samples
+            .iter()
+            .position(|span| span.span_context.span_id() == span_id)
+    }
+
+    fn end(&mut self, span: SpanData, samples_per_bucket: usize) {
+        let samples = if matches!(span.status, Status::Error { .. }) {
+            self.error_count += 1;
+            &mut self.error_samples
+        } else {
+            let latency = span
+                .end_time
+                .duration_since(span.start_time)
+                .unwrap_or_default();
+            let bucket = latency_bucket(latency);
+            self.latency_counts[bucket] += 1;
+            &mut self.latency_samples[bucket]
+        };
+
+        if samples_per_bucket == 0 {
+            return;
+        }
+        if samples.len() >= samples_per_bucket {
+            samples.pop_front();
+        }
+        samples.push_back(span);
+    }
+}
+
+/// The index of the latency bucket of a span lasting `latency`.
+pub fn latency_bucket(latency: Duration) -> usize {
+    LATENCY_BUCKET_BOUNDARIES
+        .iter()
+        .position(|boundary| latency < *boundary)
+        .unwrap_or(LATENCY_BUCKET_BOUNDARIES.len())
+}
+
+/// The spans recorded for a span name.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct TracezSummary {
+    /// The name of the spans.
+    pub span_name: Cow<'static, str>,
+    /// The number of running spans.
+    pub running: usize,
+    /// The number of spans ended without error, per latency bucket.
+    pub latency: [u64; LATENCY_BUCKET_COUNT],
+    /// The number of spans ended with an error.
+    pub errors: u64,
+}
+
+impl Tracez {
+    /// The summary of the recorded spans per span name, sorted by name.
+    pub fn summaries(&self) -> Vec<TracezSummary> {
+        let inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(_) => return Vec::new(),
+        };
+        let mut summaries: Vec<_> = inner
+            .spans
+            .iter()
+            .map(|(name, spans)| TracezSummary {
+                span_name: name.clone(),
+                running: spans.running_count,
+                latency: spans.latency_counts,
+                errors: spans.error_count,
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.span_name.cmp(&b.span_name));
+        summaries
+    }
+
+    /// Samples of the running spans of the given name, as of their start.
+    pub fn running(&self, span_name: &str) -> Vec<SpanData> {
+        let mut spans = self.with_span_name(span_name, |spans| spans.running_samples.clone());
+        spans.sort_by_key(|span| span.start_time);
+        spans
+    }
+
+    /// Samples of the spans of the given name ended with an error.
+    pub fn errors(&self, span_name: &str) -> Vec<SpanData> {
+        self.with_span_name(span_name, |spans| {
+            spans.error_samples.iter().cloned().collect()
+        })
+    }
+
+    /// Samples of the spans of the given name ended without error, whose
+    /// latency falls in the bucket of the given index.
+    ///
+    /// See [`LATENCY_BUCKET_BOUNDARIES`] for the bounds of the buckets.
+    pub fn latency(&self, span_name: &str, bucket: usize) -> Vec<SpanData> {
+        self.with_span_name(span_name, |spans| {
+            spans
+                .latency_samples
+                .get(bucket)
+                .map(|samples| samples.iter().cloned().collect())
+                .unwrap_or_default()
+        })
+    }
+
+    fn with_span_name<T: Default>(&self, span_name: &str, f: impl FnOnce(&SpanNameData) -> T) -> T {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.spans.get(span_name).map(f))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::trace::TracerProvider;
+    use opentelemetry::trace::{Span as _, Tracer, TracerProvider as _};
+    use std::time::SystemTime;
+
+    fn provider(processor: ZPagesSpanProcessor) -> TracerProvider {
+        TracerProvider::builder()
+            .with_span_processor(processor)
+            .build()
+    }
+
+    #[test]
+    fn latency_buckets() {
+        assert_eq!(latency_bucket(Duration::ZERO), 0);
+        assert_eq!(latency_bucket(Duration::from_micros(10)), 1);
+        assert_eq!(latency_bucket(Duration::from_millis(5)), 3);
+        assert_eq!(latency_bucket(Duration::from_secs(1000)), 8);
+    }
+
+    #[test]
+    fn tracks_running_and_ended_spans() {
+        let processor = ZPagesSpanProcessor::new();
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        let mut running = tracer.start("op");
+        let start = SystemTime::now();
+        let mut ok = tracer
+            .span_builder("op")
+            .with_start_time(start)
+            .start(&tracer);
+        ok.end_with_timestamp(start + Duration::from_millis(5));
+        let mut failed = tracer.start("op");
+        failed.set_status(Status::error("failed"));
+        failed.end();
+
+        let summaries = tracez.summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].span_name, "op");
+        assert_eq!(summaries[0].running, 1);
+        assert_eq!(summaries[0].errors, 1);
+        assert_eq!(summaries[0].latency.iter().sum::<u64>(), 1);
+        assert_eq!(summaries[0].latency[3], 1);
+        assert_eq!(tracez.latency("op", 3).len(), 1);
+        assert_eq!(tracez.errors("op").len(), 1);
+        assert_eq!(
+            tracez.running("op")[0].span_context,
+            running.span_context().clone()
+        );
+
+        running.end();
+        assert_eq!(tracez.summaries()[0].running, 0);
+    }
+
+    #[test]
+    fn keeps_latest_samples() {
+        let processor = ZPagesSpanProcessor::new().with_samples_per_bucket(2);
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        for _ in 0..5 {
+            let mut span = tracer.start("op");
+            span.set_status(Status::error("failed"));
+            span.end();
+        }
+
+        assert_eq!(tracez.summaries()[0].errors, 5);
+        assert_eq!(tracez.errors("op").len(), 2);
+        assert!(tracez.errors("unknown").is_empty());
+    }
+
+    #[test]
+    fn samples_running_spans() {
+        let processor = ZPagesSpanProcessor::new().with_samples_per_bucket(2);
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        let mut spans: Vec<_> = (0..5).map(|_| tracer.start("op")).collect();
+        assert_eq!(tracez.summaries()[0].running, 5);
+        assert_eq!(tracez.running("op").len(), 2);
+
+        spans[0].end();
+        spans[4].end();
+        assert_eq!(tracez.summaries()[0].running, 3);
+        assert_eq!(tracez.running("op").len(), 1);
+
+        let mut renamed = tracer.start("before");
+        renamed.update_name("after");
+        renamed.end();
+        let summaries = tracez.summaries();
+        assert_eq!(summaries[0].span_name, "after");
+        assert_eq!(summaries[1].span_name, "before");
+        assert_eq!(summaries[1].running, 0);
+    }
+
+    #[test]
+    fn ends_spans_renamed_to_running_names() {
+        let processor = ZPagesSpanProcessor::new();
+        let tracez = processor.tracez();
+        let provider = provider(processor);
+        let tracer = provider.tracer("test");
+
+        let mut other = tracer.start("after");
+        let mut renamed = tracer.start("before");
+        renamed.update_name("after");
+        renamed.end();
+
+        let summaries = tracez.summaries();
+        assert_eq!(summaries[0].span_name, "after");
+        assert_eq!(summaries[0].running, 1);
+        assert_eq!(summaries[0].latency.iter().sum::<u64>(), 1);
+        assert_eq!(summaries[1].span_name, "before");
+        assert_eq!(summaries[1].running, 0);
+        assert_eq!(
+            tracez.running("after")[0].span_context,
+            other.span_context().clone()
+        );
+
+        other.end();
+        assert_eq!(tracez.summaries()[0].running, 0);
+    }
+}
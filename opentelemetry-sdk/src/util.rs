@@ -7,3 +7,96 @@ pub fn tokio_interval_stream(
 ) -> tokio_stream::wrappers::IntervalStream {
     tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(period))
 }
+
+/// Whether the SDK is disabled with the `OTEL_SDK_DISABLED` environment
+/// variable, in which case the providers only create no-op tracers, meters and
+/// loggers.
+#[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+pub(crate) fn sdk_disabled() -> bool {
+    std::env::var("OTEL_SDK_DISABLED")
+        .map(|value| value.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Truncates `s` to its first `max_length` characters.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) fn truncate_string(s: &mut opentelemetry::StringValue, max_length: usize) {
+    if let Some((idx, _)) = s.as_str().char_indices().nth(max_length) {
+        *s = s.as_str()[..idx].to_string().into();
+    }
+}
+
+/// Runs `future` to completion on the current thread, parking it while the
+/// future is pending. Returns `None` if the future is not complete after
+/// `timeout`.
+///
+/// This is the executor of the processors and readers running on a dedicated
+/// thread, so the futures it runs must not depend on an async runtime.
+#[cfg(any(feature = "trace", feature = "metrics", feature = "logs"))]
+pub(crate) fn block_on_timeout<F: std::future::Future>(
+    future: F,
+    timeout: std::time::Duration,
+) -> Option<F::Output> {
+    use std::{
+        sync::Arc,
+        task::{Context, Poll, Wake},
+        thread::{self, Thread},
+        time::Instant,
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let deadline = Instant::now().checked_add(timeout);
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    futures_util::pin_mut!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+        match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return None;
+                }
+                thread::park_timeout(remaining);
+            }
+            None => thread::park(),
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "trace", feature = "metrics", feature = "logs")))]
+mod tests {
+    use super::block_on_timeout;
+    use futures_channel::oneshot;
+    use std::{future, thread, time::Duration};
+
+    #[test]
+    fn block_on_with_timeout() {
+        assert_eq!(block_on_timeout(async { 42 }, Duration::ZERO), Some(42));
+        assert_eq!(
+            block_on_timeout(future::pending::<()>(), Duration::from_millis(10)),
+            None
+        );
+
+        let (sender, receiver) = oneshot::channel();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.send(42).unwrap();
+        });
+        assert_eq!(
+            block_on_timeout(receiver, Duration::from_secs(60)),
+            Some(Ok(42))
+        );
+        handle.join().unwrap();
+    }
+}
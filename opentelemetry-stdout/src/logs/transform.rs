@@ -132,7 +132,7 @@ impl From<opentelemetry_sdk::export::logs::LogData> for LogRecord {
                         .collect()
                 })
                 .unwrap_or_default(),
-            dropped_attributes_count: 0,
+            dropped_attributes_count: value.record.dropped_attributes_count,
             severity_text: value.record.severity_text,
             body: value.record.body.map(|a| a.into()),
         }
@@ -199,4 +199,4 @@ mod exporter;
 mod propagator;
 
 pub use exporter::{new_pipeline, Error, Exporter, ZipkinPipelineBuilder};
-pub use propagator::{B3Encoding, Propagator};
+pub use propagator::{register_propagators, B3Encoding, Propagator};
@@ -19,6 +19,7 @@ use opentelemetry::{
     trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
     Context,
 };
+use opentelemetry_sdk::propagation::PropagatorRegistry;
 
 const B3_SINGLE_HEADER: &str = "b3";
 /// As per spec, the multiple header should be case sensitive. But different protocol will use
@@ -217,6 +218,29 @@ impl Propagator {
     }
 }
 
+/// Registers the B3 propagators in `registry`, under the `b3` name for the
+/// single header encoding and `b3multi` for the multiple headers one, as
+/// expected in the `OTEL_PROPAGATORS` environment variable.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::propagation::PropagatorRegistry;
+///
+/// let mut registry = PropagatorRegistry::new();
+/// opentelemetry_zipkin::register_propagators(&mut registry);
+/// opentelemetry::global::set_text_map_propagator(registry.build_from_env());
+/// ```
+pub fn register_propagators(registry: &mut PropagatorRegistry) {
+    registry
+        .register("b3", || {
+            Box::new(Propagator::with_encoding(B3Encoding::SingleHeader))
+        })
+        .register("b3multi", || {
+            Box::new(Propagator::with_encoding(B3Encoding::MultipleHeader))
+        });
+}
+
 impl TextMapPropagator for Propagator {
     /// Properly encodes the values of the `Context`'s `SpanContext` and injects
     /// them into the `Injector`.
@@ -680,4 +704,24 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn register_b3_propagators() {
+        let mut registry = PropagatorRegistry::new();
+        register_propagators(&mut registry);
+
+        let single = registry.get("b3").expect("b3 is registered");
+        assert_eq!(
+            single.fields().collect::<Vec<&str>>(),
+            vec![B3_SINGLE_HEADER]
+        );
+        let multi = registry.get("b3multi").expect("b3multi is registered");
+        assert_eq!(
+            multi.fields().collect::<Vec<&str>>(),
+            B3_MULTI_FIELDS
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        );
+    }
 }
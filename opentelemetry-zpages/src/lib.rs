@@ -0,0 +1,586 @@
+//! Pages for the in-process inspection of OpenTelemetry spans, following
+//! the [tracez] page of zPages.
+//!
+//! The spans recorded by the `ZPagesSpanProcessor` of the SDK are queried
+//! with its [`Tracez`] handle, and rendered by [`handle`] as HTML pages or as
+//! JSON documents of the tracez messages of `opentelemetry-proto`. [`serve`]
+//! serves them over HTTP:
+//!
+//! * `/tracez`: the HTML summary of the recorded spans per span name, linking
+//!   to the HTML lists of spans.
+//! * `/api/tracez`: the JSON summary of the recorded spans.
+//!
+//! Both accept the `name` and `type` query parameters to list the spans of a
+//! name instead, where `type` is one of `running`, `errors` or `latency`. The
+//! latency bucket is then selected by the `bucket` query parameter.
+//!
+//! ```no_run
+//! use opentelemetry_sdk::trace::{TracerProvider, ZPagesSpanProcessor};
+//!
+//! let processor = ZPagesSpanProcessor::new();
+//! let tracez = processor.tracez();
+//! let provider = TracerProvider::builder()
+//!     .with_span_processor(processor)
+//!     .build();
+//!
+//! // browse http://127.0.0.1:8888/tracez
+//! let server = opentelemetry_zpages::serve(tracez, "127.0.0.1:8888").unwrap();
+//! ```
+//!
+//! *Compiler support: [requires `rustc` 1.65+][msrv]*
+//!
+//! [tracez]: https://opencensus.io/zpages/#tracez
+//! [msrv]: #supported-rust-versions
+//!
+//! # Supported Rust Versions
+//!
+//! OpenTelemetry is built against the latest stable release. The minimum
+//! supported version is 1.65. The current OpenTelemetry version is not
+//! guaranteed to build on Rust versions earlier than the minimum supported
+//! version.
+//!
+//! The current stable Rust compiler and the three most recent minor versions
+//! before it will always be supported. For example, if the current stable
+//! compiler version is 1.65, the minimum supported version will not be
+//! increased past 1.62, three minor versions prior. Increasing the minimum
+//! supported compiler version is not considered a semver breaking change as
+//! long as doing so complies with this policy.
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use opentelemetry::global;
+use opentelemetry::trace::{Status, TraceError};
+use opentelemetry_proto::tonic::tracez::v1::{ErrorData, LatencyData, RunningData, TracezCounts};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::trace::zpages::{Tracez, TracezSummary, LATENCY_BUCKET_COUNT};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+
+/// The maximum number of connections served at once, the connections
+/// accepted past it are answered with a `503` status.
+const MAX_CONNECTIONS: usize = 16;
+
+/// The time allowed to a client to send its request, and to read the
+/// response.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+const BUCKET_LABELS: [&str; LATENCY_BUCKET_COUNT] = [
+    "[0, 10µs)",
+    "[10µs, 100µs)",
+    "[100µs, 1ms)",
+    "[1ms, 10ms)",
+    "[10ms, 100ms)",
+    "[100ms, 1s)",
+    "[1s, 10s)",
+    "[10s, 100s)",
+    "[100s, +Inf)",
+];
+
+/// A response to a request of a tracez view.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ZPagesResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The value of the `Content-Type` header.
+    pub content_type: &'static str,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl ZPagesResponse {
+    fn new(status: u16, content_type: &'static str, body: String) -> Self {
+        ZPagesResponse {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn not_found() -> Self {
+        ZPagesResponse::new(404, "text/plain", "not found".into())
+    }
+}
+
+/// Which spans of a span name are listed.
+enum SpanList {
+    Running,
+    Errors,
+    Latency(usize),
+}
+
+struct Query {
+    name: Option<String>,
+    list: Option<SpanList>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Option<Self> {
+        let mut name = None;
+        let mut kind = None;
+        let mut bucket = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode_str(&value.replace('+', " "))
+                .decode_utf8_lossy()
+                .into_owned();
+            match key {
+                "name" => name = Some(value),
+                "type" => kind = Some(value),
+                "bucket" => bucket = Some(value.parse::<usize>().ok()?),
+                _ => {}
+            }
+        }
+
+        let list = match kind.as_deref() {
+            None => None,
+            Some("running") => Some(SpanList::Running),
+            Some("errors") => Some(SpanList::Errors),
+            Some("latency") => match bucket {
+                Some(bucket) if bucket < LATENCY_BUCKET_COUNT => Some(SpanList::Latency(bucket)),
+                _ => return None,
+            },
+            Some(_) => return None,
+        };
+
+        Some(Query { name, list })
+    }
+}
+
+/// Render the tracez view requested with the given path and query, e.g.
+/// `/tracez?name=my_span&type=errors`.
+///
+/// This lets the views be served by an existing HTTP server.
+pub fn handle(tracez: &Tracez, path_and_query: &str) -> ZPagesResponse {
+    let (path, query) = path_and_query
+        .split_once('?')
+        .unwrap_or((path_and_query, ""));
+    let query = match Query::parse(query) {
+        Some(query) => query,
+        None => return ZPagesResponse::new(400, "text/plain", "invalid query".into()),
+    };
+
+    match (path.trim_end_matches('/'), query) {
+        (
+            "/tracez",
+            Query {
+                name: Some(name),
+                list: Some(list),
+            },
+        ) => ZPagesResponse::new(
+            200,
+            "text/html; charset=utf-8",
+            spans_html(&name, &list, &spans(tracez, &name, &list)),
+        ),
+        ("/tracez", _) => ZPagesResponse::new(
+            200,
+            "text/html; charset=utf-8",
+            summary_html(&tracez.summaries()),
+        ),
+        (
+            "/api/tracez",
+            Query {
+                name: Some(name),
+                list: Some(list),
+            },
+        ) => {
+            let spans = spans(tracez, &name, &list).into_iter();
+            let json = match list {
+                SpanList::Running => {
+                    serde_json::to_string(&spans.map(RunningData::from).collect::<Vec<_>>())
+                }
+                SpanList::Errors => {
+                    serde_json::to_string(&spans.map(ErrorData::from).collect::<Vec<_>>())
+                }
+                SpanList::Latency(_) => {
+                    serde_json::to_string(&spans.map(LatencyData::from).collect::<Vec<_>>())
+                }
+            };
+            ZPagesResponse::new(200, "application/json", json.unwrap_or_default())
+        }
+        ("/api/tracez", _) => {
+            let counts: Vec<_> = tracez
+                .summaries()
+                .into_iter()
+                .map(TracezCounts::from)
+                .collect();
+            let json = serde_json::to_string(&counts).unwrap_or_default();
+            ZPagesResponse::new(200, "application/json", json)
+        }
+        _ => ZPagesResponse::not_found(),
+    }
+}
+
+/// Serve the tracez views over HTTP on the given address.
+///
+/// Connections are accepted by a background thread, and each of them is
+/// served by its own thread, so slow clients do not delay the others. The
+/// server stops when the returned [`ZPagesServer`] is dropped.
+pub fn serve<A: ToSocketAddrs>(tracez: Tracez, addr: A) -> io::Result<ZPagesServer> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let thread_stopped = stopped.clone();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let handle = thread::Builder::new()
+        .name("opentelemetry-zpages".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stopped.load(Ordering::Acquire) {
+                    break;
+                }
+                let result = stream.and_then(|stream| {
+                    if connections.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::AcqRel);
+                        let response =
+                            ZPagesResponse::new(503, "text/plain", "too many connections".into());
+                        return write_response(stream, &response);
+                    }
+
+                    let tracez = tracez.clone();
+                    let connections = connections.clone();
+                    thread::Builder::new()
+                        .name("opentelemetry-zpages-connection".to_string())
+                        .spawn(move || {
+                            if let Err(err) = serve_connection(&tracez, stream) {
+                                report_error(err);
+                            }
+                            connections.fetch_sub(1, Ordering::AcqRel);
+                        })
+                        .map(|_| ())
+                });
+                if let Err(err) = result {
+                    report_error(err);
+                }
+            }
+        })?;
+
+    Ok(ZPagesServer {
+        local_addr,
+        stopped,
+        handle: Some(handle),
+    })
+}
+
+fn report_error(err: io::Error) {
+    global::handle_error(TraceError::from(format!("zPages request failed: {}", err)));
+}
+
+fn spans(tracez: &Tracez, name: &str, list: &SpanList) -> Vec<SpanData> {
+    match list {
+        SpanList::Running => tracez.running(name),
+        SpanList::Errors => tracez.errors(name),
+        SpanList::Latency(bucket) => tracez.latency(name, *bucket),
+    }
+}
+
+/// A HTTP server of the tracez views, started with [`serve`].
+#[derive(Debug)]
+pub struct ZPagesServer {
+    local_addr: SocketAddr,
+    stopped: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl ZPagesServer {
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for ZPagesServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+        // wake the server up from waiting for a connection
+        let _ = TcpStream::connect_timeout(&self.local_addr, Duration::from_secs(1));
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve_connection(tracez: &Tracez, stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // the headers are not used, but must be read before responding
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => handle(tracez, target),
+        (Some(_), Some(_)) => ZPagesResponse::new(405, "text/plain", "method not allowed".into()),
+        _ => ZPagesResponse::new(400, "text/plain", "bad request".into()),
+    };
+    write_response(stream, &response)
+}
+
+fn write_response(mut stream: TcpStream, response: &ZPagesResponse) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default()
+}
+
+const HTML_HEAD: &str =
+    "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>tracez</title>\
+<style>body{font-family:sans-serif}table{border-collapse:collapse}\
+td,th{border:1px solid #ccc;padding:4px 8px;text-align:left}</style></head><body>\n";
+
+const HTML_TAIL: &str = "</body></html>\n";
+
+fn summary_html(summaries: &[TracezSummary]) -> String {
+    let mut html = String::from(HTML_HEAD);
+    html.push_str("<h1>tracez</h1>\n<table>\n<tr><th>Span name</th><th>Running</th>");
+    for label in BUCKET_LABELS {
+        let _ = write!(html, "<th>{}</th>", label);
+    }
+    html.push_str("<th>Errors</th></tr>\n");
+
+    for summary in summaries {
+        let name = utf8_percent_encode(&summary.span_name, NON_ALPHANUMERIC).to_string();
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td><a href=\"?name={}&amp;type=running\">{}</a></td>",
+            escape(&summary.span_name),
+            name,
+            summary.running
+        );
+        for (bucket, count) in summary.latency.iter().enumerate() {
+            let _ = write!(
+                html,
+                "<td><a href=\"?name={}&amp;type=latency&amp;bucket={}\">{}</a></td>",
+                name, bucket, count
+            );
+        }
+        let _ = writeln!(
+            html,
+            "<td><a href=\"?name={}&amp;type=errors\">{}</a></td></tr>",
+            name, summary.errors
+        );
+    }
+
+    html.push_str("</table>\n");
+    html.push_str(HTML_TAIL);
+    html
+}
+
+fn spans_html(name: &str, list: &SpanList, spans: &[SpanData]) -> String {
+    let title = match list {
+        SpanList::Running => "running".to_string(),
+        SpanList::Errors => "errors".to_string(),
+        SpanList::Latency(bucket) => format!("latency {}", BUCKET_LABELS[*bucket]),
+    };
+
+    let mut html = String::from(HTML_HEAD);
+    let _ = write!(
+        html,
+        "<h1>{}: {}</h1>\n<p><a href=\"?\">back to summary</a></p>\n<table>\n\
+<tr><th>Trace id</th><th>Span id</th><th>Parent span id</th><th>Start</th>\
+<th>Duration</th><th>Status</th><th>Attributes</th><th>Events</th></tr>\n",
+        escape(name),
+        title
+    );
+
+    for span in spans {
+        let duration = match list {
+            SpanList::Running => "running".to_string(),
+            _ => format!(
+                "{:?}",
+                span.end_time
+                    .duration_since(span.start_time)
+                    .unwrap_or_default()
+            ),
+        };
+        let status = match &span.status {
+            Status::Unset => "unset".to_string(),
+            Status::Ok => "ok".to_string(),
+            Status::Error { description } => format!("error: {}", description),
+        };
+        let attributes = span
+            .attributes
+            .iter()
+            .map(|kv| format!("{}={}", kv.key, kv.value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let events = span
+            .events
+            .iter()
+            .map(|event| event.name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            span.span_context.trace_id(),
+            span.span_context.span_id(),
+            span.parent_span_id,
+            unix_nanos(span.start_time),
+            duration,
+            escape(&status),
+            escape(&attributes),
+            escape(&events),
+        );
+    }
+
+    html.push_str("</table>\n");
+    html.push_str(HTML_TAIL);
+    html
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Span as _, Tracer, TracerProvider as _};
+    use opentelemetry_sdk::trace::{TracerProvider, ZPagesSpanProcessor};
+    use std::io::Read;
+
+    fn tracez_with_spans() -> (Tracez, TracerProvider) {
+        let processor = ZPagesSpanProcessor::new();
+        let tracez = processor.tracez();
+        let provider = TracerProvider::builder()
+            .with_span_processor(processor)
+            .build();
+        let tracer = provider.tracer("test");
+        let start = std::time::SystemTime::now();
+        tracer
+            .span_builder("<op>")
+            .with_start_time(start)
+            .start(&tracer)
+            .end_with_timestamp(start + Duration::from_millis(5));
+        let mut span = tracer.start("<op>");
+        span.set_status(Status::error("failed"));
+        span.end();
+
+        (tracez, provider)
+    }
+
+    #[test]
+    fn renders_json_views() {
+        let (tracez, _provider) = tracez_with_spans();
+
+        let response = handle(&tracez, "/api/tracez");
+        assert_eq!(response.status, 200);
+        let summary: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(summary[0]["spanname"], "<op>");
+        assert_eq!(summary[0]["error"], 1);
+        assert_eq!(summary[0]["latency"][3], 1);
+
+        let response = handle(&tracez, "/api/tracez?name=%3Cop%3E&type=errors");
+        let spans: Vec<ErrorData> = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(spans[0].status.as_ref().unwrap().message, "failed");
+        let spans: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(spans[0]["traceid"].as_str().unwrap().len(), 32);
+
+        assert_eq!(
+            handle(&tracez, "/api/tracez?name=%3Cop%3E&type=latency&bucket=9").status,
+            400
+        );
+        assert_eq!(handle(&tracez, "/unknown").status, 404);
+    }
+
+    #[test]
+    fn renders_html_views() {
+        let (tracez, _provider) = tracez_with_spans();
+
+        let response = handle(&tracez, "/tracez");
+        assert_eq!(response.content_type, "text/html; charset=utf-8");
+        assert!(response.body.contains("<td>&lt;op&gt;</td>"));
+        assert!(response.body.contains("?name=%3Cop%3E&amp;type=errors"));
+
+        let response = handle(&tracez, "/tracez?name=%3Cop%3E&type=errors");
+        assert!(response.body.contains("error: failed"));
+    }
+
+    #[test]
+    fn serves_views_over_http() {
+        let (tracez, _provider) = tracez_with_spans();
+        let server = serve(tracez, "127.0.0.1:0").unwrap();
+
+        // a client that never sends its request does not delay the others
+        let _idle = TcpStream::connect(server.local_addr()).unwrap();
+
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        stream
+            .write_all(b"GET /api/tracez HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\"spanname\":\"<op>\""));
+        drop(server);
+    }
+}
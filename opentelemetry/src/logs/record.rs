@@ -31,6 +31,10 @@ pub struct LogRecord {
 
     /// Additional attributes associated with this record
     pub attributes: Option<Vec<(Key, AnyValue)>>,
+
+    /// The number of attributes dropped from the record, e.g. by the limits
+    /// of the SDK
+    pub dropped_attributes_count: u32,
 }
 
 impl Default for LogRecord {
@@ -44,6 +48,7 @@ impl Default for LogRecord {
             severity_number: None,
             body: None,
             attributes: None,
+            dropped_attributes_count: 0,
         }
     }
 }